@@ -0,0 +1,265 @@
+pub const WAVEFORM_V2_MAGIC: &[u8; 4] = b"RWF2";
+pub const WAVEFORM_V2_VERSION: u16 = 2;
+pub const WAVEFORM_V2_SAMPLE_RATE: u32 = 48_000;
+// Finest to coarsest; each level is 4x coarser than the previous one.
+pub const WAVEFORM_V2_SAMPLES_PER_PEAK: [u32; 4] = [256, 1_024, 4_096, 16_384];
+
+const HEADER_LEN: usize = 24;
+const LEVEL_HEADER_LEN: usize = 8;
+const PEAK_LEN: usize = 6;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WaveformPeak {
+    pub min: i16,
+    pub max: i16,
+    pub rms: i16,
+}
+
+// `peaks` is interleaved by channel: peak `i` of channel `c` is at
+// `i * channel_count + c`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WaveformZoomLevel {
+    pub samples_per_peak: u32,
+    pub peaks: Vec<WaveformPeak>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WaveformPeaks {
+    pub channel_count: u16,
+    pub sample_rate: u32,
+    pub duration_ms: u64,
+    pub levels: Vec<WaveformZoomLevel>,
+}
+
+impl WaveformPeaks {
+    pub fn peak(&self, level: usize, index: usize, channel: u16) -> Option<WaveformPeak> {
+        let level = self.levels.get(level)?;
+        if channel >= self.channel_count {
+            return None;
+        }
+        level
+            .peaks
+            .get(index * usize::from(self.channel_count) + usize::from(channel))
+            .copied()
+    }
+
+    // Little-endian layout:
+    //   header: magic `RWF2`, version u16, channel_count u16, sample_rate u32,
+    //           duration_ms u64, level_count u16, reserved u16
+    //   per level: samples_per_peak u32, peak_count u32, then
+    //              peak_count x channel_count x (min i16, max i16, rms i16)
+    pub fn encode(&self) -> Vec<u8> {
+        let peak_bytes = self
+            .levels
+            .iter()
+            .map(|level| LEVEL_HEADER_LEN + level.peaks.len() * PEAK_LEN)
+            .sum::<usize>();
+        let mut bytes = Vec::with_capacity(HEADER_LEN + peak_bytes);
+        bytes.extend_from_slice(WAVEFORM_V2_MAGIC);
+        bytes.extend_from_slice(&WAVEFORM_V2_VERSION.to_le_bytes());
+        bytes.extend_from_slice(&self.channel_count.to_le_bytes());
+        bytes.extend_from_slice(&self.sample_rate.to_le_bytes());
+        bytes.extend_from_slice(&self.duration_ms.to_le_bytes());
+        let level_count = u16::try_from(self.levels.len()).unwrap_or(u16::MAX);
+        bytes.extend_from_slice(&level_count.to_le_bytes());
+        bytes.extend_from_slice(&0_u16.to_le_bytes());
+        let channel_count = usize::from(self.channel_count.max(1));
+        for level in self.levels.iter().take(usize::from(level_count)) {
+            let peak_count = u32::try_from(level.peaks.len() / channel_count).unwrap_or(u32::MAX);
+            bytes.extend_from_slice(&level.samples_per_peak.to_le_bytes());
+            bytes.extend_from_slice(&peak_count.to_le_bytes());
+            for peak in &level.peaks {
+                bytes.extend_from_slice(&peak.min.to_le_bytes());
+                bytes.extend_from_slice(&peak.max.to_le_bytes());
+                bytes.extend_from_slice(&peak.rms.to_le_bytes());
+            }
+        }
+        bytes
+    }
+
+    pub fn decode(bytes: &[u8]) -> Option<Self> {
+        let mut reader = ByteReader { bytes, offset: 0 };
+        if reader.take(4)? != WAVEFORM_V2_MAGIC || reader.u16()? != WAVEFORM_V2_VERSION {
+            return None;
+        }
+        let channel_count = reader.u16()?;
+        let sample_rate = reader.u32()?;
+        let duration_ms = reader.u64()?;
+        let level_count = reader.u16()?;
+        reader.u16()?;
+        if channel_count == 0 {
+            return None;
+        }
+        let mut levels = Vec::with_capacity(usize::from(level_count));
+        for _ in 0..level_count {
+            let samples_per_peak = reader.u32()?;
+            let value_count = usize::try_from(reader.u32()?)
+                .ok()?
+                .checked_mul(usize::from(channel_count))?;
+            let raw = reader.take(value_count.checked_mul(PEAK_LEN)?)?;
+            let peaks = raw
+                .chunks_exact(PEAK_LEN)
+                .map(|chunk| WaveformPeak {
+                    min: i16::from_le_bytes([chunk[0], chunk[1]]),
+                    max: i16::from_le_bytes([chunk[2], chunk[3]]),
+                    rms: i16::from_le_bytes([chunk[4], chunk[5]]),
+                })
+                .collect();
+            levels.push(WaveformZoomLevel {
+                samples_per_peak,
+                peaks,
+            });
+        }
+        (reader.offset == bytes.len()).then_some(Self {
+            channel_count,
+            sample_rate,
+            duration_ms,
+            levels,
+        })
+    }
+}
+
+struct ByteReader<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> ByteReader<'a> {
+    fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        let slice = self.bytes.get(self.offset..self.offset.checked_add(len)?)?;
+        self.offset += len;
+        Some(slice)
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        Some(u16::from_le_bytes(self.take(2)?.try_into().ok()?))
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        Some(u32::from_le_bytes(self.take(4)?.try_into().ok()?))
+    }
+
+    fn u64(&mut self) -> Option<u64> {
+        Some(u64::from_le_bytes(self.take(8)?.try_into().ok()?))
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct PeakAccumulator {
+    min: i16,
+    max: i16,
+    sum_squares: f64,
+}
+
+impl Default for PeakAccumulator {
+    fn default() -> Self {
+        Self {
+            min: i16::MAX,
+            max: i16::MIN,
+            sum_squares: 0.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct LevelAccumulator {
+    samples_per_peak: u32,
+    filled: u32,
+    channels: Vec<PeakAccumulator>,
+    peaks: Vec<WaveformPeak>,
+}
+
+impl LevelAccumulator {
+    fn flush(&mut self) {
+        if self.filled == 0 {
+            return;
+        }
+        let filled = f64::from(self.filled);
+        for channel in &mut self.channels {
+            let rms = (channel.sum_squares / filled).sqrt().round();
+            self.peaks.push(WaveformPeak {
+                min: channel.min,
+                max: channel.max,
+                rms: rms.min(f64::from(i16::MAX)) as i16,
+            });
+            *channel = PeakAccumulator::default();
+        }
+        self.filled = 0;
+    }
+}
+
+// Builds every zoom level in a single pass over interleaved frames, so long
+// sources never have to be held in memory.
+#[derive(Debug, Clone)]
+pub struct WaveformPeaksBuilder {
+    channel_count: u16,
+    sample_rate: u32,
+    frame_count: u64,
+    levels: Vec<LevelAccumulator>,
+}
+
+impl WaveformPeaksBuilder {
+    pub fn new(channel_count: u16, sample_rate: u32, samples_per_peak: &[u32]) -> Self {
+        let channel_count = channel_count.max(1);
+        Self {
+            channel_count,
+            sample_rate,
+            frame_count: 0,
+            levels: samples_per_peak
+                .iter()
+                .map(|samples_per_peak| LevelAccumulator {
+                    samples_per_peak: (*samples_per_peak).max(1),
+                    filled: 0,
+                    channels: vec![PeakAccumulator::default(); usize::from(channel_count)],
+                    peaks: Vec::new(),
+                })
+                .collect(),
+        }
+    }
+
+    pub fn frame_count(&self) -> u64 {
+        self.frame_count
+    }
+
+    // `frame` holds one sample per channel; missing channels count as silence.
+    pub fn push_frame(&mut self, frame: &[i16]) {
+        for level in &mut self.levels {
+            for (index, channel) in level.channels.iter_mut().enumerate() {
+                let sample = frame.get(index).copied().unwrap_or(0);
+                channel.min = channel.min.min(sample);
+                channel.max = channel.max.max(sample);
+                channel.sum_squares += f64::from(sample) * f64::from(sample);
+            }
+            level.filled += 1;
+            if level.filled == level.samples_per_peak {
+                level.flush();
+            }
+        }
+        self.frame_count += 1;
+    }
+
+    pub fn finish(mut self) -> WaveformPeaks {
+        let duration_ms = if self.sample_rate == 0 {
+            0
+        } else {
+            (self.frame_count * 1_000 + u64::from(self.sample_rate) / 2)
+                / u64::from(self.sample_rate)
+        };
+        WaveformPeaks {
+            channel_count: self.channel_count,
+            sample_rate: self.sample_rate,
+            duration_ms,
+            levels: self
+                .levels
+                .iter_mut()
+                .map(|level| {
+                    level.flush();
+                    WaveformZoomLevel {
+                        samples_per_peak: level.samples_per_peak,
+                        peaks: std::mem::take(&mut level.peaks),
+                    }
+                })
+                .collect(),
+        }
+    }
+}
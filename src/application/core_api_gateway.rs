@@ -2,6 +2,7 @@ use std::collections::{BTreeMap, BTreeSet};
 
 use thiserror::Error;
 
+use crate::application::derived_processing_gateway::DerivedJobType;
 use crate::domain::capabilities::{declared_agent_capabilities, has_required_capabilities};
 use crate::domain::runtime_ui::{
     ConnectivityState, JobFailure, JobStage, JobStatus, RuntimeSnapshot,
@@ -19,6 +20,7 @@ pub enum CoreJobState {
 pub struct CoreJobView {
     pub job_id: String,
     pub asset_uuid: String,
+    pub job_type: Option<DerivedJobType>,
     pub state: CoreJobState,
     pub required_capabilities: Vec<String>,
 }
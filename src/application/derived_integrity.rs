@@ -0,0 +1,142 @@
+use std::fs::File;
+use std::io::Read;
+use std::ops::Range;
+use std::path::Path;
+
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+use crate::application::derived_job_executor::DerivedUploadPlan;
+use crate::application::derived_processing_gateway::DerivedUploadPart;
+
+const HASH_BUFFER_BYTES: usize = 64 * 1024;
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum DerivedIntegrityError {
+    #[error("derived artifact unreadable {path}: {reason}")]
+    Unreadable { path: String, reason: String },
+    #[error("derived artifact sha256 mismatch for {path}: expected {expected}, got {actual}")]
+    Mismatch {
+        path: String,
+        expected: String,
+        actual: String,
+    },
+}
+
+impl DerivedIntegrityError {
+    pub fn error_code(&self) -> &'static str {
+        match self {
+            Self::Unreadable { .. } => "DERIVED_ARTIFACT_UNREADABLE",
+            Self::Mismatch { .. } => "DERIVED_INTEGRITY_MISMATCH",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DerivedArtifactDigest {
+    pub sha256: String,
+    pub part_sha256: Vec<String>,
+}
+
+pub fn sha256_file(path: &Path) -> Result<String, DerivedIntegrityError> {
+    digest_artifact(path, &[]).map(|digest| digest.sha256)
+}
+
+pub fn digest_artifact(
+    path: &Path,
+    parts: &[DerivedUploadPart],
+) -> Result<DerivedArtifactDigest, DerivedIntegrityError> {
+    let unreadable = |error: std::io::Error| DerivedIntegrityError::Unreadable {
+        path: path.display().to_string(),
+        reason: error.to_string(),
+    };
+    let mut file = File::open(path).map_err(unreadable)?;
+    let mut whole = Sha256::new();
+    let mut part_hashers = parts
+        .iter()
+        .map(|part| (part.byte_range.clone(), Sha256::new()))
+        .collect::<Vec<_>>();
+    let mut buffer = vec![0_u8; HASH_BUFFER_BYTES];
+    let mut offset = 0_u64;
+    loop {
+        let read = file.read(&mut buffer).map_err(unreadable)?;
+        if read == 0 {
+            break;
+        }
+        let chunk = &buffer[..read];
+        whole.update(chunk);
+        let chunk_range = offset..offset + read as u64;
+        for (byte_range, hasher) in &mut part_hashers {
+            if let Some(overlap) = overlapping_slice(chunk, &chunk_range, byte_range.as_ref()) {
+                hasher.update(overlap);
+            }
+        }
+        offset = chunk_range.end;
+    }
+
+    Ok(DerivedArtifactDigest {
+        sha256: hex::encode(whole.finalize()),
+        part_sha256: part_hashers
+            .into_iter()
+            .map(|(_, hasher)| hex::encode(hasher.finalize()))
+            .collect(),
+    })
+}
+
+pub fn attach_upload_integrity(
+    upload: &mut DerivedUploadPlan,
+    artifact_path: &Path,
+) -> Result<String, DerivedIntegrityError> {
+    let digest = digest_artifact(artifact_path, &upload.parts)?;
+    upload.init.sha256 = Some(digest.sha256.clone());
+    for (part, sha256) in upload.parts.iter_mut().zip(digest.part_sha256) {
+        part.sha256 = Some(sha256);
+    }
+    Ok(digest.sha256)
+}
+
+pub fn verify_upload_integrity(upload: &DerivedUploadPlan) -> Result<(), DerivedIntegrityError> {
+    let Some(artifact_path) = upload.parts.first().map(|part| part.chunk_path.as_path()) else {
+        return Ok(());
+    };
+    if upload.init.sha256.is_none() && upload.parts.iter().all(|part| part.sha256.is_none()) {
+        return Ok(());
+    }
+    let digest = digest_artifact(artifact_path, &upload.parts)?;
+    let mismatch = |expected: &str, actual: &str| DerivedIntegrityError::Mismatch {
+        path: artifact_path.display().to_string(),
+        expected: expected.to_string(),
+        actual: actual.to_string(),
+    };
+    for (part, actual) in upload.parts.iter().zip(&digest.part_sha256) {
+        if let Some(expected) = part.sha256.as_deref()
+            && !expected.eq_ignore_ascii_case(actual)
+        {
+            return Err(mismatch(expected, actual));
+        }
+    }
+    if let Some(expected) = upload.init.sha256.as_deref()
+        && !expected.eq_ignore_ascii_case(&digest.sha256)
+    {
+        return Err(mismatch(expected, &digest.sha256));
+    }
+    Ok(())
+}
+
+fn overlapping_slice<'a>(
+    chunk: &'a [u8],
+    chunk_range: &Range<u64>,
+    byte_range: Option<&Range<u64>>,
+) -> Option<&'a [u8]> {
+    let Some(byte_range) = byte_range else {
+        return Some(chunk);
+    };
+    let start = chunk_range.start.max(byte_range.start);
+    let end = chunk_range.end.min(byte_range.end);
+    if start >= end {
+        return None;
+    }
+    let local_start = (start - chunk_range.start) as usize;
+    let local_end = (end - chunk_range.start) as usize;
+    Some(&chunk[local_start..local_end])
+}
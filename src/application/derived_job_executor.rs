@@ -1,21 +1,38 @@
+use std::hash::{DefaultHasher, Hash, Hasher};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 use crate::AgentRuntimeConfig;
+use crate::application::derived_integrity::{DerivedIntegrityError, verify_upload_integrity};
+use crate::application::derived_job_journal::{
+    DerivedJobJournal, DerivedJobJournalEntry, DerivedJobJournalStage, NoopDerivedJobJournal,
+};
+use crate::application::derived_lease_keeper::{
+    DerivedJobLease, DerivedJobLeaseRegistry, LeaseKeeperPolicy, is_lease_lost,
+};
 use crate::application::derived_processing_gateway::{
-    ClaimedDerivedJob, DerivedProcessingError, DerivedProcessingGateway, DerivedUploadComplete,
-    DerivedUploadInit, DerivedUploadPart, SubmitDerivedPayload,
+    ClaimedDerivedJob, DerivedJobFailure, DerivedProcessingError, DerivedProcessingGateway,
+    DerivedUploadComplete, DerivedUploadInit, DerivedUploadPart, SubmitDerivedPayload,
 };
+use crate::application::gateway_retry::{GatewayCall, GatewayRetryPolicies, retry_gateway_call};
+use crate::application::job_cancellation::{CancellationReason, CancellationToken};
+use crate::application::proxy_generator::{ProxyGenerationError, ProxyProgressSink};
 use crate::application::source_staging::{SourceStagingError, stage_claimed_job_source};
+use crate::domain::runtime_status_tracker::{RuntimeStatusEvent, RuntimeStatusTracker};
+use crate::domain::runtime_ui::JobStage;
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct DerivedUploadPlan {
     pub init: DerivedUploadInit,
     pub parts: Vec<DerivedUploadPart>,
     pub complete: DerivedUploadComplete,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct DerivedExecutionPlan {
     pub uploads: Vec<DerivedUploadPlan>,
     pub submit: SubmitDerivedPayload,
@@ -63,11 +80,66 @@ pub enum DerivedJobExecutorError {
     UploadKindNotInSubmitManifest(crate::application::derived_processing_gateway::DerivedKind),
     #[error("source staging failed: {0}")]
     SourceStaging(SourceStagingError),
+    #[error("proxy generation failed: {0}")]
+    ProxyGeneration(ProxyGenerationError),
+    #[error("derived artifact integrity check failed: {0}")]
+    Integrity(DerivedIntegrityError),
     #[error("planner error: {0}")]
     Planner(String),
+    #[error("job cancelled: {}", .0.as_str())]
+    Cancelled(CancellationReason),
+}
+
+impl DerivedJobExecutorError {
+    pub fn error_code(&self) -> &'static str {
+        match self {
+            Self::Gateway(error) => error.error_code(),
+            Self::MissingSubmitIdempotencyKey => "MISSING_SUBMIT_IDEMPOTENCY_KEY",
+            Self::UploadAssetMismatch { .. } => "UPLOAD_ASSET_MISMATCH",
+            Self::UploadInitCompleteAssetMismatch => "UPLOAD_INIT_COMPLETE_ASSET_MISMATCH",
+            Self::SubmitJobTypeMismatch { .. } => "SUBMIT_JOB_TYPE_MISMATCH",
+            Self::MissingSubmitManifestForJobType(_) => "MISSING_SUBMIT_MANIFEST",
+            Self::MissingFactsPatchForExtractFacts => "MISSING_FACTS_PATCH",
+            Self::MissingTranscriptPatchForTranscribeAudio => "MISSING_TRANSCRIPT_PATCH",
+            Self::IncompatibleDerivedKindForJobType { .. } => "INCOMPATIBLE_DERIVED_KIND",
+            Self::UploadKindNotInSubmitManifest(_) => "UPLOAD_KIND_NOT_IN_MANIFEST",
+            Self::SourceStaging(error) => error.error_code(),
+            Self::ProxyGeneration(error) => error.error_code(),
+            Self::Integrity(error) => error.error_code(),
+            Self::Planner(_) => "PLANNER_FAILED",
+            Self::Cancelled(_) => "JOB_CANCELLED",
+        }
+    }
+
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::Gateway(error) => error.is_retryable(),
+            Self::SourceStaging(error) => error.is_retryable(),
+            Self::ProxyGeneration(error) => error.is_retryable(),
+            Self::Integrity(_) | Self::Cancelled(_) => true,
+            Self::MissingSubmitIdempotencyKey
+            | Self::UploadAssetMismatch { .. }
+            | Self::UploadInitCompleteAssetMismatch
+            | Self::SubmitJobTypeMismatch { .. }
+            | Self::MissingSubmitManifestForJobType(_)
+            | Self::MissingFactsPatchForExtractFacts
+            | Self::MissingTranscriptPatchForTranscribeAudio
+            | Self::IncompatibleDerivedKindForJobType { .. }
+            | Self::UploadKindNotInSubmitManifest(_)
+            | Self::Planner(_) => false,
+        }
+    }
+
+    pub fn job_failure(&self) -> DerivedJobFailure {
+        DerivedJobFailure {
+            error_code: self.error_code().to_string(),
+            retryable: self.is_retryable(),
+            message: Some(self.to_string()),
+        }
+    }
 }
 
-pub trait DerivedExecutionPlanner {
+pub trait DerivedExecutionPlanner: Send + Sync {
     fn plan_for_claimed_job(
         &self,
         claimed: &ClaimedDerivedJob,
@@ -81,6 +153,76 @@ pub trait DerivedExecutionPlanner {
     ) -> Result<DerivedExecutionPlan, DerivedJobExecutorError> {
         self.plan_for_claimed_job(claimed)
     }
+
+    fn plan_for_claimed_job_with_progress(
+        &self,
+        claimed: &ClaimedDerivedJob,
+        staged_source_path: Option<&Path>,
+        staged_sidecar_paths: &[PathBuf],
+        _progress: &dyn ProxyProgressSink,
+    ) -> Result<DerivedExecutionPlan, DerivedJobExecutorError> {
+        self.plan_for_claimed_job_with_source(claimed, staged_source_path, staged_sidecar_paths)
+    }
+}
+
+pub trait DerivedJobProgressSink: Send + Sync {
+    fn report(&self, event: RuntimeStatusEvent);
+}
+
+impl DerivedJobProgressSink for Mutex<RuntimeStatusTracker> {
+    fn report(&self, event: RuntimeStatusEvent) {
+        self.lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .apply(event);
+    }
+}
+
+struct NoopProgressSink;
+
+#[derive(Clone, Copy)]
+pub(crate) struct JobExecutionContext<'a> {
+    pub(crate) settings: Option<&'a AgentRuntimeConfig>,
+    pub(crate) progress: &'a dyn DerivedJobProgressSink,
+    pub(crate) journal: &'a dyn DerivedJobJournal,
+    pub(crate) cancellation: &'a CancellationToken,
+    pub(crate) retry_policies: GatewayRetryPolicies,
+    pub(crate) leases: Option<&'a DerivedJobLeaseRegistry>,
+}
+
+const PROCESSING_PROGRESS_START: u8 = 10;
+const PROCESSING_PROGRESS_END: u8 = 60;
+
+struct ProcessingProgress<'a> {
+    claimed: &'a ClaimedDerivedJob,
+    sink: &'a dyn DerivedJobProgressSink,
+    cancellation: &'a CancellationToken,
+    last_percent: AtomicU8,
+}
+
+impl ProxyProgressSink for ProcessingProgress<'_> {
+    fn cancellation(&self) -> Option<&CancellationToken> {
+        Some(self.cancellation)
+    }
+
+    fn report_progress(&self, percent: u8) {
+        let percent = percent.min(100);
+        if self.last_percent.fetch_max(percent, Ordering::Relaxed) >= percent {
+            return;
+        }
+        let span = u16::from(PROCESSING_PROGRESS_END - PROCESSING_PROGRESS_START);
+        let progress_percent = PROCESSING_PROGRESS_START + (span * u16::from(percent) / 100) as u8;
+        self.sink.report(RuntimeStatusEvent::JobProgress {
+            job_id: self.claimed.job_id.clone(),
+            asset_uuid: self.claimed.asset_uuid.clone(),
+            progress_percent,
+            stage: JobStage::Processing,
+            short_status: format!("processing {percent}%"),
+        });
+    }
+}
+
+impl DerivedJobProgressSink for NoopProgressSink {
+    fn report(&self, _event: RuntimeStatusEvent) {}
 }
 
 pub fn execute_derived_job_once<
@@ -91,7 +233,36 @@ pub fn execute_derived_job_once<
     planner: &P,
     job_id: &str,
 ) -> Result<DerivedExecutionReport, DerivedJobExecutorError> {
-    execute_derived_job_once_internal(gateway, planner, job_id, None)
+    execute_derived_job_once_with_retry_policies(
+        gateway,
+        planner,
+        job_id,
+        GatewayRetryPolicies::default(),
+    )
+}
+
+pub fn execute_derived_job_once_with_retry_policies<
+    G: DerivedProcessingGateway + ?Sized,
+    P: DerivedExecutionPlanner + ?Sized,
+>(
+    gateway: &G,
+    planner: &P,
+    job_id: &str,
+    retry_policies: GatewayRetryPolicies,
+) -> Result<DerivedExecutionReport, DerivedJobExecutorError> {
+    execute_derived_job_once_internal(
+        gateway,
+        planner,
+        job_id,
+        &JobExecutionContext {
+            settings: None,
+            progress: &NoopProgressSink,
+            journal: &NoopDerivedJobJournal,
+            cancellation: &CancellationToken::new(),
+            retry_policies,
+            leases: None,
+        },
+    )
 }
 
 pub fn execute_derived_job_once_with_source_staging<
@@ -103,46 +274,272 @@ pub fn execute_derived_job_once_with_source_staging<
     job_id: &str,
     settings: &AgentRuntimeConfig,
 ) -> Result<DerivedExecutionReport, DerivedJobExecutorError> {
-    execute_derived_job_once_internal(gateway, planner, job_id, Some(settings))
+    execute_derived_job_once_internal(
+        gateway,
+        planner,
+        job_id,
+        &JobExecutionContext {
+            settings: Some(settings),
+            progress: &NoopProgressSink,
+            journal: &NoopDerivedJobJournal,
+            cancellation: &CancellationToken::new(),
+            retry_policies: GatewayRetryPolicies::default(),
+            leases: None,
+        },
+    )
 }
 
-fn execute_derived_job_once_internal<
+pub fn execute_derived_job_once_with_progress<
     G: DerivedProcessingGateway + ?Sized,
     P: DerivedExecutionPlanner + ?Sized,
 >(
     gateway: &G,
     planner: &P,
     job_id: &str,
-    settings: Option<&AgentRuntimeConfig>,
+    settings: &AgentRuntimeConfig,
+    progress: &dyn DerivedJobProgressSink,
+    journal: &dyn DerivedJobJournal,
+    cancellation: &CancellationToken,
 ) -> Result<DerivedExecutionReport, DerivedJobExecutorError> {
-    let mut claimed = gateway
+    execute_derived_job_once_internal(
+        gateway,
+        planner,
+        job_id,
+        &JobExecutionContext {
+            settings: Some(settings),
+            progress,
+            journal,
+            cancellation,
+            retry_policies: GatewayRetryPolicies::default(),
+            leases: None,
+        },
+    )
+}
+
+pub(crate) fn execute_derived_job_once_internal<
+    G: DerivedProcessingGateway + ?Sized,
+    P: DerivedExecutionPlanner + ?Sized,
+>(
+    gateway: &G,
+    planner: &P,
+    job_id: &str,
+    context: &JobExecutionContext<'_>,
+) -> Result<DerivedExecutionReport, DerivedJobExecutorError> {
+    let JobExecutionContext {
+        progress,
+        journal,
+        cancellation,
+        leases,
+        ..
+    } = *context;
+    if let Some(reason) = cancellation.reason() {
+        return Err(DerivedJobExecutorError::Cancelled(reason));
+    }
+    let entry = claim_or_resume_job(gateway, job_id, journal)?;
+    let resumed_staging_dir = entry.staging_dir.clone();
+    let mut claimed = entry.claimed.clone();
+    let mut cursor = JournalCursor { journal, entry };
+    progress.report(RuntimeStatusEvent::JobClaimed {
+        job_id: claimed.job_id.clone(),
+        asset_uuid: claimed.asset_uuid.clone(),
+    });
+    let lease = Arc::new(
+        DerivedJobLease::new(&claimed, LeaseKeeperPolicy::default())
+            .with_cancellation(cancellation.clone()),
+    );
+    if let Some(leases) = leases {
+        leases.register(&claimed, Arc::clone(&lease));
+    }
+    let result = std::thread::scope(|scope| {
+        scope.spawn(|| lease.keep_alive(gateway));
+        let result = execute_claimed_job(gateway, planner, &claimed, &lease, context, &mut cursor);
+        lease.stop();
+        result.map_err(|error| cancellation_error(&lease, error))
+    });
+    if let Some(leases) = leases {
+        leases.unregister(&claimed.job_id);
+    }
+    claimed.fencing_token = lease.fencing_token();
+    let _ = journal.remove(&claimed.job_id);
+    if let Some(staging_dir) = resumed_staging_dir {
+        let _ = std::fs::remove_dir_all(staging_dir);
+    }
+    match result {
+        Ok(upload_count) => {
+            progress.report(RuntimeStatusEvent::JobCompleted {
+                job_id: claimed.job_id.clone(),
+            });
+            Ok(DerivedExecutionReport {
+                job_id: claimed.job_id,
+                asset_uuid: claimed.asset_uuid,
+                upload_count,
+            })
+        }
+        Err(error) => {
+            report_job_failure(gateway, &claimed, &lease, &error);
+            progress.report(RuntimeStatusEvent::JobFailed {
+                job_id: claimed.job_id.clone(),
+                error_code: error.error_code().to_string(),
+            });
+            Err(error)
+        }
+    }
+}
+
+fn cancellation_error(
+    lease: &DerivedJobLease,
+    error: DerivedJobExecutorError,
+) -> DerivedJobExecutorError {
+    if !matches!(
+        error,
+        DerivedJobExecutorError::ProxyGeneration(ProxyGenerationError::Cancelled)
+    ) {
+        return error;
+    }
+    if let Some(lost) = lease.lost_error() {
+        return DerivedJobExecutorError::Gateway(lost);
+    }
+    match lease.cancellation().reason() {
+        Some(reason) => DerivedJobExecutorError::Cancelled(reason),
+        None => error,
+    }
+}
+
+fn claim_or_resume_job<G: DerivedProcessingGateway + ?Sized>(
+    gateway: &G,
+    job_id: &str,
+    journal: &dyn DerivedJobJournal,
+) -> Result<DerivedJobJournalEntry, DerivedJobExecutorError> {
+    if let Ok(Some(mut entry)) = journal.load(job_id) {
+        let claimed = &entry.claimed;
+        match gateway.heartbeat(&claimed.job_id, &claimed.lock_token, claimed.fencing_token) {
+            Ok(receipt) => {
+                entry.claimed.fencing_token = receipt.fencing_token;
+                return Ok(entry);
+            }
+            Err(error) if is_lease_lost(&error) || !error.is_retryable() => {
+                let _ = journal.remove(job_id);
+                if let Some(staging_dir) = entry.staging_dir.as_ref() {
+                    let _ = std::fs::remove_dir_all(staging_dir);
+                }
+            }
+            Err(error) => return Err(DerivedJobExecutorError::Gateway(error)),
+        }
+    }
+    let claimed = gateway
         .claim_job(job_id)
         .map_err(DerivedJobExecutorError::Gateway)?;
-    send_heartbeat(gateway, &mut claimed)?;
-    let staged_source = if let Some(settings) = settings {
-        Some(
-            stage_claimed_job_source(settings, &claimed)
-                .map_err(DerivedJobExecutorError::SourceStaging)?,
-        )
-    } else {
-        None
+    let entry = DerivedJobJournalEntry::claimed(&claimed);
+    let _ = journal.save(&entry);
+    Ok(entry)
+}
+
+struct JournalCursor<'a> {
+    journal: &'a dyn DerivedJobJournal,
+    entry: DerivedJobJournalEntry,
+}
+
+impl JournalCursor<'_> {
+    fn persist(&mut self, lease: &DerivedJobLease) {
+        self.entry.claimed.fencing_token = lease.fencing_token();
+        let _ = self.journal.save(&self.entry);
+    }
+}
+
+fn journaled_plan_is_reusable(entry: &DerivedJobJournalEntry) -> Option<DerivedExecutionPlan> {
+    let plan = entry.plan.as_ref()?;
+    let artifacts_available = plan
+        .uploads
+        .iter()
+        .filter(|upload| !entry.is_upload_completed(upload))
+        .all(|upload| {
+            upload.parts.iter().all(|part| part.chunk_path.is_file())
+                && verify_upload_integrity(upload).is_ok()
+        });
+    artifacts_available.then(|| plan.clone())
+}
+
+fn execute_claimed_job<
+    G: DerivedProcessingGateway + ?Sized,
+    P: DerivedExecutionPlanner + ?Sized,
+>(
+    gateway: &G,
+    planner: &P,
+    claimed: &ClaimedDerivedJob,
+    lease: &DerivedJobLease,
+    context: &JobExecutionContext<'_>,
+    cursor: &mut JournalCursor<'_>,
+) -> Result<usize, DerivedJobExecutorError> {
+    let JobExecutionContext {
+        settings, progress, ..
+    } = *context;
+    let retrier = GatewayRetrier {
+        lease,
+        policies: &context.retry_policies,
+        jitter_seed: retry_jitter_seed(&claimed.job_id),
+    };
+    let report_stage = |stage: JobStage, progress_percent: u8, short_status: String| {
+        progress.report(RuntimeStatusEvent::JobProgress {
+            job_id: claimed.job_id.clone(),
+            asset_uuid: claimed.asset_uuid.clone(),
+            progress_percent,
+            stage,
+            short_status,
+        });
+    };
+    send_heartbeat(gateway, &retrier)?;
+    let mut staged_source = None;
+    let mut plan = match journaled_plan_is_reusable(&cursor.entry) {
+        Some(plan) => {
+            report_stage(
+                JobStage::Processing,
+                PROCESSING_PROGRESS_START,
+                "resuming".to_string(),
+            );
+            plan
+        }
+        None => {
+            report_stage(JobStage::Processing, 5, "staging source".to_string());
+            if let Some(settings) = settings {
+                staged_source = Some(
+                    stage_claimed_job_source(settings, claimed)
+                        .map_err(DerivedJobExecutorError::SourceStaging)?,
+                );
+            }
+            send_heartbeat(gateway, &retrier)?;
+
+            let staged_sidecars: &[PathBuf] = staged_source
+                .as_ref()
+                .map(|staged| staged.sidecar_paths())
+                .unwrap_or(&[]);
+            report_stage(
+                JobStage::Processing,
+                PROCESSING_PROGRESS_START,
+                "processing".to_string(),
+            );
+            let plan = planner.plan_for_claimed_job_with_progress(
+                claimed,
+                staged_source.as_ref().map(|s| s.path()),
+                staged_sidecars,
+                &ProcessingProgress {
+                    claimed,
+                    sink: progress,
+                    cancellation: lease.cancellation(),
+                    last_percent: AtomicU8::new(0),
+                },
+            )?;
+            let staging_dir = staged_source
+                .as_ref()
+                .map(|staged| staged.staging_dir().to_path_buf());
+            cursor.entry.record_plan(&plan, staging_dir);
+            cursor.persist(lease);
+            plan
+        }
     };
-    send_heartbeat(gateway, &mut claimed)?;
-
-    let staged_sidecars: &[PathBuf] = staged_source
-        .as_ref()
-        .map(|staged| staged.sidecar_paths())
-        .unwrap_or(&[]);
-    let plan = planner.plan_for_claimed_job_with_source(
-        &claimed,
-        staged_source.as_ref().map(|s| s.path()),
-        staged_sidecars,
-    )?;
-    let mut plan = plan;
     if plan.submit_idempotency_key.trim().is_empty() {
         return Err(DerivedJobExecutorError::MissingSubmitIdempotencyKey);
     }
-    validate_submit_payload_for_claimed_job(&claimed, &plan.submit)?;
+    validate_submit_payload_for_claimed_job(claimed, &plan.submit)?;
     validate_uploads_against_submit_manifest(&plan)?;
     if !plan.uploads.is_empty() {
         let revision_etag = gateway
@@ -157,7 +554,13 @@ fn execute_derived_job_once_internal<
         }
     }
 
-    for upload in &plan.uploads {
+    let upload_count = plan.uploads.len();
+    for (index, upload) in plan.uploads.iter().enumerate() {
+        report_stage(
+            JobStage::Upload,
+            upload_progress_percent(index, upload_count),
+            format!("uploading {}/{upload_count}", index + 1),
+        );
         if upload.init.asset_uuid != claimed.asset_uuid
             || upload.complete.asset_uuid != claimed.asset_uuid
         {
@@ -168,43 +571,140 @@ fn execute_derived_job_once_internal<
         if upload.init.asset_uuid != upload.complete.asset_uuid {
             return Err(DerivedJobExecutorError::UploadInitCompleteAssetMismatch);
         }
+        if cursor.entry.is_upload_completed(upload) {
+            continue;
+        }
 
-        send_heartbeat(gateway, &mut claimed)?;
-        gateway
-            .upload_init(&upload.init)
-            .map_err(DerivedJobExecutorError::Gateway)?;
+        send_heartbeat(gateway, &retrier)?;
+        retrier.call(GatewayCall::UploadInit, || {
+            gateway.upload_init(&upload.init)
+        })?;
         let mut completed_parts = Vec::with_capacity(upload.parts.len());
         for part in &upload.parts {
-            send_heartbeat(gateway, &mut claimed)?;
-            let uploaded_part = gateway
-                .upload_part(part)
-                .map_err(DerivedJobExecutorError::Gateway)?;
+            let journaled = part.sha256.as_deref().and_then(|sha256| {
+                cursor.entry.completed_part(
+                    &upload.complete.upload_id,
+                    part.part_number,
+                    Some(sha256),
+                )
+            });
+            let uploaded_part = match journaled {
+                Some(uploaded_part) => uploaded_part.clone(),
+                None => {
+                    ensure_lease_held(lease)?;
+                    let uploaded_part =
+                        retrier.call(GatewayCall::UploadPart, || gateway.upload_part(part))?;
+                    cursor.entry.record_part(
+                        &upload.complete.upload_id,
+                        &uploaded_part,
+                        part.sha256.as_deref(),
+                    );
+                    cursor.persist(lease);
+                    uploaded_part
+                }
+            };
             completed_parts.push(uploaded_part);
         }
+        verify_upload_integrity(upload).map_err(DerivedJobExecutorError::Integrity)?;
         let mut complete = upload.complete.clone();
         complete.parts = Some(completed_parts);
-        send_heartbeat(gateway, &mut claimed)?;
-        gateway
-            .upload_complete(&complete)
-            .map_err(DerivedJobExecutorError::Gateway)?;
+        send_heartbeat(gateway, &retrier)?;
+        retrier.call(GatewayCall::UploadComplete, || {
+            gateway.upload_complete(&complete)
+        })?;
+        cursor.entry.record_upload_completed(upload);
+        cursor.persist(lease);
     }
 
-    send_heartbeat(gateway, &mut claimed)?;
-    gateway
-        .submit_derived(
+    report_stage(JobStage::Submit, 95, "submitting".to_string());
+    send_heartbeat(gateway, &retrier)?;
+    cursor.entry.stage = DerivedJobJournalStage::Submitting;
+    cursor.persist(lease);
+    retrier.call(GatewayCall::Submit, || {
+        gateway.submit_derived(
             &claimed.job_id,
             &claimed.lock_token,
-            claimed.fencing_token,
+            lease.fencing_token(),
             &plan.submit_idempotency_key,
             &plan.submit,
         )
-        .map_err(DerivedJobExecutorError::Gateway)?;
+    })?;
 
-    Ok(DerivedExecutionReport {
-        job_id: claimed.job_id,
-        asset_uuid: claimed.asset_uuid,
-        upload_count: plan.uploads.len(),
-    })
+    Ok(upload_count)
+}
+
+fn upload_progress_percent(index: usize, upload_count: usize) -> u8 {
+    let span = 30 * index / upload_count.max(1);
+    (usize::from(PROCESSING_PROGRESS_END) + span).min(90) as u8
+}
+
+struct GatewayRetrier<'a> {
+    lease: &'a DerivedJobLease,
+    policies: &'a GatewayRetryPolicies,
+    jitter_seed: u64,
+}
+
+impl GatewayRetrier<'_> {
+    fn call<T>(
+        &self,
+        call: GatewayCall,
+        operation: impl FnMut() -> Result<T, DerivedProcessingError>,
+    ) -> Result<T, DerivedJobExecutorError> {
+        retry_gateway_call(
+            &self.policies.for_call(call),
+            self.jitter_seed,
+            self.lease.deadline(),
+            self.lease.cancellation(),
+            operation,
+        )
+        .map_err(|error| match self.lease.cancellation().reason() {
+            Some(reason) if reason != CancellationReason::LeaseLost => {
+                DerivedJobExecutorError::Cancelled(reason)
+            }
+            _ => DerivedJobExecutorError::Gateway(error),
+        })
+    }
+}
+
+fn retry_jitter_seed(job_id: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    job_id.hash(&mut hasher);
+    hasher.finish()
+}
+
+// Scoped to the claim so a re-claimed job does not replay the previous fail.
+pub(crate) fn fail_idempotency_key(claimed: &ClaimedDerivedJob) -> String {
+    format!("agent-fail-{}-{}", claimed.job_id, claimed.lock_token)
+}
+
+fn report_job_failure<G: DerivedProcessingGateway + ?Sized>(
+    gateway: &G,
+    claimed: &ClaimedDerivedJob,
+    lease: &DerivedJobLease,
+    error: &DerivedJobExecutorError,
+) {
+    // Core already took the lease back: a fail would be rejected, or worse,
+    // land on the next holder's claim.
+    let lease_lost = lease.lost_error().is_some()
+        || lease.cancellation().reason() == Some(CancellationReason::LeaseLost)
+        || matches!(error, DerivedJobExecutorError::Gateway(error) if is_lease_lost(error));
+    if lease_lost {
+        return;
+    }
+    if let Err(fail_error) = gateway.fail_job(
+        &claimed.job_id,
+        &claimed.lock_token,
+        claimed.fencing_token,
+        &fail_idempotency_key(claimed),
+        &error.job_failure(),
+    ) {
+        tracing::warn!(
+            job_id = %claimed.job_id,
+            error_code = error.error_code(),
+            fail_error = %fail_error,
+            "could not report job failure to Core"
+        );
+    }
 }
 
 fn validate_submit_payload_for_claimed_job(
@@ -234,6 +734,7 @@ fn validate_submit_payload_for_claimed_job(
             for item in &submit.manifest {
                 match item.kind {
                     DerivedKind::PreviewVideo
+                    | DerivedKind::PreviewVideoHls
                     | DerivedKind::PreviewAudio
                     | DerivedKind::PreviewPhoto => {}
                     kind => {
@@ -252,7 +753,10 @@ fn validate_submit_payload_for_claimed_job(
                 ));
             }
             for item in &submit.manifest {
-                if item.kind != DerivedKind::Thumb {
+                if !matches!(
+                    item.kind,
+                    DerivedKind::Thumb | DerivedKind::ThumbSprite | DerivedKind::ThumbVtt
+                ) {
                     return Err(DerivedJobExecutorError::IncompatibleDerivedKindForJobType {
                         job_type: DerivedJobType::GenerateThumbnails,
                         kind: item.kind,
@@ -287,15 +791,30 @@ fn validate_submit_payload_for_claimed_job(
 
 fn send_heartbeat<G: DerivedProcessingGateway + ?Sized>(
     gateway: &G,
-    claimed: &mut ClaimedDerivedJob,
+    retrier: &GatewayRetrier<'_>,
 ) -> Result<(), DerivedJobExecutorError> {
-    let receipt = gateway
-        .heartbeat(&claimed.job_id, &claimed.lock_token, claimed.fencing_token)
-        .map_err(DerivedJobExecutorError::Gateway)?;
-    claimed.fencing_token = receipt.fencing_token;
+    let lease = retrier.lease;
+    if let Some(reason) = lease.cancellation().reason()
+        && reason != CancellationReason::LeaseLost
+    {
+        return Err(DerivedJobExecutorError::Cancelled(reason));
+    }
+    retrier.call(GatewayCall::Heartbeat, || lease.renew(gateway))?;
     Ok(())
 }
 
+// Parts only check the lease locally: the background keeper renews it and
+// cancels the job as soon as Core takes it back.
+fn ensure_lease_held(lease: &DerivedJobLease) -> Result<(), DerivedJobExecutorError> {
+    lease
+        .ensure_held()
+        .map_err(DerivedJobExecutorError::Gateway)?;
+    match lease.cancellation().reason() {
+        Some(reason) => Err(DerivedJobExecutorError::Cancelled(reason)),
+        None => Ok(()),
+    }
+}
+
 fn validate_uploads_against_submit_manifest(
     plan: &DerivedExecutionPlan,
 ) -> Result<(), DerivedJobExecutorError> {
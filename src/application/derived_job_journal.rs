@@ -0,0 +1,157 @@
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::application::derived_job_executor::{DerivedExecutionPlan, DerivedUploadPlan};
+use crate::application::derived_processing_gateway::{ClaimedDerivedJob, UploadedDerivedPart};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DerivedJobJournalStage {
+    Claimed,
+    Planned,
+    Uploading,
+    Submitting,
+}
+
+impl DerivedJobJournalStage {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Claimed => "claimed",
+            Self::Planned => "planned",
+            Self::Uploading => "uploading",
+            Self::Submitting => "submitting",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JournaledUploadPart {
+    pub upload_id: String,
+    pub part: UploadedDerivedPart,
+    pub sha256: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JournaledUpload {
+    pub upload_id: String,
+    pub sha256: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DerivedJobJournalEntry {
+    pub claimed: ClaimedDerivedJob,
+    pub stage: DerivedJobJournalStage,
+    pub staging_dir: Option<PathBuf>,
+    pub plan: Option<DerivedExecutionPlan>,
+    pub completed_parts: Vec<JournaledUploadPart>,
+    pub completed_uploads: Vec<JournaledUpload>,
+}
+
+impl DerivedJobJournalEntry {
+    pub fn claimed(claimed: &ClaimedDerivedJob) -> Self {
+        Self {
+            claimed: claimed.clone(),
+            stage: DerivedJobJournalStage::Claimed,
+            staging_dir: None,
+            plan: None,
+            completed_parts: Vec::new(),
+            completed_uploads: Vec::new(),
+        }
+    }
+
+    pub fn record_plan(&mut self, plan: &DerivedExecutionPlan, staging_dir: Option<PathBuf>) {
+        self.stage = DerivedJobJournalStage::Planned;
+        self.plan = Some(plan.clone());
+        self.staging_dir = staging_dir;
+        self.completed_parts.clear();
+        self.completed_uploads.clear();
+    }
+
+    pub fn record_part(
+        &mut self,
+        upload_id: &str,
+        part: &UploadedDerivedPart,
+        sha256: Option<&str>,
+    ) {
+        self.stage = DerivedJobJournalStage::Uploading;
+        self.completed_parts.retain(|existing| {
+            existing.upload_id != upload_id || existing.part.part_number != part.part_number
+        });
+        self.completed_parts.push(JournaledUploadPart {
+            upload_id: upload_id.to_string(),
+            part: part.clone(),
+            sha256: sha256.map(str::to_string),
+        });
+    }
+
+    pub fn record_upload_completed(&mut self, upload: &DerivedUploadPlan) {
+        self.stage = DerivedJobJournalStage::Uploading;
+        self.completed_uploads.push(JournaledUpload {
+            upload_id: upload.complete.upload_id.clone(),
+            sha256: upload.init.sha256.clone(),
+        });
+    }
+
+    pub fn completed_part(
+        &self,
+        upload_id: &str,
+        part_number: u32,
+        sha256: Option<&str>,
+    ) -> Option<&UploadedDerivedPart> {
+        self.completed_parts
+            .iter()
+            .find(|existing| {
+                existing.upload_id == upload_id
+                    && existing.part.part_number == part_number
+                    && existing.sha256.as_deref() == sha256
+            })
+            .map(|existing| &existing.part)
+    }
+
+    pub fn is_upload_completed(&self, upload: &DerivedUploadPlan) -> bool {
+        self.completed_uploads.iter().any(|existing| {
+            existing.upload_id == upload.complete.upload_id && existing.sha256 == upload.init.sha256
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum DerivedJobJournalError {
+    #[error("job journal storage error: {0}")]
+    Storage(String),
+    #[error("job journal entry is corrupted: {0}")]
+    Corrupted(String),
+}
+
+pub trait DerivedJobJournal: Send + Sync {
+    fn load(&self, job_id: &str) -> Result<Option<DerivedJobJournalEntry>, DerivedJobJournalError>;
+    fn save(&self, entry: &DerivedJobJournalEntry) -> Result<(), DerivedJobJournalError>;
+    fn remove(&self, job_id: &str) -> Result<(), DerivedJobJournalError>;
+    fn pending_job_ids(&self) -> Result<Vec<String>, DerivedJobJournalError>;
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NoopDerivedJobJournal;
+
+impl DerivedJobJournal for NoopDerivedJobJournal {
+    fn load(
+        &self,
+        _job_id: &str,
+    ) -> Result<Option<DerivedJobJournalEntry>, DerivedJobJournalError> {
+        Ok(None)
+    }
+
+    fn save(&self, _entry: &DerivedJobJournalEntry) -> Result<(), DerivedJobJournalError> {
+        Ok(())
+    }
+
+    fn remove(&self, _job_id: &str) -> Result<(), DerivedJobJournalError> {
+        Ok(())
+    }
+
+    fn pending_job_ids(&self) -> Result<Vec<String>, DerivedJobJournalError> {
+        Ok(Vec::new())
+    }
+}
@@ -0,0 +1,268 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+use backon::{BackoffBuilder, ExponentialBackoff, ExponentialBuilder};
+use chrono::{DateTime, Utc};
+
+use crate::application::derived_processing_gateway::{
+    ClaimedDerivedJob, DerivedProcessingError, DerivedProcessingGateway, HeartbeatReceipt,
+};
+use crate::application::job_cancellation::{CancellationReason, CancellationToken};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LeaseKeeperPolicy {
+    pub fallback_interval: Duration,
+    pub min_interval: Duration,
+    pub max_interval: Duration,
+}
+
+impl Default for LeaseKeeperPolicy {
+    fn default() -> Self {
+        Self {
+            fallback_interval: Duration::from_secs(30),
+            min_interval: Duration::from_secs(1),
+            max_interval: Duration::from_secs(60),
+        }
+    }
+}
+
+impl LeaseKeeperPolicy {
+    pub fn next_renewal_delay(&self, locked_until: Option<&str>, now: DateTime<Utc>) -> Duration {
+        let remaining = locked_until
+            .and_then(|value| DateTime::parse_from_rfc3339(value).ok())
+            .map(|deadline| {
+                (deadline.with_timezone(&Utc) - now)
+                    .to_std()
+                    .unwrap_or(Duration::ZERO)
+            });
+        let delay = match remaining {
+            Some(remaining) => remaining / 2,
+            None => self.fallback_interval,
+        };
+        delay.clamp(self.min_interval, self.max_interval)
+    }
+
+    // Failed renewals back off from `min_interval` up to `max_interval`.
+    pub fn retry_backoff(&self) -> ExponentialBackoff {
+        ExponentialBuilder::default()
+            .with_factor(2.0)
+            .with_min_delay(self.min_interval)
+            .with_max_delay(self.max_interval)
+            .without_max_times()
+            .build()
+    }
+}
+
+#[derive(Debug)]
+struct LeaseState {
+    fencing_token: i32,
+    locked_until: Option<String>,
+    next_renewal_at: Instant,
+    retry_backoff: Option<ExponentialBackoff>,
+    lost: Option<DerivedProcessingError>,
+    stopped: bool,
+}
+
+#[derive(Debug)]
+pub struct DerivedJobLease {
+    job_id: String,
+    lock_token: String,
+    policy: LeaseKeeperPolicy,
+    state: Mutex<LeaseState>,
+    wake: Condvar,
+    renewing: Mutex<()>,
+    cancellation: CancellationToken,
+}
+
+impl DerivedJobLease {
+    pub fn new(claimed: &ClaimedDerivedJob, policy: LeaseKeeperPolicy) -> Self {
+        Self {
+            job_id: claimed.job_id.clone(),
+            lock_token: claimed.lock_token.clone(),
+            policy,
+            state: Mutex::new(LeaseState {
+                fencing_token: claimed.fencing_token,
+                locked_until: None,
+                next_renewal_at: Instant::now() + policy.fallback_interval,
+                retry_backoff: None,
+                lost: None,
+                stopped: false,
+            }),
+            wake: Condvar::new(),
+            renewing: Mutex::new(()),
+            cancellation: CancellationToken::new(),
+        }
+    }
+
+    pub fn with_cancellation(mut self, cancellation: CancellationToken) -> Self {
+        self.cancellation = cancellation;
+        self
+    }
+
+    pub fn cancellation(&self) -> &CancellationToken {
+        &self.cancellation
+    }
+
+    pub fn fencing_token(&self) -> i32 {
+        self.lock_state().fencing_token
+    }
+
+    pub fn locked_until(&self) -> Option<String> {
+        self.lock_state().locked_until.clone()
+    }
+
+    pub fn deadline(&self) -> Option<Instant> {
+        let locked_until = self.locked_until()?;
+        let deadline = DateTime::parse_from_rfc3339(&locked_until).ok()?;
+        let remaining = (deadline.with_timezone(&Utc) - Utc::now())
+            .to_std()
+            .unwrap_or(Duration::ZERO);
+        Some(Instant::now() + remaining)
+    }
+
+    pub fn lost_error(&self) -> Option<DerivedProcessingError> {
+        self.lock_state().lost.clone()
+    }
+
+    pub fn ensure_held(&self) -> Result<(), DerivedProcessingError> {
+        match self.lost_error() {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
+    }
+
+    pub fn renew<G: DerivedProcessingGateway + ?Sized>(
+        &self,
+        gateway: &G,
+    ) -> Result<HeartbeatReceipt, DerivedProcessingError> {
+        let _renewing = self
+            .renewing
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        self.ensure_held()?;
+        let fencing_token = self.fencing_token();
+        match gateway.heartbeat(&self.job_id, &self.lock_token, fencing_token) {
+            Ok(receipt) => {
+                let delay = self
+                    .policy
+                    .next_renewal_delay(receipt.locked_until.as_deref(), Utc::now());
+                let mut state = self.lock_state();
+                state.fencing_token = receipt.fencing_token;
+                state.locked_until = receipt.locked_until.clone();
+                state.next_renewal_at = Instant::now() + delay;
+                state.retry_backoff = None;
+                drop(state);
+                self.wake.notify_all();
+                Ok(receipt)
+            }
+            Err(error) => {
+                if is_lease_lost(&error) {
+                    self.lock_state().lost = Some(error.clone());
+                    self.cancellation.cancel(CancellationReason::LeaseLost);
+                    self.wake.notify_all();
+                }
+                Err(error)
+            }
+        }
+    }
+
+    pub fn keep_alive<G: DerivedProcessingGateway + ?Sized>(&self, gateway: &G) {
+        loop {
+            {
+                let mut state = self.lock_state();
+                loop {
+                    if state.stopped || state.lost.is_some() {
+                        return;
+                    }
+                    let now = Instant::now();
+                    if now >= state.next_renewal_at {
+                        break;
+                    }
+                    let timeout = state.next_renewal_at - now;
+                    state = self
+                        .wake
+                        .wait_timeout(state, timeout)
+                        .unwrap_or_else(|poisoned| poisoned.into_inner())
+                        .0;
+                }
+            }
+            if self.renew(gateway).is_err() {
+                let deadline = self.deadline();
+                let mut state = self.lock_state();
+                if state.lost.is_none() {
+                    let policy = self.policy;
+                    let delay = state
+                        .retry_backoff
+                        .get_or_insert_with(|| policy.retry_backoff())
+                        .next()
+                        .unwrap_or(policy.max_interval);
+                    let now = Instant::now();
+                    // Never back off past the lease expiry: one last attempt is
+                    // made while the lock may still be held.
+                    state.next_renewal_at = match deadline {
+                        Some(deadline) if deadline > now => (now + delay).min(deadline),
+                        _ => now + delay,
+                    };
+                }
+            }
+        }
+    }
+
+    pub fn stop(&self) {
+        self.lock_state().stopped = true;
+        self.wake.notify_all();
+    }
+
+    fn lock_state(&self) -> std::sync::MutexGuard<'_, LeaseState> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+// Leases of the jobs in flight, so a pool can release the claim a stuck
+// worker still holds with the fencing token of its latest renewal.
+#[derive(Debug, Default)]
+pub(crate) struct DerivedJobLeaseRegistry {
+    leases: Mutex<HashMap<String, (ClaimedDerivedJob, Arc<DerivedJobLease>)>>,
+}
+
+impl DerivedJobLeaseRegistry {
+    pub(crate) fn register(&self, claimed: &ClaimedDerivedJob, lease: Arc<DerivedJobLease>) {
+        self.lock_leases()
+            .insert(claimed.job_id.clone(), (claimed.clone(), lease));
+    }
+
+    pub(crate) fn unregister(&self, job_id: &str) {
+        self.lock_leases().remove(job_id);
+    }
+
+    // `None` once Core took the lease back: failing it would hit the next holder.
+    pub(crate) fn live_claim(&self, job_id: &str) -> Option<ClaimedDerivedJob> {
+        let leases = self.lock_leases();
+        let (claimed, lease) = leases.get(job_id)?;
+        if lease.lost_error().is_some() {
+            return None;
+        }
+        Some(ClaimedDerivedJob {
+            fencing_token: lease.fencing_token(),
+            ..claimed.clone()
+        })
+    }
+
+    fn lock_leases(
+        &self,
+    ) -> std::sync::MutexGuard<'_, HashMap<String, (ClaimedDerivedJob, Arc<DerivedJobLease>)>> {
+        self.leases
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+pub fn is_lease_lost(error: &DerivedProcessingError) -> bool {
+    matches!(
+        error,
+        DerivedProcessingError::StaleLockToken | DerivedProcessingError::LockInvalid
+    )
+}
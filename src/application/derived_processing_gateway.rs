@@ -1,10 +1,15 @@
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
 
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use thiserror::Error;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum DerivedJobType {
     ExtractFacts,
     GeneratePreview,
@@ -13,12 +18,16 @@ pub enum DerivedJobType {
     TranscribeAudio,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum DerivedKind {
     PreviewVideo,
+    PreviewVideoHls,
     PreviewAudio,
     PreviewPhoto,
     Thumb,
+    ThumbSprite,
+    ThumbVtt,
     Waveform,
 }
 
@@ -26,9 +35,12 @@ impl DerivedKind {
     pub fn as_str(self) -> &'static str {
         match self {
             Self::PreviewVideo => "preview_video",
+            Self::PreviewVideoHls => "preview_video_hls",
             Self::PreviewAudio => "preview_audio",
             Self::PreviewPhoto => "preview_photo",
             Self::Thumb => "thumb",
+            Self::ThumbSprite => "thumb_sprite",
+            Self::ThumbVtt => "thumb_vtt",
             Self::Waveform => "waveform",
         }
     }
@@ -37,14 +49,25 @@ impl DerivedKind {
         let value = content_type.trim().to_ascii_lowercase();
         match self {
             Self::PreviewVideo => value == "video/mp4",
+            // Master/variant playlists, fMP4 init segments and media segments.
+            Self::PreviewVideoHls => matches!(
+                value.as_str(),
+                "application/vnd.apple.mpegurl"
+                    | "application/x-mpegurl"
+                    | "video/mp4"
+                    | "video/iso.segment"
+            ),
             Self::PreviewAudio => value == "audio/mp4" || value == "audio/mpeg",
-            Self::PreviewPhoto | Self::Thumb => value == "image/jpeg" || value == "image/webp",
+            Self::PreviewPhoto | Self::Thumb | Self::ThumbSprite => {
+                value == "image/jpeg" || value == "image/webp"
+            }
+            Self::ThumbVtt => value == "text/vtt",
             Self::Waveform => value == "application/json" || value == "application/octet-stream",
         }
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ClaimedDerivedJob {
     pub job_id: String,
     pub asset_uuid: String,
@@ -62,13 +85,13 @@ pub struct HeartbeatReceipt {
     pub fencing_token: i32,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct UploadedDerivedPart {
     pub part_number: u32,
     pub part_etag: String,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct DerivedManifestItem {
     pub kind: DerivedKind,
     pub reference: String,
@@ -76,7 +99,7 @@ pub struct DerivedManifestItem {
     pub sha256: Option<String>,
 }
 
-#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct FactsPatchPayload {
     pub duration_ms: Option<i32>,
     pub media_format: Option<String>,
@@ -118,7 +141,7 @@ pub struct FactsPatchPayload {
     pub dji_metadata_track_types: Option<Vec<String>>,
 }
 
-#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct TranscriptPatchPayload {
     pub status: Option<String>,
     pub text: Option<String>,
@@ -127,7 +150,7 @@ pub struct TranscriptPatchPayload {
     pub updated_at: Option<String>,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SubmitDerivedPayload {
     pub job_type: DerivedJobType,
     pub manifest: Vec<DerivedManifestItem>,
@@ -137,7 +160,7 @@ pub struct SubmitDerivedPayload {
     pub metrics: Option<HashMap<String, Value>>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct DerivedUploadInit {
     pub asset_uuid: String,
     pub revision_etag: String,
@@ -148,16 +171,64 @@ pub struct DerivedUploadInit {
     pub idempotency_key: String,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct DerivedUploadPart {
     pub asset_uuid: String,
     pub revision_etag: String,
     pub upload_id: String,
     pub part_number: u32,
     pub chunk_path: PathBuf,
+    pub byte_range: Option<Range<u64>>,
+    pub sha256: Option<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+impl DerivedUploadPart {
+    pub fn read_chunk(&self) -> std::io::Result<Vec<u8>> {
+        let Some(range) = self.byte_range.as_ref() else {
+            return std::fs::read(&self.chunk_path);
+        };
+        let mut file = File::open(&self.chunk_path)?;
+        file.seek(SeekFrom::Start(range.start))?;
+        let mut chunk = vec![0_u8; range.end.saturating_sub(range.start) as usize];
+        file.read_exact(&mut chunk)?;
+        Ok(chunk)
+    }
+}
+
+pub fn split_upload_parts(
+    asset_uuid: &str,
+    upload_id: &str,
+    chunk_path: &Path,
+    size_bytes: u64,
+    part_size_bytes: u64,
+) -> Vec<DerivedUploadPart> {
+    let part_size_bytes = part_size_bytes.max(1);
+    let part = |part_number: u32, byte_range: Option<Range<u64>>| DerivedUploadPart {
+        asset_uuid: asset_uuid.to_string(),
+        revision_etag: String::new(),
+        upload_id: upload_id.to_string(),
+        part_number,
+        chunk_path: chunk_path.to_path_buf(),
+        byte_range,
+        sha256: None,
+    };
+    if size_bytes <= part_size_bytes {
+        return vec![part(1, None)];
+    }
+    // Bounds computed in u64 so part sizes above usize::MAX (32-bit targets)
+    // are not truncated.
+    let mut parts = Vec::new();
+    let mut start = 0_u64;
+    while start < size_bytes {
+        let end = start.saturating_add(part_size_bytes).min(size_bytes);
+        let part_number = u32::try_from(parts.len() + 1).unwrap_or(u32::MAX);
+        parts.push(part(part_number, Some(start..end)));
+        start = end;
+    }
+    parts
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct DerivedUploadComplete {
     pub asset_uuid: String,
     pub revision_etag: String,
@@ -166,12 +237,19 @@ pub struct DerivedUploadComplete {
     pub parts: Option<Vec<UploadedDerivedPart>>,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DerivedJobFailure {
+    pub error_code: String,
+    pub retryable: bool,
+    pub message: Option<String>,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Error)]
 pub enum DerivedProcessingError {
     #[error("core API unauthorized")]
     Unauthorized,
     #[error("core API throttled")]
-    Throttled,
+    Throttled { retry_after_ms: Option<u64> },
     #[error("core API returned unexpected status {0}")]
     UnexpectedStatus(u16),
     #[error("core API transport error: {0}")]
@@ -194,9 +272,51 @@ pub enum DerivedProcessingError {
     StaleLockToken,
     #[error("numeric conversion overflow: {0}")]
     NumericOverflow(String),
+    #[error("derived kind not in the Core API contract: {0}")]
+    DerivedKindNotInContract(String),
+}
+
+impl DerivedProcessingError {
+    pub fn error_code(&self) -> &'static str {
+        match self {
+            Self::Unauthorized => "CORE_UNAUTHORIZED",
+            Self::Throttled { .. } => "CORE_THROTTLED",
+            Self::UnexpectedStatus(_) => "CORE_UNEXPECTED_STATUS",
+            Self::Transport(_) => "CORE_TRANSPORT",
+            Self::InvalidDerivedContentType { .. } => "INVALID_DERIVED_CONTENT_TYPE",
+            Self::InvalidDerivedSize(_) => "INVALID_DERIVED_SIZE",
+            Self::NotDerivedJobType(_) => "NOT_DERIVED_JOB_TYPE",
+            Self::MissingLockToken => "MISSING_LOCK_TOKEN",
+            Self::MissingFencingToken => "MISSING_FENCING_TOKEN",
+            Self::LockRequired => "LOCK_REQUIRED",
+            Self::LockInvalid => "LOCK_INVALID",
+            Self::StaleLockToken => "STALE_LOCK_TOKEN",
+            Self::NumericOverflow(_) => "NUMERIC_OVERFLOW",
+            Self::DerivedKindNotInContract(_) => "DERIVED_KIND_NOT_IN_CONTRACT",
+        }
+    }
+
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::Throttled { .. } | Self::Transport(_) => true,
+            Self::UnexpectedStatus(status) => *status >= 500,
+            // Auth and lock errors will not clear up by calling again.
+            Self::Unauthorized
+            | Self::MissingLockToken
+            | Self::MissingFencingToken
+            | Self::LockRequired
+            | Self::LockInvalid
+            | Self::StaleLockToken
+            | Self::InvalidDerivedContentType { .. }
+            | Self::InvalidDerivedSize(_)
+            | Self::NotDerivedJobType(_)
+            | Self::NumericOverflow(_)
+            | Self::DerivedKindNotInContract(_) => false,
+        }
+    }
 }
 
-pub trait DerivedProcessingGateway {
+pub trait DerivedProcessingGateway: Send + Sync {
     fn claim_job(&self, job_id: &str) -> Result<ClaimedDerivedJob, DerivedProcessingError>;
     fn fetch_asset_revision_etag(&self, asset_uuid: &str)
     -> Result<String, DerivedProcessingError>;
@@ -214,6 +334,14 @@ pub trait DerivedProcessingGateway {
         idempotency_key: &str,
         payload: &SubmitDerivedPayload,
     ) -> Result<(), DerivedProcessingError>;
+    fn fail_job(
+        &self,
+        job_id: &str,
+        lock_token: &str,
+        fencing_token: i32,
+        idempotency_key: &str,
+        failure: &DerivedJobFailure,
+    ) -> Result<(), DerivedProcessingError>;
     fn upload_init(&self, request: &DerivedUploadInit) -> Result<(), DerivedProcessingError>;
     fn upload_part(
         &self,
@@ -0,0 +1,163 @@
+use std::time::{Duration, Instant};
+
+use backon::{BackoffBuilder, ExponentialBackoff};
+
+use crate::application::derived_processing_gateway::DerivedProcessingError;
+use crate::application::job_cancellation::CancellationToken;
+use crate::domain::runtime_orchestration::{
+    BASE_BACKOFF_MS, MAX_BACKOFF_MS, throttled_backoff_builder,
+};
+
+const CANCELLATION_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GatewayCall {
+    Heartbeat,
+    UploadInit,
+    UploadPart,
+    UploadComplete,
+    Submit,
+}
+
+impl GatewayCall {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Heartbeat => "heartbeat",
+            Self::UploadInit => "upload_init",
+            Self::UploadPart => "upload_part",
+            Self::UploadComplete => "upload_complete",
+            Self::Submit => "submit",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GatewayRetryPolicy {
+    pub max_attempts: u32,
+    pub min_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl Default for GatewayRetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            min_delay: Duration::from_millis(BASE_BACKOFF_MS),
+            max_delay: Duration::from_millis(MAX_BACKOFF_MS),
+        }
+    }
+}
+
+impl GatewayRetryPolicy {
+    pub fn no_retry() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Self::default()
+        }
+    }
+
+    pub fn backoff(&self, jitter_seed: u64) -> ExponentialBackoff {
+        throttled_backoff_builder(self.min_delay, self.max_delay, jitter_seed)
+            .with_max_times(self.max_attempts.saturating_sub(1) as usize)
+            .build()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GatewayRetryPolicies {
+    pub heartbeat: GatewayRetryPolicy,
+    pub upload_init: GatewayRetryPolicy,
+    pub upload_part: GatewayRetryPolicy,
+    pub upload_complete: GatewayRetryPolicy,
+    pub submit: GatewayRetryPolicy,
+}
+
+impl Default for GatewayRetryPolicies {
+    fn default() -> Self {
+        Self {
+            // The lease keeper renews in the background too, so heartbeats give up sooner.
+            heartbeat: GatewayRetryPolicy {
+                max_attempts: 3,
+                ..GatewayRetryPolicy::default()
+            },
+            upload_init: GatewayRetryPolicy::default(),
+            upload_part: GatewayRetryPolicy::default(),
+            upload_complete: GatewayRetryPolicy::default(),
+            submit: GatewayRetryPolicy::default(),
+        }
+    }
+}
+
+impl GatewayRetryPolicies {
+    pub fn uniform(policy: GatewayRetryPolicy) -> Self {
+        Self {
+            heartbeat: policy,
+            upload_init: policy,
+            upload_part: policy,
+            upload_complete: policy,
+            submit: policy,
+        }
+    }
+
+    pub fn for_call(&self, call: GatewayCall) -> GatewayRetryPolicy {
+        match call {
+            GatewayCall::Heartbeat => self.heartbeat,
+            GatewayCall::UploadInit => self.upload_init,
+            GatewayCall::UploadPart => self.upload_part,
+            GatewayCall::UploadComplete => self.upload_complete,
+            GatewayCall::Submit => self.submit,
+        }
+    }
+}
+
+// Retries `call` on retryable errors. A Retry-After hint replaces the computed
+// backoff and is waited in full, even past `max_delay`; a wait that would run
+// past `deadline` (the lease expiry) gives up instead.
+pub fn retry_gateway_call<T, F>(
+    policy: &GatewayRetryPolicy,
+    jitter_seed: u64,
+    deadline: Option<Instant>,
+    cancellation: &CancellationToken,
+    mut call: F,
+) -> Result<T, DerivedProcessingError>
+where
+    F: FnMut() -> Result<T, DerivedProcessingError>,
+{
+    let mut backoff = policy.backoff(jitter_seed);
+    loop {
+        let error = match call() {
+            Ok(value) => return Ok(value),
+            Err(error) if error.is_retryable() => error,
+            Err(error) => return Err(error),
+        };
+        let Some(backoff_delay) = backoff.next() else {
+            return Err(error);
+        };
+        let delay = match &error {
+            DerivedProcessingError::Throttled {
+                retry_after_ms: Some(retry_after_ms),
+            } => Duration::from_millis(*retry_after_ms),
+            _ => backoff_delay,
+        };
+        if deadline.is_some_and(|deadline| Instant::now() + delay >= deadline) {
+            return Err(error);
+        }
+        if !sleep_unless_cancelled(delay, cancellation) {
+            return Err(error);
+        }
+    }
+}
+
+fn sleep_unless_cancelled(delay: Duration, cancellation: &CancellationToken) -> bool {
+    let wake_at = Instant::now() + delay;
+    loop {
+        if cancellation.is_cancelled() {
+            return false;
+        }
+        let now = Instant::now();
+        if now >= wake_at {
+            return true;
+        }
+        std::thread::sleep((wake_at - now).min(CANCELLATION_POLL_INTERVAL));
+    }
+}
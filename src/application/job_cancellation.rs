@@ -0,0 +1,54 @@
+use std::sync::{Arc, Mutex};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CancellationReason {
+    LeaseLost,
+    Paused,
+    Stopped,
+    ShutdownDeadline,
+}
+
+impl CancellationReason {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::LeaseLost => "lease_lost",
+            Self::Paused => "paused",
+            Self::Stopped => "stopped",
+            Self::ShutdownDeadline => "shutdown_deadline",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    reason: Arc<Mutex<Option<CancellationReason>>>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self, reason: CancellationReason) -> bool {
+        let mut current = self
+            .reason
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if current.is_some() {
+            return false;
+        }
+        *current = Some(reason);
+        true
+    }
+
+    pub fn reason(&self) -> Option<CancellationReason> {
+        *self
+            .reason
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.reason().is_some()
+    }
+}
@@ -0,0 +1,142 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
+
+use crate::application::core_api_gateway::{CoreJobState, CoreJobView};
+use crate::application::derived_processing_gateway::DerivedJobType;
+use crate::domain::capabilities::has_required_capabilities;
+use crate::domain::configuration::JobSelectionStrategy;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct JobSelectionContext {
+    pub declared_capabilities: BTreeSet<String>,
+    pub running_job_ids: BTreeSet<String>,
+    pub busy_asset_uuids: BTreeSet<String>,
+}
+
+pub trait JobSelectionPolicy: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    // Orders eligible pending jobs, most wanted first.
+    fn rank(&self, candidates: Vec<CoreJobView>, context: &JobSelectionContext)
+    -> Vec<CoreJobView>;
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct FifoSelection;
+
+impl JobSelectionPolicy for FifoSelection {
+    fn name(&self) -> &'static str {
+        JobSelectionStrategy::Fifo.as_str()
+    }
+
+    fn rank(
+        &self,
+        candidates: Vec<CoreJobView>,
+        _context: &JobSelectionContext,
+    ) -> Vec<CoreJobView> {
+        candidates
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct JobTypePrioritySelection;
+
+impl JobSelectionPolicy for JobTypePrioritySelection {
+    fn name(&self) -> &'static str {
+        JobSelectionStrategy::JobTypePriority.as_str()
+    }
+
+    fn rank(
+        &self,
+        mut candidates: Vec<CoreJobView>,
+        _context: &JobSelectionContext,
+    ) -> Vec<CoreJobView> {
+        candidates.sort_by_key(|job| job_type_priority(job.job_type));
+        candidates
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct AssetFairSelection;
+
+impl JobSelectionPolicy for AssetFairSelection {
+    fn name(&self) -> &'static str {
+        JobSelectionStrategy::AssetFair.as_str()
+    }
+
+    fn rank(
+        &self,
+        candidates: Vec<CoreJobView>,
+        context: &JobSelectionContext,
+    ) -> Vec<CoreJobView> {
+        let mut asset_order = Vec::new();
+        let mut per_asset: BTreeMap<String, Vec<CoreJobView>> = BTreeMap::new();
+        for job in JobTypePrioritySelection.rank(candidates, context) {
+            if !per_asset.contains_key(&job.asset_uuid) {
+                asset_order.push(job.asset_uuid.clone());
+            }
+            per_asset
+                .entry(job.asset_uuid.clone())
+                .or_default()
+                .push(job);
+        }
+        // Assets already being processed go last so idle assets get a turn first.
+        asset_order.sort_by_key(|asset_uuid| context.busy_asset_uuids.contains(asset_uuid));
+
+        let mut queues = asset_order
+            .into_iter()
+            .filter_map(|asset_uuid| per_asset.remove(&asset_uuid))
+            .map(Vec::into_iter)
+            .collect::<Vec<_>>();
+        let mut ranked = Vec::new();
+        loop {
+            let before = ranked.len();
+            ranked.extend(queues.iter_mut().filter_map(Iterator::next));
+            if ranked.len() == before {
+                return ranked;
+            }
+        }
+    }
+}
+
+pub fn job_selection_policy_for(strategy: JobSelectionStrategy) -> Arc<dyn JobSelectionPolicy> {
+    match strategy {
+        JobSelectionStrategy::Fifo => Arc::new(FifoSelection),
+        JobSelectionStrategy::JobTypePriority => Arc::new(JobTypePrioritySelection),
+        JobSelectionStrategy::AssetFair => Arc::new(AssetFairSelection),
+    }
+}
+
+pub fn job_type_priority(job_type: Option<DerivedJobType>) -> u8 {
+    match job_type {
+        Some(DerivedJobType::ExtractFacts) => 0,
+        Some(DerivedJobType::GenerateThumbnails) => 1,
+        Some(DerivedJobType::GenerateAudioWaveform) => 2,
+        Some(DerivedJobType::GeneratePreview) => 3,
+        Some(DerivedJobType::TranscribeAudio) => 4,
+        None => 5,
+    }
+}
+
+pub fn select_pending_jobs(
+    policy: &dyn JobSelectionPolicy,
+    jobs: Vec<CoreJobView>,
+    context: &JobSelectionContext,
+    slots: usize,
+) -> Vec<CoreJobView> {
+    if slots == 0 {
+        return Vec::new();
+    }
+    let candidates = jobs
+        .into_iter()
+        .filter(|job| matches!(job.state, CoreJobState::Pending))
+        .filter(|job| !context.running_job_ids.contains(&job.job_id))
+        // A job we can only partially serve would fail after claim; leave it to another agent.
+        .filter(|job| {
+            has_required_capabilities(&job.required_capabilities, &context.declared_capabilities)
+        })
+        .collect::<Vec<_>>();
+    let mut selected = policy.rank(candidates, context);
+    selected.truncate(slots);
+    selected
+}
@@ -1,19 +1,31 @@
 pub mod agent_registration;
 pub mod agent_runtime_app;
+pub mod audio_waveform;
 pub mod config_repository;
 pub mod core_api_gateway;
 pub mod daemon_manager;
+pub mod derived_integrity;
 pub mod derived_job_executor;
+pub mod derived_job_journal;
+pub mod derived_lease_keeper;
 pub mod derived_processing_gateway;
+pub mod gateway_retry;
+pub mod job_cancellation;
+pub mod job_selection;
 pub mod notification_bridge;
+pub mod offline_processing;
 pub mod proxy_generator;
 pub mod runtime_cli_shell;
 pub mod runtime_derived_planner;
 pub mod runtime_desktop_shell_controller;
 pub mod runtime_gui_shell;
+pub mod runtime_job_pool;
 pub mod runtime_job_worker;
 pub mod runtime_loop_engine;
 pub mod runtime_poll_cycle;
 pub mod runtime_session;
 pub mod runtime_sync_coordinator;
+pub mod shutdown_drain;
 pub mod source_staging;
+pub mod thumbnail_sprite;
+pub mod video_storyboard;
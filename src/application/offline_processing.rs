@@ -0,0 +1,146 @@
+use std::path::{Path, PathBuf};
+
+use thiserror::Error;
+
+use crate::application::derived_job_executor::{DerivedExecutionPlanner, DerivedJobExecutorError};
+use crate::application::derived_processing_gateway::{
+    ClaimedDerivedJob, DerivedJobType, SubmitDerivedPayload,
+};
+
+pub const OFFLINE_SUBMIT_PAYLOAD_FILE_NAME: &str = "submit.json";
+pub const OFFLINE_JOB_ID: &str = "offline-local-job";
+pub const OFFLINE_ASSET_UUID: &str = "00000000-0000-0000-0000-000000000000";
+pub const OFFLINE_STORAGE_ID: &str = "local";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OfflineProcessRequest {
+    pub source_path: PathBuf,
+    pub sidecar_paths: Vec<PathBuf>,
+    pub job_type: DerivedJobType,
+    pub output_dir: PathBuf,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct OfflineProcessReport {
+    pub claimed: ClaimedDerivedJob,
+    pub artifact_paths: Vec<PathBuf>,
+    pub submit_payload_path: PathBuf,
+    pub submit: SubmitDerivedPayload,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum OfflineProcessError {
+    #[error("source path is not a regular file: {0}")]
+    SourceNotFile(String),
+    #[error("offline processing io failed: {0}")]
+    Io(String),
+    #[error("planner failed: {0}")]
+    Planner(DerivedJobExecutorError),
+    #[error("unable to serialize submit payload: {0}")]
+    Serialize(String),
+}
+
+pub fn synthetic_claimed_job(
+    source_relative: &str,
+    sidecars_relative: Vec<String>,
+    job_type: DerivedJobType,
+) -> ClaimedDerivedJob {
+    ClaimedDerivedJob {
+        job_id: OFFLINE_JOB_ID.to_string(),
+        asset_uuid: OFFLINE_ASSET_UUID.to_string(),
+        lock_token: "offline".to_string(),
+        fencing_token: 0,
+        job_type,
+        source_storage_id: OFFLINE_STORAGE_ID.to_string(),
+        source_original_relative: source_relative.to_string(),
+        source_sidecars_relative: sidecars_relative,
+    }
+}
+
+pub fn run_offline_processing<P: DerivedExecutionPlanner + ?Sized>(
+    planner: &P,
+    request: &OfflineProcessRequest,
+) -> Result<OfflineProcessReport, OfflineProcessError> {
+    let source_name = regular_file_name(&request.source_path)?;
+    let sidecar_names = request
+        .sidecar_paths
+        .iter()
+        .map(|path| regular_file_name(path))
+        .collect::<Result<Vec<_>, _>>()?;
+    std::fs::create_dir_all(&request.output_dir)
+        .map_err(|error| OfflineProcessError::Io(error.to_string()))?;
+
+    // The planner writes artifacts next to the source, so it works on a scratch
+    // copy inside the output directory and never touches the original folder.
+    let staging_dir = tempfile::Builder::new()
+        .prefix(".retaia-offline-")
+        .tempdir_in(&request.output_dir)
+        .map_err(|error| OfflineProcessError::Io(error.to_string()))?;
+    let staged_source = stage_file(&request.source_path, &source_name, staging_dir.path())?;
+    let staged_sidecars = request
+        .sidecar_paths
+        .iter()
+        .zip(&sidecar_names)
+        .map(|(path, name)| stage_file(path, name, staging_dir.path()))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let claimed = synthetic_claimed_job(&source_name, sidecar_names, request.job_type);
+    let plan = planner
+        .plan_for_claimed_job_with_source(&claimed, Some(&staged_source), &staged_sidecars)
+        .map_err(OfflineProcessError::Planner)?;
+
+    let mut artifact_paths: Vec<PathBuf> = Vec::new();
+    for part in plan.uploads.iter().flat_map(|upload| &upload.parts) {
+        let Some(file_name) = part.chunk_path.file_name() else {
+            continue;
+        };
+        let target = request.output_dir.join(file_name);
+        // Large artifacts are split into several parts of the same file.
+        if artifact_paths.contains(&target) {
+            continue;
+        }
+        std::fs::copy(&part.chunk_path, &target)
+            .map_err(|error| OfflineProcessError::Io(error.to_string()))?;
+        artifact_paths.push(target);
+    }
+
+    let submit_payload_path = request.output_dir.join(OFFLINE_SUBMIT_PAYLOAD_FILE_NAME);
+    let payload = serde_json::to_vec_pretty(&plan.submit)
+        .map_err(|error| OfflineProcessError::Serialize(error.to_string()))?;
+    std::fs::write(&submit_payload_path, payload)
+        .map_err(|error| OfflineProcessError::Io(error.to_string()))?;
+
+    Ok(OfflineProcessReport {
+        claimed,
+        artifact_paths,
+        submit_payload_path,
+        submit: plan.submit,
+    })
+}
+
+fn regular_file_name(path: &Path) -> Result<String, OfflineProcessError> {
+    let metadata = std::fs::metadata(path)
+        .map_err(|_| OfflineProcessError::SourceNotFile(path.display().to_string()))?;
+    if !metadata.is_file() {
+        return Err(OfflineProcessError::SourceNotFile(
+            path.display().to_string(),
+        ));
+    }
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .ok_or_else(|| OfflineProcessError::SourceNotFile(path.display().to_string()))
+}
+
+fn stage_file(
+    source: &Path,
+    file_name: &str,
+    staging_dir: &Path,
+) -> Result<PathBuf, OfflineProcessError> {
+    let staged = staging_dir.join(file_name);
+    // Camera files can be large; a hard link avoids the copy when both live on one volume.
+    if std::fs::hard_link(source, &staged).is_err() {
+        std::fs::copy(source, &staged)
+            .map_err(|error| OfflineProcessError::Io(error.to_string()))?;
+    }
+    Ok(staged)
+}
@@ -1,5 +1,8 @@
 use crate::application::derived_processing_gateway::FactsPatchPayload;
+use crate::application::job_cancellation::CancellationToken;
+use crate::application::thumbnail_sprite::SpriteSheetLayout;
 use crate::{AgentRuntimeConfig, resolve_source_path};
+use std::time::Duration;
 use thiserror::Error;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -30,6 +33,39 @@ pub struct VideoProxyRequest {
     pub audio_bitrate_kbps: u32,
 }
 
+pub const HLS_MASTER_PLAYLIST_FILE_NAME: &str = "master.m3u8";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VideoRendition {
+    pub name: String,
+    pub max_width: u16,
+    pub max_height: u16,
+    pub video_bitrate_kbps: u32,
+}
+
+impl VideoRendition {
+    pub fn new(name: &str, max_width: u16, max_height: u16, video_bitrate_kbps: u32) -> Self {
+        Self {
+            name: name.to_string(),
+            max_width,
+            max_height,
+            video_bitrate_kbps,
+        }
+    }
+}
+
+// All renditions are encoded in one ffmpeg pass and packaged as fMP4 HLS in
+// `output_dir`: `master.m3u8`, then `<name>.m3u8`, `<name>_init.mp4` and
+// `<name>_<n>.m4s` per rendition.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VideoHlsLadderRequest {
+    pub input_path: String,
+    pub output_dir: String,
+    pub renditions: Vec<VideoRendition>,
+    pub audio_bitrate_kbps: u32,
+    pub segment_duration_secs: u32,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct AudioProxyRequest {
     pub input_path: String,
@@ -48,6 +84,26 @@ pub struct PhotoProxyRequest {
     pub max_height: u16,
 }
 
+// How a photo source was turned into pixels, reported in submit metrics.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PhotoDecodePath {
+    Image,
+    Heif,
+    EmbeddedPreview,
+    RawDevelopment,
+}
+
+impl PhotoDecodePath {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Image => "image",
+            Self::Heif => "heif",
+            Self::EmbeddedPreview => "embedded_preview",
+            Self::RawDevelopment => "raw_development",
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct VideoThumbnailRequest {
     pub input_path: String,
@@ -57,11 +113,314 @@ pub struct VideoThumbnailRequest {
     pub seek_ms: u64,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VideoSpriteSheetRequest {
+    pub input_path: String,
+    pub output_path: String,
+    pub format: ThumbnailFormat,
+    pub layout: SpriteSheetLayout,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VideoFrameAnalysisRequest {
+    pub input_path: String,
+    pub duration_ms: u64,
+    pub sample_interval_ms: u64,
+}
+
+// One sampled frame: scene change score against the previous sample (0..1),
+// mean luma and 10th-90th percentile luma spread (8-bit scale).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VideoFrameSample {
+    pub timestamp_ms: u64,
+    pub scene_score: f32,
+    pub luma_mean: f32,
+    pub luma_spread: f32,
+}
+
+// EBU R128 summary of the first audio stream; `true_peak_dbtp` is `None` for
+// digital silence.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LoudnessMeasurement {
+    pub integrated_lufs: f64,
+    pub loudness_range_lu: f64,
+    pub true_peak_dbtp: Option<f64>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AudioWaveformFormat {
+    // Mono peak envelope, `bucket_count` values in JSON.
+    #[default]
+    Json,
+    // Per-channel min/max/RMS peaks at several zoom levels, binary
+    // (`WaveformPeaks::encode`); `bucket_count` is ignored.
+    PeaksV2,
+}
+
+impl AudioWaveformFormat {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::PeaksV2 => "waveform_v2",
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct AudioWaveformRequest {
     pub input_path: String,
     pub output_path: String,
     pub bucket_count: usize,
+    pub format: AudioWaveformFormat,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MediaOperation {
+    Probe,
+    Thumbnail,
+    WaveformDecode,
+    SceneAnalysis,
+    SpriteSheet,
+    LoudnessAnalysis,
+    PhotoDecode,
+    VideoProxy,
+    VideoHlsLadder,
+    AudioProxy,
+}
+
+impl MediaOperation {
+    pub const ALL: [Self; 10] = [
+        Self::Probe,
+        Self::Thumbnail,
+        Self::WaveformDecode,
+        Self::SceneAnalysis,
+        Self::SpriteSheet,
+        Self::LoudnessAnalysis,
+        Self::PhotoDecode,
+        Self::VideoProxy,
+        Self::VideoHlsLadder,
+        Self::AudioProxy,
+    ];
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|operation| operation.as_str() == value)
+    }
+
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Probe => "probe",
+            Self::Thumbnail => "thumbnail",
+            Self::WaveformDecode => "waveform_decode",
+            Self::SceneAnalysis => "scene_analysis",
+            Self::SpriteSheet => "sprite_sheet",
+            Self::LoudnessAnalysis => "loudness_analysis",
+            Self::PhotoDecode => "photo_decode",
+            Self::VideoProxy => "video_proxy",
+            Self::VideoHlsLadder => "video_hls_ladder",
+            Self::AudioProxy => "audio_proxy",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MediaTimeoutKind {
+    WallClock,
+    NoProgress,
+}
+
+impl MediaTimeoutKind {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::WallClock => "wall_clock",
+            Self::NoProgress => "no_progress",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MediaOperationTimeout {
+    pub base: Duration,
+    pub per_media_second: Duration,
+    pub max: Duration,
+    pub no_progress: Option<Duration>,
+}
+
+impl MediaOperationTimeout {
+    pub const fn fixed(wall_clock: Duration, no_progress: Option<Duration>) -> Self {
+        Self {
+            base: wall_clock,
+            per_media_second: Duration::ZERO,
+            max: wall_clock,
+            no_progress,
+        }
+    }
+
+    pub fn wall_clock_for(&self, media_duration_ms: Option<u64>) -> Duration {
+        // Unknown duration: only the cap can be trusted.
+        let Some(media_duration_ms) = media_duration_ms else {
+            return self.max;
+        };
+        let scaled_ms = self
+            .per_media_second
+            .as_millis()
+            .saturating_mul(u128::from(media_duration_ms))
+            / 1_000;
+        let scaled = Duration::from_millis(u64::try_from(scaled_ms).unwrap_or(u64::MAX));
+        self.base.saturating_add(scaled).min(self.max)
+    }
+}
+
+// `<operation>=<wall_clock_secs>[,<no_progress_secs>]`: the wall clock caps the
+// duration-scaled timeout, a no-progress of `0` disables the watchdog.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MediaTimeoutOverride {
+    pub operation: MediaOperation,
+    pub wall_clock: Duration,
+    pub no_progress: Option<Duration>,
+}
+
+impl MediaTimeoutOverride {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let (operation, secs) = value
+            .split_once('=')
+            .ok_or_else(|| format!("expected <operation>=<secs>, got `{value}`"))?;
+        let operation = MediaOperation::parse(operation.trim())
+            .ok_or_else(|| format!("unknown media operation `{}`", operation.trim()))?;
+        let (wall_clock, no_progress) = match secs.split_once(',') {
+            Some((wall_clock, no_progress)) => (wall_clock, Some(no_progress)),
+            None => (secs, None),
+        };
+        let parse_secs = |raw: &str| {
+            raw.trim()
+                .parse::<u64>()
+                .map(Duration::from_secs)
+                .map_err(|_| format!("invalid seconds `{}`", raw.trim()))
+        };
+        let wall_clock = parse_secs(wall_clock)?;
+        if wall_clock.is_zero() {
+            return Err(format!(
+                "{} wall clock timeout must be > 0",
+                operation.as_str()
+            ));
+        }
+        Ok(Self {
+            operation,
+            wall_clock,
+            no_progress: no_progress.map(parse_secs).transpose()?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MediaToolTimeouts {
+    pub probe: MediaOperationTimeout,
+    pub thumbnail: MediaOperationTimeout,
+    pub waveform_decode: MediaOperationTimeout,
+    pub scene_analysis: MediaOperationTimeout,
+    pub sprite_sheet: MediaOperationTimeout,
+    pub loudness_analysis: MediaOperationTimeout,
+    pub photo_decode: MediaOperationTimeout,
+    pub video_proxy: MediaOperationTimeout,
+    pub video_hls_ladder: MediaOperationTimeout,
+    pub audio_proxy: MediaOperationTimeout,
+}
+
+impl Default for MediaToolTimeouts {
+    fn default() -> Self {
+        Self {
+            probe: MediaOperationTimeout::fixed(Duration::from_secs(60), None),
+            thumbnail: MediaOperationTimeout::fixed(
+                Duration::from_secs(120),
+                Some(Duration::from_secs(60)),
+            ),
+            waveform_decode: MediaOperationTimeout {
+                base: Duration::from_secs(60),
+                per_media_second: Duration::from_secs(1),
+                max: Duration::from_secs(4 * 60 * 60),
+                no_progress: Some(Duration::from_secs(120)),
+            },
+            scene_analysis: MediaOperationTimeout {
+                base: Duration::from_secs(60),
+                per_media_second: Duration::from_secs(1),
+                max: Duration::from_secs(4 * 60 * 60),
+                no_progress: Some(Duration::from_secs(120)),
+            },
+            sprite_sheet: MediaOperationTimeout {
+                base: Duration::from_secs(60),
+                per_media_second: Duration::from_secs(1),
+                max: Duration::from_secs(4 * 60 * 60),
+                no_progress: Some(Duration::from_secs(120)),
+            },
+            loudness_analysis: MediaOperationTimeout {
+                base: Duration::from_secs(60),
+                per_media_second: Duration::from_secs(1),
+                max: Duration::from_secs(4 * 60 * 60),
+                no_progress: Some(Duration::from_secs(120)),
+            },
+            // libheif prints nothing while decoding, so only the wall clock applies.
+            photo_decode: MediaOperationTimeout::fixed(Duration::from_secs(120), None),
+            video_proxy: MediaOperationTimeout {
+                base: Duration::from_secs(120),
+                per_media_second: Duration::from_secs(4),
+                max: Duration::from_secs(12 * 60 * 60),
+                no_progress: Some(Duration::from_secs(120)),
+            },
+            // One pass encodes every rendition: sized for the default
+            // three-rendition ladder at the single proxy's rate each.
+            video_hls_ladder: MediaOperationTimeout {
+                base: Duration::from_secs(120),
+                per_media_second: Duration::from_secs(12),
+                max: Duration::from_secs(36 * 60 * 60),
+                no_progress: Some(Duration::from_secs(120)),
+            },
+            audio_proxy: MediaOperationTimeout {
+                base: Duration::from_secs(60),
+                per_media_second: Duration::from_secs(1),
+                max: Duration::from_secs(4 * 60 * 60),
+                no_progress: Some(Duration::from_secs(120)),
+            },
+        }
+    }
+}
+
+impl MediaToolTimeouts {
+    pub fn with_override(mut self, timeout: MediaTimeoutOverride) -> Self {
+        let slot = match timeout.operation {
+            MediaOperation::Probe => &mut self.probe,
+            MediaOperation::Thumbnail => &mut self.thumbnail,
+            MediaOperation::WaveformDecode => &mut self.waveform_decode,
+            MediaOperation::SceneAnalysis => &mut self.scene_analysis,
+            MediaOperation::SpriteSheet => &mut self.sprite_sheet,
+            MediaOperation::LoudnessAnalysis => &mut self.loudness_analysis,
+            MediaOperation::PhotoDecode => &mut self.photo_decode,
+            MediaOperation::VideoProxy => &mut self.video_proxy,
+            MediaOperation::VideoHlsLadder => &mut self.video_hls_ladder,
+            MediaOperation::AudioProxy => &mut self.audio_proxy,
+        };
+        slot.max = timeout.wall_clock;
+        slot.base = slot.base.min(timeout.wall_clock);
+        if let Some(no_progress) = timeout.no_progress {
+            slot.no_progress = (!no_progress.is_zero()).then_some(no_progress);
+        }
+        self
+    }
+
+    pub fn for_operation(&self, operation: MediaOperation) -> MediaOperationTimeout {
+        match operation {
+            MediaOperation::Probe => self.probe,
+            MediaOperation::Thumbnail => self.thumbnail,
+            MediaOperation::WaveformDecode => self.waveform_decode,
+            MediaOperation::SceneAnalysis => self.scene_analysis,
+            MediaOperation::SpriteSheet => self.sprite_sheet,
+            MediaOperation::LoudnessAnalysis => self.loudness_analysis,
+            MediaOperation::PhotoDecode => self.photo_decode,
+            MediaOperation::VideoProxy => self.video_proxy,
+            MediaOperation::VideoHlsLadder => self.video_hls_ladder,
+            MediaOperation::AudioProxy => self.audio_proxy,
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Error)]
@@ -73,28 +432,112 @@ pub enum ProxyGenerationError {
         status_code: Option<i32>,
         stderr: String,
     },
+    // I/O, spawn and tool availability failures: worth another attempt.
     #[error("proxy generation process failed: {0}")]
     Process(String),
+    // The source itself cannot be decoded: retrying the same bytes cannot help.
+    #[error("source could not be decoded: {0}")]
+    DecodeFailed(String),
+    #[error("proxy generation cancelled")]
+    Cancelled,
+    #[error("{} timed out ({})", .operation.as_str(), .kind.as_str())]
+    TimedOut {
+        operation: MediaOperation,
+        kind: MediaTimeoutKind,
+    },
+}
+
+impl ProxyGenerationError {
+    pub fn error_code(&self) -> &'static str {
+        match self {
+            Self::InvalidRequest(_) => "PROXY_INVALID_REQUEST",
+            Self::CommandFailed { .. } => "PROXY_COMMAND_FAILED",
+            Self::Process(_) => "PROXY_PROCESS_FAILED",
+            Self::DecodeFailed(_) => "PROXY_DECODE_FAILED",
+            Self::Cancelled => "PROXY_CANCELLED",
+            Self::TimedOut { .. } => "PROXY_TIMEOUT",
+        }
+    }
+
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            Self::Process(_) | Self::Cancelled | Self::TimedOut { .. }
+        )
+    }
 }
 
-pub trait ProxyGenerator {
-    fn generate_video_proxy(&self, request: &VideoProxyRequest)
-    -> Result<(), ProxyGenerationError>;
-    fn generate_audio_proxy(&self, request: &AudioProxyRequest)
-    -> Result<(), ProxyGenerationError>;
-    fn generate_photo_proxy(&self, request: &PhotoProxyRequest)
-    -> Result<(), ProxyGenerationError>;
+pub trait ProxyProgressSink: Send + Sync {
+    fn report_progress(&self, percent: u8);
+
+    fn cancellation(&self) -> Option<&CancellationToken> {
+        None
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NoopProxyProgressSink;
+
+impl ProxyProgressSink for NoopProxyProgressSink {
+    fn report_progress(&self, _percent: u8) {}
+}
+
+pub trait ProxyGenerator: Send + Sync {
+    fn generate_video_proxy(
+        &self,
+        request: &VideoProxyRequest,
+        progress: &dyn ProxyProgressSink,
+    ) -> Result<(), ProxyGenerationError>;
+    fn generate_audio_proxy(
+        &self,
+        request: &AudioProxyRequest,
+        progress: &dyn ProxyProgressSink,
+    ) -> Result<(), ProxyGenerationError>;
+    fn generate_video_hls_ladder(
+        &self,
+        _request: &VideoHlsLadderRequest,
+        _progress: &dyn ProxyProgressSink,
+    ) -> Result<(), ProxyGenerationError> {
+        Err(ProxyGenerationError::InvalidRequest(
+            "HLS ladder generation is not supported by this generator".to_string(),
+        ))
+    }
+    fn generate_photo_proxy(
+        &self,
+        request: &PhotoProxyRequest,
+        progress: &dyn ProxyProgressSink,
+    ) -> Result<Option<PhotoDecodePath>, ProxyGenerationError>;
     fn generate_video_thumbnail(
         &self,
         _request: &VideoThumbnailRequest,
+        _progress: &dyn ProxyProgressSink,
     ) -> Result<(), ProxyGenerationError> {
         Err(ProxyGenerationError::InvalidRequest(
             "video thumbnail generation is not supported by this generator".to_string(),
         ))
     }
+    fn generate_video_sprite_sheet(
+        &self,
+        _request: &VideoSpriteSheetRequest,
+        _progress: &dyn ProxyProgressSink,
+    ) -> Result<(), ProxyGenerationError> {
+        Err(ProxyGenerationError::InvalidRequest(
+            "video sprite sheet generation is not supported by this generator".to_string(),
+        ))
+    }
+    fn analyze_video_frames(
+        &self,
+        _request: &VideoFrameAnalysisRequest,
+        _progress: &dyn ProxyProgressSink,
+    ) -> Result<Vec<VideoFrameSample>, ProxyGenerationError> {
+        Err(ProxyGenerationError::InvalidRequest(
+            "video frame analysis is not supported by this generator".to_string(),
+        ))
+    }
     fn generate_audio_waveform(
         &self,
         _request: &AudioWaveformRequest,
+        _progress: &dyn ProxyProgressSink,
     ) -> Result<(), ProxyGenerationError> {
         Err(ProxyGenerationError::InvalidRequest(
             "audio waveform generation is not supported by this generator".to_string(),
@@ -103,11 +546,21 @@ pub trait ProxyGenerator {
     fn extract_media_facts(
         &self,
         _input_path: &str,
+        _progress: &dyn ProxyProgressSink,
     ) -> Result<FactsPatchPayload, ProxyGenerationError> {
         Err(ProxyGenerationError::InvalidRequest(
             "fact extraction is not supported by this generator".to_string(),
         ))
     }
+    fn analyze_loudness(
+        &self,
+        _input_path: &str,
+        _progress: &dyn ProxyProgressSink,
+    ) -> Result<LoudnessMeasurement, ProxyGenerationError> {
+        Err(ProxyGenerationError::InvalidRequest(
+            "loudness analysis is not supported by this generator".to_string(),
+        ))
+    }
 }
 
 pub fn resolve_processing_input_path(
@@ -1,3 +1,4 @@
+use crate::application::runtime_derived_planner::DEFAULT_UPLOAD_PART_SIZE_BYTES;
 use crate::application::runtime_session::RuntimeSession;
 use crate::domain::configuration::AgentRuntimeConfig;
 use crate::domain::runtime_ui::{AgentRunState, JobStage, MenuAction};
@@ -153,6 +154,13 @@ pub fn format_settings(config: &AgentRuntimeConfig) -> String {
         ),
         format!("max_parallel_jobs={}", config.max_parallel_jobs),
         format!("log_level={}", log_level_label(config)),
+        format!("job_selection={}", config.job_selection.as_str()),
+        format!(
+            "upload_part_size_bytes={}",
+            config
+                .upload_part_size_bytes
+                .unwrap_or(DEFAULT_UPLOAD_PART_SIZE_BYTES)
+        ),
     ];
     lines.push(String::new());
     lines.join("\n")
@@ -1,14 +1,24 @@
+use crate::application::audio_waveform::{WAVEFORM_V2_SAMPLE_RATE, WAVEFORM_V2_SAMPLES_PER_PEAK};
+use crate::application::derived_integrity::attach_upload_integrity;
 use crate::application::derived_job_executor::{
     DerivedExecutionPlan, DerivedExecutionPlanner, DerivedJobExecutorError, DerivedUploadPlan,
 };
 use crate::application::derived_processing_gateway::{
     ClaimedDerivedJob, DerivedJobType, DerivedKind, DerivedManifestItem, DerivedUploadComplete,
-    DerivedUploadInit, DerivedUploadPart, FactsPatchPayload, SubmitDerivedPayload,
+    DerivedUploadInit, FactsPatchPayload, SubmitDerivedPayload, split_upload_parts,
 };
 use crate::application::proxy_generator::{
-    AudioProxyFormat, AudioProxyRequest, AudioWaveformRequest, PhotoProxyFormat, PhotoProxyRequest,
-    ProxyGenerationError, ProxyGenerator, ThumbnailFormat, VideoProxyRequest,
-    VideoThumbnailRequest,
+    AudioProxyFormat, AudioProxyRequest, AudioWaveformFormat, AudioWaveformRequest,
+    HLS_MASTER_PLAYLIST_FILE_NAME, LoudnessMeasurement, NoopProxyProgressSink, PhotoDecodePath,
+    PhotoProxyFormat, PhotoProxyRequest, ProxyGenerationError, ProxyGenerator, ProxyProgressSink,
+    ThumbnailFormat, VideoFrameAnalysisRequest, VideoHlsLadderRequest, VideoProxyRequest,
+    VideoRendition, VideoSpriteSheetRequest, VideoThumbnailRequest,
+};
+use crate::application::thumbnail_sprite::{
+    SpriteSheetLayout, THUMBNAIL_SPRITE_PROFILE, ThumbnailSpriteProfile, sprite_sheet_webvtt,
+};
+use crate::application::video_storyboard::{
+    STORYBOARD_FRAME_COUNT, select_storyboard_frames, storyboard_sample_interval_ms,
 };
 use crate::domain::capabilities::photo_source_extension_supported;
 use crate::infrastructure::ffmpeg_proxy_generator::FfmpegProxyGenerator;
@@ -18,15 +28,49 @@ use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
+pub const DEFAULT_UPLOAD_PART_SIZE_BYTES: u64 = 8 * 1024 * 1024;
+const PHOTO_THUMBNAIL_PROFILE: &str = "photo_representative_v1";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum VideoPreviewProfile {
+    // One 1280x720 MP4 submitted as `preview_video`.
+    #[default]
+    SingleMp4,
+    // fMP4 HLS ladder submitted as `preview_video_hls`; offline only until the
+    // Core contract defines an HLS kind uploaded as one bundle.
+    HlsLadder(Vec<VideoRendition>),
+}
+
+impl VideoPreviewProfile {
+    pub fn default_hls_ladder() -> Self {
+        Self::HlsLadder(vec![
+            VideoRendition::new("360p", 640, 360, 800),
+            VideoRendition::new("720p", 1280, 720, 2_500),
+            VideoRendition::new("1080p", 1920, 1080, 5_000),
+        ])
+    }
+}
+
 #[derive(Clone)]
 pub struct RuntimeDerivedPlanner {
     av_generator: Arc<dyn ProxyGenerator>,
     photo_generator: Arc<dyn ProxyGenerator>,
+    upload_part_size_bytes: u64,
+    video_preview_profile: VideoPreviewProfile,
+    thumbnail_sprite_profile: Option<ThumbnailSpriteProfile>,
+    scene_storyboard: bool,
+    waveform_format: AudioWaveformFormat,
 }
 
 impl std::fmt::Debug for RuntimeDerivedPlanner {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("RuntimeDerivedPlanner").finish()
+        f.debug_struct("RuntimeDerivedPlanner")
+            .field("upload_part_size_bytes", &self.upload_part_size_bytes)
+            .field("video_preview_profile", &self.video_preview_profile)
+            .field("thumbnail_sprite_profile", &self.thumbnail_sprite_profile)
+            .field("scene_storyboard", &self.scene_storyboard)
+            .field("waveform_format", &self.waveform_format)
+            .finish()
     }
 }
 
@@ -35,6 +79,11 @@ impl Default for RuntimeDerivedPlanner {
         Self {
             av_generator: Arc::new(FfmpegProxyGenerator::default()),
             photo_generator: Arc::new(RustPhotoProxyGenerator::default()),
+            upload_part_size_bytes: DEFAULT_UPLOAD_PART_SIZE_BYTES,
+            video_preview_profile: VideoPreviewProfile::default(),
+            thumbnail_sprite_profile: None,
+            scene_storyboard: false,
+            waveform_format: AudioWaveformFormat::default(),
         }
     }
 }
@@ -47,8 +96,68 @@ impl RuntimeDerivedPlanner {
         Self {
             av_generator,
             photo_generator,
+            upload_part_size_bytes: DEFAULT_UPLOAD_PART_SIZE_BYTES,
+            video_preview_profile: VideoPreviewProfile::default(),
+            thumbnail_sprite_profile: None,
+            scene_storyboard: false,
+            waveform_format: AudioWaveformFormat::default(),
         }
     }
+
+    pub fn with_upload_part_size_bytes(mut self, upload_part_size_bytes: u64) -> Self {
+        self.upload_part_size_bytes = upload_part_size_bytes.max(1);
+        self
+    }
+
+    pub fn upload_part_size_bytes(&self) -> u64 {
+        self.upload_part_size_bytes
+    }
+
+    pub fn with_video_preview_profile(
+        mut self,
+        video_preview_profile: VideoPreviewProfile,
+    ) -> Self {
+        self.video_preview_profile = video_preview_profile;
+        self
+    }
+
+    pub fn video_preview_profile(&self) -> &VideoPreviewProfile {
+        &self.video_preview_profile
+    }
+
+    // Adds a scrubbing sprite sheet and its WebVTT track to video thumbnail jobs.
+    // Offline only: the Core contract has no `thumb_sprite`/`thumb_vtt` kind yet.
+    pub fn with_thumbnail_sprite_profile(
+        mut self,
+        thumbnail_sprite_profile: ThumbnailSpriteProfile,
+    ) -> Self {
+        self.thumbnail_sprite_profile = Some(thumbnail_sprite_profile);
+        self
+    }
+
+    pub fn thumbnail_sprite_profile(&self) -> Option<&ThumbnailSpriteProfile> {
+        self.thumbnail_sprite_profile.as_ref()
+    }
+
+    // Picks video thumbnails per scene (`video_storyboard_v2`), at the cost of
+    // one extra decode pass over the whole source.
+    pub fn with_scene_storyboard(mut self, scene_storyboard: bool) -> Self {
+        self.scene_storyboard = scene_storyboard;
+        self
+    }
+
+    pub fn scene_storyboard(&self) -> bool {
+        self.scene_storyboard
+    }
+
+    pub fn with_waveform_format(mut self, waveform_format: AudioWaveformFormat) -> Self {
+        self.waveform_format = waveform_format;
+        self
+    }
+
+    pub fn waveform_format(&self) -> AudioWaveformFormat {
+        self.waveform_format
+    }
 }
 
 impl DerivedExecutionPlanner for RuntimeDerivedPlanner {
@@ -65,7 +174,7 @@ impl DerivedExecutionPlanner for RuntimeDerivedPlanner {
                 facts_patch: None,
                 transcript_patch: None,
                 warnings: None,
-                metrics: base_metrics_for_job(claimed),
+                metrics: base_metrics_for_job(claimed, self.waveform_format),
             },
             submit_idempotency_key: format!("agent-submit-{}", claimed.job_id),
         })
@@ -76,6 +185,21 @@ impl DerivedExecutionPlanner for RuntimeDerivedPlanner {
         claimed: &ClaimedDerivedJob,
         staged_source_path: Option<&Path>,
         staged_sidecar_paths: &[PathBuf],
+    ) -> Result<DerivedExecutionPlan, DerivedJobExecutorError> {
+        self.plan_for_claimed_job_with_progress(
+            claimed,
+            staged_source_path,
+            staged_sidecar_paths,
+            &NoopProxyProgressSink,
+        )
+    }
+
+    fn plan_for_claimed_job_with_progress(
+        &self,
+        claimed: &ClaimedDerivedJob,
+        staged_source_path: Option<&Path>,
+        staged_sidecar_paths: &[PathBuf],
+        progress: &dyn ProxyProgressSink,
     ) -> Result<DerivedExecutionPlan, DerivedJobExecutorError> {
         let mut plan = self.plan_for_claimed_job(claimed)?;
         merge_metrics(
@@ -86,15 +210,41 @@ impl DerivedExecutionPlanner for RuntimeDerivedPlanner {
             return Ok(plan);
         };
         if claimed.job_type == DerivedJobType::ExtractFacts {
-            plan.submit.facts_patch =
-                Some(self.extract_facts(source_path, staged_sidecar_paths, claimed)?);
+            let facts = self.extract_facts(source_path, staged_sidecar_paths, claimed, progress)?;
+            // `FactsPatch` has no loudness fields yet: the free-form metrics carry them.
+            if infer_preview_kind(claimed) != DerivedKind::PreviewPhoto
+                && facts.audio_codec.is_some()
+            {
+                merge_metrics(
+                    &mut plan.submit.metrics,
+                    self.measure_loudness(source_path, progress)?
+                        .as_ref()
+                        .map(loudness_metrics),
+                );
+            }
+            plan.submit.facts_patch = Some(facts);
             return Ok(plan);
         }
         if claimed.job_type == DerivedJobType::GenerateThumbnails {
-            let thumbnail_artifacts = self.generate_thumbnail_artifacts(source_path)?;
-            plan.uploads = thumbnail_uploads_for_claimed_job(claimed, &thumbnail_artifacts)?;
+            let thumbnail_artifacts =
+                self.generate_thumbnail_artifacts(claimed, source_path, progress)?;
+            plan.uploads = thumbnail_uploads_for_claimed_job(
+                claimed,
+                &thumbnail_artifacts,
+                self.upload_part_size_bytes,
+            )?;
             plan.submit.manifest =
                 thumbnail_manifest_for_claimed_job(claimed, &thumbnail_artifacts);
+            for (item, upload) in plan.submit.manifest.iter_mut().zip(&plan.uploads) {
+                item.sha256 = upload.init.sha256.clone();
+            }
+            merge_metrics(
+                &mut plan.submit.metrics,
+                thumbnail_artifacts
+                    .sprite
+                    .as_ref()
+                    .map(|sprite| thumbnail_sprite_metrics(&sprite.layout)),
+            );
             merge_metrics(
                 &mut plan.submit.metrics,
                 Some(thumbnail_metrics(
@@ -102,6 +252,26 @@ impl DerivedExecutionPlanner for RuntimeDerivedPlanner {
                     thumbnail_artifacts.files.len(),
                 )),
             );
+            merge_metrics(
+                &mut plan.submit.metrics,
+                thumbnail_artifacts
+                    .photo_decode_path
+                    .map(photo_decode_metrics),
+            );
+            return Ok(plan);
+        }
+        if claimed.job_type == DerivedJobType::GeneratePreview
+            && infer_preview_kind(claimed) == DerivedKind::PreviewVideo
+            && let VideoPreviewProfile::HlsLadder(renditions) = &self.video_preview_profile
+        {
+            let files = self.generate_hls_ladder_artifacts(source_path, renditions, progress)?;
+            plan.uploads =
+                hls_uploads_for_claimed_job(claimed, &files, self.upload_part_size_bytes)?;
+            plan.submit.manifest = hls_manifest_for_claimed_job(claimed, &files, &plan.uploads);
+            merge_metrics(
+                &mut plan.submit.metrics,
+                Some(hls_ladder_metrics(renditions)),
+            );
             return Ok(plan);
         }
 
@@ -113,11 +283,17 @@ impl DerivedExecutionPlanner for RuntimeDerivedPlanner {
             .unwrap_or_else(|| infer_preview_kind(claimed));
         let generated_path = match claimed.job_type {
             DerivedJobType::GeneratePreview => {
-                self.generate_preview_artifact(source_path, upload_kind)?
+                let (path, photo_decode_path) =
+                    self.generate_preview_artifact(source_path, upload_kind, progress)?;
+                merge_metrics(
+                    &mut plan.submit.metrics,
+                    photo_decode_path.map(photo_decode_metrics),
+                );
+                path
             }
             DerivedJobType::GenerateThumbnails => unreachable!("handled above"),
             DerivedJobType::GenerateAudioWaveform => {
-                self.generate_waveform_artifact(source_path)?
+                self.generate_waveform_artifact(source_path, progress)?
             }
             DerivedJobType::ExtractFacts => source_path.to_path_buf(),
             DerivedJobType::TranscribeAudio => {
@@ -127,44 +303,20 @@ impl DerivedExecutionPlanner for RuntimeDerivedPlanner {
             }
         };
 
-        let metadata = std::fs::metadata(&generated_path)
-            .map_err(|error| DerivedJobExecutorError::Planner(error.to_string()))?;
-        let size_bytes = metadata.len();
-        let upload_id = format!(
-            "upload-{}-{}",
-            claimed.asset_uuid,
-            upload_kind.as_str().replace('_', "-")
-        );
-
-        plan.uploads = vec![DerivedUploadPlan {
-            init: DerivedUploadInit {
-                asset_uuid: claimed.asset_uuid.clone(),
-                revision_etag: String::new(),
-                kind: upload_kind,
-                content_type: content_type_for_kind(upload_kind).to_string(),
-                size_bytes,
-                sha256: None,
-                idempotency_key: format!("init-{}-{}", claimed.job_id, upload_kind.as_str()),
-            },
-            parts: vec![DerivedUploadPart {
-                asset_uuid: claimed.asset_uuid.clone(),
-                revision_etag: String::new(),
-                upload_id: upload_id.clone(),
-                part_number: 1,
-                chunk_path: generated_path.clone(),
-            }],
-            complete: DerivedUploadComplete {
-                asset_uuid: claimed.asset_uuid.clone(),
-                revision_etag: String::new(),
-                upload_id,
-                idempotency_key: format!("complete-{}-{}", claimed.job_id, upload_kind.as_str()),
-                parts: None,
-            },
-        }];
-
+        let mut upload = single_upload_for_kind(
+            claimed,
+            upload_kind,
+            &generated_path,
+            self.upload_part_size_bytes,
+        )?;
+        if claimed.job_type == DerivedJobType::GenerateAudioWaveform {
+            upload.init.content_type = waveform_content_type(self.waveform_format).to_string();
+        }
         if let Some(first) = plan.submit.manifest.first_mut() {
-            first.size_bytes = Some(size_bytes);
+            first.size_bytes = Some(upload.init.size_bytes);
+            first.sha256 = upload.init.sha256.clone();
         }
+        plan.uploads = vec![upload];
         Ok(plan)
     }
 }
@@ -219,77 +371,245 @@ impl RuntimeDerivedPlanner {
         &self,
         source_path: &Path,
         kind: DerivedKind,
-    ) -> Result<PathBuf, DerivedJobExecutorError> {
+        progress: &dyn ProxyProgressSink,
+    ) -> Result<(PathBuf, Option<PhotoDecodePath>), DerivedJobExecutorError> {
         let output_path = generated_preview_output_path(source_path, kind);
         let input_path = source_path.to_string_lossy().to_string();
 
+        let mut photo_decode_path = None;
         let result = match kind {
-            DerivedKind::PreviewVideo => {
-                self.av_generator
-                    .generate_video_proxy(&canonical_video_preview_request(
-                        input_path,
-                        output_path.to_string_lossy().to_string(),
-                    ))
-            }
-            DerivedKind::PreviewAudio => {
-                self.av_generator
-                    .generate_audio_proxy(&canonical_audio_preview_request(
-                        input_path,
-                        output_path.to_string_lossy().to_string(),
-                    ))
-            }
-            DerivedKind::PreviewPhoto => {
-                self.photo_generator
-                    .generate_photo_proxy(&canonical_photo_preview_request(
+            DerivedKind::PreviewVideo => self.av_generator.generate_video_proxy(
+                &canonical_video_preview_request(
+                    input_path,
+                    output_path.to_string_lossy().to_string(),
+                ),
+                progress,
+            ),
+            DerivedKind::PreviewAudio => self.av_generator.generate_audio_proxy(
+                &canonical_audio_preview_request(
+                    input_path,
+                    output_path.to_string_lossy().to_string(),
+                ),
+                progress,
+            ),
+            DerivedKind::PreviewPhoto => self
+                .photo_generator
+                .generate_photo_proxy(
+                    &canonical_photo_preview_request(
                         input_path,
                         output_path.to_string_lossy().to_string(),
-                    ))
-            }
-            DerivedKind::Thumb | DerivedKind::Waveform => Ok(()),
+                    ),
+                    progress,
+                )
+                .map(|decode_path| photo_decode_path = decode_path),
+            DerivedKind::PreviewVideoHls
+            | DerivedKind::Thumb
+            | DerivedKind::ThumbSprite
+            | DerivedKind::ThumbVtt
+            | DerivedKind::Waveform => Ok(()),
         };
 
         result.map_err(map_preview_generation_error)?;
-        Ok(output_path)
+        Ok((output_path, photo_decode_path))
+    }
+
+    fn generate_hls_ladder_artifacts(
+        &self,
+        source_path: &Path,
+        renditions: &[VideoRendition],
+        progress: &dyn ProxyProgressSink,
+    ) -> Result<Vec<PathBuf>, DerivedJobExecutorError> {
+        let output_dir = generated_hls_output_dir(source_path);
+        self.av_generator
+            .generate_video_hls_ladder(
+                &canonical_hls_ladder_request(
+                    source_path.to_string_lossy().to_string(),
+                    output_dir.to_string_lossy().to_string(),
+                    renditions.to_vec(),
+                ),
+                progress,
+            )
+            .map_err(map_preview_generation_error)?;
+        hls_ladder_files(&output_dir)
     }
 
     fn generate_thumbnail_artifacts(
         &self,
+        claimed: &ClaimedDerivedJob,
         source_path: &Path,
+        progress: &dyn ProxyProgressSink,
     ) -> Result<GeneratedThumbnailArtifacts, DerivedJobExecutorError> {
-        let duration_ms = self
+        // Stills have no timeline to seek: one thumb straight from the photo decoder.
+        if infer_preview_kind(claimed) == DerivedKind::PreviewPhoto {
+            let output_path = generated_thumb_output_path(source_path, 0);
+            let photo_decode_path = self
+                .photo_generator
+                .generate_photo_proxy(
+                    &canonical_photo_thumbnail_request(
+                        source_path.to_string_lossy().to_string(),
+                        output_path.to_string_lossy().to_string(),
+                    ),
+                    progress,
+                )
+                .map_err(map_preview_generation_error)?;
+            return Ok(GeneratedThumbnailArtifacts {
+                profile: PHOTO_THUMBNAIL_PROFILE,
+                files: vec![output_path],
+                photo_decode_path,
+                sprite: None,
+            });
+        }
+
+        // An unreadable duration only degrades to a single thumb, but a
+        // cancelled or timed out probe stops the job.
+        let facts = match self
             .av_generator
-            .extract_media_facts(&source_path.to_string_lossy())
-            .ok()
+            .extract_media_facts(&source_path.to_string_lossy(), progress)
+        {
+            Ok(facts) => Some(facts),
+            Err(
+                error @ (ProxyGenerationError::Cancelled | ProxyGenerationError::TimedOut { .. }),
+            ) => return Err(map_preview_generation_error(error)),
+            Err(_) => None,
+        };
+        let duration_ms = facts
+            .as_ref()
             .and_then(|facts| facts.duration_ms)
             .and_then(|value| u64::try_from(value).ok());
 
-        let (profile, seek_points) = storyboard_plan_for_duration(duration_ms);
+        let scene_seek_points = if self.scene_storyboard {
+            self.scene_storyboard_seek_points(source_path, duration_ms, progress)?
+        } else {
+            None
+        };
+        let (profile, seek_points) = match scene_seek_points {
+            Some(seek_points) => ("video_storyboard_v2", seek_points),
+            None => storyboard_plan_for_duration(duration_ms),
+        };
         let mut files = Vec::with_capacity(seek_points.len());
         for (index, seek_ms) in seek_points.iter().enumerate() {
             let output_path = generated_thumb_output_path(source_path, index);
             self.av_generator
-                .generate_video_thumbnail(&canonical_thumbnail_request(
-                    source_path.to_string_lossy().to_string(),
-                    output_path.to_string_lossy().to_string(),
-                    *seek_ms,
-                ))
+                .generate_video_thumbnail(
+                    &canonical_thumbnail_request(
+                        source_path.to_string_lossy().to_string(),
+                        output_path.to_string_lossy().to_string(),
+                        *seek_ms,
+                    ),
+                    progress,
+                )
                 .map_err(map_preview_generation_error)?;
             files.push(output_path);
         }
 
-        Ok(GeneratedThumbnailArtifacts { profile, files })
+        let sprite = match (&self.thumbnail_sprite_profile, duration_ms) {
+            (Some(sprite_profile), Some(duration_ms)) => self.generate_sprite_artifacts(
+                claimed,
+                source_path,
+                sprite_profile,
+                duration_ms,
+                facts.as_ref(),
+                progress,
+            )?,
+            _ => None,
+        };
+
+        Ok(GeneratedThumbnailArtifacts {
+            profile,
+            files,
+            photo_decode_path: None,
+            sprite,
+        })
+    }
+
+    fn generate_sprite_artifacts(
+        &self,
+        claimed: &ClaimedDerivedJob,
+        source_path: &Path,
+        sprite_profile: &ThumbnailSpriteProfile,
+        duration_ms: u64,
+        facts: Option<&FactsPatchPayload>,
+        progress: &dyn ProxyProgressSink,
+    ) -> Result<Option<GeneratedSpriteArtifacts>, DerivedJobExecutorError> {
+        let dimension = |value: Option<i32>| value.and_then(|value| u32::try_from(value).ok());
+        let Some(layout) = sprite_profile.layout_for(
+            duration_ms,
+            dimension(facts.and_then(|facts| facts.width)),
+            dimension(facts.and_then(|facts| facts.height)),
+        ) else {
+            return Ok(None);
+        };
+        let sheet_path = generated_preview_output_path(source_path, DerivedKind::ThumbSprite);
+        self.av_generator
+            .generate_video_sprite_sheet(
+                &VideoSpriteSheetRequest {
+                    input_path: source_path.to_string_lossy().to_string(),
+                    output_path: sheet_path.to_string_lossy().to_string(),
+                    format: ThumbnailFormat::Webp,
+                    layout,
+                },
+                progress,
+            )
+            .map_err(map_preview_generation_error)?;
+        let vtt_path = generated_preview_output_path(source_path, DerivedKind::ThumbVtt);
+        let sprite_reference =
+            stable_core_derived_reference(&claimed.asset_uuid, DerivedKind::ThumbSprite);
+        std::fs::write(
+            &vtt_path,
+            sprite_sheet_webvtt(&layout, duration_ms, &sprite_reference),
+        )
+        .map_err(|error| DerivedJobExecutorError::Planner(error.to_string()))?;
+        Ok(Some(GeneratedSpriteArtifacts {
+            sheet_path,
+            vtt_path,
+            layout,
+        }))
+    }
+
+    // Scene-aware seek points; `None` (unknown duration or no usable scene)
+    // falls back to the evenly spaced storyboard. Analysis failures fail the job.
+    fn scene_storyboard_seek_points(
+        &self,
+        source_path: &Path,
+        duration_ms: Option<u64>,
+        progress: &dyn ProxyProgressSink,
+    ) -> Result<Option<Vec<u64>>, DerivedJobExecutorError> {
+        let Some(duration_ms) = duration_ms.filter(|duration_ms| *duration_ms > 0) else {
+            return Ok(None);
+        };
+        let samples = self
+            .av_generator
+            .analyze_video_frames(
+                &VideoFrameAnalysisRequest {
+                    input_path: source_path.to_string_lossy().to_string(),
+                    duration_ms,
+                    sample_interval_ms: storyboard_sample_interval_ms(duration_ms),
+                },
+                progress,
+            )
+            .map_err(map_preview_generation_error)?;
+        let seek_points = select_storyboard_frames(&samples, STORYBOARD_FRAME_COUNT);
+        Ok((!seek_points.is_empty()).then_some(seek_points))
     }
 
     fn generate_waveform_artifact(
         &self,
         source_path: &Path,
+        progress: &dyn ProxyProgressSink,
     ) -> Result<PathBuf, DerivedJobExecutorError> {
-        let output_path = generated_preview_output_path(source_path, DerivedKind::Waveform);
+        let mut output_path = generated_preview_output_path(source_path, DerivedKind::Waveform);
+        if self.waveform_format == AudioWaveformFormat::PeaksV2 {
+            output_path.set_extension("bin");
+        }
         self.av_generator
-            .generate_audio_waveform(&canonical_waveform_request(
-                source_path.to_string_lossy().to_string(),
-                output_path.to_string_lossy().to_string(),
-            ))
+            .generate_audio_waveform(
+                &canonical_waveform_request(
+                    source_path.to_string_lossy().to_string(),
+                    output_path.to_string_lossy().to_string(),
+                    self.waveform_format,
+                ),
+                progress,
+            )
             .map_err(map_preview_generation_error)?;
         Ok(output_path)
     }
@@ -299,19 +619,39 @@ impl RuntimeDerivedPlanner {
         source_path: &Path,
         staged_sidecar_paths: &[PathBuf],
         claimed: &ClaimedDerivedJob,
+        progress: &dyn ProxyProgressSink,
     ) -> Result<FactsPatchPayload, DerivedJobExecutorError> {
-        let generator: &Arc<dyn ProxyGenerator> =
-            if infer_preview_kind(claimed) == DerivedKind::PreviewPhoto {
-                &self.photo_generator
-            } else {
-                &self.av_generator
-            };
+        let is_photo = infer_preview_kind(claimed) == DerivedKind::PreviewPhoto;
+        let generator: &Arc<dyn ProxyGenerator> = if is_photo {
+            &self.photo_generator
+        } else {
+            &self.av_generator
+        };
         let mut facts = generator
-            .extract_media_facts(&source_path.to_string_lossy())
+            .extract_media_facts(&source_path.to_string_lossy(), progress)
             .map_err(map_preview_generation_error)?;
         merge_sidecar_facts(&mut facts, staged_sidecar_paths);
         Ok(facts)
     }
+
+    // Loudness is best effort: a failed pass must not lose the probed facts,
+    // but a cancelled job stops here.
+    fn measure_loudness(
+        &self,
+        source_path: &Path,
+        progress: &dyn ProxyProgressSink,
+    ) -> Result<Option<LoudnessMeasurement>, DerivedJobExecutorError> {
+        match self
+            .av_generator
+            .analyze_loudness(&source_path.to_string_lossy(), progress)
+        {
+            Ok(loudness) => Ok(Some(loudness)),
+            Err(ProxyGenerationError::Cancelled) => Err(map_preview_generation_error(
+                ProxyGenerationError::Cancelled,
+            )),
+            Err(_) => Ok(None),
+        }
+    }
 }
 
 fn generated_preview_output_path(source_path: &Path, kind: DerivedKind) -> PathBuf {
@@ -323,14 +663,54 @@ fn generated_preview_output_path(source_path: &Path, kind: DerivedKind) -> PathB
         .unwrap_or("derived");
     let extension = match kind {
         DerivedKind::PreviewVideo => "mp4",
+        DerivedKind::PreviewVideoHls => "m3u8",
         DerivedKind::PreviewAudio => "m4a",
         DerivedKind::PreviewPhoto => "webp",
         DerivedKind::Thumb => "webp",
+        DerivedKind::ThumbSprite => "webp",
+        DerivedKind::ThumbVtt => "vtt",
         DerivedKind::Waveform => "json",
     };
     parent.join(format!("{stem}.{}.{}", kind.as_str(), extension))
 }
 
+fn generated_hls_output_dir(source_path: &Path) -> PathBuf {
+    let parent = source_path.parent().unwrap_or_else(|| Path::new("."));
+    let stem = source_path
+        .file_stem()
+        .and_then(|value| value.to_str())
+        .filter(|value| !value.is_empty())
+        .unwrap_or("derived");
+    parent.join(format!("{stem}.{}", DerivedKind::PreviewVideoHls.as_str()))
+}
+
+// Master playlist first so Core can resolve the ladder entry point from the manifest head.
+fn hls_ladder_files(output_dir: &Path) -> Result<Vec<PathBuf>, DerivedJobExecutorError> {
+    let mut files = std::fs::read_dir(output_dir)
+        .map_err(|error| DerivedJobExecutorError::Planner(error.to_string()))?
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+        .collect::<Vec<_>>();
+    files.sort_by_key(|path| {
+        let name = path
+            .file_name()
+            .map(|value| value.to_string_lossy().to_string())
+            .unwrap_or_default();
+        (name != HLS_MASTER_PLAYLIST_FILE_NAME, name)
+    });
+    let has_master = files
+        .first()
+        .and_then(|path| path.file_name())
+        .is_some_and(|name| name == HLS_MASTER_PLAYLIST_FILE_NAME);
+    if !has_master {
+        return Err(DerivedJobExecutorError::Planner(format!(
+            "HLS ladder has no {HLS_MASTER_PLAYLIST_FILE_NAME}"
+        )));
+    }
+    Ok(files)
+}
+
 fn generated_thumb_output_path(source_path: &Path, index: usize) -> PathBuf {
     let parent = source_path.parent().unwrap_or_else(|| Path::new("."));
     let stem = source_path
@@ -507,6 +887,20 @@ fn canonical_video_preview_request(input_path: String, output_path: String) -> V
     }
 }
 
+fn canonical_hls_ladder_request(
+    input_path: String,
+    output_dir: String,
+    renditions: Vec<VideoRendition>,
+) -> VideoHlsLadderRequest {
+    VideoHlsLadderRequest {
+        input_path,
+        output_dir,
+        renditions,
+        audio_bitrate_kbps: 128,
+        segment_duration_secs: 4,
+    }
+}
+
 fn canonical_audio_preview_request(input_path: String, output_path: String) -> AudioProxyRequest {
     AudioProxyRequest {
         input_path,
@@ -527,6 +921,16 @@ fn canonical_photo_preview_request(input_path: String, output_path: String) -> P
     }
 }
 
+fn canonical_photo_thumbnail_request(input_path: String, output_path: String) -> PhotoProxyRequest {
+    PhotoProxyRequest {
+        input_path,
+        output_path,
+        format: PhotoProxyFormat::Webp,
+        max_width: 480,
+        max_height: 480,
+    }
+}
+
 fn canonical_thumbnail_request(
     input_path: String,
     output_path: String,
@@ -567,8 +971,6 @@ fn storyboard_seek_points_ms(duration_ms: u64, frame_count: usize) -> Vec<u64> {
 }
 
 fn storyboard_plan_for_duration(duration_ms: Option<u64>) -> (&'static str, Vec<u64>) {
-    const STORYBOARD_FRAME_COUNT: usize = 9;
-
     match duration_ms {
         Some(duration_ms) if duration_ms > 0 => (
             "video_storyboard_v1",
@@ -581,16 +983,49 @@ fn storyboard_plan_for_duration(duration_ms: Option<u64>) -> (&'static str, Vec<
     }
 }
 
-fn canonical_waveform_request(input_path: String, output_path: String) -> AudioWaveformRequest {
+fn canonical_waveform_request(
+    input_path: String,
+    output_path: String,
+    format: AudioWaveformFormat,
+) -> AudioWaveformRequest {
     AudioWaveformRequest {
         input_path,
         output_path,
         bucket_count: 1_000,
+        format,
+    }
+}
+
+fn waveform_content_type(format: AudioWaveformFormat) -> &'static str {
+    match format {
+        AudioWaveformFormat::Json => content_type_for_kind(DerivedKind::Waveform),
+        AudioWaveformFormat::PeaksV2 => "application/octet-stream",
     }
 }
 
+fn waveform_metrics(format: AudioWaveformFormat) -> HashMap<String, Value> {
+    let mut metrics = HashMap::new();
+    metrics.insert("waveform_format".to_string(), Value::from(format.as_str()));
+    match format {
+        AudioWaveformFormat::Json => {
+            metrics.insert("waveform_bucket_count".to_string(), Value::from(1_000_u64));
+        }
+        AudioWaveformFormat::PeaksV2 => {
+            metrics.insert(
+                "waveform_sample_rate".to_string(),
+                Value::from(WAVEFORM_V2_SAMPLE_RATE),
+            );
+            metrics.insert(
+                "waveform_samples_per_peak".to_string(),
+                Value::from(WAVEFORM_V2_SAMPLES_PER_PEAK.to_vec()),
+            );
+        }
+    }
+    metrics
+}
+
 fn map_preview_generation_error(error: ProxyGenerationError) -> DerivedJobExecutorError {
-    DerivedJobExecutorError::Planner(format!("preview generation failed: {error}"))
+    DerivedJobExecutorError::ProxyGeneration(error)
 }
 
 fn is_audio_extension(extension: &str) -> bool {
@@ -603,14 +1038,20 @@ fn is_audio_extension(extension: &str) -> bool {
 fn content_type_for_kind(kind: DerivedKind) -> &'static str {
     match kind {
         DerivedKind::PreviewVideo => "video/mp4",
+        DerivedKind::PreviewVideoHls => "application/vnd.apple.mpegurl",
         DerivedKind::PreviewAudio => "audio/mp4",
         DerivedKind::PreviewPhoto => "image/webp",
         DerivedKind::Thumb => "image/webp",
+        DerivedKind::ThumbSprite => "image/webp",
+        DerivedKind::ThumbVtt => "text/vtt",
         DerivedKind::Waveform => "application/json",
     }
 }
 
-fn base_metrics_for_job(claimed: &ClaimedDerivedJob) -> Option<HashMap<String, Value>> {
+fn base_metrics_for_job(
+    claimed: &ClaimedDerivedJob,
+    waveform_format: AudioWaveformFormat,
+) -> Option<HashMap<String, Value>> {
     let mut metrics = HashMap::new();
     if claimed.job_type == DerivedJobType::GeneratePreview {
         let kind = infer_preview_kind(claimed);
@@ -623,10 +1064,14 @@ fn base_metrics_for_job(claimed: &ClaimedDerivedJob) -> Option<HashMap<String, V
             Value::from(canonical_preview_profile_for_kind(kind)),
         );
     } else if claimed.job_type == DerivedJobType::GenerateThumbnails {
-        metrics.extend(thumbnail_metrics("video_representative_v1", 1));
+        let profile = if infer_preview_kind(claimed) == DerivedKind::PreviewPhoto {
+            PHOTO_THUMBNAIL_PROFILE
+        } else {
+            "video_representative_v1"
+        };
+        metrics.extend(thumbnail_metrics(profile, 1));
     } else if claimed.job_type == DerivedJobType::GenerateAudioWaveform {
-        metrics.insert("waveform_bucket_count".to_string(), Value::from(1_000_u64));
-        metrics.insert("waveform_format".to_string(), Value::from("json"));
+        metrics.extend(waveform_metrics(waveform_format));
     }
 
     if metrics.is_empty() {
@@ -658,7 +1103,7 @@ fn thumbnail_manifest_for_claimed_job(
     claimed: &ClaimedDerivedJob,
     artifacts: &GeneratedThumbnailArtifacts,
 ) -> Vec<DerivedManifestItem> {
-    artifacts
+    let mut manifest = artifacts
         .files
         .iter()
         .enumerate()
@@ -668,12 +1113,22 @@ fn thumbnail_manifest_for_claimed_job(
             size_bytes: std::fs::metadata(path).ok().map(|meta| meta.len()),
             sha256: None,
         })
-        .collect()
+        .collect::<Vec<_>>();
+    if let Some(sprite) = &artifacts.sprite {
+        for (kind, path) in sprite.files() {
+            manifest.push(DerivedManifestItem {
+                size_bytes: std::fs::metadata(path).ok().map(|meta| meta.len()),
+                ..manifest_item_for_kind(claimed, kind)
+            });
+        }
+    }
+    manifest
 }
 
 fn thumbnail_uploads_for_claimed_job(
     claimed: &ClaimedDerivedJob,
     artifacts: &GeneratedThumbnailArtifacts,
+    upload_part_size_bytes: u64,
 ) -> Result<Vec<DerivedUploadPlan>, DerivedJobExecutorError> {
     let mut uploads = Vec::with_capacity(artifacts.files.len());
 
@@ -682,7 +1137,7 @@ fn thumbnail_uploads_for_claimed_job(
             .map_err(|error| DerivedJobExecutorError::Planner(error.to_string()))?
             .len();
         let upload_id = format!("upload-{}-thumb-{}", claimed.asset_uuid, index + 1);
-        uploads.push(DerivedUploadPlan {
+        let mut upload = DerivedUploadPlan {
             init: DerivedUploadInit {
                 asset_uuid: claimed.asset_uuid.clone(),
                 revision_etag: String::new(),
@@ -692,37 +1147,268 @@ fn thumbnail_uploads_for_claimed_job(
                 sha256: None,
                 idempotency_key: format!("init-{}-thumb-{}", claimed.job_id, index + 1),
             },
-            parts: vec![DerivedUploadPart {
+            parts: split_upload_parts(
+                &claimed.asset_uuid,
+                &upload_id,
+                path,
+                size_bytes,
+                upload_part_size_bytes,
+            ),
+            complete: DerivedUploadComplete {
+                asset_uuid: claimed.asset_uuid.clone(),
+                revision_etag: String::new(),
+                upload_id,
+                idempotency_key: format!("complete-{}-thumb-{}", claimed.job_id, index + 1),
+                parts: None,
+            },
+        };
+        attach_upload_integrity(&mut upload, path).map_err(DerivedJobExecutorError::Integrity)?;
+        uploads.push(upload);
+    }
+    if let Some(sprite) = &artifacts.sprite {
+        for (kind, path) in sprite.files() {
+            uploads.push(single_upload_for_kind(
+                claimed,
+                kind,
+                path,
+                upload_part_size_bytes,
+            )?);
+        }
+    }
+
+    Ok(uploads)
+}
+
+fn single_upload_for_kind(
+    claimed: &ClaimedDerivedJob,
+    kind: DerivedKind,
+    path: &Path,
+    upload_part_size_bytes: u64,
+) -> Result<DerivedUploadPlan, DerivedJobExecutorError> {
+    let size_bytes = std::fs::metadata(path)
+        .map_err(|error| DerivedJobExecutorError::Planner(error.to_string()))?
+        .len();
+    let upload_id = format!(
+        "upload-{}-{}",
+        claimed.asset_uuid,
+        kind.as_str().replace('_', "-")
+    );
+    let mut upload = DerivedUploadPlan {
+        init: DerivedUploadInit {
+            asset_uuid: claimed.asset_uuid.clone(),
+            revision_etag: String::new(),
+            kind,
+            content_type: content_type_for_kind(kind).to_string(),
+            size_bytes,
+            sha256: None,
+            idempotency_key: format!("init-{}-{}", claimed.job_id, kind.as_str()),
+        },
+        parts: split_upload_parts(
+            &claimed.asset_uuid,
+            &upload_id,
+            path,
+            size_bytes,
+            upload_part_size_bytes,
+        ),
+        complete: DerivedUploadComplete {
+            asset_uuid: claimed.asset_uuid.clone(),
+            revision_etag: String::new(),
+            upload_id,
+            idempotency_key: format!("complete-{}-{}", claimed.job_id, kind.as_str()),
+            parts: None,
+        },
+    };
+    attach_upload_integrity(&mut upload, path).map_err(DerivedJobExecutorError::Integrity)?;
+    Ok(upload)
+}
+
+fn hls_content_type(path: &Path) -> &'static str {
+    match path.extension().and_then(|value| value.to_str()) {
+        Some("m3u8") => content_type_for_kind(DerivedKind::PreviewVideoHls),
+        Some("mp4") => "video/mp4",
+        _ => "video/iso.segment",
+    }
+}
+
+fn hls_uploads_for_claimed_job(
+    claimed: &ClaimedDerivedJob,
+    files: &[PathBuf],
+    upload_part_size_bytes: u64,
+) -> Result<Vec<DerivedUploadPlan>, DerivedJobExecutorError> {
+    let kind = DerivedKind::PreviewVideoHls;
+    let mut uploads = Vec::with_capacity(files.len());
+
+    for path in files {
+        let file_name = path
+            .file_name()
+            .map(|value| value.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let size_bytes = std::fs::metadata(path)
+            .map_err(|error| DerivedJobExecutorError::Planner(error.to_string()))?
+            .len();
+        let upload_id = format!(
+            "upload-{}-preview-video-hls-{}",
+            claimed.asset_uuid,
+            file_name.replace(['.', '_'], "-")
+        );
+        let mut upload = DerivedUploadPlan {
+            init: DerivedUploadInit {
                 asset_uuid: claimed.asset_uuid.clone(),
                 revision_etag: String::new(),
-                upload_id: upload_id.clone(),
-                part_number: 1,
-                chunk_path: path.clone(),
-            }],
+                kind,
+                content_type: hls_content_type(path).to_string(),
+                size_bytes,
+                sha256: None,
+                idempotency_key: format!("init-{}-{}-{file_name}", claimed.job_id, kind.as_str()),
+            },
+            parts: split_upload_parts(
+                &claimed.asset_uuid,
+                &upload_id,
+                path,
+                size_bytes,
+                upload_part_size_bytes,
+            ),
             complete: DerivedUploadComplete {
                 asset_uuid: claimed.asset_uuid.clone(),
                 revision_etag: String::new(),
                 upload_id,
-                idempotency_key: format!("complete-{}-thumb-{}", claimed.job_id, index + 1),
+                idempotency_key: format!(
+                    "complete-{}-{}-{file_name}",
+                    claimed.job_id,
+                    kind.as_str()
+                ),
                 parts: None,
             },
-        });
+        };
+        attach_upload_integrity(&mut upload, path).map_err(DerivedJobExecutorError::Integrity)?;
+        uploads.push(upload);
     }
 
     Ok(uploads)
 }
 
+fn hls_manifest_for_claimed_job(
+    claimed: &ClaimedDerivedJob,
+    files: &[PathBuf],
+    uploads: &[DerivedUploadPlan],
+) -> Vec<DerivedManifestItem> {
+    let base_reference =
+        stable_core_derived_reference(&claimed.asset_uuid, DerivedKind::PreviewVideoHls);
+    files
+        .iter()
+        .zip(uploads)
+        .map(|(path, upload)| DerivedManifestItem {
+            kind: DerivedKind::PreviewVideoHls,
+            reference: format!(
+                "{base_reference}/{}",
+                path.file_name()
+                    .map(|value| value.to_string_lossy())
+                    .unwrap_or_default()
+            ),
+            size_bytes: Some(upload.init.size_bytes),
+            sha256: upload.init.sha256.clone(),
+        })
+        .collect()
+}
+
+fn loudness_metrics(loudness: &LoudnessMeasurement) -> HashMap<String, Value> {
+    let mut metrics = HashMap::new();
+    metrics.insert(
+        "loudness_integrated_lufs".to_string(),
+        Value::from(loudness.integrated_lufs),
+    );
+    metrics.insert(
+        "loudness_range_lu".to_string(),
+        Value::from(loudness.loudness_range_lu),
+    );
+    if let Some(true_peak_dbtp) = loudness.true_peak_dbtp {
+        metrics.insert("true_peak_dbtp".to_string(), Value::from(true_peak_dbtp));
+    }
+    metrics
+}
+
+fn photo_decode_metrics(decode_path: PhotoDecodePath) -> HashMap<String, Value> {
+    let mut metrics = HashMap::new();
+    metrics.insert(
+        "photo_decode_path".to_string(),
+        Value::from(decode_path.as_str()),
+    );
+    metrics
+}
+
+fn hls_ladder_metrics(renditions: &[VideoRendition]) -> HashMap<String, Value> {
+    let kind = DerivedKind::PreviewVideoHls;
+    let mut metrics = HashMap::new();
+    metrics.insert("preview_kind".to_string(), Value::from(kind.as_str()));
+    metrics.insert(
+        "preview_profile".to_string(),
+        Value::from(canonical_preview_profile_for_kind(kind)),
+    );
+    metrics.insert(
+        "preview_renditions".to_string(),
+        Value::from(
+            renditions
+                .iter()
+                .map(|rendition| rendition.name.clone())
+                .collect::<Vec<_>>(),
+        ),
+    );
+    metrics
+}
+
+fn thumbnail_sprite_metrics(layout: &SpriteSheetLayout) -> HashMap<String, Value> {
+    let mut metrics = HashMap::new();
+    metrics.insert(
+        "thumbnail_sprite_profile".to_string(),
+        Value::from(THUMBNAIL_SPRITE_PROFILE),
+    );
+    metrics.insert(
+        "thumbnail_sprite_tiles".to_string(),
+        Value::from(layout.tile_count),
+    );
+    metrics.insert(
+        "thumbnail_sprite_grid".to_string(),
+        Value::from(format!("{}x{}", layout.columns, layout.rows)),
+    );
+    metrics.insert(
+        "thumbnail_sprite_interval_ms".to_string(),
+        Value::from(layout.interval_ms),
+    );
+    metrics
+}
+
 struct GeneratedThumbnailArtifacts {
     profile: &'static str,
     files: Vec<PathBuf>,
+    photo_decode_path: Option<PhotoDecodePath>,
+    sprite: Option<GeneratedSpriteArtifacts>,
+}
+
+struct GeneratedSpriteArtifacts {
+    sheet_path: PathBuf,
+    vtt_path: PathBuf,
+    layout: SpriteSheetLayout,
+}
+
+impl GeneratedSpriteArtifacts {
+    fn files(&self) -> [(DerivedKind, &Path); 2] {
+        [
+            (DerivedKind::ThumbSprite, self.sheet_path.as_path()),
+            (DerivedKind::ThumbVtt, self.vtt_path.as_path()),
+        ]
+    }
 }
 
 fn canonical_preview_profile_for_kind(kind: DerivedKind) -> &'static str {
     match kind {
         DerivedKind::PreviewVideo => "video_review_default_v1",
+        DerivedKind::PreviewVideoHls => "video_review_hls_ladder_v1",
         DerivedKind::PreviewAudio => "audio_review_default_v1",
         DerivedKind::PreviewPhoto => "photo_review_default_v1",
-        DerivedKind::Thumb | DerivedKind::Waveform => "unsupported",
+        DerivedKind::Thumb
+        | DerivedKind::ThumbSprite
+        | DerivedKind::ThumbVtt
+        | DerivedKind::Waveform => "unsupported",
     }
 }
 
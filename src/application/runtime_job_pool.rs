@@ -0,0 +1,322 @@
+use std::collections::BTreeSet;
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::application::core_api_gateway::CoreApiGateway;
+use crate::application::derived_job_executor::{
+    DerivedExecutionPlanner, DerivedExecutionReport, DerivedJobExecutorError, JobExecutionContext,
+    execute_derived_job_once_internal, fail_idempotency_key,
+};
+use crate::application::derived_job_journal::{DerivedJobJournal, NoopDerivedJobJournal};
+use crate::application::derived_lease_keeper::DerivedJobLeaseRegistry;
+use crate::application::derived_processing_gateway::{
+    DerivedProcessingError, DerivedProcessingGateway,
+};
+use crate::application::gateway_retry::GatewayRetryPolicies;
+use crate::application::job_cancellation::{CancellationReason, CancellationToken};
+use crate::application::job_selection::{
+    JobSelectionContext, JobSelectionPolicy, job_selection_policy_for, select_pending_jobs,
+};
+use crate::application::runtime_job_worker::RuntimeJobWorkerError;
+use crate::application::runtime_session::RuntimeSession;
+use crate::domain::capabilities::declared_agent_capabilities;
+use crate::domain::runtime_status_tracker::{RuntimeStatusEvent, RuntimeStatusTracker};
+use crate::domain::runtime_ui::{AgentRunState, RuntimeSnapshot};
+
+#[derive(Debug)]
+pub struct RuntimeJobOutcome {
+    pub job_id: String,
+    pub started_at_unix_ms: u64,
+    pub duration_ms: u64,
+    pub result: Result<DerivedExecutionReport, DerivedJobExecutorError>,
+}
+
+#[derive(Debug)]
+pub struct AbandonedJob {
+    pub job_id: String,
+    // `None` when the worker held no live lease: not claimed yet, or lost.
+    pub release: Option<Result<(), DerivedProcessingError>>,
+}
+
+struct RunningJob {
+    job_id: String,
+    asset_uuid: Option<String>,
+    started_at: Instant,
+    started_at_unix_ms: u64,
+    cancellation: CancellationToken,
+    handle: JoinHandle<Result<DerivedExecutionReport, DerivedJobExecutorError>>,
+}
+
+pub struct RuntimeJobPool {
+    derived_gateway: Arc<dyn DerivedProcessingGateway>,
+    planner: Arc<dyn DerivedExecutionPlanner>,
+    journal: Arc<dyn DerivedJobJournal>,
+    leases: Arc<DerivedJobLeaseRegistry>,
+    selection_policy: Option<Arc<dyn JobSelectionPolicy>>,
+    declared_capabilities: BTreeSet<String>,
+    tracker: Arc<Mutex<RuntimeStatusTracker>>,
+    running: Vec<RunningJob>,
+}
+
+impl std::fmt::Debug for RuntimeJobPool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RuntimeJobPool")
+            .field("running_job_ids", &self.running_job_ids())
+            .finish()
+    }
+}
+
+impl RuntimeJobPool {
+    pub fn new(
+        derived_gateway: Arc<dyn DerivedProcessingGateway>,
+        planner: Arc<dyn DerivedExecutionPlanner>,
+    ) -> Self {
+        Self {
+            derived_gateway,
+            planner,
+            journal: Arc::new(NoopDerivedJobJournal),
+            leases: Arc::new(DerivedJobLeaseRegistry::default()),
+            selection_policy: None,
+            // Probed once: each probe spawns ffmpeg, ffprobe and libheif.
+            declared_capabilities: declared_agent_capabilities(),
+            tracker: Arc::new(Mutex::new(RuntimeStatusTracker::new())),
+            running: Vec::new(),
+        }
+    }
+
+    pub fn with_journal(mut self, journal: Arc<dyn DerivedJobJournal>) -> Self {
+        self.journal = journal;
+        self
+    }
+
+    pub fn with_selection_policy(mut self, selection_policy: Arc<dyn JobSelectionPolicy>) -> Self {
+        self.selection_policy = Some(selection_policy);
+        self
+    }
+
+    pub fn with_declared_capabilities(mut self, declared_capabilities: BTreeSet<String>) -> Self {
+        self.declared_capabilities = declared_capabilities;
+        self
+    }
+
+    pub fn replace_derived_gateway(&mut self, derived_gateway: Arc<dyn DerivedProcessingGateway>) {
+        self.derived_gateway = derived_gateway;
+    }
+
+    pub fn running_job_ids(&self) -> Vec<String> {
+        self.running.iter().map(|job| job.job_id.clone()).collect()
+    }
+
+    pub fn job_started_at_unix_ms(&self, job_id: &str) -> Option<u64> {
+        self.running
+            .iter()
+            .find(|job| job.job_id == job_id)
+            .map(|job| job.started_at_unix_ms)
+    }
+
+    pub fn is_idle(&self) -> bool {
+        self.running.is_empty()
+    }
+
+    pub fn available_slots(&self, max_parallel_jobs: u16) -> usize {
+        usize::from(max_parallel_jobs.max(1)).saturating_sub(self.running.len())
+    }
+
+    pub fn snapshot(&self) -> RuntimeSnapshot {
+        self.tracker
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .snapshot()
+            .clone()
+    }
+
+    pub fn take_snapshot(&mut self) -> RuntimeSnapshot {
+        let mut tracker = self
+            .tracker
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let snapshot = tracker.snapshot().clone();
+        tracker.forget_finished_jobs();
+        snapshot
+    }
+
+    pub fn cancel_all(&self, reason: CancellationReason) -> Vec<String> {
+        self.running
+            .iter()
+            .filter(|job| job.cancellation.cancel(reason))
+            .map(|job| job.job_id.clone())
+            .collect()
+    }
+
+    pub fn cancel_for_run_state(&self, run_state: AgentRunState) -> Vec<String> {
+        match run_state {
+            AgentRunState::Running => Vec::new(),
+            AgentRunState::Paused => self.cancel_all(CancellationReason::Paused),
+            AgentRunState::Stopped => self.cancel_all(CancellationReason::Stopped),
+        }
+    }
+
+    // Last resort once the drain is abandoned: cancel every job, give workers
+    // `wait` to stop, then fail the leases still held as retryable so Core can
+    // hand them out again without waiting for the lock to expire.
+    pub fn release_abandoned_jobs(&mut self, wait: Duration) -> Vec<AbandonedJob> {
+        self.cancel_all(CancellationReason::ShutdownDeadline);
+        let wait_until = Instant::now() + wait;
+        while !self.running.iter().all(|job| job.handle.is_finished())
+            && Instant::now() < wait_until
+        {
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        self.collect_finished();
+
+        let failure =
+            DerivedJobExecutorError::Cancelled(CancellationReason::ShutdownDeadline).job_failure();
+        self.running
+            .iter()
+            .map(|job| AbandonedJob {
+                job_id: job.job_id.clone(),
+                release: self.leases.live_claim(&job.job_id).map(|claimed| {
+                    self.derived_gateway.fail_job(
+                        &claimed.job_id,
+                        &claimed.lock_token,
+                        claimed.fencing_token,
+                        &fail_idempotency_key(&claimed),
+                        &failure,
+                    )
+                }),
+            })
+            .collect()
+    }
+
+    pub fn dispatch_pending_jobs<C: CoreApiGateway + ?Sized>(
+        &mut self,
+        session: &RuntimeSession,
+        core_gateway: &C,
+    ) -> Result<Vec<String>, RuntimeJobWorkerError> {
+        if !session.can_process_jobs()
+            || !session.can_issue_mutation()
+            || session.run_state() != AgentRunState::Running
+        {
+            return Ok(Vec::new());
+        }
+        let mut running: BTreeSet<String> = self.running_job_ids().into_iter().collect();
+        let mut started = Vec::new();
+        let journaled = self.journal.pending_job_ids().unwrap_or_default();
+        for job_id in journaled {
+            if self.available_slots(session.settings().max_parallel_jobs) == 0 {
+                return Ok(started);
+            }
+            if running.insert(job_id.clone()) {
+                let asset_uuid = self
+                    .journal
+                    .load(&job_id)
+                    .ok()
+                    .flatten()
+                    .map(|entry| entry.claimed.asset_uuid);
+                self.spawn_job(session, &job_id, asset_uuid);
+                started.push(job_id);
+            }
+        }
+        let slots = self.available_slots(session.settings().max_parallel_jobs);
+        if slots == 0 {
+            return Ok(started);
+        }
+
+        let jobs = core_gateway
+            .poll_jobs()
+            .map_err(RuntimeJobWorkerError::Poll)?;
+        let context = JobSelectionContext {
+            declared_capabilities: self.declared_capabilities.clone(),
+            running_job_ids: running,
+            busy_asset_uuids: self
+                .running
+                .iter()
+                .filter_map(|job| job.asset_uuid.clone())
+                .collect(),
+        };
+        let policy = self
+            .selection_policy
+            .clone()
+            .unwrap_or_else(|| job_selection_policy_for(session.settings().job_selection));
+        for job in select_pending_jobs(policy.as_ref(), jobs, &context, slots) {
+            self.spawn_job(session, &job.job_id, Some(job.asset_uuid));
+            started.push(job.job_id);
+        }
+        Ok(started)
+    }
+
+    pub fn collect_finished(&mut self) -> Vec<RuntimeJobOutcome> {
+        let (finished, running): (Vec<_>, Vec<_>) = std::mem::take(&mut self.running)
+            .into_iter()
+            .partition(|job| job.handle.is_finished());
+        self.running = running;
+
+        finished
+            .into_iter()
+            .map(|job| {
+                let result = job.handle.join().unwrap_or_else(|_| {
+                    let error = DerivedJobExecutorError::Planner("job worker panicked".to_string());
+                    self.tracker
+                        .lock()
+                        .unwrap_or_else(|poisoned| poisoned.into_inner())
+                        .apply(RuntimeStatusEvent::JobFailed {
+                            job_id: job.job_id.clone(),
+                            error_code: error.error_code().to_string(),
+                        });
+                    Err(error)
+                });
+                RuntimeJobOutcome {
+                    job_id: job.job_id,
+                    started_at_unix_ms: job.started_at_unix_ms,
+                    duration_ms: job.started_at.elapsed().as_millis() as u64,
+                    result,
+                }
+            })
+            .collect()
+    }
+
+    fn spawn_job(&mut self, session: &RuntimeSession, job_id: &str, asset_uuid: Option<String>) {
+        let derived_gateway = Arc::clone(&self.derived_gateway);
+        let planner = Arc::clone(&self.planner);
+        let journal = Arc::clone(&self.journal);
+        let leases = Arc::clone(&self.leases);
+        let tracker = Arc::clone(&self.tracker);
+        let settings = session.settings().clone();
+        let worker_job_id = job_id.to_string();
+        let cancellation = CancellationToken::new();
+        let worker_cancellation = cancellation.clone();
+        tracker
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .apply(RuntimeStatusEvent::JobDiscovered {
+                job_id: job_id.to_string(),
+            });
+        let handle = std::thread::spawn(move || {
+            execute_derived_job_once_internal(
+                derived_gateway.as_ref(),
+                planner.as_ref(),
+                &worker_job_id,
+                &JobExecutionContext {
+                    settings: Some(&settings),
+                    progress: tracker.as_ref(),
+                    journal: journal.as_ref(),
+                    cancellation: &worker_cancellation,
+                    retry_policies: GatewayRetryPolicies::default(),
+                    leases: Some(leases.as_ref()),
+                },
+            )
+        });
+        self.running.push(RunningJob {
+            job_id: job_id.to_string(),
+            asset_uuid,
+            started_at: Instant::now(),
+            started_at_unix_ms: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|elapsed| elapsed.as_millis() as u64)
+                .unwrap_or(0),
+            cancellation,
+            handle,
+        });
+    }
+}
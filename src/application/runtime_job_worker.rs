@@ -1,14 +1,16 @@
 use thiserror::Error;
 
-use crate::application::core_api_gateway::{
-    CoreApiGateway, CoreApiGatewayError, CoreJobState, filter_jobs_for_declared_capabilities,
-};
+use crate::application::core_api_gateway::{CoreApiGateway, CoreApiGatewayError};
 use crate::application::derived_job_executor::{
     DerivedExecutionPlanner, DerivedExecutionReport, DerivedJobExecutorError,
     execute_derived_job_once_with_source_staging,
 };
 use crate::application::derived_processing_gateway::DerivedProcessingGateway;
+use crate::application::job_selection::{
+    JobSelectionContext, job_selection_policy_for, select_pending_jobs,
+};
 use crate::application::runtime_session::RuntimeSession;
+use crate::domain::capabilities::declared_agent_capabilities;
 
 #[derive(Debug, Clone, PartialEq, Eq, Error)]
 pub enum RuntimeJobWorkerError {
@@ -34,12 +36,16 @@ pub fn process_next_pending_job<
     let jobs = core_gateway
         .poll_jobs()
         .map_err(RuntimeJobWorkerError::Poll)?;
-    let filtered = filter_jobs_for_declared_capabilities(jobs);
-    let next_pending = filtered
-        .iter()
-        .find(|job| matches!(job.state, CoreJobState::Pending))
-        .map(|job| job.job_id.clone());
-    let Some(job_id) = next_pending else {
+    let context = JobSelectionContext {
+        declared_capabilities: declared_agent_capabilities(),
+        ..JobSelectionContext::default()
+    };
+    let policy = job_selection_policy_for(session.settings().job_selection);
+    let Some(job_id) = select_pending_jobs(policy.as_ref(), jobs, &context, 1)
+        .into_iter()
+        .next()
+        .map(|job| job.job_id)
+    else {
         return Ok(None);
     };
 
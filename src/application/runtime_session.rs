@@ -10,6 +10,7 @@ use crate::domain::runtime_control::RuntimeControlCommand;
 use crate::domain::runtime_orchestration::{
     ClientRuntimeTarget, PollEndpoint, PollSignal, PushChannel, PushHint,
 };
+use crate::domain::runtime_status_tracker::merge_local_job_snapshot;
 use crate::domain::runtime_ui::{AgentRunState, MenuAction, RuntimeSnapshot, SystemNotification};
 
 #[derive(Debug, Clone)]
@@ -17,6 +18,8 @@ pub struct RuntimeSession {
     app: AgentRuntimeApp,
     loop_engine: RuntimeLoopEngine,
     server_policy: CoreServerPolicy,
+    remote_snapshot: RuntimeSnapshot,
+    local_jobs: RuntimeSnapshot,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -34,6 +37,8 @@ impl RuntimeSession {
             app: AgentRuntimeApp::new(settings)?,
             loop_engine: RuntimeLoopEngine::new(target),
             server_policy: CoreServerPolicy::default(),
+            remote_snapshot: RuntimeSnapshot::default(),
+            local_jobs: RuntimeSnapshot::default(),
         })
     }
 
@@ -121,7 +126,8 @@ impl RuntimeSession {
     }
 
     pub fn update_snapshot(&mut self, snapshot: RuntimeSnapshot) -> Vec<SystemNotification> {
-        self.app.update_snapshot(snapshot)
+        self.remote_snapshot = snapshot;
+        self.publish_merged_snapshot()
     }
 
     pub fn update_snapshot_and_dispatch<S: NotificationSink>(
@@ -129,7 +135,7 @@ impl RuntimeSession {
         snapshot: RuntimeSnapshot,
         sink: &S,
     ) -> RuntimeNotificationReport {
-        let notifications = self.app.update_snapshot(snapshot);
+        let notifications = self.update_snapshot(snapshot);
         let dispatch = dispatch_notifications(sink, &notifications);
         RuntimeNotificationReport {
             notifications,
@@ -137,6 +143,27 @@ impl RuntimeSession {
         }
     }
 
+    pub fn update_local_jobs_and_dispatch<S: NotificationSink>(
+        &mut self,
+        local_jobs: RuntimeSnapshot,
+        sink: &S,
+    ) -> RuntimeNotificationReport {
+        self.local_jobs = local_jobs;
+        let notifications = self.publish_merged_snapshot();
+        let dispatch = dispatch_notifications(sink, &notifications);
+        RuntimeNotificationReport {
+            notifications,
+            dispatch,
+        }
+    }
+
+    fn publish_merged_snapshot(&mut self) -> Vec<SystemNotification> {
+        self.app.update_snapshot(merge_local_job_snapshot(
+            &self.remote_snapshot,
+            &self.local_jobs,
+        ))
+    }
+
     pub fn can_issue_mutation(&self) -> bool {
         self.loop_engine.can_issue_mutation()
     }
@@ -0,0 +1,71 @@
+use std::time::{Duration, Instant};
+
+pub const DEFAULT_DRAIN_DEADLINE: Duration = Duration::from_secs(60);
+pub const DRAIN_RELEASE_GRACE: Duration = Duration::from_secs(15);
+// Last wait for cancelled workers once abandoned, before their leases are
+// failed from the outside.
+pub const DRAIN_ABANDON_WAIT: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DrainPhase {
+    // Waiting for in-flight jobs to finish on their own.
+    Draining,
+    // Deadline passed: jobs are cancelled and released back to Core as retryable.
+    Releasing,
+    // Workers did not acknowledge cancellation in time; the daemon exits anyway.
+    Abandoned,
+}
+
+impl DrainPhase {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Draining => "draining",
+            Self::Releasing => "releasing",
+            Self::Abandoned => "abandoned",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ShutdownDrain {
+    started_at: Instant,
+    started_at_unix_ms: u64,
+    deadline: Duration,
+    release_grace: Duration,
+}
+
+impl ShutdownDrain {
+    pub fn start(deadline: Duration, now: Instant, now_unix_ms: u64) -> Self {
+        Self {
+            started_at: now,
+            started_at_unix_ms: now_unix_ms,
+            deadline,
+            release_grace: DRAIN_RELEASE_GRACE,
+        }
+    }
+
+    pub fn with_release_grace(mut self, release_grace: Duration) -> Self {
+        self.release_grace = release_grace;
+        self
+    }
+
+    pub fn phase(&self, now: Instant) -> DrainPhase {
+        let elapsed = now.saturating_duration_since(self.started_at);
+        if elapsed < self.deadline {
+            DrainPhase::Draining
+        } else if elapsed < self.deadline.saturating_add(self.release_grace) {
+            DrainPhase::Releasing
+        } else {
+            DrainPhase::Abandoned
+        }
+    }
+
+    pub fn started_at_unix_ms(&self) -> u64 {
+        self.started_at_unix_ms
+    }
+
+    pub fn deadline_at_unix_ms(&self) -> u64 {
+        self.started_at_unix_ms
+            .saturating_add(self.deadline.as_millis() as u64)
+    }
+}
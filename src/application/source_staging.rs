@@ -7,7 +7,7 @@ use crate::{AgentRuntimeConfig, resolve_source_path};
 
 #[derive(Debug)]
 pub struct StagedSourceFile {
-    _temp_dir: tempfile::TempDir,
+    temp_dir: tempfile::TempDir,
     path: PathBuf,
     sidecar_paths: Vec<PathBuf>,
     pub size_bytes: u64,
@@ -21,6 +21,10 @@ impl StagedSourceFile {
     pub fn sidecar_paths(&self) -> &[PathBuf] {
         &self.sidecar_paths
     }
+
+    pub fn staging_dir(&self) -> &Path {
+        self.temp_dir.path()
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Error)]
@@ -42,6 +46,22 @@ pub enum SourceStagingError {
     Copy(String),
 }
 
+impl SourceStagingError {
+    pub fn error_code(&self) -> &'static str {
+        match self {
+            Self::ResolvePath(_) => "SOURCE_PATH_UNRESOLVED",
+            Self::SourceIo(_) => "SOURCE_UNREADABLE",
+            Self::SourceNotFile(_) => "SOURCE_NOT_FILE",
+            Self::InsufficientDiskSpace { .. } => "INSUFFICIENT_DISK_SPACE",
+            Self::Copy(_) => "SOURCE_STAGING_COPY_FAILED",
+        }
+    }
+
+    pub fn is_retryable(&self) -> bool {
+        !matches!(self, Self::SourceNotFile(_))
+    }
+}
+
 pub trait DiskSpaceProbe {
     fn available_space(&self, path: &Path) -> Result<u64, SourceStagingError>;
 }
@@ -105,7 +125,7 @@ pub fn stage_claimed_job_source_with_probe<P: DiskSpaceProbe>(
     }
 
     Ok(StagedSourceFile {
-        _temp_dir: temp_dir,
+        temp_dir,
         path: staged_path,
         sidecar_paths: staged_sidecars,
         size_bytes: required,
@@ -0,0 +1,105 @@
+use std::fmt::Write;
+
+pub const THUMBNAIL_SPRITE_PROFILE: &str = "video_scrub_sprite_v1";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ThumbnailSpriteProfile {
+    pub interval_ms: u64,
+    pub tile_width: u16,
+    pub columns: u16,
+    pub max_tiles: u16,
+}
+
+impl Default for ThumbnailSpriteProfile {
+    fn default() -> Self {
+        Self {
+            interval_ms: 5_000,
+            tile_width: 160,
+            columns: 10,
+            max_tiles: 100,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpriteSheetLayout {
+    pub interval_ms: u64,
+    pub tile_width: u16,
+    pub tile_height: u16,
+    pub columns: u16,
+    pub rows: u16,
+    pub tile_count: u16,
+}
+
+impl ThumbnailSpriteProfile {
+    // Long videos widen the interval so the sheet never exceeds `max_tiles`;
+    // tiles keep the source aspect ratio (16:9 when unknown).
+    pub fn layout_for(
+        &self,
+        duration_ms: u64,
+        source_width: Option<u32>,
+        source_height: Option<u32>,
+    ) -> Option<SpriteSheetLayout> {
+        if duration_ms == 0 || self.tile_width == 0 {
+            return None;
+        }
+        let max_tiles = u64::from(self.max_tiles.max(1));
+        let interval_ms = self.interval_ms.max(1).max(duration_ms.div_ceil(max_tiles));
+        let tile_count = u16::try_from(duration_ms.div_ceil(interval_ms).min(max_tiles)).ok()?;
+        let columns = self.columns.clamp(1, tile_count);
+        let rows = tile_count.div_ceil(columns);
+        let (width, height) = match (source_width, source_height) {
+            (Some(width), Some(height)) if width > 0 && height > 0 => {
+                (u64::from(width), u64::from(height))
+            }
+            _ => (16, 9),
+        };
+        let tile_height = (u64::from(self.tile_width) * height / width / 2 * 2).clamp(2, 4_096);
+        Some(SpriteSheetLayout {
+            interval_ms,
+            tile_width: self.tile_width,
+            tile_height: u16::try_from(tile_height).ok()?,
+            columns,
+            rows,
+            tile_count,
+        })
+    }
+}
+
+// One cue per tile, pointing at the tile with a media fragment
+// (`#xywh=x,y,w,h`) as expected by hover-scrub players.
+pub fn sprite_sheet_webvtt(
+    layout: &SpriteSheetLayout,
+    duration_ms: u64,
+    sprite_reference: &str,
+) -> String {
+    let mut vtt = String::from("WEBVTT\n");
+    for index in 0..layout.tile_count {
+        let start_ms = u64::from(index) * layout.interval_ms;
+        if start_ms >= duration_ms {
+            break;
+        }
+        let end_ms = (start_ms + layout.interval_ms).min(duration_ms);
+        let x = u32::from(index % layout.columns) * u32::from(layout.tile_width);
+        let y = u32::from(index / layout.columns) * u32::from(layout.tile_height);
+        let _ = write!(
+            vtt,
+            "\n{} --> {}\n{sprite_reference}#xywh={x},{y},{},{}\n",
+            webvtt_timestamp(start_ms),
+            webvtt_timestamp(end_ms),
+            layout.tile_width,
+            layout.tile_height
+        );
+    }
+    vtt
+}
+
+fn webvtt_timestamp(ms: u64) -> String {
+    format!(
+        "{:02}:{:02}:{:02}.{:03}",
+        ms / 3_600_000,
+        (ms / 60_000) % 60,
+        (ms / 1_000) % 60,
+        ms % 1_000
+    )
+}
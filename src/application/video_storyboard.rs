@@ -0,0 +1,64 @@
+use crate::application::proxy_generator::VideoFrameSample;
+
+pub const STORYBOARD_FRAME_COUNT: usize = 9;
+pub const SCENE_CHANGE_THRESHOLD: f32 = 0.3;
+// Limited-range black sits at 16; fades and slates stay just above it.
+pub const NEAR_BLACK_LUMA: f32 = 28.0;
+pub const MIN_LUMA_SPREAD: f32 = 12.0;
+
+const MIN_SAMPLE_INTERVAL_MS: u64 = 500;
+const MAX_ANALYSIS_SAMPLES: u64 = 600;
+
+// Denser sampling on short clips, bounded sample count on long ones.
+pub fn storyboard_sample_interval_ms(duration_ms: u64) -> u64 {
+    (duration_ms / MAX_ANALYSIS_SAMPLES).max(MIN_SAMPLE_INTERVAL_MS)
+}
+
+pub fn frame_is_usable(sample: &VideoFrameSample) -> bool {
+    sample.luma_mean >= NEAR_BLACK_LUMA && sample.luma_spread >= MIN_LUMA_SPREAD
+}
+
+// Splits samples into scenes on scene changes and keeps, for each scene, the
+// usable frame closest to its middle. Scenes without a usable frame are
+// dropped; when there are more scenes than `max_frames`, the longest win.
+pub fn select_storyboard_frames(samples: &[VideoFrameSample], max_frames: usize) -> Vec<u64> {
+    let mut samples = samples.to_vec();
+    samples.sort_by_key(|sample| sample.timestamp_ms);
+
+    let mut scenes: Vec<&[VideoFrameSample]> = Vec::new();
+    let mut start = 0;
+    for (index, sample) in samples.iter().enumerate().skip(1) {
+        if sample.scene_score >= SCENE_CHANGE_THRESHOLD {
+            scenes.push(&samples[start..index]);
+            start = index;
+        }
+    }
+    if start < samples.len() {
+        scenes.push(&samples[start..]);
+    }
+
+    let mut picks = scenes
+        .iter()
+        .filter_map(|scene| {
+            let first = scene.first()?.timestamp_ms;
+            let last = scene.last()?.timestamp_ms;
+            let middle = first + (last - first) / 2;
+            let pick = scene
+                .iter()
+                .filter(|sample| frame_is_usable(sample))
+                .min_by_key(|sample| sample.timestamp_ms.abs_diff(middle))?;
+            Some((last - first, pick.timestamp_ms))
+        })
+        .collect::<Vec<_>>();
+
+    if picks.len() > max_frames {
+        picks.sort_by(|left, right| right.0.cmp(&left.0).then(left.1.cmp(&right.1)));
+        picks.truncate(max_frames);
+    }
+    let mut seek_points = picks
+        .into_iter()
+        .map(|(_, timestamp_ms)| timestamp_ms)
+        .collect::<Vec<_>>();
+    seek_points.sort_unstable();
+    seek_points
+}
@@ -969,6 +969,8 @@ mod desktop_shell {
                 storage_mounts,
                 max_parallel_jobs,
                 log_level: self.log_level,
+                job_selection: current.job_selection,
+                upload_part_size_bytes: current.upload_part_size_bytes,
             };
             validate_config(&config)
                 .map_err(|errors| compact_validation_reason(&errors))
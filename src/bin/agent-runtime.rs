@@ -6,13 +6,16 @@ use std::time::{Duration, Instant};
 
 use clap::{Args, Parser, Subcommand};
 use retaia_agent::{
-    AgentRuntimeConfig, AuthMode, ClientRuntimeTarget, CompletedJobEntry, ConfigRepository,
-    CoreApiGateway, DaemonCurrentJobStats, DaemonCycleEntry, DaemonLastJobStats,
-    DaemonRuntimeStats, DerivedProcessingGateway, FileConfigRepository, LogLevel,
-    RuntimeDerivedPlanner, RuntimeHistoryStore, RuntimePollCycleStatus, RuntimeSession,
+    AgentRunState, AgentRuntimeConfig, AudioWaveformFormat, AuthMode, CancellationReason,
+    ClientRuntimeTarget, CompletedJobEntry, ConfigRepository, CoreApiGateway,
+    DEFAULT_DRAIN_DEADLINE, DRAIN_ABANDON_WAIT, DaemonCurrentJobStats, DaemonCycleEntry,
+    DaemonDrainStats, DaemonLastJobStats, DaemonRuntimeStats, DerivedProcessingGateway, DrainPhase,
+    FfmpegProxyGenerator, FileConfigRepository, LogLevel, MediaTimeoutOverride, MediaToolTimeouts,
+    RuntimeDerivedPlanner, RuntimeHistoryStore, RuntimeJobPool, RuntimePollCycleStatus,
+    RuntimeSession, RustPhotoProxyGenerator, ShutdownDrain, SqliteDerivedJobJournal,
     SystemConfigRepository, compact_validation_reason, detect_language,
-    notification_sink_profile_for_target, now_unix_ms, process_next_pending_job,
-    run_runtime_poll_cycle, run_state_label, save_runtime_stats, select_notification_sink, t,
+    notification_sink_profile_for_target, now_unix_ms, run_runtime_poll_cycle, run_state_label,
+    save_runtime_stats, select_notification_sink, t,
 };
 use tracing::{info, warn};
 
@@ -42,6 +45,46 @@ enum ModeCommand {
 struct DaemonArgs {
     #[arg(long = "tick-ms", default_value_t = 5000)]
     tick_ms: u64,
+    #[arg(long = "drain-deadline-secs", default_value_t = DEFAULT_DRAIN_DEADLINE.as_secs())]
+    drain_deadline_secs: u64,
+    #[arg(long = "upload-part-size-bytes", value_parser = clap::value_parser!(u64).range(1..))]
+    upload_part_size_bytes: Option<u64>,
+    #[arg(long = "media-timeout", value_parser = MediaTimeoutOverride::parse)]
+    media_timeouts: Vec<MediaTimeoutOverride>,
+    #[arg(long = "scene-storyboard", default_value_t = false)]
+    scene_storyboard: bool,
+    #[arg(long = "waveform-v2", default_value_t = false)]
+    waveform_v2: bool,
+}
+
+fn daemon_planner(args: &DaemonArgs, settings: &AgentRuntimeConfig) -> RuntimeDerivedPlanner {
+    let mut planner = if args.media_timeouts.is_empty() {
+        RuntimeDerivedPlanner::default()
+    } else {
+        let timeouts = args
+            .media_timeouts
+            .iter()
+            .fold(MediaToolTimeouts::default(), |timeouts, timeout| {
+                timeouts.with_override(*timeout)
+            });
+        RuntimeDerivedPlanner::new(
+            Arc::new(FfmpegProxyGenerator::default().with_timeouts(timeouts)),
+            Arc::new(RustPhotoProxyGenerator::default().with_timeouts(timeouts)),
+        )
+    };
+    if let Some(upload_part_size_bytes) = args
+        .upload_part_size_bytes
+        .or(settings.upload_part_size_bytes)
+    {
+        planner = planner.with_upload_part_size_bytes(upload_part_size_bytes);
+    }
+    if args.scene_storyboard {
+        planner = planner.with_scene_storyboard(true);
+    }
+    if args.waveform_v2 {
+        planner = planner.with_waveform_format(AudioWaveformFormat::PeaksV2);
+    }
+    planner
 }
 
 fn run() -> Result<(), String> {
@@ -73,7 +116,16 @@ fn run_with_repository<R: ConfigRepository>(
         })?;
 
     match cli.mode {
-        Some(ModeCommand::Daemon(args)) => run_daemon_loop(&mut session, repository, args.tick_ms),
+        Some(ModeCommand::Daemon(args)) => {
+            let planner = daemon_planner(&args, session.settings());
+            run_daemon_loop(
+                &mut session,
+                repository,
+                args.tick_ms,
+                Duration::from_secs(args.drain_deadline_secs),
+                planner,
+            )
+        }
         None => Err(t(lang, "runtime.interactive_disabled").to_string()),
     }
 }
@@ -82,6 +134,8 @@ fn run_daemon_loop<R: ConfigRepository>(
     session: &mut RuntimeSession,
     repository: &R,
     tick_ms: u64,
+    drain_deadline: Duration,
+    planner: RuntimeDerivedPlanner,
 ) -> Result<(), String> {
     const COMPACTION_INTERVAL_TICKS: u64 = 600;
     const KEEP_LAST_CYCLES: usize = 250_000;
@@ -100,9 +154,14 @@ fn run_daemon_loop<R: ConfigRepository>(
     }
     #[cfg_attr(not(feature = "core-api-client"), allow(unused_mut))]
     let mut gateway = build_gateway(session.settings());
-    #[cfg_attr(not(feature = "core-api-client"), allow(unused_mut))]
-    let mut derived_gateway = build_derived_gateway(session.settings());
-    let planner = RuntimeDerivedPlanner::default();
+    let mut job_pool = RuntimeJobPool::new(
+        Arc::from(build_derived_gateway(session.settings())),
+        Arc::new(planner),
+    );
+    match SqliteDerivedJobJournal::open_default() {
+        Ok(journal) => job_pool = job_pool.with_journal(Arc::new(journal)),
+        Err(error) => warn!(error = %error, "{}", t(lang, "runtime.job_journal_unavailable")),
+    }
     let sink = select_notification_sink(notification_sink_profile_for_target(session.target()));
     let sleep_duration = Duration::from_millis(tick_ms.max(100));
     let mut next_policy_poll_at = Instant::now();
@@ -115,15 +174,12 @@ fn run_daemon_loop<R: ConfigRepository>(
         }
     };
     let mut tick = 0_u64;
-    let mut current_job_id: Option<String> = None;
-    let mut current_job_started_at: Option<Instant> = None;
-    let mut current_job_started_at_unix_ms: Option<u64> = None;
     let mut last_job: Option<DaemonLastJobStats> = None;
     let mut last_cycle_fingerprint: Option<String> = None;
     let mut last_persisted_cycle_tick: u64 = 0;
     let mut last_outcome_status = RuntimePollCycleStatus::Success;
     let shutdown_requested = install_shutdown_signal()?;
-    let mut shutdown_log_emitted = false;
+    let mut shutdown_drain: Option<ShutdownDrain> = None;
     info!(
         target = ?session.target(),
         run_state = ?session.run_state(),
@@ -143,7 +199,8 @@ fn run_daemon_loop<R: ConfigRepository>(
             if outcome.auth_changed {
                 register_daemon_agent(session.settings())?;
                 gateway = build_gateway(session.settings());
-                derived_gateway = build_derived_gateway(session.settings());
+                job_pool
+                    .replace_derived_gateway(Arc::from(build_derived_gateway(session.settings())));
                 next_policy_poll_at = Instant::now();
                 next_jobs_poll_at = Instant::now();
             }
@@ -177,12 +234,62 @@ fn run_daemon_loop<R: ConfigRepository>(
             }
             None
         };
+        if outcome.is_some() && !shutdown_requested.load(Ordering::Relaxed) {
+            match job_pool.dispatch_pending_jobs(session, gateway.as_ref()) {
+                Ok(started) => {
+                    for job_id in started {
+                        info!(tick, job_id = %job_id, "runtime started pending job");
+                    }
+                }
+                Err(error) => {
+                    warn!(tick, error = %error, "runtime processing pass failed");
+                }
+            }
+        }
+        for job_id in job_pool.cancel_for_run_state(session.run_state()) {
+            info!(tick, job_id = %job_id, run_state = ?session.run_state(), "runtime cancelling in-flight job");
+        }
+        for finished in job_pool.collect_finished() {
+            match &finished.result {
+                Ok(report) => {
+                    info!(
+                        tick,
+                        job_id = %report.job_id,
+                        asset_uuid = %report.asset_uuid,
+                        uploads = report.upload_count,
+                        "runtime processed one pending job"
+                    );
+                }
+                Err(error) => {
+                    warn!(tick, job_id = %finished.job_id, error = %error, "runtime job failed");
+                }
+            }
+            let completed = DaemonLastJobStats {
+                job_id: finished.job_id,
+                duration_ms: finished.duration_ms,
+                completed_at_unix_ms: now_unix_ms(),
+            };
+            if let Some(store) = history_store.as_mut() {
+                let entry = CompletedJobEntry {
+                    completed_at_unix_ms: completed.completed_at_unix_ms,
+                    job_id: completed.job_id.clone(),
+                    duration_ms: completed.duration_ms,
+                };
+                if let Err(error) = store.insert_completed_job(&entry) {
+                    warn!(tick, error = %error, "{}", t(lang, "runtime.persist_completed_failed"));
+                }
+            }
+            last_job = Some(completed);
+        }
+        session.update_local_jobs_and_dispatch(job_pool.take_snapshot(), &sink);
+
         let status = session.status_view();
-        if let Some(job) = status.current_job {
+        if let Some(job) = status.current_job.as_ref() {
             info!(
                 tick,
                 outcome = ?last_outcome_status,
                 run_state = ?status.run_state,
+                running_jobs = job_pool.running_job_ids().len(),
                 job_id = %job.job_id,
                 asset_uuid = %job.asset_uuid,
                 progress_percent = job.progress_percent,
@@ -203,104 +310,68 @@ fn run_daemon_loop<R: ConfigRepository>(
         if outcome.as_ref().map(|cycle| cycle.status) == Some(RuntimePollCycleStatus::Throttled) {
             warn!(tick, "{}", t(lang, "runtime.throttled"));
         }
-        if outcome.is_some() {
-            match process_next_pending_job(
-                session,
-                gateway.as_ref(),
-                derived_gateway.as_ref(),
-                &planner,
-            ) {
-                Ok(Some(report)) => {
-                    info!(
-                        tick,
-                        job_id = %report.job_id,
-                        asset_uuid = %report.asset_uuid,
-                        uploads = report.upload_count,
-                        "runtime processed one pending job"
-                    );
-                }
-                Ok(None) => {}
-                Err(error) => {
-                    warn!(tick, error = %error, "runtime processing pass failed");
+        if shutdown_requested.load(Ordering::Relaxed) {
+            let drain = *shutdown_drain.get_or_insert_with(|| {
+                info!(
+                    tick,
+                    drain_deadline_secs = drain_deadline.as_secs(),
+                    "shutdown requested: draining active jobs before stop"
+                );
+                ShutdownDrain::start(drain_deadline, now, now_unix_ms())
+            });
+            let phase = drain.phase(now);
+            if phase != DrainPhase::Draining {
+                for job_id in job_pool.cancel_all(CancellationReason::ShutdownDeadline) {
+                    warn!(tick, job_id = %job_id, "drain deadline reached: releasing in-flight job");
                 }
             }
-        }
-        let status = session.status_view();
-        let current_job_snapshot = status.current_job.clone();
-        match current_job_snapshot.as_ref() {
-            Some(job) => match current_job_id.as_deref() {
-                Some(existing) if existing == job.job_id.as_str() => {}
-                Some(existing) => {
-                    if let Some(started) = current_job_started_at.take() {
-                        let completed = DaemonLastJobStats {
-                            job_id: existing.to_string(),
-                            duration_ms: started.elapsed().as_millis() as u64,
-                            completed_at_unix_ms: now_unix_ms(),
-                        };
-                        if let Some(store) = history_store.as_mut() {
-                            let entry = CompletedJobEntry {
-                                completed_at_unix_ms: completed.completed_at_unix_ms,
-                                job_id: completed.job_id.clone(),
-                                duration_ms: completed.duration_ms,
-                            };
-                            if let Err(error) = store.insert_completed_job(&entry) {
-                                warn!(tick, error = %error, "{}", t(lang, "runtime.persist_completed_failed"));
-                            }
+            if job_pool.is_idle() || phase == DrainPhase::Abandoned {
+                if job_pool.is_idle() {
+                    info!(tick, "graceful shutdown complete");
+                } else {
+                    for abandoned in job_pool.release_abandoned_jobs(DRAIN_ABANDON_WAIT) {
+                        match abandoned.release {
+                            Some(Ok(())) => warn!(
+                                tick,
+                                job_id = %abandoned.job_id,
+                                "drain release grace expired: lease released to Core"
+                            ),
+                            Some(Err(error)) => warn!(
+                                tick,
+                                job_id = %abandoned.job_id,
+                                error = %error,
+                                "drain release grace expired: could not release lease"
+                            ),
+                            None => warn!(
+                                tick,
+                                job_id = %abandoned.job_id,
+                                "drain release grace expired: job was not claimed yet"
+                            ),
                         }
-                        last_job = Some(completed);
                     }
-                    current_job_id = Some(job.job_id.clone());
-                    current_job_started_at = Some(Instant::now());
-                    current_job_started_at_unix_ms = Some(now_unix_ms());
                 }
-                None => {
-                    current_job_id = Some(job.job_id.clone());
-                    current_job_started_at = Some(Instant::now());
-                    current_job_started_at_unix_ms = Some(now_unix_ms());
-                }
-            },
-            None => {
-                if let Some(existing) = current_job_id.take()
-                    && let Some(started) = current_job_started_at.take()
-                {
-                    let completed = DaemonLastJobStats {
-                        job_id: existing,
-                        duration_ms: started.elapsed().as_millis() as u64,
-                        completed_at_unix_ms: now_unix_ms(),
-                    };
-                    if let Some(store) = history_store.as_mut() {
-                        let entry = CompletedJobEntry {
-                            completed_at_unix_ms: completed.completed_at_unix_ms,
-                            job_id: completed.job_id.clone(),
-                            duration_ms: completed.duration_ms,
-                        };
-                        if let Err(error) = store.insert_completed_job(&entry) {
-                            warn!(tick, error = %error, "{}", t(lang, "runtime.persist_completed_failed"));
-                        }
-                    }
-                    last_job = Some(completed);
+                let stats = DaemonRuntimeStats {
+                    run_state: run_state_label(AgentRunState::Stopped).to_string(),
+                    last_job: last_job.clone(),
+                    ..DaemonRuntimeStats::new_idle(tick)
+                };
+                if let Err(error) = save_runtime_stats(&stats) {
+                    warn!(tick, error = %error, "{}", t(lang, "runtime.persist_stats_failed"));
                 }
-                current_job_started_at_unix_ms = None;
-            }
-        }
-        if shutdown_requested.load(Ordering::Relaxed) {
-            if !shutdown_log_emitted {
-                info!(tick, "shutdown requested: draining active job before stop");
-                shutdown_log_emitted = true;
-            }
-            if current_job_id.is_none() {
-                info!(tick, "graceful shutdown complete");
                 return Ok(());
             }
         }
 
+        let current_job_snapshot = status.current_job.clone();
         let current_job = current_job_snapshot.map(|job| DaemonCurrentJobStats {
-            job_id: job.job_id,
+            job_id: job.job_id.clone(),
             asset_uuid: job.asset_uuid,
             progress_percent: job.progress_percent,
             stage: format!("{:?}", job.stage).to_lowercase(),
             status: job.short_status,
-            started_at_unix_ms: current_job_started_at_unix_ms.unwrap_or_else(now_unix_ms),
+            started_at_unix_ms: job_pool
+                .job_started_at_unix_ms(&job.job_id)
+                .unwrap_or_else(now_unix_ms),
         });
         let stats = DaemonRuntimeStats {
             updated_at_unix_ms: now_unix_ms(),
@@ -308,6 +379,12 @@ fn run_daemon_loop<R: ConfigRepository>(
             tick,
             current_job,
             last_job: last_job.clone(),
+            drain: shutdown_drain.map(|drain| DaemonDrainStats {
+                phase: drain.phase(now).as_str().to_string(),
+                started_at_unix_ms: drain.started_at_unix_ms(),
+                deadline_at_unix_ms: drain.deadline_at_unix_ms(),
+                active_job_ids: job_pool.running_job_ids(),
+            }),
         };
         if let Err(error) = save_runtime_stats(&stats) {
             warn!(tick, error = %error, "{}", t(lang, "runtime.persist_stats_failed"));
@@ -519,6 +596,8 @@ where
                     clear_storage_mounts: false,
                     max_parallel_jobs: None,
                     log_level: None,
+                    job_selection: None,
+                    upload_part_size_bytes: None,
                 },
                 ConfigInterface::Cli,
             )
@@ -839,6 +918,19 @@ impl DerivedProcessingGateway for FeatureDisabledDerivedGateway {
         ))
     }
 
+    fn fail_job(
+        &self,
+        _job_id: &str,
+        _lock_token: &str,
+        _fencing_token: i32,
+        _idempotency_key: &str,
+        _failure: &retaia_agent::DerivedJobFailure,
+    ) -> Result<(), DerivedProcessingError> {
+        Err(DerivedProcessingError::Transport(
+            "core-api-client feature is disabled for this build".to_string(),
+        ))
+    }
+
     fn upload_init(
         &self,
         _request: &retaia_agent::DerivedUploadInit,
@@ -885,7 +977,8 @@ mod tests {
     };
     use retaia_agent::{
         AgentRuntimeConfig, AuthMode, ClientRuntimeTarget, CoreApiGateway, CoreApiGatewayError,
-        CoreServerPolicy, LogLevel, PollEndpoint, RuntimeSession, RuntimeSyncPlan,
+        CoreServerPolicy, JobSelectionStrategy, LogLevel, PollEndpoint, RuntimeSession,
+        RuntimeSyncPlan,
     };
     use std::collections::BTreeMap;
 
@@ -898,6 +991,8 @@ mod tests {
             storage_mounts: std::collections::BTreeMap::new(),
             max_parallel_jobs: 2,
             log_level: LogLevel::Info,
+            job_selection: JobSelectionStrategy::default(),
+            upload_part_size_bytes: None,
         }
     }
 
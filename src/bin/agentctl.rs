@@ -10,15 +10,19 @@ use genai::chat::{ChatMessage, ChatRequest};
 use genai::resolver::{Endpoint, ServiceTargetResolver};
 use genai::{Client, ModelIden, ServiceTarget, WebConfig};
 use retaia_agent::{
-    AgentRuntimeConfig, AuthMode, ConfigInterface, ConfigRepository, ConfigRepositoryError,
-    ConfigValidationError, DAEMON_STATS_FILE_NAME, DaemonInstallRequest, DaemonLabelRequest,
-    DaemonLevel, DaemonManager, DaemonManagerError, DaemonStatus, DiagnosticsLimits,
-    FileConfigRepository, LogLevel, RuntimeConfigUpdate, RuntimeHistoryStore,
-    RuntimeHistoryStoreError, RuntimeStatsStoreError, SystemConfigRepository, TechnicalAuthConfig,
+    AgentRuntimeConfig, AudioWaveformFormat, AuthMode, ConfigInterface, ConfigRepository,
+    ConfigRepositoryError, ConfigValidationError, DAEMON_STATS_FILE_NAME,
+    DEFAULT_UPLOAD_PART_SIZE_BYTES, DaemonInstallRequest, DaemonLabelRequest, DaemonLevel,
+    DaemonManager, DaemonManagerError, DaemonStatus, DerivedExecutionPlanner, DerivedJobType,
+    DiagnosticsLimits, FileConfigRepository, JobSelectionStrategy, LogLevel, MediaTimeoutOverride,
+    OfflineProcessError, OfflineProcessRequest, RuntimeConfigUpdate, RuntimeDerivedPlanner,
+    RuntimeHistoryStore, RuntimeHistoryStoreError, RuntimeStatsStoreError, SystemConfigRepository,
+    TechnicalAuthConfig, ThumbnailSpriteProfile, VideoPreviewProfile,
     append_redacted_config_markdown, apply_config_update, build_bug_report_markdown,
     collect_daemon_diagnostics, compact_validation_reason, copy_to_clipboard, detect_language,
     load_runtime_stats, normalize_core_api_url, redacted_runtime_config_from,
-    render_daemon_inspect, render_daemon_inspect_json, runtime_history_db_path, t, validate_config,
+    render_daemon_inspect, render_daemon_inspect_json, render_drain_stats, run_offline_processing,
+    runtime_history_db_path, t, validate_config,
 };
 use service_manager::{
     ServiceInstallCtx, ServiceLabel, ServiceLevel, ServiceStartCtx, ServiceStatusCtx,
@@ -49,6 +53,7 @@ enum RootCommand {
         #[command(subcommand)]
         command: DaemonCommand,
     },
+    Process(ProcessArgs),
 }
 
 #[derive(Debug, Subcommand)]
@@ -107,6 +112,46 @@ struct DaemonReportArgs {
     include_redacted_config: bool,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum ProcessJobTypeArg {
+    ExtractFacts,
+    GeneratePreview,
+    GenerateThumbnails,
+    GenerateAudioWaveform,
+    TranscribeAudio,
+}
+
+impl From<ProcessJobTypeArg> for DerivedJobType {
+    fn from(value: ProcessJobTypeArg) -> Self {
+        match value {
+            ProcessJobTypeArg::ExtractFacts => DerivedJobType::ExtractFacts,
+            ProcessJobTypeArg::GeneratePreview => DerivedJobType::GeneratePreview,
+            ProcessJobTypeArg::GenerateThumbnails => DerivedJobType::GenerateThumbnails,
+            ProcessJobTypeArg::GenerateAudioWaveform => DerivedJobType::GenerateAudioWaveform,
+            ProcessJobTypeArg::TranscribeAudio => DerivedJobType::TranscribeAudio,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Args)]
+struct ProcessArgs {
+    file: PathBuf,
+    #[arg(long = "job-type", value_enum)]
+    job_type: ProcessJobTypeArg,
+    #[arg(long = "output-dir")]
+    output_dir: PathBuf,
+    #[arg(long = "sidecar")]
+    sidecars: Vec<PathBuf>,
+    #[arg(long = "video-preview-ladder", default_value_t = false)]
+    video_preview_ladder: bool,
+    #[arg(long = "thumbnail-sprite", default_value_t = false)]
+    thumbnail_sprite: bool,
+    #[arg(long = "scene-storyboard", default_value_t = false)]
+    scene_storyboard: bool,
+    #[arg(long = "waveform-v2", default_value_t = false)]
+    waveform_v2: bool,
+}
+
 #[derive(Debug, Clone, Args)]
 struct DaemonInspectArgs {
     #[arg(long = "history-limit", default_value_t = 50)]
@@ -180,6 +225,23 @@ impl From<LogLevelArg> for LogLevel {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum JobSelectionArg {
+    Fifo,
+    JobTypePriority,
+    AssetFair,
+}
+
+impl From<JobSelectionArg> for JobSelectionStrategy {
+    fn from(value: JobSelectionArg) -> Self {
+        match value {
+            JobSelectionArg::Fifo => JobSelectionStrategy::Fifo,
+            JobSelectionArg::JobTypePriority => JobSelectionStrategy::JobTypePriority,
+            JobSelectionArg::AssetFair => JobSelectionStrategy::AssetFair,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Args)]
 struct ConfigInitArgs {
     #[command(flatten)]
@@ -198,6 +260,10 @@ struct ConfigInitArgs {
     max_parallel_jobs: Option<u16>,
     #[arg(long = "log-level", value_enum)]
     log_level: Option<LogLevelArg>,
+    #[arg(long = "job-selection", value_enum)]
+    job_selection: Option<JobSelectionArg>,
+    #[arg(long = "upload-part-size-bytes")]
+    upload_part_size_bytes: Option<u64>,
     #[arg(long = "storage-mount")]
     storage_mounts: Vec<String>,
 }
@@ -226,6 +292,10 @@ struct ConfigSetArgs {
     max_parallel_jobs: Option<u16>,
     #[arg(long = "log-level", value_enum)]
     log_level: Option<LogLevelArg>,
+    #[arg(long = "job-selection", value_enum)]
+    job_selection: Option<JobSelectionArg>,
+    #[arg(long = "upload-part-size-bytes")]
+    upload_part_size_bytes: Option<u64>,
 }
 
 #[derive(Debug, Clone, Args)]
@@ -260,6 +330,16 @@ struct DaemonInstallArgs {
     system: bool,
     #[arg(long = "working-directory")]
     working_directory: Option<PathBuf>,
+    #[arg(long = "drain-deadline-secs")]
+    drain_deadline_secs: Option<u64>,
+    #[arg(long = "upload-part-size-bytes", value_parser = clap::value_parser!(u64).range(1..))]
+    upload_part_size_bytes: Option<u64>,
+    #[arg(long = "media-timeout", value_parser = media_timeout_arg)]
+    media_timeouts: Vec<String>,
+    #[arg(long = "scene-storyboard", default_value_t = false)]
+    scene_storyboard: bool,
+    #[arg(long = "waveform-v2", default_value_t = false)]
+    waveform_v2: bool,
 }
 
 #[derive(Debug, Clone, Args)]
@@ -309,6 +389,8 @@ enum AgentCtlError {
     DaemonHistory(RuntimeHistoryStoreError),
     #[error("clipboard copy failed: {0}")]
     Clipboard(String),
+    #[error("offline processing failed: {0}")]
+    Process(OfflineProcessError),
 }
 
 #[derive(Debug, Default, Clone, Copy)]
@@ -442,6 +524,13 @@ fn print_config(config: &AgentRuntimeConfig) {
     }
     println!("max_parallel_jobs={}", config.max_parallel_jobs);
     println!("log_level={log_level}");
+    println!("job_selection={}", config.job_selection.as_str());
+    println!(
+        "upload_part_size_bytes={}",
+        config
+            .upload_part_size_bytes
+            .unwrap_or(DEFAULT_UPLOAD_PART_SIZE_BYTES)
+    );
 }
 
 fn validation_error(errors: Vec<ConfigValidationError>) -> String {
@@ -662,6 +751,8 @@ fn init_config(args: &ConfigInitArgs) -> Result<AgentRuntimeConfig, AgentCtlErro
             .map_err(AgentCtlError::InvalidConfig)?,
         max_parallel_jobs: args.max_parallel_jobs.unwrap_or(1),
         log_level: args.log_level.unwrap_or(LogLevelArg::Info).into(),
+        job_selection: args.job_selection.map(Into::into).unwrap_or_default(),
+        upload_part_size_bytes: args.upload_part_size_bytes,
     };
 
     validate_config(&config)
@@ -691,6 +782,8 @@ fn update_from_args(args: &ConfigSetArgs) -> Result<RuntimeConfigUpdate, AgentCt
         clear_storage_mounts: args.clear_storage_mounts,
         max_parallel_jobs: args.max_parallel_jobs,
         log_level: args.log_level.map(Into::into),
+        job_selection: args.job_selection.map(Into::into),
+        upload_part_size_bytes: args.upload_part_size_bytes,
     })
 }
 
@@ -754,6 +847,10 @@ fn daemon_label_request(args: &DaemonLabelArgs) -> DaemonLabelRequest {
     }
 }
 
+fn media_timeout_arg(value: &str) -> Result<String, String> {
+    MediaTimeoutOverride::parse(value).map(|_| value.to_string())
+}
+
 fn daemon_install_request(args: &DaemonInstallArgs) -> Result<DaemonInstallRequest, AgentCtlError> {
     let mut command_args = Vec::new();
 
@@ -762,6 +859,24 @@ fn daemon_install_request(args: &DaemonInstallArgs) -> Result<DaemonInstallReque
         command_args.push(config.display().to_string());
     }
     command_args.push("daemon".to_string());
+    if let Some(drain_deadline_secs) = args.drain_deadline_secs {
+        command_args.push("--drain-deadline-secs".to_string());
+        command_args.push(drain_deadline_secs.to_string());
+    }
+    if let Some(upload_part_size_bytes) = args.upload_part_size_bytes {
+        command_args.push("--upload-part-size-bytes".to_string());
+        command_args.push(upload_part_size_bytes.to_string());
+    }
+    for media_timeout in &args.media_timeouts {
+        command_args.push("--media-timeout".to_string());
+        command_args.push(media_timeout.clone());
+    }
+    if args.scene_storyboard {
+        command_args.push("--scene-storyboard".to_string());
+    }
+    if args.waveform_v2 {
+        command_args.push("--waveform-v2".to_string());
+    }
 
     Ok(DaemonInstallRequest {
         label: args.label.clone(),
@@ -842,6 +957,13 @@ fn run_daemon_command<M: DaemonManager>(
                 .status(daemon_label_request(&args))
                 .map_err(AgentCtlError::Daemon)?;
             print_daemon_status(status);
+            if let Ok(stats) = load_runtime_stats()
+                && stats.drain.is_some()
+            {
+                let mut out = String::new();
+                render_drain_stats(&mut out, stats.drain.as_ref());
+                print!("{out}");
+            }
             Ok(())
         }
         DaemonCommand::Stats => {
@@ -876,6 +998,9 @@ fn run_daemon_command<M: DaemonManager>(
                 println!("last_job_duration_ms=-");
                 println!("last_job_completed_at_unix_ms=-");
             }
+            let mut out = String::new();
+            render_drain_stats(&mut out, stats.drain.as_ref());
+            print!("{out}");
             Ok(())
         }
         DaemonCommand::History(args) => {
@@ -962,8 +1087,12 @@ fn run_daemon_command<M: DaemonManager>(
                     } else {
                         println!("storage_mounts={}", config.storage_mounts.join(","));
                     }
+                    println!("job_selection={}", config.job_selection);
+                    println!("upload_part_size_bytes={}", config.upload_part_size_bytes);
                 } else {
                     println!("storage_mounts=-");
+                    println!("job_selection=-");
+                    println!("upload_part_size_bytes=-");
                 }
             }
             Ok(())
@@ -1147,6 +1276,8 @@ fn bootstrap_device_auth<R: ConfigRepository>(
                         clear_storage_mounts: false,
                         max_parallel_jobs: None,
                         log_level: None,
+                        job_selection: None,
+                        upload_part_size_bytes: None,
                     },
                     ConfigInterface::Cli,
                 )
@@ -1215,6 +1346,8 @@ fn rotate_secret<R: ConfigRepository>(
             clear_storage_mounts: false,
             max_parallel_jobs: None,
             log_level: None,
+            job_selection: None,
+            upload_part_size_bytes: None,
         },
         ConfigInterface::Cli,
     )
@@ -1331,7 +1464,45 @@ fn run(cli: Cli) -> Result<(), AgentCtlError> {
             Ok(())
         }
         RootCommand::Daemon { command } => run_daemon_command(&NativeDaemonManager, command, lang),
+        RootCommand::Process(args) => {
+            let mut planner = RuntimeDerivedPlanner::default();
+            if args.video_preview_ladder {
+                planner =
+                    planner.with_video_preview_profile(VideoPreviewProfile::default_hls_ladder());
+            }
+            if args.thumbnail_sprite {
+                planner = planner.with_thumbnail_sprite_profile(ThumbnailSpriteProfile::default());
+            }
+            if args.scene_storyboard {
+                planner = planner.with_scene_storyboard(true);
+            }
+            if args.waveform_v2 {
+                planner = planner.with_waveform_format(AudioWaveformFormat::PeaksV2);
+            }
+            run_process_command(&planner, args)
+        }
+    }
+}
+
+fn run_process_command<P: DerivedExecutionPlanner + ?Sized>(
+    planner: &P,
+    args: ProcessArgs,
+) -> Result<(), AgentCtlError> {
+    let report = run_offline_processing(
+        planner,
+        &OfflineProcessRequest {
+            source_path: args.file,
+            sidecar_paths: args.sidecars,
+            job_type: args.job_type.into(),
+            output_dir: args.output_dir,
+        },
+    )
+    .map_err(AgentCtlError::Process)?;
+    for artifact in &report.artifact_paths {
+        println!("artifact={}", artifact.display());
     }
+    println!("submit_payload={}", report.submit_payload_path.display());
+    Ok(())
 }
 
 fn main() {
@@ -1354,8 +1525,8 @@ mod tests {
     };
 
     use super::{
-        Cli, ConfigCommand, DaemonCommand, LogLevelArg, RootCommand, daemon_install_request,
-        restart_user_daemon_if_running, run_daemon_command,
+        Cli, ConfigCommand, DaemonCommand, LogLevelArg, ProcessJobTypeArg, RootCommand,
+        daemon_install_request, restart_user_daemon_if_running, run_daemon_command,
     };
 
     #[test]
@@ -1405,6 +1576,39 @@ mod tests {
         }
     }
 
+    #[test]
+    fn tdd_clap_parses_offline_process_command() {
+        let cli = Cli::try_parse_from([
+            "agentctl",
+            "process",
+            "/media/A001_C002.mp4",
+            "--job-type",
+            "generate-preview",
+            "--output-dir",
+            "/tmp/out",
+            "--sidecar",
+            "/media/A001_C002.srt",
+        ])
+        .expect("process args should parse");
+
+        match cli.command {
+            RootCommand::Process(args) => {
+                assert_eq!(args.file, std::path::PathBuf::from("/media/A001_C002.mp4"));
+                assert_eq!(args.job_type, ProcessJobTypeArg::GeneratePreview);
+                assert_eq!(args.output_dir, std::path::PathBuf::from("/tmp/out"));
+                assert_eq!(
+                    args.sidecars,
+                    vec![std::path::PathBuf::from("/media/A001_C002.srt")]
+                );
+                assert!(!args.video_preview_ladder);
+                assert!(!args.thumbnail_sprite);
+                assert!(!args.scene_storyboard);
+                assert!(!args.waveform_v2);
+            }
+            _ => panic!("unexpected parse result"),
+        }
+    }
+
     #[test]
     fn tdd_clap_parses_bootstrap_device_flags() {
         let cli = Cli::try_parse_from([
@@ -1523,6 +1727,65 @@ mod tests {
         );
     }
 
+    #[test]
+    fn tdd_daemon_install_request_forwards_runtime_flags() {
+        let cli = Cli::try_parse_from([
+            "agentctl",
+            "daemon",
+            "install",
+            "--program",
+            "/tmp/agent-runtime",
+            "--drain-deadline-secs",
+            "120",
+            "--upload-part-size-bytes",
+            "16777216",
+            "--media-timeout",
+            "video_proxy=7200,300",
+            "--scene-storyboard",
+            "--waveform-v2",
+        ])
+        .expect("daemon install parse should succeed");
+
+        let args = match cli.command {
+            RootCommand::Daemon {
+                command: DaemonCommand::Install(args),
+            } => args,
+            _ => panic!("unexpected parse result"),
+        };
+
+        let request = daemon_install_request(&args).expect("request should build");
+        assert_eq!(
+            request.args,
+            vec![
+                "daemon".to_string(),
+                "--drain-deadline-secs".to_string(),
+                "120".to_string(),
+                "--upload-part-size-bytes".to_string(),
+                "16777216".to_string(),
+                "--media-timeout".to_string(),
+                "video_proxy=7200,300".to_string(),
+                "--scene-storyboard".to_string(),
+                "--waveform-v2".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn tdd_daemon_install_rejects_kinds_outside_the_core_contract() {
+        for flag in ["--thumbnail-sprite", "--video-preview-ladder"] {
+            let result = Cli::try_parse_from([
+                "agentctl",
+                "daemon",
+                "install",
+                "--program",
+                "/tmp/agent-runtime",
+                flag,
+            ]);
+
+            assert!(result.is_err(), "{flag} should not be accepted");
+        }
+    }
+
     #[test]
     fn tdd_clap_parses_daemon_report_args() {
         let cli = Cli::try_parse_from([
@@ -1,5 +1,6 @@
 use std::collections::BTreeSet;
-use std::process::Command;
+use std::process::{Command, Stdio};
+use std::sync::OnceLock;
 
 use image::ImageFormat;
 
@@ -41,10 +42,12 @@ pub fn declared_agent_capabilities_with_runtime(
     let mut capabilities = vec![
         AgentCapability::MediaFactsV1,
         AgentCapability::MediaThumbnailsV1,
-        AgentCapability::AudioWaveformV1,
     ];
 
+    // Native decoding only covers common audio formats; Core cannot route
+    // waveform jobs by source format, so ffmpeg stays the gate.
     if ffmpeg_is_available {
+        capabilities.push(AgentCapability::AudioWaveformV1);
         capabilities.push(AgentCapability::MediaPreviewsVideoV1);
         capabilities.push(AgentCapability::MediaPreviewsAudioV1);
     }
@@ -78,7 +81,65 @@ pub fn photo_proxy_available() -> bool {
         && photo_source_extension_supported("arw")
 }
 
+// libheif >= 1.17 ships `heif-dec`; older releases only have `heif-convert`.
+pub const HEIF_DECODER_COMMANDS: [&str; 2] = ["heif-dec", "heif-convert"];
+
+// First libheif tool that can be spawned; its exit status is irrelevant since
+// `heif-convert` has no stable `--version` across releases. Probed once per
+// process: the planner asks for every HEIF-family source.
+pub fn heif_decoder_command() -> Option<&'static str> {
+    static COMMAND: OnceLock<Option<&'static str>> = OnceLock::new();
+    *COMMAND.get_or_init(|| {
+        HEIF_DECODER_COMMANDS.into_iter().find(|command| {
+            Command::new(command)
+                .arg("--version")
+                .stdin(Stdio::null())
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .status()
+                .is_ok()
+        })
+    })
+}
+
+// HEIC/HEIF/AVIF stills are only decodable with libheif installed.
+pub fn heif_decoder_available() -> bool {
+    heif_decoder_command().is_some()
+}
+
+// Formats decoded in-process (symphonia) for waveforms.
+pub fn audio_source_extension_supported(extension: &str) -> bool {
+    let ext = extension
+        .trim()
+        .trim_start_matches('.')
+        .to_ascii_lowercase();
+    matches!(
+        ext.as_str(),
+        "wav" | "wave" | "flac" | "mp3" | "aac" | "m4a" | "ogg" | "oga"
+    )
+}
+
+pub fn heif_source_extension(extension: &str) -> bool {
+    matches!(
+        extension
+            .trim()
+            .trim_start_matches('.')
+            .to_ascii_lowercase()
+            .as_str(),
+        "heic" | "heif" | "hif" | "avif"
+    )
+}
+
+// Only HEIF-family extensions pay for the libheif probe.
 pub fn photo_source_extension_supported(extension: &str) -> bool {
+    let heif_is_available = heif_source_extension(extension) && heif_decoder_available();
+    photo_source_extension_supported_with_heif(extension, heif_is_available)
+}
+
+pub fn photo_source_extension_supported_with_heif(
+    extension: &str,
+    heif_is_available: bool,
+) -> bool {
     let ext = extension
         .trim()
         .trim_start_matches('.')
@@ -86,6 +147,9 @@ pub fn photo_source_extension_supported(extension: &str) -> bool {
     if ext.is_empty() {
         return false;
     }
+    if heif_source_extension(&ext) {
+        return heif_is_available;
+    }
 
     match ImageFormat::from_extension(&ext) {
         Some(ImageFormat::Jpeg | ImageFormat::Png | ImageFormat::Tiff | ImageFormat::WebP) => true,
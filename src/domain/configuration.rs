@@ -23,6 +23,24 @@ pub enum LogLevel {
     Trace,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum JobSelectionStrategy {
+    #[default]
+    Fifo,
+    JobTypePriority,
+    AssetFair,
+}
+
+impl JobSelectionStrategy {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Fifo => "fifo",
+            Self::JobTypePriority => "job_type_priority",
+            Self::AssetFair => "asset_fair",
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct TechnicalAuthConfig {
     pub client_id: String,
@@ -38,6 +56,8 @@ pub struct AgentRuntimeConfig {
     pub storage_mounts: BTreeMap<String, String>,
     pub max_parallel_jobs: u16,
     pub log_level: LogLevel,
+    pub job_selection: JobSelectionStrategy,
+    pub upload_part_size_bytes: Option<u64>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -51,6 +71,7 @@ pub enum ConfigValidationError {
     EmptyStorageMountId,
     StorageMountPathNotAbsolute(String),
     InvalidMaxParallelJobs,
+    InvalidUploadPartSize,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
@@ -63,6 +84,8 @@ pub enum ConfigField {
     StorageMounts,
     MaxParallelJobs,
     LogLevel,
+    JobSelection,
+    UploadPartSizeBytes,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -83,6 +106,8 @@ pub struct RuntimeConfigUpdate {
     pub clear_storage_mounts: bool,
     pub max_parallel_jobs: Option<u16>,
     pub log_level: Option<LogLevel>,
+    pub job_selection: Option<JobSelectionStrategy>,
+    pub upload_part_size_bytes: Option<u64>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Error)]
@@ -219,6 +244,9 @@ pub fn validate_config(config: &AgentRuntimeConfig) -> Result<(), Vec<ConfigVali
     if config.max_parallel_jobs == 0 {
         errors.push(ConfigValidationError::InvalidMaxParallelJobs);
     }
+    if config.upload_part_size_bytes == Some(0) {
+        errors.push(ConfigValidationError::InvalidUploadPartSize);
+    }
 
     for (storage_id, mount_path) in &config.storage_mounts {
         if storage_id.trim().is_empty() {
@@ -269,6 +297,7 @@ pub fn compact_validation_reason(errors: &[ConfigValidationError]) -> String {
                 "storage mount path is not absolute"
             }
             ConfigValidationError::InvalidMaxParallelJobs => "invalid max_parallel_jobs",
+            ConfigValidationError::InvalidUploadPartSize => "invalid upload_part_size_bytes",
         })
         .collect::<Vec<_>>()
         .join(", ")
@@ -285,6 +314,8 @@ pub fn supported_config_fields(interface: ConfigInterface) -> BTreeSet<ConfigFie
         ConfigField::StorageMounts,
         ConfigField::MaxParallelJobs,
         ConfigField::LogLevel,
+        ConfigField::JobSelection,
+        ConfigField::UploadPartSizeBytes,
     ])
 }
 
@@ -336,6 +367,12 @@ pub fn apply_config_update(
     if let Some(log_level) = update.log_level {
         next.log_level = log_level;
     }
+    if let Some(job_selection) = update.job_selection {
+        next.job_selection = job_selection;
+    }
+    if let Some(upload_part_size_bytes) = update.upload_part_size_bytes {
+        next.upload_part_size_bytes = Some(upload_part_size_bytes);
+    }
 
     let _ = interface;
     validate_config(&next)?;
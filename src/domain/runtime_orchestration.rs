@@ -63,8 +63,8 @@ pub struct PollDecision {
 }
 
 const MIN_INTERVAL_MS: u64 = 100;
-const BASE_BACKOFF_MS: u64 = 2_000;
-const MAX_BACKOFF_MS: u64 = 60_000;
+pub(crate) const BASE_BACKOFF_MS: u64 = 2_000;
+pub(crate) const MAX_BACKOFF_MS: u64 = 60_000;
 
 pub fn runtime_orchestration_mode() -> RuntimeOrchestrationMode {
     RuntimeOrchestrationMode::StatusDrivenPolling
@@ -150,16 +150,28 @@ pub fn next_poll_decision(
     }
 }
 
-pub fn throttled_backoff_with_jitter(attempt: u32, jitter_seed: u64) -> u64 {
-    let max_times = attempt.saturating_add(1) as usize;
-    let mut backoff = ExponentialBuilder::default()
+pub fn throttled_backoff_builder(
+    min_delay: Duration,
+    max_delay: Duration,
+    jitter_seed: u64,
+) -> ExponentialBuilder {
+    ExponentialBuilder::default()
         .with_factor(2.0)
-        .with_min_delay(Duration::from_millis(BASE_BACKOFF_MS))
-        .with_max_delay(Duration::from_millis(MAX_BACKOFF_MS))
-        .with_max_times(max_times)
+        .with_min_delay(min_delay)
+        .with_max_delay(max_delay)
         .with_jitter()
         .with_jitter_seed(jitter_seed)
-        .build();
+}
+
+pub fn throttled_backoff_with_jitter(attempt: u32, jitter_seed: u64) -> u64 {
+    let max_times = attempt.saturating_add(1) as usize;
+    let mut backoff = throttled_backoff_builder(
+        Duration::from_millis(BASE_BACKOFF_MS),
+        Duration::from_millis(MAX_BACKOFF_MS),
+        jitter_seed,
+    )
+    .with_max_times(max_times)
+    .build();
 
     backoff
         .nth(max_times.saturating_sub(1))
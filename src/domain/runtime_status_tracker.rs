@@ -135,6 +135,16 @@ impl RuntimeStatusTracker {
         &self.snapshot
     }
 
+    // Finished jobs only need to be reported once; forgetting them keeps a
+    // long-running tracker from growing without bound.
+    pub fn forget_finished_jobs(&mut self) {
+        let running_job_ids = &self.snapshot.running_job_ids;
+        self.snapshot
+            .known_job_ids
+            .retain(|job_id| running_job_ids.contains(job_id));
+        self.snapshot.failed_jobs.clear();
+    }
+
     fn ensure_running_order(&mut self, job_id: &str) {
         if !self.running_order.iter().any(|id| id == job_id) {
             self.running_order.push(job_id.to_string());
@@ -147,3 +157,29 @@ impl RuntimeStatusTracker {
             .find_map(|job_id| self.latest_job_status.get(job_id).cloned())
     }
 }
+
+pub fn merge_local_job_snapshot(
+    remote: &RuntimeSnapshot,
+    local: &RuntimeSnapshot,
+) -> RuntimeSnapshot {
+    let mut merged = remote.clone();
+    merged
+        .known_job_ids
+        .extend(local.known_job_ids.iter().cloned());
+    merged
+        .running_job_ids
+        .extend(local.running_job_ids.iter().cloned());
+    for failure in &local.failed_jobs {
+        if !merged
+            .failed_jobs
+            .iter()
+            .any(|existing| existing.job_id == failure.job_id)
+        {
+            merged.failed_jobs.push(failure.clone());
+        }
+    }
+    if local.current_job.is_some() {
+        merged.current_job = local.current_job.clone();
+    }
+    merged
+}
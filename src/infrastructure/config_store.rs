@@ -9,8 +9,8 @@ use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 use crate::domain::configuration::{
-    AgentRuntimeConfig, AuthMode, ConfigValidationError, LogLevel, TechnicalAuthConfig,
-    normalize_storage_mounts, validate_config,
+    AgentRuntimeConfig, AuthMode, ConfigValidationError, JobSelectionStrategy, LogLevel,
+    TechnicalAuthConfig, normalize_storage_mounts, validate_config,
 };
 use crate::infrastructure::technical_secret_store::{
     delete_technical_secret, load_technical_secret, persist_technical_secret,
@@ -70,6 +70,15 @@ enum StoredLogLevel {
     Trace,
 }
 
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum StoredJobSelectionStrategy {
+    #[default]
+    Fifo,
+    JobTypePriority,
+    AssetFair,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct StoredTechnicalAuthConfig {
     client_id: String,
@@ -87,6 +96,10 @@ struct StoredAgentRuntimeConfig {
     storage_mounts: BTreeMap<String, String>,
     max_parallel_jobs: u16,
     log_level: StoredLogLevel,
+    #[serde(default)]
+    job_selection: StoredJobSelectionStrategy,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    upload_part_size_bytes: Option<u64>,
 }
 
 impl From<StoredAuthMode> for AuthMode {
@@ -131,6 +144,26 @@ impl From<LogLevel> for StoredLogLevel {
     }
 }
 
+impl From<StoredJobSelectionStrategy> for JobSelectionStrategy {
+    fn from(value: StoredJobSelectionStrategy) -> Self {
+        match value {
+            StoredJobSelectionStrategy::Fifo => JobSelectionStrategy::Fifo,
+            StoredJobSelectionStrategy::JobTypePriority => JobSelectionStrategy::JobTypePriority,
+            StoredJobSelectionStrategy::AssetFair => JobSelectionStrategy::AssetFair,
+        }
+    }
+}
+
+impl From<JobSelectionStrategy> for StoredJobSelectionStrategy {
+    fn from(value: JobSelectionStrategy) -> Self {
+        match value {
+            JobSelectionStrategy::Fifo => StoredJobSelectionStrategy::Fifo,
+            JobSelectionStrategy::JobTypePriority => StoredJobSelectionStrategy::JobTypePriority,
+            JobSelectionStrategy::AssetFair => StoredJobSelectionStrategy::AssetFair,
+        }
+    }
+}
+
 impl From<StoredTechnicalAuthConfig> for TechnicalAuthConfig {
     fn from(value: StoredTechnicalAuthConfig) -> Self {
         Self {
@@ -159,6 +192,8 @@ impl From<StoredAgentRuntimeConfig> for AgentRuntimeConfig {
             storage_mounts: normalize_storage_mounts(&value.storage_mounts),
             max_parallel_jobs: value.max_parallel_jobs,
             log_level: value.log_level.into(),
+            job_selection: value.job_selection.into(),
+            upload_part_size_bytes: value.upload_part_size_bytes,
         }
     }
 }
@@ -173,6 +208,8 @@ impl From<AgentRuntimeConfig> for StoredAgentRuntimeConfig {
             storage_mounts: normalize_storage_mounts(&value.storage_mounts),
             max_parallel_jobs: value.max_parallel_jobs,
             log_level: value.log_level.into(),
+            job_selection: value.job_selection.into(),
+            upload_part_size_bytes: value.upload_part_size_bytes,
         }
     }
 }
@@ -268,6 +305,8 @@ fn hydrate_runtime_config(
             storage_mounts: normalize_storage_mounts(&stored.storage_mounts),
             max_parallel_jobs: stored.max_parallel_jobs,
             log_level: stored.log_level.into(),
+            job_selection: stored.job_selection.into(),
+            upload_part_size_bytes: stored.upload_part_size_bytes,
         },
         migrated_legacy_secret,
     ))
@@ -8,10 +8,13 @@ use thiserror::Error;
 use crate::application::daemon_manager::{
     DaemonLabelRequest, DaemonLevel, DaemonManager, DaemonStatus,
 };
+use crate::application::runtime_derived_planner::DEFAULT_UPLOAD_PART_SIZE_BYTES;
 use crate::infrastructure::runtime_history_store::{
     CompletedJobEntry, DaemonCycleEntry, RuntimeHistoryStore,
 };
-use crate::infrastructure::runtime_stats_store::{DaemonRuntimeStats, load_runtime_stats};
+use crate::infrastructure::runtime_stats_store::{
+    DaemonDrainStats, DaemonRuntimeStats, load_runtime_stats,
+};
 use crate::{AgentRuntimeConfig, AuthMode, LogLevel};
 
 pub const DEFAULT_DAEMON_LABEL: &str = "io.retaia.agent";
@@ -40,6 +43,8 @@ pub struct RedactedRuntimeConfig {
     pub storage_mounts: Vec<String>,
     pub max_parallel_jobs: u16,
     pub log_level: String,
+    pub job_selection: String,
+    pub upload_part_size_bytes: u64,
 }
 
 pub fn redacted_runtime_config_from(settings: &AgentRuntimeConfig) -> RedactedRuntimeConfig {
@@ -71,6 +76,10 @@ pub fn redacted_runtime_config_from(settings: &AgentRuntimeConfig) -> RedactedRu
             .collect(),
         max_parallel_jobs: settings.max_parallel_jobs,
         log_level: log_level.to_string(),
+        job_selection: settings.job_selection.as_str().to_string(),
+        upload_part_size_bytes: settings
+            .upload_part_size_bytes
+            .unwrap_or(DEFAULT_UPLOAD_PART_SIZE_BYTES),
     }
 }
 
@@ -262,6 +271,7 @@ pub fn render_daemon_inspect(
             let _ = writeln!(out, "last_job_duration_ms=-");
             let _ = writeln!(out, "last_job_completed_at_unix_ms=-");
         }
+        render_drain_stats(&mut out, stats.drain.as_ref());
     } else {
         let _ = writeln!(out, "stats=unavailable");
     }
@@ -301,6 +311,29 @@ pub fn render_daemon_inspect(
     out
 }
 
+pub fn render_drain_stats(out: &mut String, drain: Option<&DaemonDrainStats>) {
+    if let Some(drain) = drain {
+        let _ = writeln!(out, "drain_phase={}", drain.phase);
+        let _ = writeln!(out, "drain_started_at_unix_ms={}", drain.started_at_unix_ms);
+        let _ = writeln!(
+            out,
+            "drain_deadline_at_unix_ms={}",
+            drain.deadline_at_unix_ms
+        );
+        let _ = writeln!(
+            out,
+            "drain_active_job_ids={}",
+            if drain.active_job_ids.is_empty() {
+                "-".to_string()
+            } else {
+                drain.active_job_ids.join(",")
+            }
+        );
+    } else {
+        let _ = writeln!(out, "drain_phase=-");
+    }
+}
+
 pub fn render_daemon_inspect_json(
     snapshot: &DaemonDiagnosticsSnapshot,
     history_db_path: Option<&str>,
@@ -355,6 +388,12 @@ pub fn append_redacted_config_markdown(
         );
         let _ = writeln!(body, "- max_parallel_jobs: `{}`", config.max_parallel_jobs);
         let _ = writeln!(body, "- log_level: `{}`", config.log_level);
+        let _ = writeln!(body, "- job_selection: `{}`", config.job_selection);
+        let _ = writeln!(
+            body,
+            "- upload_part_size_bytes: `{}`",
+            config.upload_part_size_bytes
+        );
     } else {
         let _ = writeln!(body, "- unavailable");
     }
@@ -434,14 +473,18 @@ fn run_clipboard_command(
 mod tests {
     use crate::{
         CompletedJobEntry, DaemonCycleEntry, DaemonRuntimeStats,
-        infrastructure::runtime_stats_store::{DaemonCurrentJobStats, DaemonLastJobStats},
+        infrastructure::runtime_stats_store::{
+            DaemonCurrentJobStats, DaemonDrainStats, DaemonLastJobStats,
+        },
     };
 
     use super::{
         DaemonDiagnosticsSnapshot, append_redacted_config_markdown, build_bug_report_markdown,
         redacted_runtime_config_from, render_daemon_inspect, render_daemon_inspect_json,
     };
-    use crate::{AgentRuntimeConfig, AuthMode, LogLevel, TechnicalAuthConfig};
+    use crate::{
+        AgentRuntimeConfig, AuthMode, JobSelectionStrategy, LogLevel, TechnicalAuthConfig,
+    };
 
     #[test]
     fn tdd_render_daemon_inspect_includes_counts() {
@@ -464,6 +507,12 @@ mod tests {
                     duration_ms: 42,
                     completed_at_unix_ms: 120,
                 }),
+                drain: Some(DaemonDrainStats {
+                    phase: "draining".to_string(),
+                    started_at_unix_ms: 110,
+                    deadline_at_unix_ms: 60_110,
+                    active_job_ids: vec!["job-1".to_string()],
+                }),
             }),
             completed_jobs: vec![CompletedJobEntry {
                 completed_at_unix_ms: 120,
@@ -487,6 +536,8 @@ mod tests {
         assert!(rendered.contains("completed_jobs_count=1"));
         assert!(rendered.contains("cycles_count=1"));
         assert!(rendered.contains("history_db_path=/tmp/history.sqlite3"));
+        assert!(rendered.contains("drain_phase=draining"));
+        assert!(rendered.contains("drain_active_job_ids=job-1"));
     }
 
     #[test]
@@ -525,6 +576,8 @@ mod tests {
             )]),
             max_parallel_jobs: 4,
             log_level: LogLevel::Info,
+            job_selection: JobSelectionStrategy::default(),
+            upload_part_size_bytes: None,
         });
         let rendered = render_daemon_inspect_json(&snapshot, Some("/tmp/h.sqlite3"), Some(&config));
         assert!(rendered.contains("\"history_db_path\": \"/tmp/h.sqlite3\""));
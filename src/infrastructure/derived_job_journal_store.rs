@@ -0,0 +1,147 @@
+use std::fs;
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard};
+
+use rusqlite::{Connection, OptionalExtension, params};
+
+use crate::application::derived_job_journal::{
+    DerivedJobJournal, DerivedJobJournalEntry, DerivedJobJournalError,
+};
+use crate::infrastructure::runtime_history_store::runtime_history_db_path;
+use crate::infrastructure::runtime_stats_store::now_unix_ms;
+
+pub struct SqliteDerivedJobJournal {
+    conn: Mutex<Connection>,
+}
+
+impl std::fmt::Debug for SqliteDerivedJobJournal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SqliteDerivedJobJournal").finish()
+    }
+}
+
+impl SqliteDerivedJobJournal {
+    pub fn open_default() -> Result<Self, DerivedJobJournalError> {
+        let path = runtime_history_db_path()
+            .map_err(|error| DerivedJobJournalError::Storage(error.to_string()))?;
+        Self::open_at_path(&path)
+    }
+
+    pub fn open_at_path(path: &Path) -> Result<Self, DerivedJobJournalError> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(storage_error)?;
+        }
+        let conn = Connection::open(path).map_err(storage_error)?;
+        conn.pragma_update(None, "journal_mode", "WAL")
+            .map_err(storage_error)?;
+        conn.pragma_update(None, "synchronous", "FULL")
+            .map_err(storage_error)?;
+        conn.busy_timeout(std::time::Duration::from_secs(5))
+            .map_err(storage_error)?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS derived_job_journal (
+                job_id TEXT PRIMARY KEY,
+                asset_uuid TEXT NOT NULL,
+                lock_token TEXT NOT NULL,
+                fencing_token INTEGER NOT NULL,
+                stage TEXT NOT NULL,
+                staging_dir TEXT NULL,
+                entry_json TEXT NOT NULL,
+                updated_at_unix_ms INTEGER NOT NULL
+            );",
+        )
+        .map_err(storage_error)?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    fn conn(&self) -> MutexGuard<'_, Connection> {
+        self.conn
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl DerivedJobJournal for SqliteDerivedJobJournal {
+    fn load(&self, job_id: &str) -> Result<Option<DerivedJobJournalEntry>, DerivedJobJournalError> {
+        let entry_json: Option<String> = self
+            .conn()
+            .query_row(
+                "SELECT entry_json FROM derived_job_journal WHERE job_id = ?1",
+                [job_id],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(storage_error)?;
+        entry_json
+            .map(|json| {
+                serde_json::from_str(&json)
+                    .map_err(|error| DerivedJobJournalError::Corrupted(error.to_string()))
+            })
+            .transpose()
+    }
+
+    fn save(&self, entry: &DerivedJobJournalEntry) -> Result<(), DerivedJobJournalError> {
+        let entry_json = serde_json::to_string(entry)
+            .map_err(|error| DerivedJobJournalError::Corrupted(error.to_string()))?;
+        self.conn()
+            .execute(
+                "INSERT INTO derived_job_journal (
+                    job_id, asset_uuid, lock_token, fencing_token, stage, staging_dir, entry_json, updated_at_unix_ms
+                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+                ON CONFLICT(job_id) DO UPDATE SET
+                    asset_uuid = excluded.asset_uuid,
+                    lock_token = excluded.lock_token,
+                    fencing_token = excluded.fencing_token,
+                    stage = excluded.stage,
+                    staging_dir = excluded.staging_dir,
+                    entry_json = excluded.entry_json,
+                    updated_at_unix_ms = excluded.updated_at_unix_ms",
+                params![
+                    entry.claimed.job_id,
+                    entry.claimed.asset_uuid,
+                    entry.claimed.lock_token,
+                    entry.claimed.fencing_token,
+                    entry.stage.as_str(),
+                    entry
+                        .staging_dir
+                        .as_ref()
+                        .map(|dir| dir.display().to_string()),
+                    entry_json,
+                    now_unix_ms() as i64
+                ],
+            )
+            .map_err(storage_error)?;
+        Ok(())
+    }
+
+    fn remove(&self, job_id: &str) -> Result<(), DerivedJobJournalError> {
+        self.conn()
+            .execute(
+                "DELETE FROM derived_job_journal WHERE job_id = ?1",
+                [job_id],
+            )
+            .map_err(storage_error)?;
+        Ok(())
+    }
+
+    fn pending_job_ids(&self) -> Result<Vec<String>, DerivedJobJournalError> {
+        let conn = self.conn();
+        let mut stmt = conn
+            .prepare("SELECT job_id FROM derived_job_journal ORDER BY updated_at_unix_ms ASC")
+            .map_err(storage_error)?;
+        let rows = stmt
+            .query_map([], |row| row.get::<_, String>(0))
+            .map_err(storage_error)?;
+        let mut job_ids = Vec::new();
+        for row in rows {
+            job_ids.push(row.map_err(storage_error)?);
+        }
+        Ok(job_ids)
+    }
+}
+
+fn storage_error(error: impl std::fmt::Display) -> DerivedJobJournalError {
+    DerivedJobJournalError::Storage(error.to_string())
+}
@@ -1,14 +1,26 @@
 use std::fs;
-use std::io::BufWriter;
+use std::io::{BufRead, BufReader, BufWriter, Read};
 use std::path::Path;
-use std::process::Command;
+use std::process::{Command, Stdio};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::{Duration, Instant};
 
+use crate::application::audio_waveform::{
+    WAVEFORM_V2_SAMPLE_RATE, WAVEFORM_V2_SAMPLES_PER_PEAK, WaveformPeaksBuilder,
+};
 use crate::application::derived_processing_gateway::FactsPatchPayload;
+use crate::application::job_cancellation::CancellationToken;
 use crate::application::proxy_generator::{
-    AudioProxyFormat, AudioProxyRequest, AudioWaveformRequest, PhotoProxyRequest,
-    ProxyGenerationError, ProxyGenerator, ThumbnailFormat, VideoProxyRequest,
+    AudioProxyFormat, AudioProxyRequest, AudioWaveformFormat, AudioWaveformRequest,
+    HLS_MASTER_PLAYLIST_FILE_NAME, LoudnessMeasurement, MediaOperation, MediaOperationTimeout,
+    MediaTimeoutKind, MediaToolTimeouts, PhotoDecodePath, PhotoProxyRequest, ProxyGenerationError,
+    ProxyGenerator, ProxyProgressSink, ThumbnailFormat, VideoFrameAnalysisRequest,
+    VideoFrameSample, VideoHlsLadderRequest, VideoProxyRequest, VideoSpriteSheetRequest,
     VideoThumbnailRequest,
 };
+use crate::domain::capabilities::audio_source_extension_supported;
+use crate::infrastructure::rust_audio_decoder::decode_audio_interleaved;
 use crate::infrastructure::time::{FileTimestampProvider, StdFileTimestampProvider};
 use chrono::{Datelike, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Timelike, Utc};
 use serde::Serialize;
@@ -20,8 +32,79 @@ pub struct CommandOutput {
     pub stderr: String,
 }
 
-pub trait CommandRunner {
+#[derive(Debug, Clone, Copy)]
+pub struct CommandLimits<'a> {
+    pub operation: MediaOperation,
+    pub wall_clock: Option<Duration>,
+    pub no_progress: Option<Duration>,
+    pub cancellation: Option<&'a CancellationToken>,
+}
+
+impl<'a> CommandLimits<'a> {
+    pub fn new(operation: MediaOperation) -> Self {
+        Self {
+            operation,
+            wall_clock: None,
+            no_progress: None,
+            cancellation: None,
+        }
+    }
+
+    pub fn for_timeout(
+        operation: MediaOperation,
+        timeout: MediaOperationTimeout,
+        media_duration_ms: Option<u64>,
+    ) -> Self {
+        Self {
+            wall_clock: Some(timeout.wall_clock_for(media_duration_ms)),
+            no_progress: timeout.no_progress,
+            ..Self::new(operation)
+        }
+    }
+
+    pub fn with_cancellation(mut self, cancellation: Option<&'a CancellationToken>) -> Self {
+        self.cancellation = cancellation;
+        self
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancellation
+            .is_some_and(CancellationToken::is_cancelled)
+    }
+
+    fn is_supervised(&self) -> bool {
+        self.wall_clock.is_some() || self.no_progress.is_some() || self.cancellation.is_some()
+    }
+}
+
+pub trait CommandRunner: Send + Sync {
     fn run(&self, program: &str, args: &[String]) -> Result<CommandOutput, ProxyGenerationError>;
+
+    fn run_with_limits(
+        &self,
+        program: &str,
+        args: &[String],
+        limits: &CommandLimits<'_>,
+    ) -> Result<CommandOutput, ProxyGenerationError> {
+        if limits.is_cancelled() {
+            return Err(ProxyGenerationError::Cancelled);
+        }
+        self.run(program, args)
+    }
+
+    fn run_streaming(
+        &self,
+        program: &str,
+        args: &[String],
+        on_stdout_line: &mut dyn FnMut(&str),
+        limits: &CommandLimits<'_>,
+    ) -> Result<CommandOutput, ProxyGenerationError> {
+        let output = self.run_with_limits(program, args, limits)?;
+        for line in output.stdout.lines() {
+            on_stdout_line(line);
+        }
+        Ok(output)
+    }
 }
 
 #[derive(Debug, Default, Clone, Copy)]
@@ -39,6 +122,186 @@ impl CommandRunner for StdCommandRunner {
             stderr: String::from_utf8_lossy(&output.stderr).to_string(),
         })
     }
+
+    fn run_with_limits(
+        &self,
+        program: &str,
+        args: &[String],
+        limits: &CommandLimits<'_>,
+    ) -> Result<CommandOutput, ProxyGenerationError> {
+        if !limits.is_supervised() {
+            return self.run(program, args);
+        }
+        let mut stdout = String::new();
+        let mut output = run_supervised(
+            program,
+            args,
+            &mut |line| {
+                stdout.push_str(line);
+                stdout.push('\n');
+            },
+            limits,
+        )?;
+        output.stdout = stdout;
+        Ok(output)
+    }
+
+    fn run_streaming(
+        &self,
+        program: &str,
+        args: &[String],
+        on_stdout_line: &mut dyn FnMut(&str),
+        limits: &CommandLimits<'_>,
+    ) -> Result<CommandOutput, ProxyGenerationError> {
+        run_supervised(program, args, on_stdout_line, limits)
+    }
+}
+
+const SUPERVISION_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+fn run_supervised(
+    program: &str,
+    args: &[String],
+    on_stdout_line: &mut dyn FnMut(&str),
+    limits: &CommandLimits<'_>,
+) -> Result<CommandOutput, ProxyGenerationError> {
+    if limits.is_cancelled() {
+        return Err(ProxyGenerationError::Cancelled);
+    }
+    let mut command = Command::new(program);
+    command
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    #[cfg(unix)]
+    std::os::unix::process::CommandExt::process_group(&mut command, 0);
+    let mut child = command
+        .spawn()
+        .map_err(|error| ProxyGenerationError::Process(error.to_string()))?;
+    let child_id = child.id();
+    let stdout = child.stdout.take();
+    let mut stderr = child.stderr.take();
+    let started_at = Instant::now();
+    // Milliseconds since `started_at` of the last byte seen on stdout or stderr.
+    let last_activity_ms = AtomicU64::new(0);
+    let output_closed = AtomicBool::new(false);
+    let termination = Mutex::new(None);
+    let stderr = std::thread::scope(|scope| {
+        let last_activity_ms = &last_activity_ms;
+        let stderr_reader = scope.spawn(move || {
+            let mut buffer = Vec::new();
+            let mut chunk = [0_u8; 8192];
+            if let Some(stderr) = stderr.as_mut() {
+                while let Ok(read) = stderr.read(&mut chunk) {
+                    if read == 0 {
+                        break;
+                    }
+                    buffer.extend_from_slice(&chunk[..read]);
+                    last_activity_ms.store(elapsed_ms(started_at), Ordering::Release);
+                }
+            }
+            String::from_utf8_lossy(&buffer).to_string()
+        });
+        let output_closed = &output_closed;
+        let termination = &termination;
+        scope.spawn(move || {
+            while !output_closed.load(Ordering::Acquire) {
+                if let Some(error) = supervision_verdict(
+                    limits,
+                    started_at.elapsed(),
+                    elapsed_ms(started_at).saturating_sub(last_activity_ms.load(Ordering::Acquire)),
+                ) {
+                    *termination
+                        .lock()
+                        .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(error);
+                    kill_process_group(child_id);
+                    return;
+                }
+                std::thread::sleep(SUPERVISION_POLL_INTERVAL);
+            }
+        });
+        if let Some(stdout) = stdout {
+            // Byte lines: a non UTF-8 path or tag must not stop the drain and
+            // leave ffmpeg blocked on a full pipe.
+            let mut reader = BufReader::new(stdout);
+            let mut line = Vec::new();
+            while reader
+                .read_until(b'\n', &mut line)
+                .is_ok_and(|read| read > 0)
+            {
+                last_activity_ms.store(elapsed_ms(started_at), Ordering::Release);
+                let text = String::from_utf8_lossy(&line);
+                on_stdout_line(text.trim_end_matches(['\n', '\r']));
+                line.clear();
+            }
+        }
+        let stderr = stderr_reader.join().unwrap_or_default();
+        output_closed.store(true, Ordering::Release);
+        stderr
+    });
+    let status = child
+        .wait()
+        .map_err(|error| ProxyGenerationError::Process(error.to_string()))?;
+    if let Some(error) = termination
+        .into_inner()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+    {
+        return Err(error);
+    }
+    Ok(CommandOutput {
+        status_code: status.code(),
+        stdout: String::new(),
+        stderr,
+    })
+}
+
+fn supervision_verdict(
+    limits: &CommandLimits<'_>,
+    elapsed: Duration,
+    idle_ms: u64,
+) -> Option<ProxyGenerationError> {
+    if limits.is_cancelled() {
+        return Some(ProxyGenerationError::Cancelled);
+    }
+    let kind = if limits.wall_clock.is_some_and(|limit| elapsed >= limit) {
+        MediaTimeoutKind::WallClock
+    } else if limits
+        .no_progress
+        .is_some_and(|limit| Duration::from_millis(idle_ms) >= limit)
+    {
+        MediaTimeoutKind::NoProgress
+    } else {
+        return None;
+    };
+    Some(ProxyGenerationError::TimedOut {
+        operation: limits.operation,
+        kind,
+    })
+}
+
+fn elapsed_ms(started_at: Instant) -> u64 {
+    u64::try_from(started_at.elapsed().as_millis()).unwrap_or(u64::MAX)
+}
+
+#[cfg(unix)]
+fn kill_process_group(child_id: u32) {
+    let Ok(process_group) = i32::try_from(child_id) else {
+        return;
+    };
+    // The child leads its own process group, so this also reaps helpers it spawned.
+    unsafe {
+        libc::kill(-process_group, libc::SIGKILL);
+    }
+}
+
+#[cfg(not(unix))]
+fn kill_process_group(child_id: u32) {
+    let _ = Command::new("taskkill")
+        .args(["/F", "/T", "/PID", &child_id.to_string()])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status();
 }
 
 #[derive(Debug, Clone)]
@@ -49,6 +312,7 @@ pub struct FfmpegProxyGenerator<
     ffmpeg_binary: String,
     runner: R,
     timestamp_provider: T,
+    timeouts: MediaToolTimeouts,
 }
 
 impl Default for FfmpegProxyGenerator<StdCommandRunner, StdFileTimestampProvider> {
@@ -73,43 +337,154 @@ impl<R: CommandRunner, T: FileTimestampProvider> FfmpegProxyGenerator<R, T> {
             ffmpeg_binary,
             runner,
             timestamp_provider,
+            timeouts: MediaToolTimeouts::default(),
         }
     }
 
+    pub fn with_timeouts(mut self, timeouts: MediaToolTimeouts) -> Self {
+        self.timeouts = timeouts;
+        self
+    }
+
     pub fn runner(&self) -> &R {
         &self.runner
     }
+
+    pub fn timeouts(&self) -> &MediaToolTimeouts {
+        &self.timeouts
+    }
+
+    fn limits_for<'a>(
+        &self,
+        operation: MediaOperation,
+        media_duration_ms: Option<u64>,
+        cancellation: Option<&'a CancellationToken>,
+    ) -> CommandLimits<'a> {
+        CommandLimits::for_timeout(
+            operation,
+            self.timeouts.for_operation(operation),
+            media_duration_ms,
+        )
+        .with_cancellation(cancellation)
+    }
+
+    fn probe_duration_ms(
+        &self,
+        input_path: &str,
+        cancellation: Option<&CancellationToken>,
+    ) -> Option<u64> {
+        let output = self
+            .runner
+            .run_with_limits(
+                &ffprobe_binary(&self.ffmpeg_binary),
+                &build_ffprobe_duration_args(input_path),
+                &self.limits_for(MediaOperation::Probe, None, cancellation),
+            )
+            .ok()?;
+        if output.status_code != Some(0) {
+            return None;
+        }
+        let seconds = output.stdout.trim().parse::<f64>().ok()?;
+        (seconds.is_finite() && seconds > 0.0).then(|| (seconds * 1000.0).round() as u64)
+    }
+
+    fn run_ffmpeg_with_progress(
+        &self,
+        operation: MediaOperation,
+        args: &[String],
+        input_path: &str,
+        output_path: &str,
+        progress: &dyn ProxyProgressSink,
+    ) -> Result<(), ProxyGenerationError> {
+        let duration_ms = self.probe_duration_ms(input_path, progress.cancellation());
+        let limits = self.limits_for(operation, duration_ms, progress.cancellation());
+        let mut last_percent = None;
+        let result = self
+            .runner
+            .run_streaming(
+                &self.ffmpeg_binary,
+                &with_progress_args(args),
+                &mut |line| {
+                    let Some(percent) = ffmpeg_progress_percent(line, duration_ms) else {
+                        return;
+                    };
+                    if last_percent.is_none_or(|last| percent > last) {
+                        last_percent = Some(percent);
+                        progress.report_progress(percent);
+                    }
+                },
+                &limits,
+            )
+            .and_then(command_succeeded);
+        if result.is_err() {
+            let _ = fs::remove_file(output_path);
+        }
+        result
+    }
 }
 
 impl<R: CommandRunner, T: FileTimestampProvider> ProxyGenerator for FfmpegProxyGenerator<R, T> {
     fn generate_video_proxy(
         &self,
         request: &VideoProxyRequest,
+        progress: &dyn ProxyProgressSink,
     ) -> Result<(), ProxyGenerationError> {
         validate_video_request(request)?;
-        run_ffmpeg(
-            &self.runner,
-            &self.ffmpeg_binary,
+        self.run_ffmpeg_with_progress(
+            MediaOperation::VideoProxy,
             &build_video_proxy_args(request),
+            &request.input_path,
+            &request.output_path,
+            progress,
         )
     }
 
     fn generate_audio_proxy(
         &self,
         request: &AudioProxyRequest,
+        progress: &dyn ProxyProgressSink,
     ) -> Result<(), ProxyGenerationError> {
         validate_audio_request(request)?;
-        run_ffmpeg(
-            &self.runner,
-            &self.ffmpeg_binary,
+        self.run_ffmpeg_with_progress(
+            MediaOperation::AudioProxy,
             &build_audio_proxy_args(request),
+            &request.input_path,
+            &request.output_path,
+            progress,
         )
     }
 
+    fn generate_video_hls_ladder(
+        &self,
+        request: &VideoHlsLadderRequest,
+        progress: &dyn ProxyProgressSink,
+    ) -> Result<(), ProxyGenerationError> {
+        validate_hls_ladder_request(request)?;
+        fs::create_dir_all(&request.output_dir)
+            .map_err(|error| ProxyGenerationError::Process(error.to_string()))?;
+        // Mapping a missing audio stream into `var_stream_map` makes ffmpeg fail.
+        let has_audio = self
+            .extract_media_facts(&request.input_path, progress)
+            .is_ok_and(|facts| facts.audio_codec.is_some());
+        let master_path = Path::new(&request.output_dir).join(HLS_MASTER_PLAYLIST_FILE_NAME);
+        let result = self.run_ffmpeg_with_progress(
+            MediaOperation::VideoHlsLadder,
+            &build_video_hls_ladder_args(request, has_audio),
+            &request.input_path,
+            &master_path.to_string_lossy(),
+            progress,
+        );
+        if result.is_err() {
+            let _ = fs::remove_dir_all(&request.output_dir);
+        }
+        result
+    }
+
     fn generate_photo_proxy(
         &self,
         _request: &PhotoProxyRequest,
-    ) -> Result<(), ProxyGenerationError> {
+        _progress: &dyn ProxyProgressSink,
+    ) -> Result<Option<PhotoDecodePath>, ProxyGenerationError> {
         Err(ProxyGenerationError::InvalidRequest(
             "photo proxy generation is handled by RustPhotoProxyGenerator".to_string(),
         ))
@@ -118,18 +493,71 @@ impl<R: CommandRunner, T: FileTimestampProvider> ProxyGenerator for FfmpegProxyG
     fn generate_video_thumbnail(
         &self,
         request: &VideoThumbnailRequest,
+        progress: &dyn ProxyProgressSink,
     ) -> Result<(), ProxyGenerationError> {
         validate_thumbnail_request(request)?;
         run_ffmpeg(
             &self.runner,
             &self.ffmpeg_binary,
             &build_video_thumbnail_args(request),
+            &self.limits_for(MediaOperation::Thumbnail, None, progress.cancellation()),
         )
     }
 
+    fn generate_video_sprite_sheet(
+        &self,
+        request: &VideoSpriteSheetRequest,
+        progress: &dyn ProxyProgressSink,
+    ) -> Result<(), ProxyGenerationError> {
+        validate_sprite_sheet_request(request)?;
+        let layout = &request.layout;
+        let covered_ms = layout
+            .interval_ms
+            .saturating_mul(u64::from(layout.tile_count));
+        let result = run_ffmpeg(
+            &self.runner,
+            &self.ffmpeg_binary,
+            &build_video_sprite_sheet_args(request),
+            &self.limits_for(
+                MediaOperation::SpriteSheet,
+                Some(covered_ms),
+                progress.cancellation(),
+            ),
+        );
+        if result.is_err() {
+            let _ = fs::remove_file(&request.output_path);
+        }
+        result
+    }
+
+    fn analyze_video_frames(
+        &self,
+        request: &VideoFrameAnalysisRequest,
+        progress: &dyn ProxyProgressSink,
+    ) -> Result<Vec<VideoFrameSample>, ProxyGenerationError> {
+        validate_frame_analysis_request(request)?;
+        let output = self.runner.run_with_limits(
+            &self.ffmpeg_binary,
+            &build_video_frame_analysis_args(request),
+            &self.limits_for(
+                MediaOperation::SceneAnalysis,
+                Some(request.duration_ms),
+                progress.cancellation(),
+            ),
+        )?;
+        if output.status_code != Some(0) {
+            return Err(ProxyGenerationError::CommandFailed {
+                status_code: output.status_code,
+                stderr: output.stderr,
+            });
+        }
+        Ok(parse_video_frame_analysis(&output.stdout))
+    }
+
     fn generate_audio_waveform(
         &self,
         request: &AudioWaveformRequest,
+        progress: &dyn ProxyProgressSink,
     ) -> Result<(), ProxyGenerationError> {
         validate_waveform_request(request)?;
         let output = Path::new(&request.output_path);
@@ -139,6 +567,31 @@ impl<R: CommandRunner, T: FileTimestampProvider> ProxyGenerator for FfmpegProxyG
                     .map_err(|error| ProxyGenerationError::Process(error.to_string()))?;
             }
         }
+        // ffmpeg stays the fallback for other containers and for files the
+        // native decoders reject; cancellation and timeouts are final.
+        let natively_decodable = Path::new(&request.input_path)
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .is_some_and(audio_source_extension_supported);
+        let mut native_error = None;
+        if natively_decodable {
+            let limits = self.limits_for(
+                MediaOperation::WaveformDecode,
+                None,
+                progress.cancellation(),
+            );
+            match write_waveform_with_native_decoder(request, &limits) {
+                Ok(()) => return Ok(()),
+                Err(
+                    error @ (ProxyGenerationError::Cancelled
+                    | ProxyGenerationError::TimedOut { .. }),
+                ) => {
+                    let _ = fs::remove_file(output);
+                    return Err(error);
+                }
+                Err(error) => native_error = Some(error),
+            }
+        }
 
         let temp_dir = output
             .parent()
@@ -152,29 +605,37 @@ impl<R: CommandRunner, T: FileTimestampProvider> ProxyGenerator for FfmpegProxyG
         let wav_path = temp_wav.path().to_path_buf();
         drop(temp_wav);
 
+        let duration_ms = self.probe_duration_ms(&request.input_path, progress.cancellation());
         let generation_result = run_ffmpeg(
             &self.runner,
             &self.ffmpeg_binary,
             &build_audio_waveform_decode_args(request, &wav_path),
+            &self.limits_for(
+                MediaOperation::WaveformDecode,
+                duration_ms,
+                progress.cancellation(),
+            ),
         )
-        .and_then(|()| write_waveform_json_from_wav(&wav_path, output, request.bucket_count));
+        .and_then(|()| write_waveform_from_wav(&wav_path, request, output));
 
         let _ = fs::remove_file(&wav_path);
-        generation_result
+        generation_result.map_err(|error| with_native_decode_error(error, native_error))
     }
 
     fn extract_media_facts(
         &self,
         input_path: &str,
+        progress: &dyn ProxyProgressSink,
     ) -> Result<FactsPatchPayload, ProxyGenerationError> {
         if input_path.trim().is_empty() {
             return Err(ProxyGenerationError::InvalidRequest(
                 "facts input path is required".to_string(),
             ));
         }
-        let output = self.runner.run(
+        let output = self.runner.run_with_limits(
             &ffprobe_binary(&self.ffmpeg_binary),
             &build_ffprobe_args(input_path),
+            &self.limits_for(MediaOperation::Probe, None, progress.cancellation()),
         )?;
         if output.status_code != Some(0) {
             return Err(ProxyGenerationError::CommandFailed {
@@ -186,14 +647,75 @@ impl<R: CommandRunner, T: FileTimestampProvider> ProxyGenerator for FfmpegProxyG
         merge_wav_container_facts(input_path, &mut facts, &self.timestamp_provider)?;
         Ok(facts)
     }
+
+    fn analyze_loudness(
+        &self,
+        input_path: &str,
+        progress: &dyn ProxyProgressSink,
+    ) -> Result<LoudnessMeasurement, ProxyGenerationError> {
+        if input_path.trim().is_empty() {
+            return Err(ProxyGenerationError::InvalidRequest(
+                "loudness input path is required".to_string(),
+            ));
+        }
+        let duration_ms = self.probe_duration_ms(input_path, progress.cancellation());
+        let output = self.runner.run_with_limits(
+            &self.ffmpeg_binary,
+            &build_loudness_analysis_args(input_path),
+            &self.limits_for(
+                MediaOperation::LoudnessAnalysis,
+                duration_ms,
+                progress.cancellation(),
+            ),
+        )?;
+        if output.status_code != Some(0) {
+            return Err(ProxyGenerationError::CommandFailed {
+                status_code: output.status_code,
+                stderr: output.stderr,
+            });
+        }
+        parse_ebur128_summary(&output.stderr).ok_or_else(|| {
+            ProxyGenerationError::Process("ebur128 summary missing from ffmpeg output".to_string())
+        })
+    }
 }
 
 fn run_ffmpeg<R: CommandRunner>(
     runner: &R,
     ffmpeg_binary: &str,
     args: &[String],
+    limits: &CommandLimits<'_>,
 ) -> Result<(), ProxyGenerationError> {
-    let output = runner.run(ffmpeg_binary, args)?;
+    runner
+        .run_with_limits(ffmpeg_binary, args, limits)
+        .and_then(command_succeeded)
+}
+
+// Keeps the native decoder's reason visible when the ffmpeg fallback fails
+// too, e.g. because ffmpeg is not installed.
+fn with_native_decode_error(
+    error: ProxyGenerationError,
+    native_error: Option<ProxyGenerationError>,
+) -> ProxyGenerationError {
+    let Some(native_error) = native_error else {
+        return error;
+    };
+    match error {
+        ProxyGenerationError::Process(message) => ProxyGenerationError::Process(format!(
+            "{native_error}; ffmpeg fallback failed: {message}"
+        )),
+        ProxyGenerationError::CommandFailed {
+            status_code,
+            stderr,
+        } => ProxyGenerationError::CommandFailed {
+            status_code,
+            stderr: format!("{native_error}\n{stderr}"),
+        },
+        error => error,
+    }
+}
+
+fn command_succeeded(output: CommandOutput) -> Result<(), ProxyGenerationError> {
     if output.status_code == Some(0) {
         return Ok(());
     }
@@ -203,6 +725,30 @@ fn run_ffmpeg<R: CommandRunner>(
     })
 }
 
+fn with_progress_args(args: &[String]) -> Vec<String> {
+    let mut progress_args = vec![
+        "-progress".to_string(),
+        "pipe:1".to_string(),
+        "-nostats".to_string(),
+    ];
+    progress_args.extend_from_slice(args);
+    progress_args
+}
+
+pub fn ffmpeg_progress_percent(line: &str, duration_ms: Option<u64>) -> Option<u8> {
+    let (key, value) = line.trim().split_once('=')?;
+    match key {
+        "progress" if value == "end" => Some(100),
+        // ffmpeg reports `out_time_ms` in microseconds too.
+        "out_time_us" | "out_time_ms" => {
+            let duration_ms = duration_ms.filter(|duration| *duration > 0)?;
+            let out_time_ms = value.parse::<u64>().ok()? / 1_000;
+            Some((out_time_ms.saturating_mul(100) / duration_ms).min(99) as u8)
+        }
+        _ => None,
+    }
+}
+
 fn validate_video_request(request: &VideoProxyRequest) -> Result<(), ProxyGenerationError> {
     if request.input_path.trim().is_empty() {
         return Err(ProxyGenerationError::InvalidRequest(
@@ -227,6 +773,55 @@ fn validate_video_request(request: &VideoProxyRequest) -> Result<(), ProxyGenera
     Ok(())
 }
 
+fn validate_hls_ladder_request(
+    request: &VideoHlsLadderRequest,
+) -> Result<(), ProxyGenerationError> {
+    if request.input_path.trim().is_empty() {
+        return Err(ProxyGenerationError::InvalidRequest(
+            "HLS input path is required".to_string(),
+        ));
+    }
+    if request.output_dir.trim().is_empty() {
+        return Err(ProxyGenerationError::InvalidRequest(
+            "HLS output directory is required".to_string(),
+        ));
+    }
+    if request.renditions.is_empty() {
+        return Err(ProxyGenerationError::InvalidRequest(
+            "HLS ladder needs at least one rendition".to_string(),
+        ));
+    }
+    for rendition in &request.renditions {
+        if rendition.name.is_empty()
+            || !rendition
+                .name
+                .chars()
+                .all(|char| char.is_ascii_alphanumeric() || char == '-')
+        {
+            return Err(ProxyGenerationError::InvalidRequest(format!(
+                "invalid HLS rendition name: {:?}",
+                rendition.name
+            )));
+        }
+        if rendition.max_width == 0 || rendition.max_height == 0 {
+            return Err(ProxyGenerationError::InvalidRequest(
+                "HLS rendition max dimensions must be > 0".to_string(),
+            ));
+        }
+        if rendition.video_bitrate_kbps == 0 {
+            return Err(ProxyGenerationError::InvalidRequest(
+                "HLS rendition bitrate must be > 0".to_string(),
+            ));
+        }
+    }
+    if request.audio_bitrate_kbps == 0 || request.segment_duration_secs == 0 {
+        return Err(ProxyGenerationError::InvalidRequest(
+            "HLS audio bitrate and segment duration must be > 0".to_string(),
+        ));
+    }
+    Ok(())
+}
+
 fn validate_audio_request(request: &AudioProxyRequest) -> Result<(), ProxyGenerationError> {
     if request.input_path.trim().is_empty() {
         return Err(ProxyGenerationError::InvalidRequest(
@@ -270,6 +865,50 @@ fn validate_thumbnail_request(request: &VideoThumbnailRequest) -> Result<(), Pro
     Ok(())
 }
 
+fn validate_sprite_sheet_request(
+    request: &VideoSpriteSheetRequest,
+) -> Result<(), ProxyGenerationError> {
+    if request.input_path.trim().is_empty() {
+        return Err(ProxyGenerationError::InvalidRequest(
+            "sprite sheet input path is required".to_string(),
+        ));
+    }
+    if request.output_path.trim().is_empty() {
+        return Err(ProxyGenerationError::InvalidRequest(
+            "sprite sheet output path is required".to_string(),
+        ));
+    }
+    let layout = &request.layout;
+    if layout.interval_ms == 0
+        || layout.tile_width == 0
+        || layout.tile_height == 0
+        || layout.columns == 0
+        || layout.rows == 0
+        || layout.tile_count == 0
+    {
+        return Err(ProxyGenerationError::InvalidRequest(
+            "sprite sheet layout must have a positive interval, tile size and grid".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn validate_frame_analysis_request(
+    request: &VideoFrameAnalysisRequest,
+) -> Result<(), ProxyGenerationError> {
+    if request.input_path.trim().is_empty() {
+        return Err(ProxyGenerationError::InvalidRequest(
+            "frame analysis input path is required".to_string(),
+        ));
+    }
+    if request.sample_interval_ms == 0 {
+        return Err(ProxyGenerationError::InvalidRequest(
+            "frame analysis sample interval must be > 0".to_string(),
+        ));
+    }
+    Ok(())
+}
+
 fn validate_waveform_request(request: &AudioWaveformRequest) -> Result<(), ProxyGenerationError> {
     if request.input_path.trim().is_empty() {
         return Err(ProxyGenerationError::InvalidRequest(
@@ -281,7 +920,7 @@ fn validate_waveform_request(request: &AudioWaveformRequest) -> Result<(), Proxy
             "waveform output path is required".to_string(),
         ));
     }
-    if request.bucket_count < 100 {
+    if request.format == AudioWaveformFormat::Json && request.bucket_count < 100 {
         return Err(ProxyGenerationError::InvalidRequest(
             "waveform bucket_count must be >= 100".to_string(),
         ));
@@ -339,6 +978,114 @@ pub fn build_video_proxy_args(request: &VideoProxyRequest) -> Vec<String> {
     ]
 }
 
+pub fn build_video_hls_ladder_args(
+    request: &VideoHlsLadderRequest,
+    has_audio: bool,
+) -> Vec<String> {
+    let output_dir = Path::new(&request.output_dir);
+    let split_outputs: String = (0..request.renditions.len())
+        .map(|index| format!("[s{index}]"))
+        .collect();
+    let mut filter = format!("[0:v:0]split={}{split_outputs}", request.renditions.len());
+    for (index, rendition) in request.renditions.iter().enumerate() {
+        filter.push_str(&format!(
+            ";[s{index}]scale=w={}:h={}:force_original_aspect_ratio=decrease:force_divisible_by=2[v{index}]",
+            rendition.max_width, rendition.max_height
+        ));
+    }
+
+    let mut args = vec![
+        "-y".to_string(),
+        "-i".to_string(),
+        request.input_path.clone(),
+        "-filter_complex".to_string(),
+        filter,
+    ];
+    for index in 0..request.renditions.len() {
+        args.extend_from_slice(&["-map".to_string(), format!("[v{index}]")]);
+        if has_audio {
+            args.extend_from_slice(&["-map".to_string(), "0:a:0".to_string()]);
+        }
+    }
+    args.extend_from_slice(&[
+        "-c:v".to_string(),
+        "libx264".to_string(),
+        "-profile:v".to_string(),
+        "high".to_string(),
+        "-pix_fmt".to_string(),
+        "yuv420p".to_string(),
+        "-preset".to_string(),
+        "medium".to_string(),
+    ]);
+    for (index, rendition) in request.renditions.iter().enumerate() {
+        let bitrate = rendition.video_bitrate_kbps;
+        args.extend_from_slice(&[
+            format!("-b:v:{index}"),
+            format!("{bitrate}k"),
+            format!("-maxrate:v:{index}"),
+            format!("{}k", bitrate.saturating_mul(107) / 100),
+            format!("-bufsize:v:{index}"),
+            format!("{}k", bitrate.saturating_mul(2)),
+        ]);
+    }
+    // Keyframes on segment boundaries keep every rendition switchable at each segment.
+    args.extend_from_slice(&[
+        "-force_key_frames".to_string(),
+        format!("expr:gte(t,n_forced*{})", request.segment_duration_secs),
+        "-sc_threshold".to_string(),
+        "0".to_string(),
+    ]);
+    if has_audio {
+        args.extend_from_slice(&[
+            "-c:a".to_string(),
+            "aac".to_string(),
+            "-profile:a".to_string(),
+            "aac_low".to_string(),
+            "-b:a".to_string(),
+            format!("{}k", request.audio_bitrate_kbps),
+            "-ac".to_string(),
+            "2".to_string(),
+            "-ar".to_string(),
+            "48000".to_string(),
+        ]);
+    }
+    let var_stream_map = request
+        .renditions
+        .iter()
+        .enumerate()
+        .map(|(index, rendition)| {
+            if has_audio {
+                format!("v:{index},a:{index},name:{}", rendition.name)
+            } else {
+                format!("v:{index},name:{}", rendition.name)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+    args.extend_from_slice(&[
+        "-f".to_string(),
+        "hls".to_string(),
+        "-hls_time".to_string(),
+        request.segment_duration_secs.to_string(),
+        "-hls_playlist_type".to_string(),
+        "vod".to_string(),
+        "-hls_segment_type".to_string(),
+        "fmp4".to_string(),
+        "-hls_flags".to_string(),
+        "independent_segments".to_string(),
+        "-hls_fmp4_init_filename".to_string(),
+        "%v_init.mp4".to_string(),
+        "-hls_segment_filename".to_string(),
+        output_dir.join("%v_%05d.m4s").to_string_lossy().to_string(),
+        "-master_pl_name".to_string(),
+        HLS_MASTER_PLAYLIST_FILE_NAME.to_string(),
+        "-var_stream_map".to_string(),
+        var_stream_map,
+        output_dir.join("%v.m3u8").to_string_lossy().to_string(),
+    ]);
+    args
+}
+
 pub fn build_audio_proxy_args(request: &AudioProxyRequest) -> Vec<String> {
     let mut args = vec![
         "-y".to_string(),
@@ -405,23 +1152,202 @@ pub fn build_video_thumbnail_args(request: &VideoThumbnailRequest) -> Vec<String
     args
 }
 
+// One frame per interval, letterboxed into fixed-size tiles so WebVTT cues can
+// address them by grid position; `tile` flushes a partial last row at EOF.
+pub fn build_video_sprite_sheet_args(request: &VideoSpriteSheetRequest) -> Vec<String> {
+    let layout = &request.layout;
+    let (codec, quality_args) = match request.format {
+        ThumbnailFormat::Jpeg => ("mjpeg", ["-q:v", "3"]),
+        ThumbnailFormat::Webp => ("libwebp", ["-quality", "70"]),
+    };
+    let mut args = vec![
+        "-y".to_string(),
+        "-i".to_string(),
+        request.input_path.clone(),
+        "-map".to_string(),
+        "0:v:0".to_string(),
+        "-an".to_string(),
+        "-sn".to_string(),
+        "-dn".to_string(),
+        "-vf".to_string(),
+        format!(
+            "fps=1000/{interval},scale=w={w}:h={h}:force_original_aspect_ratio=decrease,pad=w={w}:h={h}:x=(ow-iw)/2:y=(oh-ih)/2,tile={columns}x{rows}",
+            interval = layout.interval_ms,
+            w = layout.tile_width,
+            h = layout.tile_height,
+            columns = layout.columns,
+            rows = layout.rows,
+        ),
+        "-frames:v".to_string(),
+        "1".to_string(),
+        "-c:v".to_string(),
+        codec.to_string(),
+    ];
+    args.extend(quality_args.map(str::to_string));
+    args.push(request.output_path.clone());
+    args
+}
+
+// Samples the first video stream at a fixed rate on a small 8-bit luma copy;
+// `select` scores scene changes between samples and `metadata` prints every
+// sample's scene score and signalstats on stdout.
+pub fn build_video_frame_analysis_args(request: &VideoFrameAnalysisRequest) -> Vec<String> {
+    vec![
+        "-hide_banner".to_string(),
+        "-nostats".to_string(),
+        "-i".to_string(),
+        request.input_path.clone(),
+        "-map".to_string(),
+        "0:v:0".to_string(),
+        "-an".to_string(),
+        "-sn".to_string(),
+        "-dn".to_string(),
+        "-vf".to_string(),
+        format!(
+            "fps=1000/{},scale=w=160:h=-2,format=yuv420p,select='gte(scene,0)',signalstats,metadata=mode=print:file=-:direct=1",
+            request.sample_interval_ms
+        ),
+        "-f".to_string(),
+        "null".to_string(),
+        "-".to_string(),
+    ]
+}
+
+pub fn parse_video_frame_analysis(stdout: &str) -> Vec<VideoFrameSample> {
+    #[derive(Default)]
+    struct PendingSample {
+        timestamp_ms: Option<u64>,
+        scene_score: Option<f32>,
+        luma_mean: Option<f32>,
+        luma_low: Option<f32>,
+        luma_high: Option<f32>,
+    }
+
+    impl PendingSample {
+        fn finish(self) -> Option<VideoFrameSample> {
+            Some(VideoFrameSample {
+                timestamp_ms: self.timestamp_ms?,
+                scene_score: self.scene_score.unwrap_or(0.0),
+                luma_mean: self.luma_mean?,
+                luma_spread: (self.luma_high? - self.luma_low?).max(0.0),
+            })
+        }
+    }
+
+    let mut samples = Vec::new();
+    let mut pending: Option<PendingSample> = None;
+    for line in stdout.lines().map(str::trim) {
+        if line.starts_with("frame:") {
+            samples.extend(pending.take().and_then(PendingSample::finish));
+            let timestamp_ms = line
+                .split_whitespace()
+                .find_map(|field| field.strip_prefix("pts_time:"))
+                .and_then(|value| value.parse::<f64>().ok())
+                .filter(|seconds| seconds.is_finite())
+                .map(|seconds| (seconds.max(0.0) * 1000.0).round() as u64);
+            pending = Some(PendingSample {
+                timestamp_ms,
+                ..PendingSample::default()
+            });
+            continue;
+        }
+        let (Some(sample), Some((key, value))) = (pending.as_mut(), line.split_once('=')) else {
+            continue;
+        };
+        let Ok(value) = value.trim().parse::<f32>() else {
+            continue;
+        };
+        match key {
+            "lavfi.scene_score" => sample.scene_score = Some(value),
+            "lavfi.signalstats.YAVG" => sample.luma_mean = Some(value),
+            "lavfi.signalstats.YLOW" => sample.luma_low = Some(value),
+            "lavfi.signalstats.YHIGH" => sample.luma_high = Some(value),
+            _ => {}
+        }
+    }
+    samples.extend(pending.and_then(PendingSample::finish));
+    samples
+}
+
+// `framelog=quiet` drops the 10 Hz momentary log; the summary is still
+// printed on stderr when the filter closes.
+pub fn build_loudness_analysis_args(input_path: &str) -> Vec<String> {
+    vec![
+        "-hide_banner".to_string(),
+        "-i".to_string(),
+        input_path.to_string(),
+        "-map".to_string(),
+        "0:a:0".to_string(),
+        "-vn".to_string(),
+        "-sn".to_string(),
+        "-dn".to_string(),
+        "-af".to_string(),
+        "ebur128=peak=true:framelog=quiet".to_string(),
+        "-f".to_string(),
+        "null".to_string(),
+        "-".to_string(),
+    ]
+}
+
+pub fn parse_ebur128_summary(stderr: &str) -> Option<LoudnessMeasurement> {
+    let summary = &stderr[stderr.rfind("Summary:")?..];
+    let mut integrated_lufs = None;
+    let mut loudness_range_lu = None;
+    let mut true_peak_dbtp = None;
+    let mut peak_seen = false;
+    for line in summary.split(['\n', '\r']).map(str::trim) {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.split_whitespace().next().unwrap_or_default();
+        match key {
+            "I" => integrated_lufs = value.parse::<f64>().ok(),
+            "LRA" => loudness_range_lu = value.parse::<f64>().ok(),
+            "Peak" => {
+                peak_seen = true;
+                true_peak_dbtp = value.parse::<f64>().ok().filter(|peak| peak.is_finite());
+            }
+            _ => {}
+        }
+    }
+    if !peak_seen {
+        return None;
+    }
+    Some(LoudnessMeasurement {
+        integrated_lufs: integrated_lufs?,
+        loudness_range_lu: loudness_range_lu?,
+        true_peak_dbtp,
+    })
+}
+
 pub fn build_audio_waveform_decode_args(
     request: &AudioWaveformRequest,
     wav_path: &Path,
 ) -> Vec<String> {
-    vec![
+    let mut args = vec![
         "-y".to_string(),
         "-i".to_string(),
         request.input_path.clone(),
         "-vn".to_string(),
-        "-ac".to_string(),
-        "1".to_string(),
-        "-ar".to_string(),
-        "16000".to_string(),
+    ];
+    // waveform_v2 keeps the source channel layout.
+    match request.format {
+        AudioWaveformFormat::Json => args.extend([
+            "-ac".to_string(),
+            "1".to_string(),
+            "-ar".to_string(),
+            "16000".to_string(),
+        ]),
+        AudioWaveformFormat::PeaksV2 => {
+            args.extend(["-ar".to_string(), WAVEFORM_V2_SAMPLE_RATE.to_string()])
+        }
+    }
+    args.extend([
         "-c:a".to_string(),
         "pcm_s16le".to_string(),
         wav_path.to_string_lossy().to_string(),
-    ]
+    ]);
+    args
 }
 
 pub fn build_ffprobe_args(input_path: &str) -> Vec<String> {
@@ -436,6 +1362,18 @@ pub fn build_ffprobe_args(input_path: &str) -> Vec<String> {
     ]
 }
 
+pub fn build_ffprobe_duration_args(input_path: &str) -> Vec<String> {
+    vec![
+        "-v".to_string(),
+        "error".to_string(),
+        "-show_entries".to_string(),
+        "format=duration".to_string(),
+        "-of".to_string(),
+        "default=noprint_wrappers=1:nokey=1".to_string(),
+        input_path.to_string(),
+    ]
+}
+
 #[derive(Serialize)]
 struct WaveformJson {
     duration_ms: u64,
@@ -443,58 +1381,196 @@ struct WaveformJson {
     samples: Vec<f32>,
 }
 
-fn write_waveform_json_from_wav(
+// The JSON envelope keeps the absolute mono peak of every group of frames,
+// grouped so that the envelope rate stays close to 16 kHz whatever the
+// decoded rate.
+const WAVEFORM_JSON_ENVELOPE_RATE: u32 = 16_000;
+
+struct MonoPeakEnvelope {
+    sample_rate: u32,
+    group: u32,
+    filled: u32,
+    current: u16,
+    frame_count: u64,
+    peaks: Vec<u16>,
+}
+
+impl MonoPeakEnvelope {
+    fn new(sample_rate: u32) -> Self {
+        Self {
+            sample_rate,
+            group: sample_rate.div_ceil(WAVEFORM_JSON_ENVELOPE_RATE).max(1),
+            filled: 0,
+            current: 0,
+            frame_count: 0,
+            peaks: Vec::new(),
+        }
+    }
+
+    fn push_frame(&mut self, frame: &[i16]) {
+        let sum = frame.iter().map(|sample| i32::from(*sample)).sum::<i32>();
+        let mono = sum / i32::try_from(frame.len().max(1)).unwrap_or(i32::MAX);
+        self.current = self
+            .current
+            .max(mono.unsigned_abs().min(u32::from(u16::MAX)) as u16);
+        self.filled += 1;
+        self.frame_count += 1;
+        if self.filled == self.group {
+            self.flush();
+        }
+    }
+
+    fn flush(&mut self) {
+        if self.filled > 0 {
+            self.peaks.push(self.current);
+            self.current = 0;
+            self.filled = 0;
+        }
+    }
+
+    fn write_json(
+        mut self,
+        output_path: &Path,
+        bucket_count: usize,
+    ) -> Result<(), ProxyGenerationError> {
+        self.flush();
+        let peaks = self.peaks;
+        let duration_ms =
+            ((self.frame_count as f64 / f64::from(self.sample_rate)) * 1000.0).round() as u64;
+        let mut buckets = Vec::with_capacity(bucket_count);
+        for bucket in 0..bucket_count {
+            let start = bucket * peaks.len() / bucket_count;
+            let end = ((bucket + 1) * peaks.len() / bucket_count).max(start + 1);
+            let end = end.min(peaks.len());
+            let peak =
+                peaks[start..end].iter().copied().max().unwrap_or(0) as f32 / i16::MAX as f32;
+            buckets.push(peak.clamp(0.0, 1.0));
+        }
+
+        let file = fs::File::create(output_path)
+            .map_err(|error| ProxyGenerationError::Process(error.to_string()))?;
+        serde_json::to_writer(
+            BufWriter::new(file),
+            &WaveformJson {
+                duration_ms,
+                bucket_count,
+                samples: buckets,
+            },
+        )
+        .map_err(|error| ProxyGenerationError::Process(error.to_string()))
+    }
+}
+
+// Both decoding paths (ffmpeg to WAV, native symphonia) stream interleaved
+// 16-bit frames into this writer.
+enum WaveformWriter {
+    Json {
+        envelope: MonoPeakEnvelope,
+        bucket_count: usize,
+    },
+    PeaksV2(WaveformPeaksBuilder),
+}
+
+impl WaveformWriter {
+    fn new(
+        request: &AudioWaveformRequest,
+        channels: u16,
+        sample_rate: u32,
+    ) -> Result<Self, ProxyGenerationError> {
+        if sample_rate == 0 || channels == 0 {
+            return Err(ProxyGenerationError::Process(
+                "waveform sample_rate and channels must be > 0".to_string(),
+            ));
+        }
+        Ok(match request.format {
+            AudioWaveformFormat::Json => Self::Json {
+                envelope: MonoPeakEnvelope::new(sample_rate),
+                bucket_count: request.bucket_count,
+            },
+            AudioWaveformFormat::PeaksV2 => Self::PeaksV2(WaveformPeaksBuilder::new(
+                channels,
+                sample_rate,
+                &WAVEFORM_V2_SAMPLES_PER_PEAK,
+            )),
+        })
+    }
+
+    fn push_interleaved(&mut self, samples: &[i16], channels: u16) {
+        for frame in samples.chunks_exact(usize::from(channels.max(1))) {
+            match self {
+                Self::Json { envelope, .. } => envelope.push_frame(frame),
+                Self::PeaksV2(builder) => builder.push_frame(frame),
+            }
+        }
+    }
+
+    fn frame_count(&self) -> u64 {
+        match self {
+            Self::Json { envelope, .. } => envelope.frame_count,
+            Self::PeaksV2(builder) => builder.frame_count(),
+        }
+    }
+
+    fn finish(self, output_path: &Path) -> Result<(), ProxyGenerationError> {
+        if self.frame_count() == 0 {
+            return Err(ProxyGenerationError::Process(
+                "waveform source produced no samples".to_string(),
+            ));
+        }
+        match self {
+            Self::Json {
+                envelope,
+                bucket_count,
+            } => envelope.write_json(output_path, bucket_count),
+            Self::PeaksV2(builder) => fs::write(output_path, builder.finish().encode())
+                .map_err(|error| ProxyGenerationError::Process(error.to_string())),
+        }
+    }
+}
+
+fn write_waveform_from_wav(
     wav_path: &Path,
+    request: &AudioWaveformRequest,
     output_path: &Path,
-    bucket_count: usize,
 ) -> Result<(), ProxyGenerationError> {
     let mut reader = hound::WavReader::open(wav_path)
         .map_err(|error| ProxyGenerationError::Process(error.to_string()))?;
     let spec = reader.spec();
-    let sample_rate = u64::from(spec.sample_rate);
-    if sample_rate == 0 {
-        return Err(ProxyGenerationError::Process(
-            "waveform sample_rate must be > 0".to_string(),
-        ));
+    let mut writer = WaveformWriter::new(request, spec.channels, spec.sample_rate)?;
+    let mut frame = Vec::with_capacity(usize::from(spec.channels));
+    for sample in reader.samples::<i16>() {
+        frame.push(sample.map_err(|error| ProxyGenerationError::Process(error.to_string()))?);
+        if frame.len() == usize::from(spec.channels) {
+            writer.push_interleaved(&frame, spec.channels);
+            frame.clear();
+        }
     }
+    writer.finish(output_path)
+}
 
-    let samples: Vec<i16> = reader
-        .samples::<i16>()
-        .collect::<Result<Vec<_>, _>>()
-        .map_err(|error| ProxyGenerationError::Process(error.to_string()))?;
-    if samples.is_empty() {
-        return Err(ProxyGenerationError::Process(
+// Decodes common audio formats in-process so waveforms do not need ffmpeg.
+pub fn write_waveform_with_native_decoder(
+    request: &AudioWaveformRequest,
+    limits: &CommandLimits<'_>,
+) -> Result<(), ProxyGenerationError> {
+    validate_waveform_request(request)?;
+    let mut writer: Option<Result<WaveformWriter, ProxyGenerationError>> = None;
+    let mut channels = 0;
+    decode_audio_interleaved(Path::new(&request.input_path), limits, |spec, samples| {
+        let writer = writer.get_or_insert_with(|| {
+            channels = spec.channels;
+            WaveformWriter::new(request, spec.channels, spec.sample_rate)
+        });
+        if let Ok(writer) = writer {
+            writer.push_interleaved(samples, channels);
+        }
+    })?;
+    match writer {
+        Some(writer) => writer?.finish(Path::new(&request.output_path)),
+        None => Err(ProxyGenerationError::Process(
             "waveform source produced no samples".to_string(),
-        ));
+        )),
     }
-
-    let duration_ms = ((samples.len() as f64 / sample_rate as f64) * 1000.0).round() as u64;
-    let mut buckets = Vec::with_capacity(bucket_count);
-    for bucket in 0..bucket_count {
-        let start = bucket * samples.len() / bucket_count;
-        let end = ((bucket + 1) * samples.len() / bucket_count).max(start + 1);
-        let end = end.min(samples.len());
-        let peak = samples[start..end]
-            .iter()
-            .map(|sample| i32::from(*sample).unsigned_abs())
-            .max()
-            .unwrap_or(0) as f32
-            / i16::MAX as f32;
-        buckets.push(peak.clamp(0.0, 1.0));
-    }
-
-    let file = fs::File::create(output_path)
-        .map_err(|error| ProxyGenerationError::Process(error.to_string()))?;
-    let writer = BufWriter::new(file);
-    serde_json::to_writer(
-        writer,
-        &WaveformJson {
-            duration_ms,
-            bucket_count,
-            samples: buckets,
-        },
-    )
-    .map_err(|error| ProxyGenerationError::Process(error.to_string()))
 }
 
 fn ffprobe_binary(ffmpeg_binary: &str) -> String {
@@ -0,0 +1,45 @@
+use image::DynamicImage;
+
+use crate::application::proxy_generator::ProxyGenerationError;
+use crate::domain::capabilities::{HEIF_DECODER_COMMANDS, heif_decoder_command};
+use crate::infrastructure::ffmpeg_proxy_generator::{
+    CommandLimits, CommandRunner, StdCommandRunner,
+};
+
+// Decodes HEIC/HEIF/AVIF stills through the libheif command line tools, which
+// resolve tiled grids and apply the container's `irot`/`imir` transforms. The
+// tool runs supervised, so a corrupt file cannot hang the worker.
+pub fn decode_heif_with_libheif(
+    input_path: &str,
+    limits: &CommandLimits<'_>,
+) -> Result<DynamicImage, ProxyGenerationError> {
+    let command = heif_decoder_command().ok_or_else(|| {
+        ProxyGenerationError::Process(format!(
+            "no HEIF decoder found (install libheif: {})",
+            HEIF_DECODER_COMMANDS.join(" or ")
+        ))
+    })?;
+    let workdir = tempfile::Builder::new()
+        .prefix("retaia-heif-")
+        .tempdir()
+        .map_err(|error| ProxyGenerationError::Process(error.to_string()))?;
+    let output_path = workdir.path().join("decoded.png");
+
+    let output = StdCommandRunner.run_with_limits(
+        command,
+        &[
+            input_path.to_string(),
+            output_path.to_string_lossy().to_string(),
+        ],
+        limits,
+    )?;
+    if output.status_code != Some(0) {
+        return Err(ProxyGenerationError::DecodeFailed(format!(
+            "{command} failed ({:?}): {}",
+            output.status_code,
+            output.stderr.trim()
+        )));
+    }
+    image::open(&output_path)
+        .map_err(|error| ProxyGenerationError::Process(format!("{command}: {error}")))
+}
@@ -2,8 +2,10 @@ pub mod agent_identity;
 pub mod config_repository;
 pub mod config_store;
 pub mod daemon_diagnostics;
+pub mod derived_job_journal_store;
 pub mod ffmpeg_proxy_generator;
 pub mod i18n;
+pub mod libheif_decoder;
 pub mod notification_sink;
 #[cfg(feature = "core-api-client")]
 pub mod openapi_agent_registration_gateway;
@@ -13,8 +15,12 @@ pub mod openapi_client;
 pub mod openapi_derived_processing_gateway;
 #[cfg(feature = "core-api-client")]
 pub mod openapi_jobs_gateway;
+pub mod photo_color_management;
+pub mod raw_development;
+pub mod raw_embedded_preview;
 pub mod runtime_history_store;
 pub mod runtime_stats_store;
+pub mod rust_audio_decoder;
 pub mod rust_photo_proxy_generator;
 pub mod signed_core_http;
 #[cfg(feature = "tauri-notifications")]
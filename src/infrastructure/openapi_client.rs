@@ -19,7 +19,9 @@ pub fn with_bearer_token(mut client: Configuration, token: impl Into<String>) ->
 #[cfg(test)]
 mod tests {
     use super::{build_core_api_client, with_bearer_token};
-    use crate::domain::configuration::{AgentRuntimeConfig, AuthMode, LogLevel};
+    use crate::domain::configuration::{
+        AgentRuntimeConfig, AuthMode, JobSelectionStrategy, LogLevel,
+    };
 
     fn runtime_config() -> AgentRuntimeConfig {
         AgentRuntimeConfig {
@@ -27,6 +29,8 @@ mod tests {
             ollama_url: "http://127.0.0.1:11434".to_string(),
             max_parallel_jobs: 2,
             log_level: LogLevel::Info,
+            job_selection: JobSelectionStrategy::default(),
+            upload_part_size_bytes: None,
             auth_mode: AuthMode::Interactive,
             technical_auth: None,
             storage_mounts: std::collections::BTreeMap::new(),
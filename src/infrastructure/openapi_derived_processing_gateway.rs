@@ -1,15 +1,15 @@
-#[cfg(feature = "core-api-client")]
-use std::fs;
-
 #[cfg(feature = "core-api-client")]
 use crate::application::derived_processing_gateway::{
-    ClaimedDerivedJob, DerivedJobType, DerivedManifestItem, DerivedProcessingError,
-    DerivedProcessingGateway, DerivedUploadComplete, DerivedUploadInit, DerivedUploadPart,
-    HeartbeatReceipt, SubmitDerivedPayload, UploadedDerivedPart, validate_derived_upload_init,
+    ClaimedDerivedJob, DerivedJobFailure, DerivedJobType, DerivedManifestItem,
+    DerivedProcessingError, DerivedProcessingGateway, DerivedUploadComplete, DerivedUploadInit,
+    DerivedUploadPart, HeartbeatReceipt, SubmitDerivedPayload, UploadedDerivedPart,
+    validate_derived_upload_init,
 };
 #[cfg(feature = "core-api-client")]
 use crate::infrastructure::agent_identity::AgentIdentity;
 #[cfg(feature = "core-api-client")]
+use crate::infrastructure::openapi_jobs_gateway::parse_retry_after_ms;
+#[cfg(feature = "core-api-client")]
 use crate::infrastructure::signed_core_http::{
     json_bytes, multipart_part_request, signed_empty_request, signed_json_request,
 };
@@ -230,6 +230,43 @@ impl DerivedProcessingGateway for OpenApiDerivedProcessingGateway {
         Ok(())
     }
 
+    fn fail_job(
+        &self,
+        job_id: &str,
+        lock_token: &str,
+        fencing_token: i32,
+        idempotency_key: &str,
+        failure: &DerivedJobFailure,
+    ) -> Result<(), DerivedProcessingError> {
+        let path = format!("/jobs/{job_id}/fail");
+        let mut request = models::JobsJobIdFailPostRequest::new(
+            lock_token.to_string(),
+            fencing_token,
+            failure.error_code.clone(),
+            failure.retryable,
+        );
+        request.message = failure.message.clone();
+        let body = json_bytes(&request)
+            .map_err(|error| DerivedProcessingError::Transport(error.to_string()))?;
+        let response = signed_json_request(
+            &reqwest::blocking::Client::new(),
+            &self.identity,
+            self.configuration.bearer_access_token.as_deref(),
+            &self.configuration.base_path,
+            reqwest::Method::POST,
+            &path,
+            &body,
+            None,
+        )
+        .map_err(|error| DerivedProcessingError::Transport(error.to_string()))?
+        .header("Idempotency-Key", idempotency_key)
+        .send()
+        .map_err(|error| DerivedProcessingError::Transport(error.to_string()))?;
+
+        require_success(response, map_fail_status)?;
+        Ok(())
+    }
+
     fn upload_init(&self, request: &DerivedUploadInit) -> Result<(), DerivedProcessingError> {
         validate_derived_upload_init(request)?;
 
@@ -237,7 +274,7 @@ impl DerivedProcessingGateway for OpenApiDerivedProcessingGateway {
             DerivedProcessingError::NumericOverflow("size_bytes > i32::MAX".to_string())
         })?;
         let mut payload = models::AssetsUuidDerivedUploadInitPostRequest::new(
-            map_upload_kind(request.kind),
+            map_upload_kind(request.kind)?,
             request.content_type.clone(),
             size_bytes,
         );
@@ -271,7 +308,8 @@ impl DerivedProcessingGateway for OpenApiDerivedProcessingGateway {
         request: &DerivedUploadPart,
     ) -> Result<UploadedDerivedPart, DerivedProcessingError> {
         let path = format!("/assets/{}/derived/upload/part", request.asset_uuid);
-        let chunk = fs::read(&request.chunk_path)
+        let chunk = request
+            .read_chunk()
             .map_err(|error| DerivedProcessingError::Transport(error.to_string()))?;
         let response = multipart_part_request(
             &reqwest::blocking::Client::new(),
@@ -371,11 +409,17 @@ fn map_submit_job_type(job_type: DerivedJobType) -> models::submit_derived::JobT
 #[cfg(feature = "core-api-client")]
 fn map_upload_kind(
     kind: crate::application::derived_processing_gateway::DerivedKind,
-) -> models::_assets__uuid__derived_upload_init_post_request::Kind {
-    match kind {
+) -> Result<models::_assets__uuid__derived_upload_init_post_request::Kind, DerivedProcessingError> {
+    Ok(match kind {
         crate::application::derived_processing_gateway::DerivedKind::PreviewVideo => {
             models::_assets__uuid__derived_upload_init_post_request::Kind::PreviewVideo
         }
+        // Not in the pinned OpenAPI v1 contract yet; refuse rather than send it.
+        crate::application::derived_processing_gateway::DerivedKind::PreviewVideoHls
+        | crate::application::derived_processing_gateway::DerivedKind::ThumbSprite
+        | crate::application::derived_processing_gateway::DerivedKind::ThumbVtt => {
+            return Err(derived_kind_not_in_contract(kind));
+        }
         crate::application::derived_processing_gateway::DerivedKind::PreviewAudio => {
             models::_assets__uuid__derived_upload_init_post_request::Kind::PreviewAudio
         }
@@ -388,7 +432,14 @@ fn map_upload_kind(
         crate::application::derived_processing_gateway::DerivedKind::Waveform => {
             models::_assets__uuid__derived_upload_init_post_request::Kind::Waveform
         }
-    }
+    })
+}
+
+#[cfg(feature = "core-api-client")]
+fn derived_kind_not_in_contract(
+    kind: crate::application::derived_processing_gateway::DerivedKind,
+) -> DerivedProcessingError {
+    DerivedProcessingError::DerivedKindNotInContract(kind.as_str().to_string())
 }
 
 #[cfg(feature = "core-api-client")]
@@ -404,6 +455,11 @@ fn build_derived_patch(
                 crate::application::derived_processing_gateway::DerivedKind::PreviewVideo => {
                     models::derived_patch_derived_manifest_inner::Kind::PreviewVideo
                 }
+                crate::application::derived_processing_gateway::DerivedKind::PreviewVideoHls
+                | crate::application::derived_processing_gateway::DerivedKind::ThumbSprite
+                | crate::application::derived_processing_gateway::DerivedKind::ThumbVtt => {
+                    return Err(derived_kind_not_in_contract(item.kind));
+                }
                 crate::application::derived_processing_gateway::DerivedKind::PreviewAudio => {
                     models::derived_patch_derived_manifest_inner::Kind::PreviewAudio
                 }
@@ -471,6 +527,7 @@ fn map_facts_patch(
         color_temperature_k: facts.color_temperature_k,
         has_dji_metadata_track: facts.has_dji_metadata_track,
         dji_metadata_track_types: facts.dji_metadata_track_types.clone(),
+        // Loudness facts are not in the pinned `FactsPatch` schema yet and stay local.
     }
 }
 
@@ -503,15 +560,23 @@ fn require_success(
     if status.is_success() {
         return Ok(response);
     }
+    let retry_after_ms = parse_retry_after_ms(response.headers());
     let body = response.text().unwrap_or_default();
-    Err(map_status(status, &body))
+    match map_status(status, &body) {
+        DerivedProcessingError::Throttled { .. } => {
+            Err(DerivedProcessingError::Throttled { retry_after_ms })
+        }
+        error => Err(error),
+    }
 }
 
 #[cfg(feature = "core-api-client")]
 fn map_claim_status(status: StatusCode, body: &str) -> DerivedProcessingError {
     match status.as_u16() {
         401 => DerivedProcessingError::Unauthorized,
-        429 => DerivedProcessingError::Throttled,
+        429 => DerivedProcessingError::Throttled {
+            retry_after_ms: None,
+        },
         409 | 412 => map_lock_error(status, body),
         code => DerivedProcessingError::UnexpectedStatus(code),
     }
@@ -521,7 +586,9 @@ fn map_claim_status(status: StatusCode, body: &str) -> DerivedProcessingError {
 fn map_asset_get_status(status: StatusCode, _body: &str) -> DerivedProcessingError {
     match status.as_u16() {
         401 => DerivedProcessingError::Unauthorized,
-        429 => DerivedProcessingError::Throttled,
+        429 => DerivedProcessingError::Throttled {
+            retry_after_ms: None,
+        },
         code => DerivedProcessingError::UnexpectedStatus(code),
     }
 }
@@ -530,6 +597,9 @@ fn map_asset_get_status(status: StatusCode, _body: &str) -> DerivedProcessingErr
 fn map_heartbeat_status(status: StatusCode, body: &str) -> DerivedProcessingError {
     match status.as_u16() {
         401 => DerivedProcessingError::Unauthorized,
+        429 => DerivedProcessingError::Throttled {
+            retry_after_ms: None,
+        },
         409 | 412 => map_lock_error(status, body),
         code => DerivedProcessingError::UnexpectedStatus(code),
     }
@@ -539,16 +609,30 @@ fn map_heartbeat_status(status: StatusCode, body: &str) -> DerivedProcessingErro
 fn map_submit_status(status: StatusCode, body: &str) -> DerivedProcessingError {
     match status.as_u16() {
         401 => DerivedProcessingError::Unauthorized,
-        429 => DerivedProcessingError::Throttled,
+        429 => DerivedProcessingError::Throttled {
+            retry_after_ms: None,
+        },
         409 | 412 => map_lock_error(status, body),
         code => DerivedProcessingError::UnexpectedStatus(code),
     }
 }
 
+#[cfg(feature = "core-api-client")]
+fn map_fail_status(status: StatusCode, body: &str) -> DerivedProcessingError {
+    match status.as_u16() {
+        401 => DerivedProcessingError::Unauthorized,
+        409 | 412 | 423 => map_lock_error(status, body),
+        code => DerivedProcessingError::UnexpectedStatus(code),
+    }
+}
+
 #[cfg(feature = "core-api-client")]
 fn map_upload_init_status(status: StatusCode, body: &str) -> DerivedProcessingError {
     match status.as_u16() {
         401 => DerivedProcessingError::Unauthorized,
+        429 => DerivedProcessingError::Throttled {
+            retry_after_ms: None,
+        },
         409 | 412 => map_lock_error(status, body),
         code => DerivedProcessingError::UnexpectedStatus(code),
     }
@@ -558,7 +642,9 @@ fn map_upload_init_status(status: StatusCode, body: &str) -> DerivedProcessingEr
 fn map_upload_part_status(status: StatusCode, body: &str) -> DerivedProcessingError {
     match status.as_u16() {
         401 => DerivedProcessingError::Unauthorized,
-        429 => DerivedProcessingError::Throttled,
+        429 => DerivedProcessingError::Throttled {
+            retry_after_ms: None,
+        },
         409 | 412 => map_lock_error(status, body),
         code => DerivedProcessingError::UnexpectedStatus(code),
     }
@@ -568,6 +654,9 @@ fn map_upload_part_status(status: StatusCode, body: &str) -> DerivedProcessingEr
 fn map_upload_complete_status(status: StatusCode, body: &str) -> DerivedProcessingError {
     match status.as_u16() {
         401 => DerivedProcessingError::Unauthorized,
+        429 => DerivedProcessingError::Throttled {
+            retry_after_ms: None,
+        },
         409 | 412 => map_lock_error(status, body),
         code => DerivedProcessingError::UnexpectedStatus(code),
     }
@@ -14,6 +14,8 @@ use crate::application::core_api_gateway::{
     CoreApiGateway, CoreApiGatewayError, CoreJobState, CoreJobView, CoreServerPolicy,
 };
 #[cfg(feature = "core-api-client")]
+use crate::application::derived_processing_gateway::DerivedJobType;
+#[cfg(feature = "core-api-client")]
 use crate::detect_language;
 #[cfg(feature = "core-api-client")]
 use crate::infrastructure::time::{Clock, StdClock};
@@ -27,7 +29,7 @@ use retaia_core_client::apis::configuration::Configuration;
 #[cfg(all(test, feature = "core-api-client"))]
 use retaia_core_client::apis::jobs_api::JobsGetError;
 #[cfg(feature = "core-api-client")]
-use retaia_core_client::models::job::Status;
+use retaia_core_client::models::job::{JobType, Status};
 #[cfg(feature = "core-api-client")]
 use retaia_core_client::models::{AppPolicyResponse, Job};
 
@@ -54,6 +56,7 @@ impl CoreApiGateway for OpenApiJobsGateway {
             .map(|job| CoreJobView {
                 job_id: job.job_id,
                 asset_uuid: job.asset_uuid,
+                job_type: Some(map_job_type(job.job_type)),
                 state: map_job_state(job.status),
                 required_capabilities: job.required_capabilities,
             })
@@ -134,7 +137,7 @@ impl OpenApiJobsGateway {
 }
 
 #[cfg(feature = "core-api-client")]
-fn parse_retry_after_ms(headers: &HeaderMap) -> Option<u64> {
+pub(crate) fn parse_retry_after_ms(headers: &HeaderMap) -> Option<u64> {
     parse_retry_after_ms_with_clock(headers, &StdClock)
 }
 
@@ -162,6 +165,17 @@ fn accept_language_header_value() -> &'static str {
     }
 }
 
+#[cfg(feature = "core-api-client")]
+fn map_job_type(job_type: JobType) -> DerivedJobType {
+    match job_type {
+        JobType::ExtractFacts => DerivedJobType::ExtractFacts,
+        JobType::GeneratePreview => DerivedJobType::GeneratePreview,
+        JobType::GenerateThumbnails => DerivedJobType::GenerateThumbnails,
+        JobType::GenerateAudioWaveform => DerivedJobType::GenerateAudioWaveform,
+        JobType::TranscribeAudio => DerivedJobType::TranscribeAudio,
+    }
+}
+
 #[cfg(feature = "core-api-client")]
 fn map_job_state(status: Status) -> CoreJobState {
     match status {
@@ -0,0 +1,107 @@
+use image::{DynamicImage, ImageBuffer, ImageDecoder, ImageReader};
+use moxcms::{ColorProfile, DataColorSpace, Layout, ProfileText, TransformOptions};
+
+pub const SRGB_COLOR_SPACE: &str = "sRGB";
+pub const ADOBE_RGB_COLOR_SPACE: &str = "Adobe RGB";
+pub const DISPLAY_P3_COLOR_SPACE: &str = "Display P3";
+pub const PROPHOTO_RGB_COLOR_SPACE: &str = "ProPhoto RGB";
+
+// Reads the embedded ICC profile from the container header without decoding pixels.
+pub fn read_icc_profile(input_path: &str) -> Option<Vec<u8>> {
+    let mut decoder = ImageReader::open(input_path).ok()?.into_decoder().ok()?;
+    decoder.icc_profile().ok().flatten()
+}
+
+// Normalized color space name for well-known profiles, the profile
+// description otherwise.
+pub fn icc_color_space_name(icc: &[u8]) -> Option<String> {
+    let profile = ColorProfile::new_from_slice(icc).ok()?;
+    let description = profile_description(&profile)?;
+    Some(color_space_name_from_description(&description))
+}
+
+pub fn color_space_name_from_description(description: &str) -> String {
+    let normalized = description
+        .to_ascii_lowercase()
+        .replace([' ', '-', '_'], "");
+    if normalized.contains("srgb") {
+        SRGB_COLOR_SPACE.to_string()
+    } else if normalized.contains("adobergb") {
+        ADOBE_RGB_COLOR_SPACE.to_string()
+    } else if normalized.contains("displayp3") || normalized.contains("p3d65") {
+        DISPLAY_P3_COLOR_SPACE.to_string()
+    } else if normalized.contains("prophoto") || normalized.contains("romm") {
+        PROPHOTO_RGB_COLOR_SPACE.to_string()
+    } else {
+        description.trim().to_string()
+    }
+}
+
+// Source profile for a named color space when no ICC profile is embedded
+// (EXIF/DCF only flags Adobe RGB through the interoperability index).
+pub fn color_profile_for_color_space(color_space: &str) -> Option<ColorProfile> {
+    match color_space {
+        ADOBE_RGB_COLOR_SPACE => Some(ColorProfile::new_adobe_rgb()),
+        DISPLAY_P3_COLOR_SPACE => Some(ColorProfile::new_display_p3()),
+        _ => None,
+    }
+}
+
+pub fn icc_color_profile(icc: &[u8]) -> Option<ColorProfile> {
+    ColorProfile::new_from_slice(icc).ok()
+}
+
+// Converts RGB pixels from `source` to sRGB; `None` when nothing needs to
+// change (sRGB already, or a gray/CMYK profile the decoder flattened itself).
+pub fn convert_to_srgb(
+    image: &DynamicImage,
+    source: &ColorProfile,
+) -> Result<Option<DynamicImage>, String> {
+    if source.color_space != DataColorSpace::Rgb {
+        return Ok(None);
+    }
+    let is_srgb = profile_description(source).is_some_and(|description| {
+        color_space_name_from_description(&description) == SRGB_COLOR_SPACE
+    });
+    if is_srgb {
+        return Ok(None);
+    }
+    let srgb = ColorProfile::new_srgb();
+    let (width, height) = (image.width(), image.height());
+    let (layout, pixels) = if image.color().has_alpha() {
+        (Layout::Rgba, image.to_rgba8().into_raw())
+    } else {
+        (Layout::Rgb, image.to_rgb8().into_raw())
+    };
+    let transform = source
+        .create_transform_8bit(layout, &srgb, layout, TransformOptions::default())
+        .map_err(|error| error.to_string())?;
+    let mut converted = vec![0_u8; pixels.len()];
+    transform
+        .transform(&pixels, &mut converted)
+        .map_err(|error| error.to_string())?;
+    let image = if layout == Layout::Rgba {
+        ImageBuffer::from_raw(width, height, converted).map(DynamicImage::ImageRgba8)
+    } else {
+        ImageBuffer::from_raw(width, height, converted).map(DynamicImage::ImageRgb8)
+    };
+    image
+        .map(Some)
+        .ok_or_else(|| "unable to rebuild image after color conversion".to_string())
+}
+
+fn profile_description(profile: &ColorProfile) -> Option<String> {
+    let description = match profile.description.as_ref()? {
+        ProfileText::PlainString(value) => value.clone(),
+        ProfileText::Localizable(values) => values.first()?.value.clone(),
+        ProfileText::Description(value) => {
+            if value.ascii_string.trim().is_empty() {
+                value.unicode_string.clone()
+            } else {
+                value.ascii_string.clone()
+            }
+        }
+    };
+    let trimmed = description.trim_matches(char::from(0)).trim();
+    (!trimmed.is_empty()).then(|| trimmed.to_string())
+}
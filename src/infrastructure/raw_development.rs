@@ -0,0 +1,246 @@
+use image::{DynamicImage, ImageBuffer, Rgb};
+use rawloader::{CFA, RawImage, RawImageData};
+
+// Linear sRGB (D65) to CIE XYZ.
+const SRGB_TO_XYZ: [[f32; 3]; 3] = [
+    [0.412_453, 0.357_580, 0.180_423],
+    [0.212_671, 0.715_160, 0.072_169],
+    [0.019_334, 0.119_193, 0.950_227],
+];
+const GAMMA_LUT_SIZE: usize = 4096;
+
+// Develops sensor data into an sRGB preview: crop, black/white level
+// normalization, white balance, bilinear demosaic, camera-to-sRGB matrix and
+// sRGB gamma. Tuned for previews, not for final renders.
+pub fn develop_raw_image(raw: &RawImage) -> Result<DynamicImage, String> {
+    let [top, right, bottom, left] = raw.crops;
+    let width = raw.width.saturating_sub(left.saturating_add(right));
+    let height = raw.height.saturating_sub(top.saturating_add(bottom));
+    if width == 0 || height == 0 {
+        return Err("raw image is empty after crop".to_string());
+    }
+    if raw.cpp == 0 {
+        return Err("raw decoder returned cpp=0".to_string());
+    }
+    let expected_len = raw
+        .width
+        .checked_mul(raw.height)
+        .and_then(|pixels| pixels.checked_mul(raw.cpp))
+        .ok_or_else(|| "raw dimensions overflow".to_string())?;
+    let sensor = SensorFrame::new(raw, top, left);
+    if sensor.len() < expected_len {
+        return Err("raw decoder returned less data than expected".to_string());
+    }
+
+    let white_balance = white_balance_multipliers(raw);
+    let camera_to_srgb = camera_to_srgb_matrix(raw);
+    let gamma = srgb_gamma_lut();
+    let encode = |camera: [f32; 4]| -> [u8; 3] {
+        let mut rgb = [0_u8; 3];
+        for (channel, row) in rgb.iter_mut().zip(&camera_to_srgb) {
+            let linear = row
+                .iter()
+                .zip(&camera)
+                .map(|(coefficient, value)| coefficient * value)
+                .sum::<f32>();
+            *channel = gamma_encode(&gamma, linear);
+        }
+        rgb
+    };
+
+    let mut out = Vec::with_capacity(width.saturating_mul(height).saturating_mul(3));
+    if raw.cpp >= 3 {
+        for row in 0..height {
+            for col in 0..width {
+                let mut camera = [0.0; 4];
+                for (color, value) in camera.iter_mut().take(3).enumerate() {
+                    *value =
+                        (sensor.normalized(row, col, color, color) * white_balance[color]).min(1.0);
+                }
+                out.extend_from_slice(&encode(camera));
+            }
+        }
+    } else if raw.is_monochrome() {
+        for row in 0..height {
+            for col in 0..width {
+                let value = gamma_encode(&gamma, sensor.normalized(row, col, 0, 0));
+                out.extend_from_slice(&[value, value, value]);
+            }
+        }
+    } else {
+        let cfa = raw.cropped_cfa();
+        for row in 0..height {
+            for col in 0..width {
+                let camera =
+                    demosaic_bilinear(&sensor, &cfa, &white_balance, row, col, width, height);
+                out.extend_from_slice(&encode(camera));
+            }
+        }
+    }
+
+    let width = u32::try_from(width).map_err(|_| "raw width overflow".to_string())?;
+    let height = u32::try_from(height).map_err(|_| "raw height overflow".to_string())?;
+    let image = ImageBuffer::<Rgb<u8>, Vec<u8>>::from_raw(width, height, out)
+        .ok_or_else(|| "unable to create RGB image from raw development".to_string())?;
+    Ok(DynamicImage::ImageRgb8(image))
+}
+
+struct SensorFrame<'a> {
+    data: SensorData<'a>,
+    stride: usize,
+    cpp: usize,
+    top: usize,
+    left: usize,
+    black: [f32; 4],
+    range: [f32; 4],
+}
+
+enum SensorData<'a> {
+    Integer(&'a [u16]),
+    Float(&'a [f32]),
+}
+
+impl<'a> SensorFrame<'a> {
+    fn new(raw: &'a RawImage, top: usize, left: usize) -> Self {
+        let (data, black, white) = match &raw.data {
+            RawImageData::Integer(values) => (
+                SensorData::Integer(values),
+                raw.blacklevels.map(f32::from),
+                raw.whitelevels.map(f32::from),
+            ),
+            // Float sensors are already scaled to 0..1.
+            RawImageData::Float(values) => (SensorData::Float(values), [0.0; 4], [1.0; 4]),
+        };
+        let mut range = [1.0; 4];
+        for (index, value) in range.iter_mut().enumerate() {
+            let span = white[index] - black[index];
+            if span > 0.0 {
+                *value = span;
+            }
+        }
+        Self {
+            data,
+            stride: raw.width,
+            cpp: raw.cpp,
+            top,
+            left,
+            black,
+            range,
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self.data {
+            SensorData::Integer(values) => values.len(),
+            SensorData::Float(values) => values.len(),
+        }
+    }
+
+    // `row`/`col` are relative to the crop; `level` picks the black/white level slot.
+    fn normalized(&self, row: usize, col: usize, sample: usize, level: usize) -> f32 {
+        let index = ((row + self.top) * self.stride + col + self.left) * self.cpp + sample;
+        let value = match self.data {
+            SensorData::Integer(values) => f32::from(values[index]),
+            SensorData::Float(values) => values[index],
+        };
+        ((value - self.black[level]) / self.range[level]).clamp(0.0, 1.0)
+    }
+}
+
+fn demosaic_bilinear(
+    sensor: &SensorFrame<'_>,
+    cfa: &CFA,
+    white_balance: &[f32; 4],
+    row: usize,
+    col: usize,
+    width: usize,
+    height: usize,
+) -> [f32; 4] {
+    let mut sums = [0.0_f32; 3];
+    let mut counts = [0_u32; 3];
+    for sample_row in row.saturating_sub(1)..=(row + 1).min(height - 1) {
+        for sample_col in col.saturating_sub(1)..=(col + 1).min(width - 1) {
+            let color = cfa.color_at(sample_row, sample_col).min(3);
+            // The fourth CFA color is a second green on RGBE/RGGB-style sensors.
+            let channel = if color == 3 { 1 } else { color };
+            sums[channel] += (sensor.normalized(sample_row, sample_col, 0, color)
+                * white_balance[color])
+                .min(1.0);
+            counts[channel] += 1;
+        }
+    }
+    let mut camera = [0.0; 4];
+    for channel in 0..3 {
+        if counts[channel] > 0 {
+            camera[channel] = sums[channel] / counts[channel] as f32;
+        }
+    }
+    camera
+}
+
+fn white_balance_multipliers(raw: &RawImage) -> [f32; 4] {
+    let usable = |coeffs: &[f32; 4]| {
+        coeffs[..3]
+            .iter()
+            .all(|value| value.is_finite() && *value > 0.0)
+    };
+    let mut coeffs = if usable(&raw.wb_coeffs) {
+        raw.wb_coeffs
+    } else {
+        raw.neutralwb()
+    };
+    if !usable(&coeffs) {
+        return [1.0; 4];
+    }
+    if !(coeffs[3].is_finite() && coeffs[3] > 0.0) {
+        coeffs[3] = coeffs[1];
+    }
+    // Scaling so the weakest channel stays at 1.0 clips highlights to white, not pink.
+    let min = coeffs.iter().copied().fold(f32::INFINITY, f32::min);
+    coeffs.map(|value| value / min)
+}
+
+fn camera_to_srgb_matrix(raw: &RawImage) -> [[f32; 4]; 3] {
+    let identity = [
+        [1.0, 0.0, 0.0, 0.0],
+        [0.0, 1.0, 0.0, 0.0],
+        [0.0, 0.0, 1.0, 0.0],
+    ];
+    if raw.xyz_to_cam.iter().flatten().all(|value| *value == 0.0) {
+        return identity;
+    }
+    let mut srgb_to_camera = [[0.0_f32; 3]; 4];
+    for (camera_row, xyz_row) in srgb_to_camera.iter_mut().zip(&raw.xyz_to_cam) {
+        for (column, value) in camera_row.iter_mut().enumerate() {
+            *value = (0..3)
+                .map(|index| xyz_row[index] * SRGB_TO_XYZ[index][column])
+                .sum();
+        }
+    }
+    // Normalized rows keep camera white (after white balance) mapped to sRGB white.
+    let matrix = RawImage::normalized_pseudoinverse(srgb_to_camera);
+    if matrix.iter().flatten().all(|value| value.is_finite()) {
+        matrix
+    } else {
+        identity
+    }
+}
+
+fn srgb_gamma_lut() -> Vec<u8> {
+    (0..GAMMA_LUT_SIZE)
+        .map(|index| {
+            let linear = index as f32 / (GAMMA_LUT_SIZE - 1) as f32;
+            let encoded = if linear <= 0.003_130_8 {
+                linear * 12.92
+            } else {
+                1.055 * linear.powf(1.0 / 2.4) - 0.055
+            };
+            (encoded * 255.0).round().clamp(0.0, 255.0) as u8
+        })
+        .collect()
+}
+
+fn gamma_encode(lut: &[u8], linear: f32) -> u8 {
+    let index = (linear.clamp(0.0, 1.0) * (GAMMA_LUT_SIZE - 1) as f32).round() as usize;
+    lut[index]
+}
@@ -0,0 +1,214 @@
+use std::collections::HashSet;
+use std::io::Cursor;
+use std::ops::Range;
+
+use image::{DynamicImage, ImageFormat, ImageReader};
+
+const TAG_COMPRESSION: u16 = 0x0103;
+const TAG_PHOTOMETRIC_INTERPRETATION: u16 = 0x0106;
+const TAG_STRIP_OFFSETS: u16 = 0x0111;
+const TAG_STRIP_BYTE_COUNTS: u16 = 0x0117;
+const TAG_SUB_IFDS: u16 = 0x014a;
+const TAG_JPEG_INTERCHANGE_FORMAT: u16 = 0x0201;
+const TAG_JPEG_INTERCHANGE_FORMAT_LENGTH: u16 = 0x0202;
+const TAG_EXIF_IFD: u16 = 0x8769;
+const COMPRESSION_OLD_JPEG: u32 = 6;
+const COMPRESSION_JPEG: u32 = 7;
+const PHOTOMETRIC_CFA: u32 = 32803;
+const PHOTOMETRIC_LINEAR_RAW: u32 = 34892;
+const MAX_IFDS: usize = 64;
+
+// Byte ranges of JPEG streams embedded in a TIFF-based RAW container (CR2, NEF,
+// ARW, DNG, ...): `JPEGInterchangeFormat` thumbnails and JPEG-compressed strips,
+// found by walking the IFD chain, `SubIFDs` and the Exif IFD. Sensor data
+// strips (CFA / LinearRaw) are skipped.
+pub fn embedded_jpeg_ranges(bytes: &[u8]) -> Vec<Range<usize>> {
+    let Some((reader, first_ifd)) = TiffReader::parse(bytes) else {
+        return Vec::new();
+    };
+    let mut ranges = Vec::new();
+    let mut visited = HashSet::new();
+    let mut pending = vec![first_ifd];
+    while let Some(offset) = pending.pop() {
+        if offset == 0 || visited.len() >= MAX_IFDS || !visited.insert(offset) {
+            continue;
+        }
+        let Some(ifd) = reader.ifd(offset) else {
+            continue;
+        };
+        if let Some(next) = ifd.next {
+            pending.push(next);
+        }
+        pending.extend(ifd.values(TAG_SUB_IFDS));
+        pending.extend(ifd.values(TAG_EXIF_IFD));
+
+        if let (Some(start), Some(length)) = (
+            ifd.value(TAG_JPEG_INTERCHANGE_FORMAT),
+            ifd.value(TAG_JPEG_INTERCHANGE_FORMAT_LENGTH),
+        ) {
+            ranges.extend(jpeg_range(bytes, start, length));
+        }
+        let compressed_as_jpeg = matches!(
+            ifd.value(TAG_COMPRESSION),
+            Some(COMPRESSION_OLD_JPEG | COMPRESSION_JPEG)
+        );
+        let sensor_data = matches!(
+            ifd.value(TAG_PHOTOMETRIC_INTERPRETATION),
+            Some(PHOTOMETRIC_CFA | PHOTOMETRIC_LINEAR_RAW)
+        );
+        let strip_offsets = ifd.values(TAG_STRIP_OFFSETS);
+        let strip_byte_counts = ifd.values(TAG_STRIP_BYTE_COUNTS);
+        if compressed_as_jpeg
+            && !sensor_data
+            && let ([start], [length]) = (strip_offsets.as_slice(), strip_byte_counts.as_slice())
+        {
+            ranges.extend(jpeg_range(bytes, *start, *length));
+        }
+    }
+    ranges.sort_by_key(|range| range.start);
+    ranges.dedup();
+    ranges
+}
+
+// Decodes the largest embedded JPEG preview, provided it covers the target box
+// without upscaling (at least as wide or as tall as requested).
+pub fn decode_largest_embedded_preview(
+    bytes: &[u8],
+    target_width: u32,
+    target_height: u32,
+) -> Option<DynamicImage> {
+    let (range, (width, height)) = embedded_jpeg_ranges(bytes)
+        .into_iter()
+        .filter_map(|range| {
+            let dimensions =
+                ImageReader::with_format(Cursor::new(&bytes[range.clone()]), ImageFormat::Jpeg)
+                    .into_dimensions()
+                    .ok()?;
+            Some((range, dimensions))
+        })
+        .max_by_key(|(_, (width, height))| u64::from(*width) * u64::from(*height))?;
+    if width < target_width && height < target_height {
+        return None;
+    }
+    image::load_from_memory_with_format(&bytes[range], ImageFormat::Jpeg).ok()
+}
+
+fn jpeg_range(bytes: &[u8], start: u32, length: u32) -> Option<Range<usize>> {
+    let start = usize::try_from(start).ok()?;
+    let end = start.checked_add(usize::try_from(length).ok()?)?;
+    let stream = bytes.get(start..end)?;
+    stream.starts_with(&[0xff, 0xd8]).then_some(start..end)
+}
+
+struct TiffReader<'a> {
+    bytes: &'a [u8],
+    big_endian: bool,
+}
+
+struct Ifd {
+    entries: Vec<(u16, Vec<u32>)>,
+    next: Option<u32>,
+}
+
+impl Ifd {
+    fn values(&self, tag: u16) -> Vec<u32> {
+        self.entries
+            .iter()
+            .find(|(entry_tag, _)| *entry_tag == tag)
+            .map(|(_, values)| values.clone())
+            .unwrap_or_default()
+    }
+
+    fn value(&self, tag: u16) -> Option<u32> {
+        self.values(tag).first().copied()
+    }
+}
+
+impl<'a> TiffReader<'a> {
+    fn parse(bytes: &'a [u8]) -> Option<(Self, u32)> {
+        let big_endian = match bytes.get(0..2)? {
+            b"II" => false,
+            b"MM" => true,
+            _ => return None,
+        };
+        let reader = Self { bytes, big_endian };
+        // 42 for TIFF/DNG/NEF/ARW/CR2; Olympus and Panasonic use their own magic.
+        if !matches!(reader.u16_at(2)?, 42 | 0x4f52 | 0x5352 | 0x55) {
+            return None;
+        }
+        let first_ifd = reader.u32_at(4)?;
+        Some((reader, first_ifd))
+    }
+
+    fn ifd(&self, offset: u32) -> Option<Ifd> {
+        let offset = usize::try_from(offset).ok()?;
+        let count = usize::from(self.u16_at(offset)?);
+        let mut entries = Vec::with_capacity(count);
+        for index in 0..count {
+            let entry = offset + 2 + index * 12;
+            let tag = self.u16_at(entry)?;
+            let field_type = self.u16_at(entry + 2)?;
+            let value_count = usize::try_from(self.u32_at(entry + 4)?).ok()?;
+            if let Some(values) = self.entry_values(entry + 8, field_type, value_count) {
+                entries.push((tag, values));
+            }
+        }
+        let next = self
+            .u32_at(offset + 2 + count * 12)
+            .filter(|next| *next != 0);
+        Some(Ifd { entries, next })
+    }
+
+    // Only SHORT, LONG and IFD fields are decoded; they cover every offset/size tag we read.
+    fn entry_values(&self, value_offset: usize, field_type: u16, count: usize) -> Option<Vec<u32>> {
+        let width = match field_type {
+            3 => 2,
+            4 | 13 => 4,
+            _ => return None,
+        };
+        if count == 0 || count > 1024 {
+            return None;
+        }
+        let start = if width * count <= 4 {
+            value_offset
+        } else {
+            usize::try_from(self.u32_at(value_offset)?).ok()?
+        };
+        (0..count)
+            .map(|index| {
+                let position = start + index * width;
+                if width == 2 {
+                    self.u16_at(position).map(u32::from)
+                } else {
+                    self.u32_at(position)
+                }
+            })
+            .collect()
+    }
+
+    fn u16_at(&self, offset: usize) -> Option<u16> {
+        let raw: [u8; 2] = self
+            .bytes
+            .get(offset..offset.checked_add(2)?)?
+            .try_into()
+            .ok()?;
+        Some(if self.big_endian {
+            u16::from_be_bytes(raw)
+        } else {
+            u16::from_le_bytes(raw)
+        })
+    }
+
+    fn u32_at(&self, offset: usize) -> Option<u32> {
+        let raw: [u8; 4] = self
+            .bytes
+            .get(offset..offset.checked_add(4)?)?
+            .try_into()
+            .ok()?;
+        Some(if self.big_endian {
+            u32::from_be_bytes(raw)
+        } else {
+            u32::from_le_bytes(raw)
+        })
+    }
+}
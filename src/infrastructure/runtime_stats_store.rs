@@ -28,6 +28,14 @@ pub struct DaemonLastJobStats {
     pub completed_at_unix_ms: u64,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct DaemonDrainStats {
+    pub phase: String,
+    pub started_at_unix_ms: u64,
+    pub deadline_at_unix_ms: u64,
+    pub active_job_ids: Vec<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct DaemonRuntimeStats {
     pub updated_at_unix_ms: u64,
@@ -35,6 +43,8 @@ pub struct DaemonRuntimeStats {
     pub tick: u64,
     pub current_job: Option<DaemonCurrentJobStats>,
     pub last_job: Option<DaemonLastJobStats>,
+    #[serde(default)]
+    pub drain: Option<DaemonDrainStats>,
 }
 
 impl DaemonRuntimeStats {
@@ -45,6 +55,7 @@ impl DaemonRuntimeStats {
             tick,
             current_job: None,
             last_job: None,
+            drain: None,
         }
     }
 }
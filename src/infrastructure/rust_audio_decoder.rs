@@ -0,0 +1,127 @@
+use std::fs::File;
+use std::path::Path;
+use std::time::Instant;
+
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::codecs::{CODEC_TYPE_NULL, DecoderOptions};
+use symphonia::core::errors::Error as SymphoniaError;
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+
+use crate::application::job_cancellation::CancellationToken;
+use crate::application::proxy_generator::{MediaTimeoutKind, ProxyGenerationError};
+use crate::infrastructure::ffmpeg_proxy_generator::CommandLimits;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PcmBlockSpec {
+    pub channels: u16,
+    pub sample_rate: u32,
+}
+
+// Decodes the first audio track with symphonia and hands interleaved 16-bit
+// blocks to `on_block`. Corrupt packets are skipped like ffmpeg does; any
+// other decoder error aborts. `limits` are checked before every packet, the
+// same cancellation and wall-clock rules as a supervised ffmpeg run.
+// Returns the number of decoded frames.
+pub fn decode_audio_interleaved<F>(
+    input_path: &Path,
+    limits: &CommandLimits<'_>,
+    mut on_block: F,
+) -> Result<u64, ProxyGenerationError>
+where
+    F: FnMut(PcmBlockSpec, &[i16]),
+{
+    let started_at = Instant::now();
+    let file =
+        File::open(input_path).map_err(|error| ProxyGenerationError::Process(error.to_string()))?;
+    let mut hint = Hint::new();
+    if let Some(extension) = input_path.extension().and_then(|value| value.to_str()) {
+        hint.with_extension(extension);
+    }
+    let probed = symphonia::default::get_probe()
+        .format(
+            &hint,
+            MediaSourceStream::new(Box::new(file), Default::default()),
+            &FormatOptions::default(),
+            &MetadataOptions::default(),
+        )
+        .map_err(map_symphonia_error)?;
+    let mut format = probed.format;
+    let track = format
+        .tracks()
+        .iter()
+        .find(|track| {
+            track.codec_params.codec != CODEC_TYPE_NULL && track.codec_params.sample_rate.is_some()
+        })
+        .ok_or_else(|| {
+            ProxyGenerationError::DecodeFailed("source has no audio track".to_string())
+        })?;
+    let track_id = track.id;
+    let mut decoder = symphonia::default::get_codecs()
+        .make(&track.codec_params, &DecoderOptions::default())
+        .map_err(map_symphonia_error)?;
+
+    let mut buffer: Option<SampleBuffer<i16>> = None;
+    let mut frame_count = 0_u64;
+    loop {
+        if limits
+            .cancellation
+            .is_some_and(CancellationToken::is_cancelled)
+        {
+            return Err(ProxyGenerationError::Cancelled);
+        }
+        if limits
+            .wall_clock
+            .is_some_and(|limit| started_at.elapsed() >= limit)
+        {
+            return Err(ProxyGenerationError::TimedOut {
+                operation: limits.operation,
+                kind: MediaTimeoutKind::WallClock,
+            });
+        }
+        let packet = match format.next_packet() {
+            Ok(packet) => packet,
+            Err(SymphoniaError::IoError(error))
+                if error.kind() == std::io::ErrorKind::UnexpectedEof =>
+            {
+                break;
+            }
+            Err(error) => return Err(map_symphonia_error(error)),
+        };
+        if packet.track_id() != track_id {
+            continue;
+        }
+        let decoded = match decoder.decode(&packet) {
+            Ok(decoded) => decoded,
+            Err(SymphoniaError::DecodeError(_)) => continue,
+            Err(error) => return Err(map_symphonia_error(error)),
+        };
+        let signal_spec = *decoded.spec();
+        let spec = PcmBlockSpec {
+            channels: u16::try_from(signal_spec.channels.count()).unwrap_or(u16::MAX),
+            sample_rate: signal_spec.rate,
+        };
+        if spec.channels == 0 || decoded.frames() == 0 {
+            continue;
+        }
+        let capacity = decoded.capacity() as u64;
+        let samples = buffer.get_or_insert_with(|| SampleBuffer::new(capacity, signal_spec));
+        if samples.capacity() < decoded.capacity() * usize::from(spec.channels) {
+            *samples = SampleBuffer::new(capacity, signal_spec);
+        }
+        samples.copy_interleaved_ref(decoded);
+        frame_count += (samples.samples().len() / usize::from(spec.channels)) as u64;
+        on_block(spec, samples.samples());
+    }
+    Ok(frame_count)
+}
+
+fn map_symphonia_error(error: SymphoniaError) -> ProxyGenerationError {
+    let message = format!("native audio decode failed: {error}");
+    match error {
+        SymphoniaError::IoError(_) => ProxyGenerationError::Process(message),
+        _ => ProxyGenerationError::DecodeFailed(message),
+    }
+}
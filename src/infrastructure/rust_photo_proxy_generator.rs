@@ -5,16 +5,36 @@ use std::path::Path;
 use chrono::{FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
 use exif::{DateTime as ExifDateTime, In, Rational, Reader as ExifReader, SRational, Tag, Value};
 use image::imageops::FilterType;
-use image::{DynamicImage, ImageBuffer, ImageFormat, Rgb};
+use image::metadata::Orientation;
+use image::{DynamicImage, ImageError, ImageFormat};
 
 use crate::application::derived_processing_gateway::FactsPatchPayload;
+use crate::application::job_cancellation::CancellationToken;
 use crate::application::proxy_generator::{
-    AudioProxyRequest, PhotoProxyFormat, PhotoProxyRequest, ProxyGenerationError, ProxyGenerator,
-    VideoProxyRequest,
+    AudioProxyRequest, MediaOperation, MediaToolTimeouts, PhotoDecodePath, PhotoProxyFormat,
+    PhotoProxyRequest, ProxyGenerationError, ProxyGenerator, ProxyProgressSink, VideoProxyRequest,
 };
+use crate::domain::capabilities::heif_source_extension;
+use crate::infrastructure::ffmpeg_proxy_generator::CommandLimits;
+use crate::infrastructure::libheif_decoder::decode_heif_with_libheif;
+use crate::infrastructure::photo_color_management::{
+    ADOBE_RGB_COLOR_SPACE, SRGB_COLOR_SPACE, color_profile_for_color_space, convert_to_srgb,
+    icc_color_profile, icc_color_space_name, read_icc_profile,
+};
+use crate::infrastructure::raw_development::develop_raw_image;
+use crate::infrastructure::raw_embedded_preview::decode_largest_embedded_preview;
 
-pub trait RawPhotoDecoder {
+// Decoders for stills the `image` crate cannot open. HEIF/AVIF output is
+// expected upright already: the container transforms win over EXIF orientation.
+pub trait RawPhotoDecoder: Send + Sync {
     fn decode_photo(&self, input_path: &str) -> Result<DynamicImage, String>;
+    fn decode_heif(
+        &self,
+        input_path: &str,
+        limits: &CommandLimits<'_>,
+    ) -> Result<DynamicImage, ProxyGenerationError> {
+        decode_heif_with_libheif(input_path, limits)
+    }
 }
 
 #[derive(Debug, Default, Clone, Copy)]
@@ -23,26 +43,18 @@ pub struct RawloaderPhotoDecoder;
 impl RawPhotoDecoder for RawloaderPhotoDecoder {
     fn decode_photo(&self, input_path: &str) -> Result<DynamicImage, String> {
         let decoded = rawloader::decode_file(input_path).map_err(|error| error.to_string())?;
-        let width = u32::try_from(decoded.width).map_err(|_| "raw width overflow".to_string())?;
-        let height =
-            u32::try_from(decoded.height).map_err(|_| "raw height overflow".to_string())?;
-        let rgb = match decoded.data {
-            rawloader::RawImageData::Integer(values) => {
-                to_rgb8_from_integer(&values, decoded.cpp, width, height)?
-            }
-            rawloader::RawImageData::Float(values) => {
-                to_rgb8_from_float(&values, decoded.cpp, width, height)?
-            }
-        };
-        let image = ImageBuffer::<Rgb<u8>, Vec<u8>>::from_raw(width, height, rgb)
-            .ok_or_else(|| "unable to create RGB image from raw decode".to_string())?;
-        Ok(DynamicImage::ImageRgb8(image))
+        let developed = develop_raw_image(&decoded)?;
+        Ok(apply_exif_orientation(
+            developed,
+            u32::from(decoded.orientation.to_u16()),
+        ))
     }
 }
 
 #[derive(Debug, Clone)]
 pub struct RustPhotoProxyGenerator<D: RawPhotoDecoder = RawloaderPhotoDecoder> {
     raw_decoder: D,
+    timeouts: MediaToolTimeouts,
 }
 
 impl Default for RustPhotoProxyGenerator<RawloaderPhotoDecoder> {
@@ -53,18 +65,42 @@ impl Default for RustPhotoProxyGenerator<RawloaderPhotoDecoder> {
 
 impl<D: RawPhotoDecoder> RustPhotoProxyGenerator<D> {
     pub fn new(raw_decoder: D) -> Self {
-        Self { raw_decoder }
+        Self {
+            raw_decoder,
+            timeouts: MediaToolTimeouts::default(),
+        }
+    }
+
+    pub fn with_timeouts(mut self, timeouts: MediaToolTimeouts) -> Self {
+        self.timeouts = timeouts;
+        self
     }
 
     pub fn raw_decoder(&self) -> &D {
         &self.raw_decoder
     }
+
+    pub fn timeouts(&self) -> &MediaToolTimeouts {
+        &self.timeouts
+    }
+
+    // Only external decoders (libheif) are supervised; in-process codecs
+    // cannot be interrupted mid-image.
+    fn decode_limits<'a>(&self, cancellation: Option<&'a CancellationToken>) -> CommandLimits<'a> {
+        CommandLimits::for_timeout(
+            MediaOperation::PhotoDecode,
+            self.timeouts.photo_decode,
+            None,
+        )
+        .with_cancellation(cancellation)
+    }
 }
 
 impl<D: RawPhotoDecoder> ProxyGenerator for RustPhotoProxyGenerator<D> {
     fn generate_video_proxy(
         &self,
         _request: &VideoProxyRequest,
+        _progress: &dyn ProxyProgressSink,
     ) -> Result<(), ProxyGenerationError> {
         Err(ProxyGenerationError::InvalidRequest(
             "video proxy generation is handled by FfmpegProxyGenerator".to_string(),
@@ -74,6 +110,7 @@ impl<D: RawPhotoDecoder> ProxyGenerator for RustPhotoProxyGenerator<D> {
     fn generate_audio_proxy(
         &self,
         _request: &AudioProxyRequest,
+        _progress: &dyn ProxyProgressSink,
     ) -> Result<(), ProxyGenerationError> {
         Err(ProxyGenerationError::InvalidRequest(
             "audio proxy generation is handled by FfmpegProxyGenerator".to_string(),
@@ -83,22 +120,35 @@ impl<D: RawPhotoDecoder> ProxyGenerator for RustPhotoProxyGenerator<D> {
     fn generate_photo_proxy(
         &self,
         request: &PhotoProxyRequest,
-    ) -> Result<(), ProxyGenerationError> {
+        progress: &dyn ProxyProgressSink,
+    ) -> Result<Option<PhotoDecodePath>, ProxyGenerationError> {
         validate_photo_request(request)?;
-        let source = load_source_image(&self.raw_decoder, &request.input_path)?;
+        let (source, decode_path) = load_proxy_source_image(
+            &self.raw_decoder,
+            &request.input_path,
+            u32::from(request.max_width),
+            u32::from(request.max_height),
+            &self.decode_limits(progress.cancellation()),
+        )?;
         let resized = source.resize(
             u32::from(request.max_width),
             u32::from(request.max_height),
             FilterType::Lanczos3,
         );
-        write_photo_proxy(&resized, &request.output_path, request.format)
+        write_photo_proxy(&resized, &request.output_path, request.format)?;
+        Ok(Some(decode_path))
     }
 
     fn extract_media_facts(
         &self,
         input_path: &str,
+        progress: &dyn ProxyProgressSink,
     ) -> Result<FactsPatchPayload, ProxyGenerationError> {
-        let source = load_source_image(&self.raw_decoder, input_path)?;
+        let (source, upright) = load_source_image_with_limits(
+            &self.raw_decoder,
+            input_path,
+            &self.decode_limits(progress.cancellation()),
+        )?;
         let format = image::ImageFormat::from_path(input_path)
             .ok()
             .map(|format| {
@@ -128,6 +178,22 @@ impl<D: RawPhotoDecoder> ProxyGenerator for RustPhotoProxyGenerator<D> {
         if let Some(exif_facts) = extract_exif_facts(input_path) {
             merge_photo_facts(&mut facts, exif_facts);
         }
+        // Facts carry the stored pixel size next to `orientation`, like video
+        // facts next to `rotation_deg`: upright RAW/HEIF output is turned back.
+        if upright
+            && facts
+                .orientation
+                .and_then(|orientation| u32::try_from(orientation).ok())
+                .is_some_and(exif_orientation_swaps_axes)
+        {
+            std::mem::swap(&mut facts.width, &mut facts.height);
+        }
+        // An embedded ICC profile describes the pixels better than the EXIF flag.
+        if let Some(color_space) =
+            read_icc_profile(input_path).and_then(|icc| icc_color_space_name(&icc))
+        {
+            facts.color_space = Some(color_space);
+        }
         Ok(facts)
     }
 }
@@ -145,6 +211,7 @@ fn merge_photo_facts(target: &mut FactsPatchPayload, extra: FactsPatchPayload) {
     target.gps_latitude = extra.gps_latitude.or(target.gps_latitude);
     target.gps_longitude = extra.gps_longitude.or(target.gps_longitude);
     target.gps_altitude_m = extra.gps_altitude_m.or(target.gps_altitude_m);
+    target.color_space = extra.color_space.or_else(|| target.color_space.take());
 }
 
 #[doc(hidden)]
@@ -166,6 +233,7 @@ pub fn extract_exif_facts(input_path: &str) -> Option<FactsPatchPayload> {
         gps_latitude: exif_gps_coordinate(&exif, Tag::GPSLatitude, Tag::GPSLatitudeRef),
         gps_longitude: exif_gps_coordinate(&exif, Tag::GPSLongitude, Tag::GPSLongitudeRef),
         gps_altitude_m: exif_gps_altitude(&exif),
+        color_space: exif_color_space(&exif),
         ..FactsPatchPayload::default()
     })
 }
@@ -178,6 +246,14 @@ fn exif_captured_at(exif: &exif::Exif) -> Option<String> {
     .or_else(|| exif_gps_timestamp(exif))
 }
 
+// DCF: ColorSpace 1 is sRGB; Adobe RGB files are "uncalibrated" with interop index R03.
+fn exif_color_space(exif: &exif::Exif) -> Option<String> {
+    if exif_ascii_field(exif, Tag::InteroperabilityIndex).as_deref() == Some("R03") {
+        return Some(ADOBE_RGB_COLOR_SPACE.to_string());
+    }
+    (exif_uint_field(exif, Tag::ColorSpace) == Some(1)).then(|| SRGB_COLOR_SPACE.to_string())
+}
+
 fn exif_ascii_field(exif: &exif::Exif, tag: Tag) -> Option<String> {
     let field = exif.get_field(tag, In::PRIMARY)?;
     match &field.value {
@@ -363,18 +439,170 @@ pub fn load_source_image<D: RawPhotoDecoder>(
     raw_decoder: &D,
     input_path: &str,
 ) -> Result<DynamicImage, ProxyGenerationError> {
+    load_source_image_with_limits(
+        raw_decoder,
+        input_path,
+        &CommandLimits::for_timeout(
+            MediaOperation::PhotoDecode,
+            MediaToolTimeouts::default().photo_decode,
+            None,
+        ),
+    )
+    .map(|(image, _)| image)
+}
+
+// The flag tells whether the pixels came back upright (RAW and HEIF decoders)
+// rather than as stored (`image` sources).
+fn load_source_image_with_limits<D: RawPhotoDecoder>(
+    raw_decoder: &D,
+    input_path: &str,
+    limits: &CommandLimits<'_>,
+) -> Result<(DynamicImage, bool), ProxyGenerationError> {
+    if is_heif_source(input_path) {
+        return decode_heif_source(raw_decoder, input_path, limits).map(|image| (image, true));
+    }
     match image::open(input_path) {
-        Ok(image) => Ok(image),
+        Ok(image) => Ok((image, false)),
         Err(image_error) => raw_decoder
             .decode_photo(input_path)
-            .map_err(|raw_error| {
-                ProxyGenerationError::Process(format!(
-                    "unable to decode photo source with image crate ({image_error}) or raw fallback ({raw_error})"
-                ))
-            }),
+            .map(|image| (image, true))
+            .map_err(|raw_error| photo_decode_error(&image_error, &raw_error)),
+    }
+}
+
+// Like `load_source_image`, but a RAW container whose largest embedded JPEG
+// preview already covers the target box skips the sensor decode entirely.
+// Pixels come back upright: EXIF orientation is applied to `image` and
+// embedded-preview sources, raw decoders are expected to orient their output.
+#[doc(hidden)]
+pub fn load_proxy_source_image<D: RawPhotoDecoder>(
+    raw_decoder: &D,
+    input_path: &str,
+    target_width: u32,
+    target_height: u32,
+    limits: &CommandLimits<'_>,
+) -> Result<(DynamicImage, PhotoDecodePath), ProxyGenerationError> {
+    if is_heif_source(input_path) {
+        return decode_heif_source(raw_decoder, input_path, limits)
+            .map(|image| (image, PhotoDecodePath::Heif));
+    }
+    let orientation = read_exif_orientation(input_path);
+    let image_error = match image::open(input_path) {
+        Ok(image) => {
+            let image = convert_source_to_srgb(image, input_path);
+            return Ok((
+                apply_exif_orientation(image, orientation),
+                PhotoDecodePath::Image,
+            ));
+        }
+        Err(error) => error,
+    };
+    // Previews are stored unrotated, so a portrait target box is checked sideways.
+    let (target_width, target_height) = if exif_orientation_swaps_axes(orientation) {
+        (target_height, target_width)
+    } else {
+        (target_width, target_height)
+    };
+    let embedded_preview = std::fs::read(input_path)
+        .ok()
+        .and_then(|bytes| decode_largest_embedded_preview(&bytes, target_width, target_height));
+    if let Some(preview) = embedded_preview {
+        let preview = convert_source_to_srgb(preview, input_path);
+        return Ok((
+            apply_exif_orientation(preview, orientation),
+            PhotoDecodePath::EmbeddedPreview,
+        ));
+    }
+    raw_decoder
+        .decode_photo(input_path)
+        .map(|image| (image, PhotoDecodePath::RawDevelopment))
+        .map_err(|raw_error| photo_decode_error(&image_error, &raw_error))
+}
+
+// An unreadable file is an I/O problem; anything else means neither decoder
+// understands the bytes.
+fn photo_decode_error(image_error: &ImageError, raw_error: &str) -> ProxyGenerationError {
+    let message = format!(
+        "unable to decode photo source with image crate ({image_error}) or raw fallback ({raw_error})"
+    );
+    match image_error {
+        ImageError::IoError(_) => ProxyGenerationError::Process(message),
+        _ => ProxyGenerationError::DecodeFailed(message),
+    }
+}
+
+// Pixels are kept as decoded when the source profile is unknown or cannot be
+// turned into a transform: a slightly off preview beats a failed job.
+fn convert_source_to_srgb(image: DynamicImage, input_path: &str) -> DynamicImage {
+    let profile = read_icc_profile(input_path)
+        .and_then(|icc| icc_color_profile(&icc))
+        .or_else(|| {
+            extract_exif_facts(input_path)
+                .and_then(|facts| facts.color_space)
+                .and_then(|color_space| color_profile_for_color_space(&color_space))
+        });
+    match profile.map(|profile| convert_to_srgb(&image, &profile)) {
+        Some(Ok(Some(converted))) => converted,
+        _ => image,
     }
 }
 
+fn is_heif_source(input_path: &str) -> bool {
+    Path::new(input_path)
+        .extension()
+        .and_then(|value| value.to_str())
+        .is_some_and(heif_source_extension)
+}
+
+fn decode_heif_source<D: RawPhotoDecoder>(
+    raw_decoder: &D,
+    input_path: &str,
+    limits: &CommandLimits<'_>,
+) -> Result<DynamicImage, ProxyGenerationError> {
+    raw_decoder
+        .decode_heif(input_path, limits)
+        .map_err(|error| match error {
+            ProxyGenerationError::Process(message) => ProxyGenerationError::Process(format!(
+                "unable to decode HEIF/AVIF photo source ({message})"
+            )),
+            ProxyGenerationError::DecodeFailed(message) => ProxyGenerationError::DecodeFailed(
+                format!("unable to decode HEIF/AVIF photo source ({message})"),
+            ),
+            error => error,
+        })
+}
+
+fn read_exif_orientation(input_path: &str) -> u32 {
+    let Ok(file) = File::open(input_path) else {
+        return 1;
+    };
+    let mut reader = std::io::BufReader::new(file);
+    ExifReader::new()
+        .read_from_container(&mut reader)
+        .ok()
+        .and_then(|exif| {
+            exif.get_field(Tag::Orientation, In::PRIMARY)
+                .and_then(|field| field.value.get_uint(0))
+        })
+        .unwrap_or(1)
+}
+
+// EXIF values 5..=8 turn the image a quarter, so width and height trade places.
+fn exif_orientation_swaps_axes(orientation: u32) -> bool {
+    (5..=8).contains(&orientation)
+}
+
+#[doc(hidden)]
+pub fn apply_exif_orientation(mut image: DynamicImage, orientation: u32) -> DynamicImage {
+    let orientation = u8::try_from(orientation)
+        .ok()
+        .and_then(Orientation::from_exif);
+    if let Some(orientation) = orientation {
+        image.apply_orientation(orientation);
+    }
+    image
+}
+
 #[doc(hidden)]
 pub fn write_photo_proxy(
     image: &DynamicImage,
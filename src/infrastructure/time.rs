@@ -20,7 +20,7 @@ impl Clock for StdClock {
     }
 }
 
-pub trait FileTimestampProvider {
+pub trait FileTimestampProvider: Send + Sync {
     fn created_at_utc(&self, path: &Path) -> Option<DateTime<Utc>>;
     fn modified_at_utc(&self, path: &Path) -> Option<DateTime<Utc>>;
 }
@@ -10,6 +10,10 @@ pub use application::agent_registration::{
 pub use application::agent_runtime_app::{
     AgentRuntimeApp, RuntimeStatusView, SettingsSaveError, TrayMenuModel,
 };
+pub use application::audio_waveform::{
+    WAVEFORM_V2_MAGIC, WAVEFORM_V2_SAMPLE_RATE, WAVEFORM_V2_SAMPLES_PER_PEAK, WAVEFORM_V2_VERSION,
+    WaveformPeak, WaveformPeaks, WaveformPeaksBuilder, WaveformZoomLevel,
+};
 pub use application::config_repository::{ConfigRepository, ConfigRepositoryError};
 pub use application::core_api_gateway::{
     CoreApiGateway, CoreApiGatewayError, CoreJobState, CoreJobView, CoreServerPolicy,
@@ -20,30 +24,60 @@ pub use application::daemon_manager::{
     DaemonInstallRequest, DaemonLabelRequest, DaemonLevel, DaemonManager, DaemonManagerError,
     DaemonStatus,
 };
+pub use application::derived_integrity::{
+    DerivedArtifactDigest, DerivedIntegrityError, attach_upload_integrity, digest_artifact,
+    sha256_file, verify_upload_integrity,
+};
 pub use application::derived_job_executor::{
     DerivedExecutionPlan, DerivedExecutionPlanner, DerivedExecutionReport, DerivedJobExecutorError,
-    DerivedUploadPlan, execute_derived_job_once, execute_derived_job_once_with_source_staging,
+    DerivedJobProgressSink, DerivedUploadPlan, execute_derived_job_once,
+    execute_derived_job_once_with_progress, execute_derived_job_once_with_retry_policies,
+    execute_derived_job_once_with_source_staging,
 };
+pub use application::derived_job_journal::{
+    DerivedJobJournal, DerivedJobJournalEntry, DerivedJobJournalError, DerivedJobJournalStage,
+    JournaledUpload, JournaledUploadPart, NoopDerivedJobJournal,
+};
+pub use application::derived_lease_keeper::{DerivedJobLease, LeaseKeeperPolicy, is_lease_lost};
 pub use application::derived_processing_gateway::{
-    ClaimedDerivedJob, DerivedJobType, DerivedKind, DerivedManifestItem, DerivedProcessingError,
-    DerivedProcessingGateway, DerivedUploadComplete, DerivedUploadInit, DerivedUploadPart,
-    FactsPatchPayload, HeartbeatReceipt, SubmitDerivedPayload, UploadedDerivedPart,
-    validate_derived_upload_init,
+    ClaimedDerivedJob, DerivedJobFailure, DerivedJobType, DerivedKind, DerivedManifestItem,
+    DerivedProcessingError, DerivedProcessingGateway, DerivedUploadComplete, DerivedUploadInit,
+    DerivedUploadPart, FactsPatchPayload, HeartbeatReceipt, SubmitDerivedPayload,
+    UploadedDerivedPart, split_upload_parts, validate_derived_upload_init,
+};
+pub use application::gateway_retry::{
+    GatewayCall, GatewayRetryPolicies, GatewayRetryPolicy, retry_gateway_call,
+};
+pub use application::job_cancellation::{CancellationReason, CancellationToken};
+pub use application::job_selection::{
+    AssetFairSelection, FifoSelection, JobSelectionContext, JobSelectionPolicy,
+    JobTypePrioritySelection, job_selection_policy_for, job_type_priority, select_pending_jobs,
 };
 pub use application::notification_bridge::{
     NotificationBridgeError, NotificationDispatchReport, NotificationMessage, NotificationSink,
     dispatch_notifications, notification_message,
 };
+pub use application::offline_processing::{
+    OFFLINE_ASSET_UUID, OFFLINE_JOB_ID, OFFLINE_STORAGE_ID, OFFLINE_SUBMIT_PAYLOAD_FILE_NAME,
+    OfflineProcessError, OfflineProcessReport, OfflineProcessRequest, run_offline_processing,
+    synthetic_claimed_job,
+};
 pub use application::proxy_generator::{
-    AudioProxyFormat, AudioProxyRequest, AudioWaveformRequest, PhotoProxyFormat, PhotoProxyRequest,
-    ProxyGenerationError, ProxyGenerator, ThumbnailFormat, VideoProxyRequest,
+    AudioProxyFormat, AudioProxyRequest, AudioWaveformFormat, AudioWaveformRequest,
+    HLS_MASTER_PLAYLIST_FILE_NAME, LoudnessMeasurement, MediaOperation, MediaOperationTimeout,
+    MediaTimeoutKind, MediaTimeoutOverride, MediaToolTimeouts, NoopProxyProgressSink,
+    PhotoDecodePath, PhotoProxyFormat, PhotoProxyRequest, ProxyGenerationError, ProxyGenerator,
+    ProxyProgressSink, ThumbnailFormat, VideoFrameAnalysisRequest, VideoFrameSample,
+    VideoHlsLadderRequest, VideoProxyRequest, VideoRendition, VideoSpriteSheetRequest,
     VideoThumbnailRequest, resolve_processing_input_path,
 };
 pub use application::runtime_cli_shell::{
     ShellCommand, ShellCommandResult, execute_shell_command, format_menu, format_settings,
     format_status, help_text, parse_shell_command,
 };
-pub use application::runtime_derived_planner::RuntimeDerivedPlanner;
+pub use application::runtime_derived_planner::{
+    DEFAULT_UPLOAD_PART_SIZE_BYTES, RuntimeDerivedPlanner, VideoPreviewProfile,
+};
 pub use application::runtime_desktop_shell_controller::{
     DesktopShellBridge, DesktopShellController,
 };
@@ -51,6 +85,7 @@ pub use application::runtime_gui_shell::{
     GuiActionOutcome, GuiDaemonContext, GuiMenuAction, GuiMenuView, RuntimeGuiShellError,
     apply_gui_menu_action, menu_view, settings_panel_content, status_window_content,
 };
+pub use application::runtime_job_pool::{AbandonedJob, RuntimeJobOutcome, RuntimeJobPool};
 pub use application::runtime_job_worker::{RuntimeJobWorkerError, process_next_pending_job};
 pub use application::runtime_loop_engine::RuntimeLoopEngine;
 pub use application::runtime_poll_cycle::{
@@ -58,21 +93,34 @@ pub use application::runtime_poll_cycle::{
 };
 pub use application::runtime_session::{RuntimeNotificationReport, RuntimeSession};
 pub use application::runtime_sync_coordinator::{RuntimeSyncCoordinator, RuntimeSyncPlan};
+pub use application::shutdown_drain::{
+    DEFAULT_DRAIN_DEADLINE, DRAIN_ABANDON_WAIT, DRAIN_RELEASE_GRACE, DrainPhase, ShutdownDrain,
+};
 pub use application::source_staging::{
     DiskSpaceProbe, Fs2DiskSpaceProbe, SourceStagingError, StagedSourceFile,
     stage_claimed_job_source, stage_claimed_job_source_with_probe,
 };
+pub use application::thumbnail_sprite::{
+    SpriteSheetLayout, THUMBNAIL_SPRITE_PROFILE, ThumbnailSpriteProfile, sprite_sheet_webvtt,
+};
+pub use application::video_storyboard::{
+    MIN_LUMA_SPREAD, NEAR_BLACK_LUMA, SCENE_CHANGE_THRESHOLD, STORYBOARD_FRAME_COUNT,
+    frame_is_usable, select_storyboard_frames, storyboard_sample_interval_ms,
+};
 pub use domain::capabilities::{
-    AgentCapability, declared_agent_capabilities, declared_agent_capabilities_with_ffmpeg,
+    AgentCapability, HEIF_DECODER_COMMANDS, audio_source_extension_supported,
+    declared_agent_capabilities, declared_agent_capabilities_with_ffmpeg,
     declared_agent_capabilities_with_runtime, ffmpeg_available, has_required_capabilities,
-    photo_proxy_available, photo_source_extension_supported,
+    heif_decoder_available, heif_decoder_command, heif_source_extension, photo_proxy_available,
+    photo_source_extension_supported, photo_source_extension_supported_with_heif,
 };
 pub use domain::configuration::{
-    AgentRuntimeConfig, AuthMode, ConfigField, ConfigInterface, ConfigValidationError, LogLevel,
-    RuntimeConfigUpdate, SourcePathResolveError, StorageMarkerProvider, StorageMarkerRead,
-    TechnicalAuthConfig, apply_config_update, compact_validation_reason, normalize_core_api_url,
-    normalize_storage_mount_path, resolve_source_path, resolve_source_path_with_marker_provider,
-    supported_config_fields, validate_config,
+    AgentRuntimeConfig, AuthMode, ConfigField, ConfigInterface, ConfigValidationError,
+    JobSelectionStrategy, LogLevel, RuntimeConfigUpdate, SourcePathResolveError,
+    StorageMarkerProvider, StorageMarkerRead, TechnicalAuthConfig, apply_config_update,
+    compact_validation_reason, normalize_core_api_url, normalize_storage_mount_path,
+    resolve_source_path, resolve_source_path_with_marker_provider, supported_config_fields,
+    validate_config,
 };
 pub use domain::feature_flags::{
     ClientKind, can_issue_client_token, can_process_jobs, resolve_effective_features,
@@ -86,9 +134,11 @@ pub use domain::runtime_orchestration::{
     PushHint, PushHintDecision, RuntimeOrchestrationMode, can_issue_mutation_after_poll,
     is_push_channel_supported_for_target, is_push_hint_fresh, mobile_push_allowed_for_target,
     next_poll_decision, push_channels_allowed, push_is_authoritative, runtime_orchestration_mode,
-    should_trigger_poll_from_push, throttled_backoff_with_jitter,
+    should_trigger_poll_from_push, throttled_backoff_builder, throttled_backoff_with_jitter,
+};
+pub use domain::runtime_status_tracker::{
+    RuntimeStatusEvent, RuntimeStatusTracker, merge_local_job_snapshot,
 };
-pub use domain::runtime_status_tracker::{RuntimeStatusEvent, RuntimeStatusTracker};
 pub use domain::runtime_sync::{PushProcessResult, RuntimeSyncState};
 pub use domain::runtime_ui::{
     AgentRunState, AgentUiRuntime, ConnectivityState, JobFailure, JobStage, JobStatus, MenuAction,
@@ -105,13 +155,18 @@ pub use infrastructure::daemon_diagnostics::{
     DiagnosticsLimits, RedactedRuntimeConfig, append_redacted_config_markdown,
     build_bug_report_markdown, collect_daemon_diagnostics, copy_to_clipboard,
     daemon_status_as_label, redacted_runtime_config_from, render_daemon_inspect,
-    render_daemon_inspect_json,
+    render_daemon_inspect_json, render_drain_stats,
 };
+pub use infrastructure::derived_job_journal_store::SqliteDerivedJobJournal;
 pub use infrastructure::ffmpeg_proxy_generator::{
-    CommandOutput, CommandRunner, FfmpegProxyGenerator, StdCommandRunner, build_audio_proxy_args,
-    build_video_proxy_args,
+    CommandLimits, CommandOutput, CommandRunner, FfmpegProxyGenerator, StdCommandRunner,
+    build_audio_proxy_args, build_audio_waveform_decode_args, build_ffprobe_duration_args,
+    build_loudness_analysis_args, build_video_frame_analysis_args, build_video_hls_ladder_args,
+    build_video_proxy_args, build_video_sprite_sheet_args, ffmpeg_progress_percent,
+    parse_ebur128_summary, parse_video_frame_analysis, write_waveform_with_native_decoder,
 };
 pub use infrastructure::i18n::{Language, detect_language, parse_language, t};
+pub use infrastructure::libheif_decoder::decode_heif_with_libheif;
 pub use infrastructure::notification_sink::{
     NotificationSinkProfile, RuntimeNotificationSink, StdoutNotificationSink,
     SystemNotificationSink, dispatch_system_notification, notification_sink_profile_for_target,
@@ -125,15 +180,24 @@ pub use infrastructure::openapi_client::{build_core_api_client, with_bearer_toke
 pub use infrastructure::openapi_derived_processing_gateway::OpenApiDerivedProcessingGateway;
 #[cfg(feature = "core-api-client")]
 pub use infrastructure::openapi_jobs_gateway::OpenApiJobsGateway;
+pub use infrastructure::photo_color_management::{
+    ADOBE_RGB_COLOR_SPACE, DISPLAY_P3_COLOR_SPACE, PROPHOTO_RGB_COLOR_SPACE, SRGB_COLOR_SPACE,
+    color_space_name_from_description, convert_to_srgb, icc_color_space_name, read_icc_profile,
+};
+pub use infrastructure::raw_development::develop_raw_image;
+pub use infrastructure::raw_embedded_preview::{
+    decode_largest_embedded_preview, embedded_jpeg_ranges,
+};
 pub use infrastructure::runtime_history_store::{
     CompletedJobEntry, DAEMON_HISTORY_DB_FILE_NAME, DaemonCycleEntry, RuntimeHistoryStore,
     RuntimeHistoryStoreError, runtime_history_db_path,
 };
 pub use infrastructure::runtime_stats_store::{
-    DAEMON_STATS_FILE_NAME, DaemonCurrentJobStats, DaemonLastJobStats, DaemonRuntimeStats,
-    RuntimeStatsStoreError, load_runtime_stats, now_unix_ms, now_unix_ms_with_clock,
-    run_state_label, save_runtime_stats,
+    DAEMON_STATS_FILE_NAME, DaemonCurrentJobStats, DaemonDrainStats, DaemonLastJobStats,
+    DaemonRuntimeStats, RuntimeStatsStoreError, load_runtime_stats, now_unix_ms,
+    now_unix_ms_with_clock, run_state_label, save_runtime_stats,
 };
+pub use infrastructure::rust_audio_decoder::{PcmBlockSpec, decode_audio_interleaved};
 pub use infrastructure::rust_photo_proxy_generator::{
     RawPhotoDecoder, RawloaderPhotoDecoder, RustPhotoProxyGenerator,
 };
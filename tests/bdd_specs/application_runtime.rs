@@ -1,5 +1,6 @@
 use retaia_agent::{
-    AgentRunState, AgentRuntimeApp, AgentRuntimeConfig, AuthMode, LogLevel, MenuAction,
+    AgentRunState, AgentRuntimeApp, AgentRuntimeConfig, AuthMode, JobSelectionStrategy, LogLevel,
+    MenuAction,
 };
 
 fn interactive_settings() -> AgentRuntimeConfig {
@@ -11,6 +12,8 @@ fn interactive_settings() -> AgentRuntimeConfig {
         storage_mounts: std::collections::BTreeMap::new(),
         max_parallel_jobs: 2,
         log_level: LogLevel::Info,
+        job_selection: JobSelectionStrategy::default(),
+        upload_part_size_bytes: None,
     }
 }
 
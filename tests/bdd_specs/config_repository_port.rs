@@ -1,7 +1,8 @@
 use tempfile::tempdir;
 
 use retaia_agent::{
-    AgentRuntimeApp, AgentRuntimeConfig, AuthMode, ConfigRepository, FileConfigRepository, LogLevel,
+    AgentRuntimeApp, AgentRuntimeConfig, AuthMode, ConfigRepository, FileConfigRepository,
+    JobSelectionStrategy, LogLevel,
 };
 
 fn config() -> AgentRuntimeConfig {
@@ -13,6 +14,8 @@ fn config() -> AgentRuntimeConfig {
         storage_mounts: std::collections::BTreeMap::new(),
         max_parallel_jobs: 2,
         log_level: LogLevel::Info,
+        job_selection: JobSelectionStrategy::default(),
+        upload_part_size_bytes: None,
     }
 }
 
@@ -1,8 +1,8 @@
 use tempfile::tempdir;
 
 use retaia_agent::{
-    AgentRuntimeConfig, AuthMode, ConfigStoreError, LogLevel, RuntimeConfigUpdate,
-    apply_config_update, load_config_from_path, save_config_to_path,
+    AgentRuntimeConfig, AuthMode, ConfigStoreError, JobSelectionStrategy, LogLevel,
+    RuntimeConfigUpdate, apply_config_update, load_config_from_path, save_config_to_path,
 };
 
 fn base_config() -> AgentRuntimeConfig {
@@ -14,6 +14,8 @@ fn base_config() -> AgentRuntimeConfig {
         storage_mounts: std::collections::BTreeMap::new(),
         max_parallel_jobs: 2,
         log_level: LogLevel::Info,
+        job_selection: JobSelectionStrategy::default(),
+        upload_part_size_bytes: None,
     }
 }
 
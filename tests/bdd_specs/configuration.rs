@@ -1,6 +1,6 @@
 use retaia_agent::{
-    AgentRuntimeConfig, AuthMode, ConfigValidationError, LogLevel, TechnicalAuthConfig,
-    validate_config,
+    AgentRuntimeConfig, AuthMode, ConfigValidationError, JobSelectionStrategy, LogLevel,
+    TechnicalAuthConfig, validate_config,
 };
 
 fn base_settings() -> AgentRuntimeConfig {
@@ -12,6 +12,8 @@ fn base_settings() -> AgentRuntimeConfig {
         storage_mounts: std::collections::BTreeMap::new(),
         max_parallel_jobs: 2,
         log_level: LogLevel::Info,
+        job_selection: JobSelectionStrategy::default(),
+        upload_part_size_bytes: None,
     }
 }
 
@@ -1,6 +1,6 @@
 use retaia_agent::{
-    AgentRuntimeConfig, AuthMode, ConfigInterface, ConfigValidationError, LogLevel,
-    RuntimeConfigUpdate, apply_config_update,
+    AgentRuntimeConfig, AuthMode, ConfigInterface, ConfigValidationError, JobSelectionStrategy,
+    LogLevel, RuntimeConfigUpdate, apply_config_update,
 };
 
 fn base_config() -> AgentRuntimeConfig {
@@ -12,6 +12,8 @@ fn base_config() -> AgentRuntimeConfig {
         storage_mounts: std::collections::BTreeMap::new(),
         max_parallel_jobs: 2,
         log_level: LogLevel::Info,
+        job_selection: JobSelectionStrategy::default(),
+        upload_part_size_bytes: None,
     }
 }
 
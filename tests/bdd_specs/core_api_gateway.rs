@@ -20,12 +20,14 @@ fn bdd_given_polled_jobs_when_building_runtime_snapshot_then_running_and_current
             CoreJobView {
                 job_id: "job-pending".to_string(),
                 asset_uuid: "asset-1".to_string(),
+                job_type: None,
                 state: CoreJobState::Pending,
                 required_capabilities: vec!["media.facts@1".to_string()],
             },
             CoreJobView {
                 job_id: "job-claimed".to_string(),
                 asset_uuid: "asset-2".to_string(),
+                job_type: None,
                 state: CoreJobState::Claimed,
                 required_capabilities: vec!["media.facts@1".to_string()],
             },
@@ -48,6 +50,7 @@ fn bdd_given_polled_jobs_with_incompatible_capability_when_building_runtime_snap
         jobs: vec![CoreJobView {
             job_id: "job-incompatible".to_string(),
             asset_uuid: "asset-x".to_string(),
+            job_type: None,
             state: CoreJobState::Claimed,
             required_capabilities: vec!["media.unknown@1".to_string()],
         }],
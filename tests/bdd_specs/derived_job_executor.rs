@@ -1,6 +1,6 @@
 use retaia_agent::{
     ClaimedDerivedJob, DerivedExecutionPlan, DerivedExecutionPlanner, DerivedJobExecutorError,
-    DerivedJobType, DerivedKind, DerivedManifestItem, DerivedProcessingError,
+    DerivedJobFailure, DerivedJobType, DerivedKind, DerivedManifestItem, DerivedProcessingError,
     DerivedProcessingGateway, DerivedUploadComplete, DerivedUploadInit, DerivedUploadPart,
     HeartbeatReceipt, SubmitDerivedPayload, UploadedDerivedPart, execute_derived_job_once,
 };
@@ -29,6 +29,8 @@ impl DerivedExecutionPlanner for AssetMismatchPlanner {
                     upload_id: "up-1".to_string(),
                     part_number: 1,
                     chunk_path: std::path::PathBuf::from("/tmp/up-1.bin"),
+                    byte_range: None,
+                    sha256: None,
                 }],
                 complete: DerivedUploadComplete {
                     asset_uuid: claimed.asset_uuid.clone(),
@@ -102,6 +104,17 @@ impl DerivedProcessingGateway for NoopGateway {
         Ok(())
     }
 
+    fn fail_job(
+        &self,
+        _job_id: &str,
+        _lock_token: &str,
+        _fencing_token: i32,
+        _idempotency_key: &str,
+        _failure: &DerivedJobFailure,
+    ) -> Result<(), DerivedProcessingError> {
+        Ok(())
+    }
+
     fn upload_init(&self, _request: &DerivedUploadInit) -> Result<(), DerivedProcessingError> {
         Ok(())
     }
@@ -183,6 +196,17 @@ impl DerivedProcessingGateway for WaveformOptionalManifestGateway {
         Ok(())
     }
 
+    fn fail_job(
+        &self,
+        _job_id: &str,
+        _lock_token: &str,
+        _fencing_token: i32,
+        _idempotency_key: &str,
+        _failure: &DerivedJobFailure,
+    ) -> Result<(), DerivedProcessingError> {
+        Ok(())
+    }
+
     fn upload_init(&self, _request: &DerivedUploadInit) -> Result<(), DerivedProcessingError> {
         Ok(())
     }
@@ -1,5 +1,8 @@
 use image::GenericImageView;
-use retaia_agent::{PhotoProxyFormat, PhotoProxyRequest, ProxyGenerator, RustPhotoProxyGenerator};
+use retaia_agent::{
+    NoopProxyProgressSink, PhotoProxyFormat, PhotoProxyRequest, ProxyGenerator,
+    RustPhotoProxyGenerator,
+};
 
 use crate::external_fixtures::load_manifest_entries;
 
@@ -38,13 +41,16 @@ fn bdd_given_supported_raw_external_fixtures_when_generating_photo_proxy_then_ou
     for (index, entry) in entries.iter().enumerate() {
         let output = temp.path().join(format!("raw-proxy-{index}.webp"));
         generator
-            .generate_photo_proxy(&PhotoProxyRequest {
-                input_path: entry.absolute_path().display().to_string(),
-                output_path: output.display().to_string(),
-                format: PhotoProxyFormat::Webp,
-                max_width: 480,
-                max_height: 320,
-            })
+            .generate_photo_proxy(
+                &PhotoProxyRequest {
+                    input_path: entry.absolute_path().display().to_string(),
+                    output_path: output.display().to_string(),
+                    format: PhotoProxyFormat::Webp,
+                    max_width: 480,
+                    max_height: 320,
+                },
+                &NoopProxyProgressSink,
+            )
             .unwrap_or_else(|error| {
                 panic!(
                     "raw fixture should generate proxy: {} ({error:?})",
@@ -77,7 +83,10 @@ fn bdd_given_supported_raw_external_fixtures_when_extracting_facts_then_dimensio
 
     for entry in entries {
         let facts = generator
-            .extract_media_facts(&entry.absolute_path().display().to_string())
+            .extract_media_facts(
+                &entry.absolute_path().display().to_string(),
+                &NoopProxyProgressSink,
+            )
             .unwrap_or_else(|error| {
                 panic!(
                     "raw fixture should expose facts: {} ({error:?})",
@@ -103,7 +112,10 @@ fn bdd_given_real_canon_raw_fixture_when_extracting_facts_then_stable_exif_value
     let generator = RustPhotoProxyGenerator::default();
 
     let facts = generator
-        .extract_media_facts(&entry.absolute_path().display().to_string())
+        .extract_media_facts(
+            &entry.absolute_path().display().to_string(),
+            &NoopProxyProgressSink,
+        )
         .expect("raw fixture should expose facts");
 
     assert_eq!(facts.media_format.as_deref(), Some("cr2"));
@@ -131,7 +143,10 @@ fn bdd_given_real_nikon_nef_fixture_when_extracting_facts_then_vendor_metadata_i
     let generator = RustPhotoProxyGenerator::default();
 
     let facts = generator
-        .extract_media_facts(&entry.absolute_path().display().to_string())
+        .extract_media_facts(
+            &entry.absolute_path().display().to_string(),
+            &NoopProxyProgressSink,
+        )
         .expect("raw fixture should expose facts");
 
     assert_eq!(facts.media_format.as_deref(), Some("nef"));
@@ -159,7 +174,10 @@ fn bdd_given_real_nikon_nrw_fixture_when_extracting_facts_then_vendor_metadata_i
     let generator = RustPhotoProxyGenerator::default();
 
     let facts = generator
-        .extract_media_facts(&entry.absolute_path().display().to_string())
+        .extract_media_facts(
+            &entry.absolute_path().display().to_string(),
+            &NoopProxyProgressSink,
+        )
         .expect("raw fixture should expose facts");
 
     assert_eq!(facts.media_format.as_deref(), Some("nrw"));
@@ -187,7 +205,10 @@ fn bdd_given_real_sony_arw_fixture_when_extracting_facts_then_vendor_metadata_is
     let generator = RustPhotoProxyGenerator::default();
 
     let facts = generator
-        .extract_media_facts(&entry.absolute_path().display().to_string())
+        .extract_media_facts(
+            &entry.absolute_path().display().to_string(),
+            &NoopProxyProgressSink,
+        )
         .expect("raw fixture should expose facts");
 
     assert_eq!(facts.media_format.as_deref(), Some("arw"));
@@ -215,7 +236,10 @@ fn bdd_given_second_real_sony_arw_fixture_when_extracting_facts_then_vendor_meta
     let generator = RustPhotoProxyGenerator::default();
 
     let facts = generator
-        .extract_media_facts(&entry.absolute_path().display().to_string())
+        .extract_media_facts(
+            &entry.absolute_path().display().to_string(),
+            &NoopProxyProgressSink,
+        )
         .expect("raw fixture should expose facts");
 
     assert_eq!(facts.media_format.as_deref(), Some("arw"));
@@ -2,8 +2,8 @@ use std::sync::Mutex;
 
 use retaia_agent::{
     AudioProxyFormat, AudioProxyRequest, CommandOutput, CommandRunner, FfmpegProxyGenerator,
-    ProxyGenerationError, ProxyGenerator, ThumbnailFormat, VideoProxyRequest,
-    VideoThumbnailRequest,
+    NoopProxyProgressSink, ProxyGenerationError, ProxyGenerator, ThumbnailFormat,
+    VideoProxyRequest, VideoThumbnailRequest,
 };
 
 struct ScenarioRunner {
@@ -28,6 +28,15 @@ impl ScenarioRunner {
             .clone()
     }
 
+    fn last_args(&self) -> Vec<String> {
+        self.calls
+            .lock()
+            .expect("calls")
+            .last()
+            .expect("last call")
+            .clone()
+    }
+
     fn call_count(&self) -> usize {
         self.calls.lock().expect("calls").len()
     }
@@ -50,16 +59,19 @@ fn bdd_given_audio_proxy_format_mpeg_when_generating_then_ffmpeg_uses_libmp3lame
     let generator = FfmpegProxyGenerator::new("ffmpeg".to_string(), runner);
 
     generator
-        .generate_audio_proxy(&AudioProxyRequest {
-            input_path: "/tmp/in.wav".to_string(),
-            output_path: "/tmp/out.mp3".to_string(),
-            format: AudioProxyFormat::Mpeg,
-            audio_bitrate_kbps: 192,
-            sample_rate_hz: 44100,
-        })
+        .generate_audio_proxy(
+            &AudioProxyRequest {
+                input_path: "/tmp/in.wav".to_string(),
+                output_path: "/tmp/out.mp3".to_string(),
+                format: AudioProxyFormat::Mpeg,
+                audio_bitrate_kbps: 192,
+                sample_rate_hz: 44100,
+            },
+            &NoopProxyProgressSink,
+        )
         .expect("mpeg proxy should succeed");
 
-    let args = generator.runner().first_args().join(" ");
+    let args = generator.runner().last_args().join(" ");
     assert!(args.contains("-c:a libmp3lame"));
     assert!(!args.contains("-movflags +faststart"));
 }
@@ -74,13 +86,16 @@ fn bdd_given_ffmpeg_non_zero_exit_when_generating_proxy_then_command_failed_erro
     let generator = FfmpegProxyGenerator::new("ffmpeg".to_string(), runner);
 
     let err = generator
-        .generate_audio_proxy(&AudioProxyRequest {
-            input_path: "/tmp/in.wav".to_string(),
-            output_path: "/tmp/out.m4a".to_string(),
-            format: AudioProxyFormat::Mp4Aac,
-            audio_bitrate_kbps: 160,
-            sample_rate_hz: 48000,
-        })
+        .generate_audio_proxy(
+            &AudioProxyRequest {
+                input_path: "/tmp/in.wav".to_string(),
+                output_path: "/tmp/out.m4a".to_string(),
+                format: AudioProxyFormat::Mp4Aac,
+                audio_bitrate_kbps: 160,
+                sample_rate_hz: 48000,
+            },
+            &NoopProxyProgressSink,
+        )
         .expect_err("ffmpeg failure should be propagated");
 
     assert!(matches!(err, ProxyGenerationError::CommandFailed { .. }));
@@ -97,17 +112,20 @@ fn bdd_given_video_proxy_request_when_generating_then_ffmpeg_is_called_with_h264
     let generator = FfmpegProxyGenerator::new("ffmpeg".to_string(), runner);
 
     generator
-        .generate_video_proxy(&VideoProxyRequest {
-            input_path: "/tmp/in.mov".to_string(),
-            output_path: "/tmp/out.mp4".to_string(),
-            max_width: 1280,
-            max_height: 720,
-            video_bitrate_kbps: 3000,
-            audio_bitrate_kbps: 128,
-        })
+        .generate_video_proxy(
+            &VideoProxyRequest {
+                input_path: "/tmp/in.mov".to_string(),
+                output_path: "/tmp/out.mp4".to_string(),
+                max_width: 1280,
+                max_height: 720,
+                video_bitrate_kbps: 3000,
+                audio_bitrate_kbps: 128,
+            },
+            &NoopProxyProgressSink,
+        )
         .expect("video proxy should succeed");
 
-    let args = generator.runner().first_args().join(" ");
+    let args = generator.runner().last_args().join(" ");
     assert!(args.contains("-c:v libx264"));
     assert!(args.contains("-profile:v high"));
     assert!(args.contains("-preset medium"));
@@ -130,14 +148,17 @@ fn bdd_given_invalid_video_proxy_request_when_generating_then_validation_fails_b
     let generator = FfmpegProxyGenerator::new("ffmpeg".to_string(), runner);
 
     let err = generator
-        .generate_video_proxy(&VideoProxyRequest {
-            input_path: String::new(),
-            output_path: "/tmp/out.mp4".to_string(),
-            max_width: 1280,
-            max_height: 720,
-            video_bitrate_kbps: 3000,
-            audio_bitrate_kbps: 128,
-        })
+        .generate_video_proxy(
+            &VideoProxyRequest {
+                input_path: String::new(),
+                output_path: "/tmp/out.mp4".to_string(),
+                max_width: 1280,
+                max_height: 720,
+                video_bitrate_kbps: 3000,
+                audio_bitrate_kbps: 128,
+            },
+            &NoopProxyProgressSink,
+        )
         .expect_err("invalid video request should fail");
 
     assert!(matches!(err, ProxyGenerationError::InvalidRequest(_)));
@@ -154,13 +175,16 @@ fn bdd_given_invalid_audio_proxy_request_when_zero_sample_rate_then_validation_f
     let generator = FfmpegProxyGenerator::new("ffmpeg".to_string(), runner);
 
     let err = generator
-        .generate_audio_proxy(&AudioProxyRequest {
-            input_path: "/tmp/in.wav".to_string(),
-            output_path: "/tmp/out.m4a".to_string(),
-            format: AudioProxyFormat::Mp4Aac,
-            audio_bitrate_kbps: 160,
-            sample_rate_hz: 0,
-        })
+        .generate_audio_proxy(
+            &AudioProxyRequest {
+                input_path: "/tmp/in.wav".to_string(),
+                output_path: "/tmp/out.m4a".to_string(),
+                format: AudioProxyFormat::Mp4Aac,
+                audio_bitrate_kbps: 160,
+                sample_rate_hz: 0,
+            },
+            &NoopProxyProgressSink,
+        )
         .expect_err("invalid audio request should fail");
 
     assert!(matches!(err, ProxyGenerationError::InvalidRequest(_)));
@@ -177,13 +201,16 @@ fn bdd_given_thumbnail_request_when_generating_then_ffmpeg_extracts_single_webp_
     let generator = FfmpegProxyGenerator::new("ffmpeg".to_string(), runner);
 
     generator
-        .generate_video_thumbnail(&VideoThumbnailRequest {
-            input_path: "/tmp/in.mov".to_string(),
-            output_path: "/tmp/thumb.webp".to_string(),
-            format: ThumbnailFormat::Webp,
-            max_width: 480,
-            seek_ms: 1_000,
-        })
+        .generate_video_thumbnail(
+            &VideoThumbnailRequest {
+                input_path: "/tmp/in.mov".to_string(),
+                output_path: "/tmp/thumb.webp".to_string(),
+                format: ThumbnailFormat::Webp,
+                max_width: 480,
+                seek_ms: 1_000,
+            },
+            &NoopProxyProgressSink,
+        )
         .expect("thumbnail should succeed");
 
     let args = generator.runner().first_args().join(" ");
@@ -6,9 +6,10 @@ use tempfile::tempdir;
 
 use retaia_agent::{
     AgentRunState, AgentRuntimeApp, AgentRuntimeConfig, AuthMode, CONFIG_FILE_ENV,
-    ClientRuntimeTarget, ConfigRepository, ConfigRepositoryError, ConnectivityState, JobStage,
-    JobStatus, LogLevel, MenuAction, PollEndpoint, PollSignal, RuntimeSession, RuntimeSnapshot,
-    RuntimeSyncPlan, SystemConfigRepository, SystemNotification, SystemNotificationSink,
+    ClientRuntimeTarget, ConfigRepository, ConfigRepositoryError, ConnectivityState,
+    JobSelectionStrategy, JobStage, JobStatus, LogLevel, MenuAction, PollEndpoint, PollSignal,
+    RuntimeSession, RuntimeSnapshot, RuntimeSyncPlan, SystemConfigRepository, SystemNotification,
+    SystemNotificationSink,
 };
 
 fn settings() -> AgentRuntimeConfig {
@@ -20,6 +21,8 @@ fn settings() -> AgentRuntimeConfig {
         storage_mounts: std::collections::BTreeMap::new(),
         max_parallel_jobs: 2,
         log_level: LogLevel::Info,
+        job_selection: JobSelectionStrategy::default(),
+        upload_part_size_bytes: None,
     }
 }
 
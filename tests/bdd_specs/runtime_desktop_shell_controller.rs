@@ -1,7 +1,7 @@
 use retaia_agent::{
     AgentRuntimeConfig, AuthMode, ClientRuntimeTarget, DaemonLabelRequest, DaemonManager,
     DaemonManagerError, DaemonStatus, DesktopShellBridge, DesktopShellController, GuiMenuAction,
-    LogLevel, RuntimeSession,
+    JobSelectionStrategy, LogLevel, RuntimeSession,
 };
 
 fn config() -> AgentRuntimeConfig {
@@ -13,6 +13,8 @@ fn config() -> AgentRuntimeConfig {
         storage_mounts: std::collections::BTreeMap::new(),
         max_parallel_jobs: 2,
         log_level: LogLevel::Info,
+        job_selection: JobSelectionStrategy::default(),
+        upload_part_size_bytes: None,
     }
 }
 
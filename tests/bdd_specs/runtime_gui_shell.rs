@@ -1,7 +1,7 @@
 use retaia_agent::{
     AgentRuntimeConfig, AuthMode, ClientRuntimeTarget, DaemonLabelRequest, DaemonManager,
-    DaemonManagerError, DaemonStatus, LogLevel, RuntimeSession, settings_panel_content,
-    status_window_content,
+    DaemonManagerError, DaemonStatus, JobSelectionStrategy, LogLevel, RuntimeSession,
+    settings_panel_content, status_window_content,
 };
 use retaia_agent::{GuiDaemonContext, GuiMenuAction, apply_gui_menu_action};
 
@@ -14,6 +14,8 @@ fn config() -> AgentRuntimeConfig {
         storage_mounts: std::collections::BTreeMap::new(),
         max_parallel_jobs: 2,
         log_level: LogLevel::Info,
+        job_selection: JobSelectionStrategy::default(),
+        upload_part_size_bytes: None,
     }
 }
 
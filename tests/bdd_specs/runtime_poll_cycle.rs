@@ -1,7 +1,8 @@
 use retaia_agent::{
     AgentRuntimeConfig, AuthMode, ClientRuntimeTarget, CoreApiGateway, CoreApiGatewayError,
-    LogLevel, NotificationBridgeError, NotificationMessage, NotificationSink, PollEndpoint,
-    RuntimePollCycleStatus, RuntimeSession, SystemNotification, run_runtime_poll_cycle,
+    JobSelectionStrategy, LogLevel, NotificationBridgeError, NotificationMessage, NotificationSink,
+    PollEndpoint, RuntimePollCycleStatus, RuntimeSession, SystemNotification,
+    run_runtime_poll_cycle,
 };
 
 fn config() -> AgentRuntimeConfig {
@@ -13,6 +14,8 @@ fn config() -> AgentRuntimeConfig {
         storage_mounts: std::collections::BTreeMap::new(),
         max_parallel_jobs: 1,
         log_level: LogLevel::Info,
+        job_selection: JobSelectionStrategy::default(),
+        upload_part_size_bytes: None,
     }
 }
 
@@ -5,10 +5,10 @@ use tempfile::tempdir;
 
 use retaia_agent::{
     AgentRunState, AgentRuntimeApp, AgentRuntimeConfig, AuthMode, ClientRuntimeTarget,
-    ConfigRepository, ConfigRepositoryError, FileConfigRepository, LogLevel, MenuAction,
-    NotificationMessage, NotificationSink, PollEndpoint, PollSignal, RuntimeControlCommand,
-    RuntimeLoopEngine, RuntimeSession, RuntimeSyncPlan, SettingsSaveError, StdoutNotificationSink,
-    SystemNotification, notification_message,
+    ConfigRepository, ConfigRepositoryError, FileConfigRepository, JobSelectionStrategy, LogLevel,
+    MenuAction, NotificationMessage, NotificationSink, PollEndpoint, PollSignal,
+    RuntimeControlCommand, RuntimeLoopEngine, RuntimeSession, RuntimeSyncPlan, SettingsSaveError,
+    StdoutNotificationSink, SystemNotification, notification_message,
 };
 
 fn settings() -> AgentRuntimeConfig {
@@ -20,6 +20,8 @@ fn settings() -> AgentRuntimeConfig {
         storage_mounts: std::collections::BTreeMap::new(),
         max_parallel_jobs: 2,
         log_level: LogLevel::Info,
+        job_selection: JobSelectionStrategy::default(),
+        upload_part_size_bytes: None,
     }
 }
 
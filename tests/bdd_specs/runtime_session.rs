@@ -1,7 +1,8 @@
 use retaia_agent::{
-    AgentRunState, AgentRuntimeConfig, AuthMode, ClientRuntimeTarget, LogLevel, MenuAction,
-    NotificationBridgeError, NotificationMessage, NotificationSink, PollEndpoint, PushChannel,
-    PushHint, RuntimeSession, RuntimeSnapshot, RuntimeSyncPlan, SystemNotification,
+    AgentRunState, AgentRuntimeConfig, AuthMode, ClientRuntimeTarget, JobSelectionStrategy,
+    LogLevel, MenuAction, NotificationBridgeError, NotificationMessage, NotificationSink,
+    PollEndpoint, PushChannel, PushHint, RuntimeSession, RuntimeSnapshot, RuntimeSyncPlan,
+    SystemNotification,
 };
 use std::cell::RefCell;
 
@@ -14,6 +15,8 @@ fn config() -> AgentRuntimeConfig {
         storage_mounts: std::collections::BTreeMap::new(),
         max_parallel_jobs: 2,
         log_level: LogLevel::Info,
+        job_selection: JobSelectionStrategy::default(),
+        upload_part_size_bytes: None,
     }
 }
 
@@ -4,8 +4,9 @@ use retaia_agent::infrastructure::rust_photo_proxy_generator::{
     validate_photo_request, write_photo_proxy,
 };
 use retaia_agent::{
-    AudioProxyRequest, PhotoProxyFormat, PhotoProxyRequest, ProxyGenerationError, ProxyGenerator,
-    RawPhotoDecoder, RustPhotoProxyGenerator, VideoProxyRequest,
+    AudioProxyRequest, NoopProxyProgressSink, PhotoProxyFormat, PhotoProxyRequest,
+    ProxyGenerationError, ProxyGenerator, RawPhotoDecoder, RustPhotoProxyGenerator,
+    VideoProxyRequest,
 };
 
 struct ScenarioRawDecoder;
@@ -36,13 +37,16 @@ fn bdd_given_photo_input_when_generating_webp_proxy_then_output_is_resized_and_r
 
     let generator = RustPhotoProxyGenerator::default();
     generator
-        .generate_photo_proxy(&PhotoProxyRequest {
-            input_path: input.display().to_string(),
-            output_path: output.display().to_string(),
-            format: PhotoProxyFormat::Webp,
-            max_width: 600,
-            max_height: 400,
-        })
+        .generate_photo_proxy(
+            &PhotoProxyRequest {
+                input_path: input.display().to_string(),
+                output_path: output.display().to_string(),
+                format: PhotoProxyFormat::Webp,
+                max_width: 600,
+                max_height: 400,
+            },
+            &NoopProxyProgressSink,
+        )
         .expect("proxy");
 
     let produced = image::open(&output).expect("open output");
@@ -93,8 +97,9 @@ fn bdd_given_non_image_source_and_failing_raw_decoder_when_loading_then_error_is
     std::fs::write(&path, b"invalid").expect("write source");
 
     let err = load_source_image(&FailingRawDecoder, &path.display().to_string())
-        .expect_err("failing fallback should return process error");
-    assert!(matches!(err, ProxyGenerationError::Process(_)));
+        .expect_err("failing fallback should return decode error");
+    assert!(matches!(err, ProxyGenerationError::DecodeFailed(_)));
+    assert!(!err.is_retryable());
 }
 
 #[test]
@@ -150,21 +155,27 @@ fn bdd_given_scaling_helpers_when_values_are_out_of_range_then_output_is_clamped
 #[test]
 fn bdd_given_photo_generator_when_video_or_audio_requested_then_error_is_explicit() {
     let generator = RustPhotoProxyGenerator::new(ScenarioRawDecoder);
-    let video = generator.generate_video_proxy(&VideoProxyRequest {
-        input_path: "/tmp/in.mov".to_string(),
-        output_path: "/tmp/out.mp4".to_string(),
-        max_width: 640,
-        max_height: 360,
-        video_bitrate_kbps: 1000,
-        audio_bitrate_kbps: 96,
-    });
-    let audio = generator.generate_audio_proxy(&AudioProxyRequest {
-        input_path: "/tmp/in.wav".to_string(),
-        output_path: "/tmp/out.mp3".to_string(),
-        format: retaia_agent::AudioProxyFormat::Mpeg,
-        audio_bitrate_kbps: 128,
-        sample_rate_hz: 44100,
-    });
+    let video = generator.generate_video_proxy(
+        &VideoProxyRequest {
+            input_path: "/tmp/in.mov".to_string(),
+            output_path: "/tmp/out.mp4".to_string(),
+            max_width: 640,
+            max_height: 360,
+            video_bitrate_kbps: 1000,
+            audio_bitrate_kbps: 96,
+        },
+        &NoopProxyProgressSink,
+    );
+    let audio = generator.generate_audio_proxy(
+        &AudioProxyRequest {
+            input_path: "/tmp/in.wav".to_string(),
+            output_path: "/tmp/out.mp3".to_string(),
+            format: retaia_agent::AudioProxyFormat::Mpeg,
+            audio_bitrate_kbps: 128,
+            sample_rate_hz: 44100,
+        },
+        &NoopProxyProgressSink,
+    );
     assert!(matches!(
         video,
         Err(ProxyGenerationError::InvalidRequest(_))
@@ -2,12 +2,12 @@ use std::cell::RefCell;
 
 use retaia_agent::{
     AgentRunState, AgentUiRuntime, ClientRuntimeTarget, ConfigValidationError, ConnectivityState,
-    NotificationBridgeError, NotificationMessage, NotificationSink, PollDecisionReason,
-    PollEndpoint, PollSignal, PushChannel, PushHint, RuntimeControlAvailability,
-    RuntimeControlCommand, RuntimeSession, RuntimeSnapshot, RuntimeStatusEvent,
-    RuntimeStatusTracker, RuntimeSyncCoordinator, RuntimeSyncPlan, SystemNotification,
-    apply_runtime_control, base_menu_actions, compact_validation_reason, dispatch_notifications,
-    menu_visibility, runtime_control_availability, validate_config,
+    JobSelectionStrategy, NotificationBridgeError, NotificationMessage, NotificationSink,
+    PollDecisionReason, PollEndpoint, PollSignal, PushChannel, PushHint,
+    RuntimeControlAvailability, RuntimeControlCommand, RuntimeSession, RuntimeSnapshot,
+    RuntimeStatusEvent, RuntimeStatusTracker, RuntimeSyncCoordinator, RuntimeSyncPlan,
+    SystemNotification, apply_runtime_control, base_menu_actions, compact_validation_reason,
+    dispatch_notifications, menu_visibility, runtime_control_availability, validate_config,
 };
 
 fn valid_config() -> retaia_agent::AgentRuntimeConfig {
@@ -19,6 +19,8 @@ fn valid_config() -> retaia_agent::AgentRuntimeConfig {
         storage_mounts: std::collections::BTreeMap::new(),
         max_parallel_jobs: 2,
         log_level: retaia_agent::LogLevel::Info,
+        job_selection: JobSelectionStrategy::default(),
+        upload_part_size_bytes: None,
     }
 }
 
@@ -1,6 +1,6 @@
 use retaia_agent::{
-    AgentRunState, AgentRuntimeApp, AgentRuntimeConfig, AuthMode, JobStage, JobStatus, LogLevel,
-    MenuAction, RuntimeSnapshot, SystemNotification,
+    AgentRunState, AgentRuntimeApp, AgentRuntimeConfig, AuthMode, JobSelectionStrategy, JobStage,
+    JobStatus, LogLevel, MenuAction, RuntimeSnapshot, SystemNotification,
 };
 
 fn config() -> AgentRuntimeConfig {
@@ -12,6 +12,8 @@ fn config() -> AgentRuntimeConfig {
         storage_mounts: std::collections::BTreeMap::new(),
         max_parallel_jobs: 4,
         log_level: LogLevel::Info,
+        job_selection: JobSelectionStrategy::default(),
+        upload_part_size_bytes: None,
     }
 }
 
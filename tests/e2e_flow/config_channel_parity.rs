@@ -1,6 +1,6 @@
 use retaia_agent::{
-    AgentRuntimeConfig, AuthMode, ConfigField, ConfigInterface, LogLevel, RuntimeConfigUpdate,
-    apply_config_update, supported_config_fields,
+    AgentRuntimeConfig, AuthMode, ConfigField, ConfigInterface, JobSelectionStrategy, LogLevel,
+    RuntimeConfigUpdate, apply_config_update, supported_config_fields,
 };
 
 fn base() -> AgentRuntimeConfig {
@@ -12,6 +12,8 @@ fn base() -> AgentRuntimeConfig {
         storage_mounts: std::collections::BTreeMap::new(),
         max_parallel_jobs: 2,
         log_level: LogLevel::Info,
+        job_selection: JobSelectionStrategy::default(),
+        upload_part_size_bytes: None,
     }
 }
 
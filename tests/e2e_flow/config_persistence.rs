@@ -1,8 +1,8 @@
 use tempfile::tempdir;
 
 use retaia_agent::{
-    AgentRuntimeConfig, AuthMode, ConfigInterface, LogLevel, RuntimeConfigUpdate,
-    apply_config_update, load_config_from_path, save_config_to_path,
+    AgentRuntimeConfig, AuthMode, ConfigInterface, JobSelectionStrategy, LogLevel,
+    RuntimeConfigUpdate, apply_config_update, load_config_from_path, save_config_to_path,
 };
 
 fn defaults() -> AgentRuntimeConfig {
@@ -14,6 +14,8 @@ fn defaults() -> AgentRuntimeConfig {
         storage_mounts: std::collections::BTreeMap::new(),
         max_parallel_jobs: 2,
         log_level: LogLevel::Info,
+        job_selection: JobSelectionStrategy::default(),
+        upload_part_size_bytes: None,
     }
 }
 
@@ -2,7 +2,7 @@ use tempfile::tempdir;
 
 use retaia_agent::{
     AgentRuntimeApp, AgentRuntimeConfig, AuthMode, ConfigInterface, ConfigRepository,
-    FileConfigRepository, LogLevel, RuntimeConfigUpdate, apply_config_update,
+    FileConfigRepository, JobSelectionStrategy, LogLevel, RuntimeConfigUpdate, apply_config_update,
 };
 
 fn base() -> AgentRuntimeConfig {
@@ -14,6 +14,8 @@ fn base() -> AgentRuntimeConfig {
         storage_mounts: std::collections::BTreeMap::new(),
         max_parallel_jobs: 2,
         log_level: LogLevel::Info,
+        job_selection: JobSelectionStrategy::default(),
+        upload_part_size_bytes: None,
     }
 }
 
@@ -1,7 +1,7 @@
 use retaia_agent::{
-    AgentRunState, AgentRuntimeConfig, AgentUiRuntime, AuthMode, ConfigValidationError, LogLevel,
-    RuntimeControlCommand, SystemNotification, apply_runtime_control, compact_validation_reason,
-    validate_config,
+    AgentRunState, AgentRuntimeConfig, AgentUiRuntime, AuthMode, ConfigValidationError,
+    JobSelectionStrategy, LogLevel, RuntimeControlCommand, SystemNotification,
+    apply_runtime_control, compact_validation_reason, validate_config,
 };
 
 #[test]
@@ -15,6 +15,8 @@ fn e2e_invalid_settings_emit_single_deduplicated_notification() {
         storage_mounts: std::collections::BTreeMap::new(),
         max_parallel_jobs: 0,
         log_level: LogLevel::Warn,
+        job_selection: JobSelectionStrategy::default(),
+        upload_part_size_bytes: None,
     };
 
     let errors = validate_config(&config).expect_err("invalid config should fail");
@@ -43,6 +45,8 @@ fn e2e_valid_settings_then_runtime_menu_controls_execute_flow() {
         storage_mounts: std::collections::BTreeMap::new(),
         max_parallel_jobs: 4,
         log_level: LogLevel::Info,
+        job_selection: JobSelectionStrategy::default(),
+        upload_part_size_bytes: None,
     };
     assert_eq!(validate_config(&config), Ok(()));
 
@@ -35,6 +35,7 @@ fn e2e_polled_jobs_gateway_projection_triggers_new_job_and_all_jobs_done_notific
         jobs: vec![CoreJobView {
             job_id: "job-10".to_string(),
             asset_uuid: "asset-10".to_string(),
+            job_type: None,
             state: CoreJobState::Claimed,
             required_capabilities: vec!["media.facts@1".to_string()],
         }],
@@ -48,6 +49,7 @@ fn e2e_polled_jobs_gateway_projection_triggers_new_job_and_all_jobs_done_notific
         jobs: vec![CoreJobView {
             job_id: "job-10".to_string(),
             asset_uuid: "asset-10".to_string(),
+            job_type: None,
             state: CoreJobState::Claimed,
             required_capabilities: vec!["media.facts@1".to_string()],
         }],
@@ -70,6 +72,7 @@ fn e2e_polled_jobs_gateway_projection_ignores_claimed_job_without_declared_capab
         jobs: vec![CoreJobView {
             job_id: "job-unsupported".to_string(),
             asset_uuid: "asset-unsupported".to_string(),
+            job_type: None,
             state: CoreJobState::Claimed,
             required_capabilities: vec!["media.unknown@1".to_string()],
         }],
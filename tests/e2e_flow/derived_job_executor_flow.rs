@@ -3,10 +3,11 @@ use std::sync::Mutex;
 
 use retaia_agent::{
     AgentRuntimeConfig, AudioProxyRequest, AuthMode, ClaimedDerivedJob, DerivedExecutionPlan,
-    DerivedExecutionPlanner, DerivedJobExecutorError, DerivedJobType, DerivedKind,
-    DerivedManifestItem, DerivedProcessingError, DerivedProcessingGateway, DerivedUploadComplete,
-    DerivedUploadInit, DerivedUploadPart, FactsPatchPayload, HeartbeatReceipt, LogLevel,
-    PhotoProxyRequest, ProxyGenerationError, ProxyGenerator, RuntimeDerivedPlanner,
+    DerivedExecutionPlanner, DerivedJobExecutorError, DerivedJobFailure, DerivedJobType,
+    DerivedKind, DerivedManifestItem, DerivedProcessingError, DerivedProcessingGateway,
+    DerivedUploadComplete, DerivedUploadInit, DerivedUploadPart, FactsPatchPayload,
+    HeartbeatReceipt, JobSelectionStrategy, LogLevel, PhotoDecodePath, PhotoProxyRequest,
+    ProxyGenerationError, ProxyGenerator, ProxyProgressSink, RuntimeDerivedPlanner,
     SubmitDerivedPayload, UploadedDerivedPart, VideoProxyRequest, execute_derived_job_once,
     execute_derived_job_once_with_source_staging,
 };
@@ -82,6 +83,21 @@ impl DerivedProcessingGateway for RecordingGateway {
         Ok(())
     }
 
+    fn fail_job(
+        &self,
+        job_id: &str,
+        _lock_token: &str,
+        _fencing_token: i32,
+        _idempotency_key: &str,
+        failure: &DerivedJobFailure,
+    ) -> Result<(), DerivedProcessingError> {
+        self.calls
+            .lock()
+            .expect("calls")
+            .push(format!("fail:{job_id}:{}", failure.error_code));
+        Ok(())
+    }
+
     fn upload_init(&self, request: &DerivedUploadInit) -> Result<(), DerivedProcessingError> {
         self.calls.lock().expect("calls").push(format!(
             "upload_init:{}:{}",
@@ -124,6 +140,7 @@ impl ProxyGenerator for WritingPreviewGenerator {
     fn generate_video_proxy(
         &self,
         request: &VideoProxyRequest,
+        _progress: &dyn ProxyProgressSink,
     ) -> Result<(), ProxyGenerationError> {
         std::fs::write(&request.output_path, b"generated-video")
             .map_err(|error| ProxyGenerationError::Process(error.to_string()))
@@ -132,6 +149,7 @@ impl ProxyGenerator for WritingPreviewGenerator {
     fn generate_audio_proxy(
         &self,
         request: &AudioProxyRequest,
+        _progress: &dyn ProxyProgressSink,
     ) -> Result<(), ProxyGenerationError> {
         std::fs::write(&request.output_path, b"generated-audio")
             .map_err(|error| ProxyGenerationError::Process(error.to_string()))
@@ -140,14 +158,17 @@ impl ProxyGenerator for WritingPreviewGenerator {
     fn generate_photo_proxy(
         &self,
         request: &PhotoProxyRequest,
-    ) -> Result<(), ProxyGenerationError> {
+        _progress: &dyn ProxyProgressSink,
+    ) -> Result<Option<PhotoDecodePath>, ProxyGenerationError> {
         std::fs::write(&request.output_path, b"generated-photo")
             .map_err(|error| ProxyGenerationError::Process(error.to_string()))
+            .map(|()| None)
     }
 
     fn extract_media_facts(
         &self,
         _input_path: &str,
+        _progress: &dyn ProxyProgressSink,
     ) -> Result<FactsPatchPayload, ProxyGenerationError> {
         Ok(FactsPatchPayload {
             duration_ms: Some(1_000),
@@ -235,6 +256,21 @@ impl DerivedProcessingGateway for ExtractFactsRecordingGateway {
         Ok(())
     }
 
+    fn fail_job(
+        &self,
+        job_id: &str,
+        _lock_token: &str,
+        _fencing_token: i32,
+        _idempotency_key: &str,
+        failure: &DerivedJobFailure,
+    ) -> Result<(), DerivedProcessingError> {
+        self.calls
+            .lock()
+            .expect("calls")
+            .push(format!("fail:{job_id}:{}", failure.error_code));
+        Ok(())
+    }
+
     fn upload_init(&self, _request: &DerivedUploadInit) -> Result<(), DerivedProcessingError> {
         unreachable!("extract_facts does not upload derived files")
     }
@@ -278,6 +314,8 @@ impl DerivedExecutionPlanner for ThumbnailPlanner {
                     upload_id: "up-thumb-1".to_string(),
                     part_number: 1,
                     chunk_path: std::path::PathBuf::from("/tmp/up-thumb-1.bin"),
+                    byte_range: None,
+                    sha256: None,
                 }],
                 complete: DerivedUploadComplete {
                     asset_uuid: claimed.asset_uuid.clone(),
@@ -324,7 +362,6 @@ fn e2e_derived_job_executor_flow_claims_uploads_and_submits_for_v1_derived_job()
             "fetch_revision_etag:asset-22".to_string(),
             "heartbeat:job-22".to_string(),
             "upload_init:asset-22:thumb".to_string(),
-            "heartbeat:job-22".to_string(),
             "upload_part:asset-22:1".to_string(),
             "heartbeat:job-22".to_string(),
             "upload_complete:asset-22".to_string(),
@@ -365,6 +402,8 @@ fn e2e_derived_job_executor_flow_extract_facts_submits_useful_patch_without_uplo
         storage_mounts,
         max_parallel_jobs: 1,
         log_level: LogLevel::Info,
+        job_selection: JobSelectionStrategy::default(),
+        upload_part_size_bytes: None,
     };
 
     let gateway = ExtractFactsRecordingGateway::default();
@@ -508,6 +547,21 @@ impl DerivedProcessingGateway for WaveformOptionalGateway {
         Ok(())
     }
 
+    fn fail_job(
+        &self,
+        job_id: &str,
+        _lock_token: &str,
+        _fencing_token: i32,
+        _idempotency_key: &str,
+        failure: &DerivedJobFailure,
+    ) -> Result<(), DerivedProcessingError> {
+        self.calls
+            .lock()
+            .expect("calls")
+            .push(format!("fail:{job_id}:{}", failure.error_code));
+        Ok(())
+    }
+
     fn upload_init(&self, _request: &DerivedUploadInit) -> Result<(), DerivedProcessingError> {
         panic!("waveform optional flow should not upload");
     }
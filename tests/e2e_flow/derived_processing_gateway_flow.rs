@@ -1,9 +1,9 @@
 use std::sync::Mutex;
 
 use retaia_agent::{
-    ClaimedDerivedJob, DerivedJobType, DerivedKind, DerivedManifestItem, DerivedProcessingError,
-    DerivedProcessingGateway, DerivedUploadComplete, DerivedUploadInit, DerivedUploadPart,
-    HeartbeatReceipt, SubmitDerivedPayload, UploadedDerivedPart,
+    ClaimedDerivedJob, DerivedJobFailure, DerivedJobType, DerivedKind, DerivedManifestItem,
+    DerivedProcessingError, DerivedProcessingGateway, DerivedUploadComplete, DerivedUploadInit,
+    DerivedUploadPart, HeartbeatReceipt, SubmitDerivedPayload, UploadedDerivedPart,
 };
 
 #[derive(Default)]
@@ -77,6 +77,21 @@ impl DerivedProcessingGateway for MemoryDerivedGateway {
         Ok(())
     }
 
+    fn fail_job(
+        &self,
+        job_id: &str,
+        _lock_token: &str,
+        _fencing_token: i32,
+        _idempotency_key: &str,
+        failure: &DerivedJobFailure,
+    ) -> Result<(), DerivedProcessingError> {
+        self.calls
+            .lock()
+            .expect("calls")
+            .push(format!("fail:{job_id}:{}", failure.error_code));
+        Ok(())
+    }
+
     fn upload_init(&self, request: &DerivedUploadInit) -> Result<(), DerivedProcessingError> {
         self.calls.lock().expect("calls").push(format!(
             "upload_init:{}:{}",
@@ -139,6 +154,8 @@ fn e2e_derived_processing_gateway_flow_claim_upload_submit_sequence_is_supported
             upload_id: "up-1".to_string(),
             part_number: 1,
             chunk_path: std::path::PathBuf::from("/tmp/up-1.bin"),
+            byte_range: None,
+            sha256: None,
         })
         .expect("upload part");
     gateway
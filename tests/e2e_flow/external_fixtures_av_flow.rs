@@ -1,6 +1,8 @@
 use retaia_agent::{
-    AudioProxyFormat, AudioProxyRequest, AudioWaveformRequest, FfmpegProxyGenerator,
-    ProxyGenerator, ThumbnailFormat, VideoProxyRequest, VideoThumbnailRequest, ffmpeg_available,
+    AudioProxyFormat, AudioProxyRequest, AudioWaveformFormat, AudioWaveformRequest, CommandLimits,
+    FfmpegProxyGenerator, MediaOperation, NoopProxyProgressSink, ProxyGenerator, ThumbnailFormat,
+    VideoProxyRequest, VideoThumbnailRequest, WaveformPeaks, audio_source_extension_supported,
+    ffmpeg_available, write_waveform_with_native_decoder,
 };
 
 use crate::external_fixtures::load_manifest_entries;
@@ -45,13 +47,16 @@ fn e2e_external_fixture_flow_generates_audio_video_proxies_with_ffmpeg_when_avai
     for (index, entry) in audio_entries.iter().enumerate() {
         let output = temp.path().join(format!("audio-proxy-{index}.m4a"));
         generator
-            .generate_audio_proxy(&AudioProxyRequest {
-                input_path: entry.absolute_path().display().to_string(),
-                output_path: output.display().to_string(),
-                format: AudioProxyFormat::Mp4Aac,
-                audio_bitrate_kbps: 128,
-                sample_rate_hz: 44100,
-            })
+            .generate_audio_proxy(
+                &AudioProxyRequest {
+                    input_path: entry.absolute_path().display().to_string(),
+                    output_path: output.display().to_string(),
+                    format: AudioProxyFormat::Mp4Aac,
+                    audio_bitrate_kbps: 128,
+                    sample_rate_hz: 44100,
+                },
+                &NoopProxyProgressSink,
+            )
             .unwrap_or_else(|error| {
                 panic!(
                     "audio fixture should generate proxy: {} ({error:?})",
@@ -69,14 +74,17 @@ fn e2e_external_fixture_flow_generates_audio_video_proxies_with_ffmpeg_when_avai
     for (index, entry) in video_entries.iter().enumerate() {
         let output = temp.path().join(format!("video-proxy-{index}.mp4"));
         generator
-            .generate_video_proxy(&VideoProxyRequest {
-                input_path: entry.absolute_path().display().to_string(),
-                output_path: output.display().to_string(),
-                max_width: 640,
-                max_height: 360,
-                video_bitrate_kbps: 1200,
-                audio_bitrate_kbps: 96,
-            })
+            .generate_video_proxy(
+                &VideoProxyRequest {
+                    input_path: entry.absolute_path().display().to_string(),
+                    output_path: output.display().to_string(),
+                    max_width: 640,
+                    max_height: 360,
+                    video_bitrate_kbps: 1200,
+                    audio_bitrate_kbps: 96,
+                },
+                &NoopProxyProgressSink,
+            )
             .unwrap_or_else(|error| {
                 panic!(
                     "video fixture should generate proxy: {} ({error:?})",
@@ -109,13 +117,16 @@ fn e2e_external_fixture_flow_generates_video_thumbnail_with_ffmpeg_when_availabl
     let generator = FfmpegProxyGenerator::default();
 
     generator
-        .generate_video_thumbnail(&VideoThumbnailRequest {
-            input_path: entry.absolute_path().display().to_string(),
-            output_path: output.display().to_string(),
-            format: ThumbnailFormat::Jpeg,
-            max_width: 480,
-            seek_ms: 1_000,
-        })
+        .generate_video_thumbnail(
+            &VideoThumbnailRequest {
+                input_path: entry.absolute_path().display().to_string(),
+                output_path: output.display().to_string(),
+                format: ThumbnailFormat::Jpeg,
+                max_width: 480,
+                seek_ms: 1_000,
+            },
+            &NoopProxyProgressSink,
+        )
         .unwrap_or_else(|error| {
             panic!(
                 "video fixture should generate thumbnail: {} ({error:?})",
@@ -144,11 +155,15 @@ fn e2e_external_fixture_flow_generates_audio_waveform_with_ffmpeg_when_available
     let generator = FfmpegProxyGenerator::default();
 
     generator
-        .generate_audio_waveform(&AudioWaveformRequest {
-            input_path: entry.absolute_path().display().to_string(),
-            output_path: output.display().to_string(),
-            bucket_count: 1000,
-        })
+        .generate_audio_waveform(
+            &AudioWaveformRequest {
+                input_path: entry.absolute_path().display().to_string(),
+                output_path: output.display().to_string(),
+                bucket_count: 1000,
+                format: AudioWaveformFormat::Json,
+            },
+            &NoopProxyProgressSink,
+        )
         .unwrap_or_else(|error| {
             panic!(
                 "audio fixture should generate waveform: {} ({error:?})",
@@ -190,7 +205,10 @@ fn e2e_external_fixture_flow_extracts_audio_facts_with_ffprobe_when_available()
         .expect("missing supported audio fixture");
 
     let facts = FfmpegProxyGenerator::default()
-        .extract_media_facts(&entry.absolute_path().display().to_string())
+        .extract_media_facts(
+            &entry.absolute_path().display().to_string(),
+            &NoopProxyProgressSink,
+        )
         .unwrap_or_else(|error| {
             panic!(
                 "audio fixture should expose facts: {} ({error:?})",
@@ -216,7 +234,10 @@ fn e2e_external_fixture_flow_extracts_expected_aac_audio_metadata() {
         .expect("missing aac fixture");
 
     let facts = FfmpegProxyGenerator::default()
-        .extract_media_facts(&entry.absolute_path().display().to_string())
+        .extract_media_facts(
+            &entry.absolute_path().display().to_string(),
+            &NoopProxyProgressSink,
+        )
         .unwrap_or_else(|error| {
             panic!(
                 "aac fixture should expose facts: {} ({error:?})",
@@ -248,7 +269,10 @@ fn e2e_external_fixture_flow_extracts_expected_flac_audio_metadata() {
         .expect("missing flac fixture");
 
     let facts = FfmpegProxyGenerator::default()
-        .extract_media_facts(&entry.absolute_path().display().to_string())
+        .extract_media_facts(
+            &entry.absolute_path().display().to_string(),
+            &NoopProxyProgressSink,
+        )
         .unwrap_or_else(|error| {
             panic!(
                 "flac fixture should expose facts: {} ({error:?})",
@@ -280,7 +304,10 @@ fn e2e_external_fixture_flow_extracts_expected_mp3_audio_metadata() {
         .expect("missing mp3 fixture");
 
     let facts = FfmpegProxyGenerator::default()
-        .extract_media_facts(&entry.absolute_path().display().to_string())
+        .extract_media_facts(
+            &entry.absolute_path().display().to_string(),
+            &NoopProxyProgressSink,
+        )
         .unwrap_or_else(|error| {
             panic!(
                 "mp3 fixture should expose facts: {} ({error:?})",
@@ -312,7 +339,10 @@ fn e2e_external_fixture_flow_extracts_expected_pcm_wav_audio_metadata() {
         .expect("missing wav fixture");
 
     let facts = FfmpegProxyGenerator::default()
-        .extract_media_facts(&entry.absolute_path().display().to_string())
+        .extract_media_facts(
+            &entry.absolute_path().display().to_string(),
+            &NoopProxyProgressSink,
+        )
         .unwrap_or_else(|error| {
             panic!(
                 "wav fixture should expose facts: {} ({error:?})",
@@ -344,7 +374,10 @@ fn e2e_external_fixture_flow_extracts_expected_facts_from_wireless_pro_wav() {
         .expect("missing Wireless PRO wav fixture");
 
     let facts = FfmpegProxyGenerator::default()
-        .extract_media_facts(&entry.absolute_path().display().to_string())
+        .extract_media_facts(
+            &entry.absolute_path().display().to_string(),
+            &NoopProxyProgressSink,
+        )
         .unwrap_or_else(|error| {
             panic!(
                 "Wireless PRO wav fixture should expose facts: {} ({error:?})",
@@ -382,10 +415,16 @@ fn e2e_external_fixture_flow_wireless_pro_app_export_matches_standard_audio_fact
 
     let generator = FfmpegProxyGenerator::default();
     let raw_facts = generator
-        .extract_media_facts(&raw_entry.absolute_path().display().to_string())
+        .extract_media_facts(
+            &raw_entry.absolute_path().display().to_string(),
+            &NoopProxyProgressSink,
+        )
         .expect("raw Wireless PRO facts");
     let app_facts = generator
-        .extract_media_facts(&app_entry.absolute_path().display().to_string())
+        .extract_media_facts(
+            &app_entry.absolute_path().display().to_string(),
+            &NoopProxyProgressSink,
+        )
         .expect("app export Wireless PRO facts");
 
     assert_eq!(app_facts.media_format, raw_facts.media_format);
@@ -414,7 +453,10 @@ fn e2e_external_fixture_flow_extracts_repaired_facts_from_wireless_pro_receiver_
         .expect("missing Wireless PRO receiver wav fixture");
 
     let facts = FfmpegProxyGenerator::default()
-        .extract_media_facts(&entry.absolute_path().display().to_string())
+        .extract_media_facts(
+            &entry.absolute_path().display().to_string(),
+            &NoopProxyProgressSink,
+        )
         .unwrap_or_else(|error| {
             panic!(
                 "Wireless PRO receiver wav fixture should expose facts: {} ({error:?})",
@@ -451,10 +493,16 @@ fn e2e_external_fixture_flow_wireless_pro_receiver_app_export_matches_repaired_f
 
     let generator = FfmpegProxyGenerator::default();
     let raw_facts = generator
-        .extract_media_facts(&raw_entry.absolute_path().display().to_string())
+        .extract_media_facts(
+            &raw_entry.absolute_path().display().to_string(),
+            &NoopProxyProgressSink,
+        )
         .expect("raw Wireless PRO receiver facts");
     let app_facts = generator
-        .extract_media_facts(&app_entry.absolute_path().display().to_string())
+        .extract_media_facts(
+            &app_entry.absolute_path().display().to_string(),
+            &NoopProxyProgressSink,
+        )
         .expect("app export Wireless PRO receiver facts");
 
     assert_eq!(app_facts.audio_codec, raw_facts.audio_codec);
@@ -485,7 +533,10 @@ fn e2e_external_fixture_flow_extracts_video_facts_with_ffprobe_when_available()
         .expect("missing supported video fixture");
 
     let facts = FfmpegProxyGenerator::default()
-        .extract_media_facts(&entry.absolute_path().display().to_string())
+        .extract_media_facts(
+            &entry.absolute_path().display().to_string(),
+            &NoopProxyProgressSink,
+        )
         .unwrap_or_else(|error| {
             panic!(
                 "video fixture should expose facts: {} ({error:?})",
@@ -514,7 +565,10 @@ fn e2e_external_fixture_flow_extracts_expected_h264_video_metadata() {
         .expect("missing h264 fixture");
 
     let facts = FfmpegProxyGenerator::default()
-        .extract_media_facts(&entry.absolute_path().display().to_string())
+        .extract_media_facts(
+            &entry.absolute_path().display().to_string(),
+            &NoopProxyProgressSink,
+        )
         .unwrap_or_else(|error| {
             panic!(
                 "h264 fixture should expose facts: {} ({error:?})",
@@ -560,7 +614,10 @@ fn e2e_external_fixture_flow_extracts_expected_h265_video_metadata() {
         .expect("missing h265 fixture");
 
     let facts = FfmpegProxyGenerator::default()
-        .extract_media_facts(&entry.absolute_path().display().to_string())
+        .extract_media_facts(
+            &entry.absolute_path().display().to_string(),
+            &NoopProxyProgressSink,
+        )
         .unwrap_or_else(|error| {
             panic!(
                 "h265 fixture should expose facts: {} ({error:?})",
@@ -592,3 +649,75 @@ fn e2e_external_fixture_flow_extracts_expected_h265_video_metadata() {
         Some(vec!["tmcd".to_string()])
     );
 }
+
+#[test]
+fn e2e_external_fixture_flow_decodes_audio_waveforms_natively_without_ffmpeg() {
+    // One fixture per extension keeps the debug-build decode time reasonable.
+    let mut extensions = std::collections::BTreeSet::new();
+    let entries = load_manifest_entries()
+        .into_iter()
+        .filter(|entry| entry.kind == "preview_audio" && entry.expected == "supported")
+        .filter(|entry| entry.absolute_path().exists())
+        .filter(|entry| {
+            entry
+                .absolute_path()
+                .extension()
+                .and_then(|extension| extension.to_str())
+                .filter(|extension| audio_source_extension_supported(extension))
+                .is_some_and(|extension| extensions.insert(extension.to_ascii_lowercase()))
+        })
+        .collect::<Vec<_>>();
+    assert!(
+        !entries.is_empty(),
+        "expected natively decodable audio fixtures"
+    );
+
+    let temp = tempfile::tempdir().expect("tempdir");
+    for entry in entries {
+        let json_output = temp.path().join("waveform.json");
+        let v2_output = temp.path().join("waveform.bin");
+        for (format, output) in [
+            (AudioWaveformFormat::Json, &json_output),
+            (AudioWaveformFormat::PeaksV2, &v2_output),
+        ] {
+            write_waveform_with_native_decoder(
+                &AudioWaveformRequest {
+                    input_path: entry.absolute_path().display().to_string(),
+                    output_path: output.display().to_string(),
+                    bucket_count: 1000,
+                    format,
+                },
+                &CommandLimits::new(MediaOperation::WaveformDecode),
+            )
+            .unwrap_or_else(|error| {
+                panic!(
+                    "audio fixture should decode natively: {} ({error:?})",
+                    entry.relative_path
+                )
+            });
+        }
+
+        let payload: serde_json::Value =
+            serde_json::from_slice(&std::fs::read(&json_output).expect("read waveform json"))
+                .expect("waveform json payload");
+        let samples = payload["samples"].as_array().expect("samples");
+        assert_eq!(samples.len(), 1000, "{}", entry.relative_path);
+        assert!(
+            samples
+                .iter()
+                .any(|sample| sample.as_f64().unwrap_or_default() > 0.0),
+            "waveform should not be silent: {}",
+            entry.relative_path
+        );
+        let peaks = WaveformPeaks::decode(&std::fs::read(&v2_output).expect("read waveform_v2"))
+            .expect("valid waveform_v2 payload");
+        assert!(peaks.channel_count >= 1);
+        assert_eq!(
+            u64::try_from(payload["duration_ms"].as_i64().expect("duration")).ok(),
+            Some(peaks.duration_ms),
+            "{}",
+            entry.relative_path
+        );
+        assert!(peaks.duration_ms > 0);
+    }
+}
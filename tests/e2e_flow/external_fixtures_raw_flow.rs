@@ -0,0 +1,99 @@
+use image::DynamicImage;
+use image::imageops::FilterType;
+use image::metadata::Orientation;
+use retaia_agent::{RawPhotoDecoder, RawloaderPhotoDecoder, decode_largest_embedded_preview};
+
+use crate::external_fixtures::{ExternalFixtureEntry, load_manifest_entries};
+
+fn present_supported_raw_entries() -> Vec<ExternalFixtureEntry> {
+    load_manifest_entries()
+        .into_iter()
+        .filter(|entry| entry.kind == "raw_photo" && entry.expected == "supported")
+        .filter(|entry| entry.absolute_path().is_file())
+        .collect()
+}
+
+fn channel_means(image: &DynamicImage) -> [f64; 3] {
+    let sample = image.resize_exact(64, 64, FilterType::Triangle).to_rgb8();
+    let mut sums = [0_f64; 3];
+    for pixel in sample.pixels() {
+        for (sum, value) in sums.iter_mut().zip(pixel.0) {
+            *sum += f64::from(value);
+        }
+    }
+    let count = f64::from(sample.width() * sample.height());
+    sums.map(|sum| sum / count)
+}
+
+#[test]
+fn e2e_external_fixture_flow_develops_raw_samples_upright_and_with_camera_like_colour() {
+    let entries = present_supported_raw_entries();
+    if entries.is_empty() {
+        eprintln!("external raw fixtures not downloaded, skipping raw development regression test");
+        return;
+    }
+
+    for entry in entries {
+        let path = entry.absolute_path();
+        let developed = RawloaderPhotoDecoder
+            .decode_photo(&path.display().to_string())
+            .unwrap_or_else(|error| {
+                panic!(
+                    "raw fixture should develop: {} ({error})",
+                    entry.relative_path
+                )
+            });
+
+        let [red, green, blue] = channel_means(&developed);
+        let luma = 0.2126 * red + 0.7152 * green + 0.0722 * blue;
+        assert!(
+            luma > 30.0,
+            "developed {} is too dark (luma {luma:.1}): missing gamma or black level",
+            entry.relative_path
+        );
+        assert!(
+            green < 1.5 * (red + blue) / 2.0,
+            "developed {} has a green cast (rgb {red:.1}/{green:.1}/{blue:.1}): missing white balance",
+            entry.relative_path
+        );
+
+        // The camera-rendered embedded JPEG is the reference for both
+        // orientation and overall colour.
+        let bytes = std::fs::read(&path).expect("read raw fixture");
+        let Some(preview) = decode_largest_embedded_preview(&bytes, 1, 1) else {
+            continue;
+        };
+        let orientation = rawloader::decode_file(&path)
+            .expect("rawloader should read the fixture")
+            .orientation
+            .to_u16();
+        let mut preview = preview;
+        if let Some(orientation) = u8::try_from(orientation)
+            .ok()
+            .and_then(Orientation::from_exif)
+        {
+            preview.apply_orientation(orientation);
+        }
+        assert_eq!(
+            developed.width() > developed.height(),
+            preview.width() > preview.height(),
+            "developed {} is not oriented like its embedded preview ({}x{} vs {}x{})",
+            entry.relative_path,
+            developed.width(),
+            developed.height(),
+            preview.width(),
+            preview.height()
+        );
+        for (channel, (developed, reference)) in ["red", "green", "blue"].into_iter().zip(
+            channel_means(&developed)
+                .into_iter()
+                .zip(channel_means(&preview)),
+        ) {
+            assert!(
+                (developed - reference).abs() < 48.0,
+                "developed {} {channel} mean {developed:.1} drifts from embedded preview {reference:.1}",
+                entry.relative_path
+            );
+        }
+    }
+}
@@ -2,7 +2,7 @@ use std::sync::Mutex;
 
 use retaia_agent::{
     AudioProxyFormat, AudioProxyRequest, CommandOutput, CommandRunner, FfmpegProxyGenerator,
-    ProxyGenerationError, ProxyGenerator, VideoProxyRequest,
+    NoopProxyProgressSink, ProxyGenerationError, ProxyGenerator, VideoProxyRequest,
 };
 
 #[derive(Default)]
@@ -36,33 +36,42 @@ fn e2e_ffmpeg_proxy_generator_flow_builds_video_and_audio_commands_with_configur
     let generator = FfmpegProxyGenerator::new("/usr/local/bin/ffmpeg".to_string(), runner);
 
     generator
-        .generate_video_proxy(&VideoProxyRequest {
-            input_path: "/tmp/source.mov".to_string(),
-            output_path: "/tmp/proxy.mp4".to_string(),
-            max_width: 1280,
-            max_height: 720,
-            video_bitrate_kbps: 3000,
-            audio_bitrate_kbps: 128,
-        })
+        .generate_video_proxy(
+            &VideoProxyRequest {
+                input_path: "/tmp/source.mov".to_string(),
+                output_path: "/tmp/proxy.mp4".to_string(),
+                max_width: 1280,
+                max_height: 720,
+                video_bitrate_kbps: 3000,
+                audio_bitrate_kbps: 128,
+            },
+            &NoopProxyProgressSink,
+        )
         .expect("video generation should succeed");
 
     generator
-        .generate_audio_proxy(&AudioProxyRequest {
-            input_path: "/tmp/source.wav".to_string(),
-            output_path: "/tmp/proxy.mp3".to_string(),
-            format: AudioProxyFormat::Mpeg,
-            audio_bitrate_kbps: 192,
-            sample_rate_hz: 44100,
-        })
+        .generate_audio_proxy(
+            &AudioProxyRequest {
+                input_path: "/tmp/source.wav".to_string(),
+                output_path: "/tmp/proxy.mp3".to_string(),
+                format: AudioProxyFormat::Mpeg,
+                audio_bitrate_kbps: 192,
+                sample_rate_hz: 44100,
+            },
+            &NoopProxyProgressSink,
+        )
         .expect("audio generation should succeed");
 
     let calls = generator.runner().calls();
-    assert_eq!(calls.len(), 2);
-    assert_eq!(calls[0].0, "/usr/local/bin/ffmpeg");
+    // Each proxy probes the source duration with the sibling ffprobe first.
+    assert_eq!(calls.len(), 4);
+    assert_eq!(calls[0].0, "/usr/local/bin/ffprobe");
     assert_eq!(calls[1].0, "/usr/local/bin/ffmpeg");
-    assert!(calls[0].1.join(" ").contains("-c:v libx264"));
-    assert!(calls[0].1.join(" ").contains("-profile:v high"));
-    assert!(calls[0].1.join(" ").contains("-crf 23"));
-    assert!(calls[1].1.join(" ").contains("-c:a libmp3lame"));
-    assert!(calls[1].1.join(" ").contains("-ac 2"));
+    assert_eq!(calls[2].0, "/usr/local/bin/ffprobe");
+    assert_eq!(calls[3].0, "/usr/local/bin/ffmpeg");
+    assert!(calls[1].1.join(" ").contains("-c:v libx264"));
+    assert!(calls[1].1.join(" ").contains("-profile:v high"));
+    assert!(calls[1].1.join(" ").contains("-crf 23"));
+    assert!(calls[3].1.join(" ").contains("-c:a libmp3lame"));
+    assert!(calls[3].1.join(" ").contains("-ac 2"));
 }
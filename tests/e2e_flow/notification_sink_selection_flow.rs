@@ -1,6 +1,6 @@
 use retaia_agent::{
-    AgentRuntimeConfig, AuthMode, ClientRuntimeTarget, LogLevel, RuntimeSession,
-    notification_sink_profile_for_target, select_notification_sink,
+    AgentRuntimeConfig, AuthMode, ClientRuntimeTarget, JobSelectionStrategy, LogLevel,
+    RuntimeSession, notification_sink_profile_for_target, select_notification_sink,
 };
 
 fn config() -> AgentRuntimeConfig {
@@ -12,6 +12,8 @@ fn config() -> AgentRuntimeConfig {
         storage_mounts: std::collections::BTreeMap::new(),
         max_parallel_jobs: 2,
         log_level: LogLevel::Info,
+        job_selection: JobSelectionStrategy::default(),
+        upload_part_size_bytes: None,
     }
 }
 
@@ -1,7 +1,7 @@
 use retaia_agent::{
-    AgentRuntimeConfig, AuthMode, ClientRuntimeTarget, ConnectivityState, JobStage, JobStatus,
-    LogLevel, RuntimeSession, RuntimeSnapshot, ShellCommand, execute_shell_command, format_menu,
-    format_settings, format_status,
+    AgentRuntimeConfig, AuthMode, ClientRuntimeTarget, ConnectivityState, JobSelectionStrategy,
+    JobStage, JobStatus, LogLevel, RuntimeSession, RuntimeSnapshot, ShellCommand,
+    execute_shell_command, format_menu, format_settings, format_status,
 };
 
 fn settings() -> AgentRuntimeConfig {
@@ -13,6 +13,8 @@ fn settings() -> AgentRuntimeConfig {
         storage_mounts: std::collections::BTreeMap::new(),
         max_parallel_jobs: 2,
         log_level: LogLevel::Info,
+        job_selection: JobSelectionStrategy::default(),
+        upload_part_size_bytes: None,
     }
 }
 
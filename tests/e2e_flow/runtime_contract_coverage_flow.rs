@@ -6,10 +6,11 @@ use tempfile::tempdir;
 
 use retaia_agent::{
     AgentRunState, AgentRuntimeApp, AgentRuntimeConfig, AuthMode, CONFIG_FILE_ENV,
-    ClientRuntimeTarget, ConfigRepository, ConfigRepositoryError, FileConfigRepository, LogLevel,
-    MenuAction, NotificationMessage, NotificationSink, PollEndpoint, PollSignal, RuntimeSession,
-    RuntimeSyncPlan, SettingsSaveError, StdoutNotificationSink, SystemConfigRepository,
-    SystemNotification, dispatch_notifications, notification_message,
+    ClientRuntimeTarget, ConfigRepository, ConfigRepositoryError, FileConfigRepository,
+    JobSelectionStrategy, LogLevel, MenuAction, NotificationMessage, NotificationSink,
+    PollEndpoint, PollSignal, RuntimeSession, RuntimeSyncPlan, SettingsSaveError,
+    StdoutNotificationSink, SystemConfigRepository, SystemNotification, dispatch_notifications,
+    notification_message,
 };
 
 fn settings() -> AgentRuntimeConfig {
@@ -21,6 +22,8 @@ fn settings() -> AgentRuntimeConfig {
         storage_mounts: std::collections::BTreeMap::new(),
         max_parallel_jobs: 2,
         log_level: LogLevel::Info,
+        job_selection: JobSelectionStrategy::default(),
+        upload_part_size_bytes: None,
     }
 }
 
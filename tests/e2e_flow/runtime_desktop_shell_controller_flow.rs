@@ -3,7 +3,7 @@ use std::sync::Mutex;
 use retaia_agent::{
     AgentRuntimeConfig, AuthMode, ClientRuntimeTarget, DaemonLabelRequest, DaemonManager,
     DaemonManagerError, DaemonStatus, DesktopShellBridge, DesktopShellController, GuiMenuAction,
-    LogLevel, RuntimeSession,
+    JobSelectionStrategy, LogLevel, RuntimeSession,
 };
 
 fn config() -> AgentRuntimeConfig {
@@ -15,6 +15,8 @@ fn config() -> AgentRuntimeConfig {
         storage_mounts: std::collections::BTreeMap::new(),
         max_parallel_jobs: 2,
         log_level: LogLevel::Info,
+        job_selection: JobSelectionStrategy::default(),
+        upload_part_size_bytes: None,
     }
 }
 
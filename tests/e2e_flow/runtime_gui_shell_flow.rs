@@ -2,8 +2,8 @@ use std::sync::Mutex;
 
 use retaia_agent::{
     AgentRuntimeConfig, AuthMode, ClientRuntimeTarget, DaemonLabelRequest, DaemonManager,
-    DaemonManagerError, DaemonStatus, LogLevel, RuntimeSession, apply_gui_menu_action,
-    settings_panel_content, status_window_content,
+    DaemonManagerError, DaemonStatus, JobSelectionStrategy, LogLevel, RuntimeSession,
+    apply_gui_menu_action, settings_panel_content, status_window_content,
 };
 use retaia_agent::{GuiDaemonContext, GuiMenuAction};
 
@@ -16,6 +16,8 @@ fn config() -> AgentRuntimeConfig {
         storage_mounts: std::collections::BTreeMap::new(),
         max_parallel_jobs: 2,
         log_level: LogLevel::Info,
+        job_selection: JobSelectionStrategy::default(),
+        upload_part_size_bytes: None,
     }
 }
 
@@ -2,9 +2,9 @@ use std::collections::VecDeque;
 
 use retaia_agent::{
     AgentRuntimeConfig, AuthMode, ClientRuntimeTarget, CoreApiGateway, CoreApiGatewayError,
-    CoreJobState, CoreJobView, LogLevel, NotificationBridgeError, NotificationMessage,
-    NotificationSink, PollEndpoint, RuntimePollCycleStatus, RuntimeSession, SystemNotification,
-    run_runtime_poll_cycle,
+    CoreJobState, CoreJobView, JobSelectionStrategy, LogLevel, NotificationBridgeError,
+    NotificationMessage, NotificationSink, PollEndpoint, RuntimePollCycleStatus, RuntimeSession,
+    SystemNotification, run_runtime_poll_cycle,
 };
 
 fn settings() -> AgentRuntimeConfig {
@@ -16,6 +16,8 @@ fn settings() -> AgentRuntimeConfig {
         storage_mounts: std::collections::BTreeMap::new(),
         max_parallel_jobs: 2,
         log_level: LogLevel::Info,
+        job_selection: JobSelectionStrategy::default(),
+        upload_part_size_bytes: None,
     }
 }
 
@@ -71,6 +73,7 @@ fn e2e_runtime_poll_cycle_handles_transport_then_successful_claimed_job_transiti
         Ok(vec![CoreJobView {
             job_id: "job-100".to_string(),
             asset_uuid: "asset-100".to_string(),
+            job_type: None,
             state: CoreJobState::Claimed,
             required_capabilities: vec!["media.facts@1".to_string()],
         }]),
@@ -1,5 +1,5 @@
 use retaia_agent::{
-    AgentRuntimeConfig, AuthMode, ClientRuntimeTarget, LogLevel, MenuAction,
+    AgentRuntimeConfig, AuthMode, ClientRuntimeTarget, JobSelectionStrategy, LogLevel, MenuAction,
     NotificationBridgeError, NotificationMessage, NotificationSink, PollDecisionReason,
     PollEndpoint, PollSignal, PushChannel, PushHint, RuntimeSession, RuntimeSnapshot,
     RuntimeSyncPlan, SystemNotification,
@@ -15,6 +15,8 @@ fn config() -> AgentRuntimeConfig {
         storage_mounts: std::collections::BTreeMap::new(),
         max_parallel_jobs: 3,
         log_level: LogLevel::Info,
+        job_selection: JobSelectionStrategy::default(),
+        upload_part_size_bytes: None,
     }
 }
 
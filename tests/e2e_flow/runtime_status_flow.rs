@@ -1,6 +1,7 @@
 use retaia_agent::{
-    AgentRuntimeApp, AgentRuntimeConfig, AgentUiRuntime, AuthMode, ConnectivityState, JobStage,
-    LogLevel, RuntimeStatusEvent, RuntimeStatusTracker, SystemNotification,
+    AgentRuntimeApp, AgentRuntimeConfig, AgentUiRuntime, AuthMode, ConnectivityState,
+    JobSelectionStrategy, JobStage, LogLevel, RuntimeStatusEvent, RuntimeStatusTracker,
+    SystemNotification,
 };
 
 fn config() -> AgentRuntimeConfig {
@@ -12,6 +13,8 @@ fn config() -> AgentRuntimeConfig {
         storage_mounts: std::collections::BTreeMap::new(),
         max_parallel_jobs: 2,
         log_level: LogLevel::Info,
+        job_selection: JobSelectionStrategy::default(),
+        upload_part_size_bytes: None,
     }
 }
 
@@ -4,8 +4,9 @@ use retaia_agent::infrastructure::rust_photo_proxy_generator::{
     validate_photo_request, write_photo_proxy,
 };
 use retaia_agent::{
-    AudioProxyFormat, AudioProxyRequest, PhotoProxyFormat, PhotoProxyRequest, ProxyGenerationError,
-    ProxyGenerator, RawPhotoDecoder, RustPhotoProxyGenerator, VideoProxyRequest,
+    AudioProxyFormat, AudioProxyRequest, NoopProxyProgressSink, PhotoProxyFormat,
+    PhotoProxyRequest, ProxyGenerationError, ProxyGenerator, RawPhotoDecoder,
+    RustPhotoProxyGenerator, VideoProxyRequest,
 };
 
 struct E2eRawDecoder;
@@ -38,23 +39,29 @@ fn e2e_rust_photo_proxy_generator_flow_supports_jpeg_and_webp_outputs() {
     let generator = RustPhotoProxyGenerator::default();
 
     generator
-        .generate_photo_proxy(&PhotoProxyRequest {
-            input_path: input.display().to_string(),
-            output_path: out_jpeg.display().to_string(),
-            format: PhotoProxyFormat::Jpeg,
-            max_width: 512,
-            max_height: 384,
-        })
+        .generate_photo_proxy(
+            &PhotoProxyRequest {
+                input_path: input.display().to_string(),
+                output_path: out_jpeg.display().to_string(),
+                format: PhotoProxyFormat::Jpeg,
+                max_width: 512,
+                max_height: 384,
+            },
+            &NoopProxyProgressSink,
+        )
         .expect("jpeg proxy generation");
 
     generator
-        .generate_photo_proxy(&PhotoProxyRequest {
-            input_path: input.display().to_string(),
-            output_path: out_webp.display().to_string(),
-            format: PhotoProxyFormat::Webp,
-            max_width: 256,
-            max_height: 192,
-        })
+        .generate_photo_proxy(
+            &PhotoProxyRequest {
+                input_path: input.display().to_string(),
+                output_path: out_webp.display().to_string(),
+                format: PhotoProxyFormat::Webp,
+                max_width: 256,
+                max_height: 192,
+            },
+            &NoopProxyProgressSink,
+        )
         .expect("webp proxy generation");
 
     let jpeg = image::open(&out_jpeg).expect("open jpeg");
@@ -86,7 +93,10 @@ fn e2e_rust_photo_proxy_generator_flow_covers_validation_and_decoder_fallback_pa
     assert_eq!(fallback.dimensions(), (240, 180));
 
     let failing = load_source_image(&E2eFailingRawDecoder, &non_image.display().to_string());
-    assert!(matches!(failing, Err(ProxyGenerationError::Process(_))));
+    assert!(matches!(
+        failing,
+        Err(ProxyGenerationError::DecodeFailed(_))
+    ));
 }
 
 #[test]
@@ -118,21 +128,27 @@ fn e2e_rust_photo_proxy_generator_flow_covers_output_write_and_conversion_helper
 #[test]
 fn e2e_rust_photo_proxy_generator_flow_rejects_audio_video_paths_on_photo_generator() {
     let generator = RustPhotoProxyGenerator::new(E2eRawDecoder);
-    let video = generator.generate_video_proxy(&VideoProxyRequest {
-        input_path: "/tmp/a.mov".to_string(),
-        output_path: "/tmp/a.mp4".to_string(),
-        max_width: 640,
-        max_height: 360,
-        video_bitrate_kbps: 1200,
-        audio_bitrate_kbps: 96,
-    });
-    let audio = generator.generate_audio_proxy(&AudioProxyRequest {
-        input_path: "/tmp/a.wav".to_string(),
-        output_path: "/tmp/a.mp3".to_string(),
-        format: AudioProxyFormat::Mpeg,
-        audio_bitrate_kbps: 160,
-        sample_rate_hz: 44100,
-    });
+    let video = generator.generate_video_proxy(
+        &VideoProxyRequest {
+            input_path: "/tmp/a.mov".to_string(),
+            output_path: "/tmp/a.mp4".to_string(),
+            max_width: 640,
+            max_height: 360,
+            video_bitrate_kbps: 1200,
+            audio_bitrate_kbps: 96,
+        },
+        &NoopProxyProgressSink,
+    );
+    let audio = generator.generate_audio_proxy(
+        &AudioProxyRequest {
+            input_path: "/tmp/a.wav".to_string(),
+            output_path: "/tmp/a.mp3".to_string(),
+            format: AudioProxyFormat::Mpeg,
+            audio_bitrate_kbps: 160,
+            sample_rate_hz: 44100,
+        },
+        &NoopProxyProgressSink,
+    );
     assert!(matches!(
         video,
         Err(ProxyGenerationError::InvalidRequest(_))
@@ -154,26 +170,32 @@ fn e2e_rust_photo_proxy_generator_flow_covers_missing_input_and_fake_raw_extensi
     let generator = RustPhotoProxyGenerator::default();
 
     let missing_err = generator
-        .generate_photo_proxy(&PhotoProxyRequest {
-            input_path: missing.display().to_string(),
-            output_path: output.display().to_string(),
-            format: PhotoProxyFormat::Webp,
-            max_width: 200,
-            max_height: 120,
-        })
+        .generate_photo_proxy(
+            &PhotoProxyRequest {
+                input_path: missing.display().to_string(),
+                output_path: output.display().to_string(),
+                format: PhotoProxyFormat::Webp,
+                max_width: 200,
+                max_height: 120,
+            },
+            &NoopProxyProgressSink,
+        )
         .expect_err("missing path must fail");
     assert!(matches!(missing_err, ProxyGenerationError::Process(_)));
 
     let fake_err = generator
-        .generate_photo_proxy(&PhotoProxyRequest {
-            input_path: fake_raw.display().to_string(),
-            output_path: output.display().to_string(),
-            format: PhotoProxyFormat::Webp,
-            max_width: 200,
-            max_height: 120,
-        })
+        .generate_photo_proxy(
+            &PhotoProxyRequest {
+                input_path: fake_raw.display().to_string(),
+                output_path: output.display().to_string(),
+                format: PhotoProxyFormat::Webp,
+                max_width: 200,
+                max_height: 120,
+            },
+            &NoopProxyProgressSink,
+        )
         .expect_err("fake raw must fail");
-    assert!(matches!(fake_err, ProxyGenerationError::Process(_)));
+    assert!(matches!(fake_err, ProxyGenerationError::DecodeFailed(_)));
 }
 
 #[test]
@@ -208,16 +230,19 @@ fn e2e_rust_photo_proxy_generator_flow_mixed_batch_has_expected_success_failure_
 
     for (index, input) in inputs.iter().enumerate() {
         let output = temp.path().join(format!("batch-proxy-{index}.jpg"));
-        let result = generator.generate_photo_proxy(&PhotoProxyRequest {
-            input_path: input.display().to_string(),
-            output_path: output.display().to_string(),
-            format: PhotoProxyFormat::Jpeg,
-            max_width: 300,
-            max_height: 300,
-        });
+        let result = generator.generate_photo_proxy(
+            &PhotoProxyRequest {
+                input_path: input.display().to_string(),
+                output_path: output.display().to_string(),
+                format: PhotoProxyFormat::Jpeg,
+                max_width: 300,
+                max_height: 300,
+            },
+            &NoopProxyProgressSink,
+        );
         match result {
-            Ok(()) => success += 1,
-            Err(ProxyGenerationError::Process(_)) => failures += 1,
+            Ok(_) => success += 1,
+            Err(ProxyGenerationError::DecodeFailed(_)) => failures += 1,
             Err(other) => panic!("unexpected error variant in mixed batch: {other:?}"),
         }
     }
@@ -244,13 +269,16 @@ fn e2e_rust_photo_proxy_generator_flow_high_volume_batch_is_stable_without_exter
             .expect("save source");
 
         generator
-            .generate_photo_proxy(&PhotoProxyRequest {
-                input_path: input.display().to_string(),
-                output_path: output.display().to_string(),
-                format: PhotoProxyFormat::Jpeg,
-                max_width: 320,
-                max_height: 180,
-            })
+            .generate_photo_proxy(
+                &PhotoProxyRequest {
+                    input_path: input.display().to_string(),
+                    output_path: output.display().to_string(),
+                    format: PhotoProxyFormat::Jpeg,
+                    max_width: 320,
+                    max_height: 180,
+                },
+                &NoopProxyProgressSink,
+            )
             .expect("volume generation should succeed");
 
         let produced = image::open(&output).expect("open output");
@@ -16,11 +16,11 @@ use retaia_agent::infrastructure::signed_core_http::{
 };
 use retaia_agent::{
     AgentRegistrationCommand, AgentRegistrationError, AgentRegistrationGateway, AgentRuntimeConfig,
-    AuthMode, CoreApiGateway, CoreApiGatewayError, DerivedJobType, DerivedKind,
+    AuthMode, CoreApiGateway, CoreApiGatewayError, DerivedJobFailure, DerivedJobType, DerivedKind,
     DerivedManifestItem, DerivedProcessingError, DerivedProcessingGateway, DerivedUploadComplete,
-    DerivedUploadInit, DerivedUploadPart, LogLevel, OpenApiAgentRegistrationGateway,
-    OpenApiDerivedProcessingGateway, OpenApiJobsGateway, SubmitDerivedPayload,
-    build_core_api_client,
+    DerivedUploadInit, DerivedUploadPart, JobSelectionStrategy, LogLevel,
+    OpenApiAgentRegistrationGateway, OpenApiDerivedProcessingGateway, OpenApiJobsGateway,
+    SubmitDerivedPayload, build_core_api_client,
 };
 use retaia_core_client::apis::assets_api::{AssetsApi, AssetsApiClient};
 use tempfile::NamedTempFile;
@@ -52,6 +52,8 @@ fn runtime_config(base_url: &str) -> AgentRuntimeConfig {
         storage_mounts: std::collections::BTreeMap::new(),
         max_parallel_jobs: 2,
         log_level: LogLevel::Info,
+        job_selection: JobSelectionStrategy::default(),
+        upload_part_size_bytes: None,
     }
 }
 
@@ -576,6 +578,76 @@ fn e2e_openapi_derived_gateway_upload_init_maps_422_from_http_response() {
     server.join().expect("server thread");
 }
 
+#[test]
+fn e2e_openapi_derived_gateway_refuses_kinds_missing_from_the_openapi_contract() {
+    // Nothing listens here: the kind must be refused before any request is sent.
+    let client = build_core_api_client(&runtime_config("http://127.0.0.1:9"));
+    let gateway = OpenApiDerivedProcessingGateway::new(client);
+    let error = gateway
+        .upload_init(&DerivedUploadInit {
+            asset_uuid: "asset-1".to_string(),
+            revision_etag: "\"asset-rev-1\"".to_string(),
+            kind: DerivedKind::PreviewVideoHls,
+            content_type: "application/vnd.apple.mpegurl".to_string(),
+            size_bytes: 64,
+            sha256: None,
+            idempotency_key: "idem-hls".to_string(),
+        })
+        .expect_err("preview_video_hls is not in the pinned contract");
+    assert_eq!(
+        error,
+        DerivedProcessingError::DerivedKindNotInContract("preview_video_hls".to_string())
+    );
+    assert!(!error.is_retryable());
+
+    let payload = SubmitDerivedPayload {
+        job_type: DerivedJobType::GeneratePreview,
+        manifest: vec![DerivedManifestItem {
+            kind: DerivedKind::PreviewVideoHls,
+            reference: "/api/v1/assets/asset-1/derived/preview_video_hls/master.m3u8".to_string(),
+            size_bytes: Some(12),
+            sha256: None,
+        }],
+        facts_patch: None,
+        transcript_patch: None,
+        warnings: None,
+        metrics: None,
+    };
+    let error = gateway
+        .submit_derived("job-hls", "lock-hls", 1, "idem-hls", &payload)
+        .expect_err("manifest must not carry preview_video_hls");
+    assert_eq!(
+        error,
+        DerivedProcessingError::DerivedKindNotInContract("preview_video_hls".to_string())
+    );
+}
+
+#[test]
+fn e2e_openapi_derived_gateway_refuses_thumb_sprite_and_vtt_until_the_contract_lists_them() {
+    let client = build_core_api_client(&runtime_config("http://127.0.0.1:9"));
+    let gateway = OpenApiDerivedProcessingGateway::new(client);
+    for (kind, content_type) in [
+        (DerivedKind::ThumbSprite, "image/webp"),
+        (DerivedKind::ThumbVtt, "text/vtt"),
+    ] {
+        let error = gateway
+            .upload_init(&DerivedUploadInit {
+                asset_uuid: "asset-1".to_string(),
+                revision_etag: "\"asset-rev-1\"".to_string(),
+                kind,
+                content_type: content_type.to_string(),
+                size_bytes: 64,
+                sha256: None,
+                idempotency_key: format!("idem-{}", kind.as_str()),
+            })
+            .expect_err("sprite kinds are not in the pinned contract");
+        assert_eq!(
+            error,
+            DerivedProcessingError::DerivedKindNotInContract(kind.as_str().to_string())
+        );
+    }
+}
+
 #[test]
 fn e2e_openapi_derived_gateway_fetch_asset_revision_etag_reads_http_etag_header() {
     let listener = TcpListener::bind("127.0.0.1:0").expect("bind mock server");
@@ -842,6 +914,84 @@ fn e2e_openapi_derived_gateway_submit_maps_lock_invalid_error() {
     server.join().expect("server thread");
 }
 
+#[test]
+fn e2e_openapi_derived_gateway_fail_job_sends_error_code_retryable_and_idempotency_key() {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("bind mock server");
+    let port = listener.local_addr().expect("local addr").port();
+    let base_url = format!("http://127.0.0.1:{port}");
+
+    let server = thread::spawn(move || {
+        let (mut stream, _) = listener.accept().expect("accept");
+        let mut buffer = [0_u8; 8192];
+        let size = stream.read(&mut buffer).expect("read request");
+        let request = String::from_utf8_lossy(&buffer[..size]);
+        let first_line = request.lines().next().unwrap_or_default().to_string();
+        assert!(first_line.starts_with("POST /api/v1/jobs/job-fail/fail"));
+        assert_request_contains_header(&request, "Idempotency-Key: agent-fail-job-fail");
+        assert!(request.contains(r#""error_code":"SOURCE_UNREADABLE""#));
+        assert!(request.contains(r#""retryable":true"#));
+        assert!(request.contains(r#""fencing_token":4"#));
+
+        let response = concat!(
+            "HTTP/1.1 200 OK\r\n",
+            "Content-Length: 0\r\n",
+            "Connection: close\r\n",
+            "\r\n"
+        );
+        stream
+            .write_all(response.as_bytes())
+            .expect("write response");
+    });
+
+    let client = build_core_api_client(&runtime_config(&base_url));
+    let gateway = OpenApiDerivedProcessingGateway::new(client);
+    gateway
+        .fail_job(
+            "job-fail",
+            "lock-fail",
+            4,
+            "agent-fail-job-fail",
+            &DerivedJobFailure {
+                error_code: "SOURCE_UNREADABLE".to_string(),
+                retryable: true,
+                message: Some("source file is missing or unreadable".to_string()),
+            },
+        )
+        .expect("fail should succeed");
+
+    server.join().expect("server thread");
+}
+
+#[test]
+fn e2e_openapi_derived_gateway_fail_job_maps_stale_lock_token_error() {
+    let (server, base_url) = spawn_mock_server(vec![MockExchange {
+        method: "POST",
+        path: "/api/v1/jobs/job-fail-stale/fail",
+        status: 409,
+        content_type: "application/json",
+        body: r#"{"code":"STALE_LOCK_TOKEN"}"#,
+    }]);
+
+    let client = build_core_api_client(&runtime_config(&base_url));
+    let gateway = OpenApiDerivedProcessingGateway::new(client);
+    let error = gateway
+        .fail_job(
+            "job-fail-stale",
+            "lock-stale",
+            1,
+            "agent-fail-job-fail-stale",
+            &DerivedJobFailure {
+                error_code: "PROXY_COMMAND_FAILED".to_string(),
+                retryable: false,
+                message: None,
+            },
+        )
+        .expect_err("must fail on STALE_LOCK_TOKEN");
+    assert_eq!(error, DerivedProcessingError::StaleLockToken);
+
+    server.join().expect("server thread");
+}
+
 #[test]
 fn e2e_openapi_derived_gateway_upload_part_maps_429_from_http_response() {
     let (server, base_url) = spawn_mock_server(vec![MockExchange {
@@ -861,11 +1011,18 @@ fn e2e_openapi_derived_gateway_upload_part_maps_429_from_http_response() {
         upload_id: "upload-2".to_string(),
         part_number: 1,
         chunk_path: chunk.path().to_path_buf(),
+        byte_range: None,
+        sha256: None,
     };
     let error = gateway
         .upload_part(&request)
         .expect_err("must fail on throttling");
-    assert_eq!(error, DerivedProcessingError::Throttled);
+    assert_eq!(
+        error,
+        DerivedProcessingError::Throttled {
+            retry_after_ms: None
+        }
+    );
 
     server.join().expect("server thread");
 }
@@ -1004,6 +1161,8 @@ fn e2e_openapi_derived_gateway_upload_part_sends_request_revision_etag_in_if_mat
             upload_id: "upload-4".to_string(),
             part_number: 1,
             chunk_path: chunk.path().to_path_buf(),
+            byte_range: None,
+            sha256: None,
         })
         .expect("upload part should succeed");
     assert_eq!(uploaded.part_etag, "part-etag-1");
@@ -8,6 +8,8 @@ mod derived_processing_gateway_flow;
 mod external_fixtures;
 #[path = "e2e_flow/external_fixtures_av_flow.rs"]
 mod external_fixtures_av_flow;
+#[path = "e2e_flow/external_fixtures_raw_flow.rs"]
+mod external_fixtures_raw_flow;
 #[path = "e2e_flow/ffmpeg_proxy_generator_flow.rs"]
 mod ffmpeg_proxy_generator_flow;
 #[path = "e2e_flow/notification_bridge_flow.rs"]
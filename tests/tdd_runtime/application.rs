@@ -3,8 +3,8 @@ use std::path::PathBuf;
 
 use retaia_agent::{
     AgentRuntimeApp, AgentRuntimeConfig, AuthMode, ConfigRepository, ConfigRepositoryError,
-    ConfigValidationError, JobStage, JobStatus, LogLevel, MenuAction, RuntimeSnapshot,
-    SettingsSaveError, SystemNotification,
+    ConfigValidationError, JobSelectionStrategy, JobStage, JobStatus, LogLevel, MenuAction,
+    RuntimeSnapshot, SettingsSaveError, SystemNotification,
 };
 
 fn valid_settings() -> AgentRuntimeConfig {
@@ -16,6 +16,8 @@ fn valid_settings() -> AgentRuntimeConfig {
         storage_mounts: std::collections::BTreeMap::new(),
         max_parallel_jobs: 2,
         log_level: LogLevel::Info,
+        job_selection: JobSelectionStrategy::default(),
+        upload_part_size_bytes: None,
     }
 }
 
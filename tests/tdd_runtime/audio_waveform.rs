@@ -0,0 +1,278 @@
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use retaia_agent::{
+    AudioProxyRequest, AudioWaveformFormat, AudioWaveformRequest, ClaimedDerivedJob, CommandOutput,
+    CommandRunner, DerivedExecutionPlanner, DerivedJobType, DerivedKind, FfmpegProxyGenerator,
+    NoopProxyProgressSink, PhotoDecodePath, PhotoProxyRequest, ProxyGenerationError,
+    ProxyGenerator, ProxyProgressSink, RuntimeDerivedPlanner, VideoProxyRequest,
+    WAVEFORM_V2_SAMPLE_RATE, WAVEFORM_V2_SAMPLES_PER_PEAK, WaveformPeak, WaveformPeaks,
+    WaveformPeaksBuilder, build_audio_waveform_decode_args, validate_derived_upload_init,
+};
+
+fn peak(min: i16, max: i16, rms: i16) -> WaveformPeak {
+    WaveformPeak { min, max, rms }
+}
+
+#[test]
+fn tdd_waveform_peaks_builder_keeps_min_max_rms_per_channel_and_zoom_level() {
+    let mut builder = WaveformPeaksBuilder::new(2, 8, &[2, 4]);
+    // Left is a square wave, right is silent except for one clipped sample.
+    for (left, right) in [(100, 0), (-100, 0), (100, i16::MAX), (-100, 0), (50, 0)] {
+        builder.push_frame(&[left, right]);
+    }
+
+    let peaks = builder.finish();
+
+    assert_eq!((peaks.channel_count, peaks.sample_rate), (2, 8));
+    assert_eq!(peaks.duration_ms, 625);
+    assert_eq!(peaks.levels[0].samples_per_peak, 2);
+    assert_eq!(peaks.levels[0].peaks.len(), 3 * 2);
+    assert_eq!(peaks.peak(0, 0, 0), Some(peak(-100, 100, 100)));
+    assert_eq!(peaks.peak(0, 0, 1), Some(peak(0, 0, 0)));
+    assert_eq!(peaks.peak(0, 1, 1), Some(peak(0, i16::MAX, 23_170)));
+    assert_eq!(peaks.peak(0, 2, 0), Some(peak(50, 50, 50)));
+    assert_eq!(peaks.levels[1].peaks.len(), 2 * 2);
+    assert_eq!(peaks.peak(1, 0, 1), Some(peak(0, i16::MAX, 16_384)));
+    assert_eq!(peaks.peak(1, 2, 0), None);
+    assert_eq!(peaks.peak(0, 0, 2), None);
+}
+
+#[test]
+fn tdd_waveform_v2_binary_encoding_round_trips_and_rejects_corrupt_input() {
+    let mut builder = WaveformPeaksBuilder::new(2, 48_000, &WAVEFORM_V2_SAMPLES_PER_PEAK);
+    for index in 0..10_000_i32 {
+        let sample = i16::try_from((index % 200) * 100 - 10_000).expect("sample");
+        builder.push_frame(&[sample, sample / 2]);
+    }
+    let peaks = builder.finish();
+
+    let bytes = peaks.encode();
+
+    assert_eq!(&bytes[..4], b"RWF2");
+    assert_eq!(u16::from_le_bytes([bytes[4], bytes[5]]), 2);
+    assert_eq!(u16::from_le_bytes([bytes[6], bytes[7]]), 2);
+    assert_eq!(WaveformPeaks::decode(&bytes), Some(peaks.clone()));
+    let expected_len = 24
+        + peaks
+            .levels
+            .iter()
+            .map(|level| 8 + level.peaks.len() * 6)
+            .sum::<usize>();
+    assert_eq!(bytes.len(), expected_len);
+    assert_eq!(WaveformPeaks::decode(&bytes[..bytes.len() - 1]), None);
+    let mut wrong_magic = bytes.clone();
+    wrong_magic[0] = b'X';
+    assert_eq!(WaveformPeaks::decode(&wrong_magic), None);
+    let mut trailing = bytes;
+    trailing.push(0);
+    assert_eq!(WaveformPeaks::decode(&trailing), None);
+}
+
+fn waveform_request(format: AudioWaveformFormat, output_path: &Path) -> AudioWaveformRequest {
+    AudioWaveformRequest {
+        input_path: "/in/stereo.wav".to_string(),
+        output_path: output_path.display().to_string(),
+        bucket_count: 1_000,
+        format,
+    }
+}
+
+#[test]
+fn tdd_ffmpeg_waveform_decode_args_keep_channels_for_waveform_v2() {
+    let wav = Path::new("/tmp/decoded.wav");
+
+    let json = build_audio_waveform_decode_args(
+        &waveform_request(AudioWaveformFormat::Json, Path::new("/out/a.json")),
+        wav,
+    );
+    let v2 = build_audio_waveform_decode_args(
+        &waveform_request(AudioWaveformFormat::PeaksV2, Path::new("/out/a.bin")),
+        wav,
+    );
+
+    assert!(json.windows(2).any(|pair| pair == ["-ac", "1"]));
+    assert!(!v2.iter().any(|arg| arg == "-ac"));
+    assert!(
+        v2.windows(2)
+            .any(|pair| pair == ["-ar", WAVEFORM_V2_SAMPLE_RATE.to_string().as_str()])
+    );
+    assert_eq!(v2.last().map(String::as_str), Some("/tmp/decoded.wav"));
+}
+
+struct StereoWavRunner {
+    calls: Mutex<Vec<Vec<String>>>,
+}
+
+impl CommandRunner for StereoWavRunner {
+    fn run(&self, program: &str, args: &[String]) -> Result<CommandOutput, ProxyGenerationError> {
+        self.calls.lock().expect("calls").push(args.to_vec());
+        if program.ends_with("ffprobe") {
+            return Ok(CommandOutput {
+                status_code: Some(0),
+                stdout: "1.0\n".to_string(),
+                stderr: String::new(),
+            });
+        }
+        let spec = hound::WavSpec {
+            channels: 2,
+            sample_rate: 1_024,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut writer = hound::WavWriter::create(args.last().expect("wav path"), spec)
+            .map_err(|error| ProxyGenerationError::Process(error.to_string()))?;
+        for index in 0..1_024_i32 {
+            let left = if index % 2 == 0 { 8_000_i16 } else { -8_000 };
+            writer
+                .write_sample(left)
+                .and_then(|()| writer.write_sample(0_i16))
+                .map_err(|error| ProxyGenerationError::Process(error.to_string()))?;
+        }
+        writer
+            .finalize()
+            .map_err(|error| ProxyGenerationError::Process(error.to_string()))?;
+        Ok(CommandOutput {
+            status_code: Some(0),
+            stdout: String::new(),
+            stderr: String::new(),
+        })
+    }
+}
+
+#[test]
+fn tdd_ffmpeg_waveform_v2_writes_per_channel_binary_peaks() {
+    let generator = FfmpegProxyGenerator::new(
+        "ffmpeg".to_string(),
+        StereoWavRunner {
+            calls: Mutex::new(Vec::new()),
+        },
+    );
+    let dir = tempfile::tempdir().expect("tempdir");
+    let output = dir.path().join("stereo.waveform.bin");
+
+    generator
+        .generate_audio_waveform(
+            &AudioWaveformRequest {
+                bucket_count: 0,
+                ..waveform_request(AudioWaveformFormat::PeaksV2, &output)
+            },
+            &NoopProxyProgressSink,
+        )
+        .expect("waveform_v2 generation");
+
+    let peaks = WaveformPeaks::decode(&std::fs::read(&output).expect("read waveform"))
+        .expect("valid waveform_v2 payload");
+    assert_eq!((peaks.channel_count, peaks.sample_rate), (2, 1_024));
+    assert_eq!(peaks.duration_ms, 1_000);
+    let samples_per_peak = peaks
+        .levels
+        .iter()
+        .map(|level| level.samples_per_peak)
+        .collect::<Vec<_>>();
+    assert_eq!(samples_per_peak, WAVEFORM_V2_SAMPLES_PER_PEAK);
+    assert_eq!(peaks.levels[0].peaks.len(), 4 * 2);
+    assert_eq!(peaks.peak(0, 3, 0), Some(peak(-8_000, 8_000, 8_000)));
+    assert_eq!(peaks.peak(0, 3, 1), Some(peak(0, 0, 0)));
+    assert_eq!(peaks.levels[3].peaks.len(), 2);
+    let leftovers = std::fs::read_dir(dir.path())
+        .expect("read dir")
+        .filter_map(Result::ok)
+        .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "wav"))
+        .count();
+    assert_eq!(leftovers, 0);
+}
+
+struct WaveformFormatGenerator {
+    requests: Mutex<Vec<AudioWaveformRequest>>,
+}
+
+impl ProxyGenerator for WaveformFormatGenerator {
+    fn generate_video_proxy(
+        &self,
+        _request: &VideoProxyRequest,
+        _progress: &dyn ProxyProgressSink,
+    ) -> Result<(), ProxyGenerationError> {
+        Ok(())
+    }
+
+    fn generate_audio_proxy(
+        &self,
+        _request: &AudioProxyRequest,
+        _progress: &dyn ProxyProgressSink,
+    ) -> Result<(), ProxyGenerationError> {
+        Ok(())
+    }
+
+    fn generate_photo_proxy(
+        &self,
+        _request: &PhotoProxyRequest,
+        _progress: &dyn ProxyProgressSink,
+    ) -> Result<Option<PhotoDecodePath>, ProxyGenerationError> {
+        Ok(None)
+    }
+
+    fn generate_audio_waveform(
+        &self,
+        request: &AudioWaveformRequest,
+        _progress: &dyn ProxyProgressSink,
+    ) -> Result<(), ProxyGenerationError> {
+        self.requests
+            .lock()
+            .expect("requests")
+            .push(request.clone());
+        let mut builder = WaveformPeaksBuilder::new(2, 48_000, &WAVEFORM_V2_SAMPLES_PER_PEAK);
+        builder.push_frame(&[1_000, -1_000]);
+        std::fs::write(&request.output_path, builder.finish().encode())
+            .map_err(|error| ProxyGenerationError::Process(error.to_string()))
+    }
+}
+
+#[test]
+fn tdd_runtime_derived_planner_submits_waveform_v2_as_octet_stream() {
+    let generator = Arc::new(WaveformFormatGenerator {
+        requests: Mutex::new(Vec::new()),
+    });
+    let planner = RuntimeDerivedPlanner::new(generator.clone(), generator.clone())
+        .with_waveform_format(AudioWaveformFormat::PeaksV2);
+    let claimed = ClaimedDerivedJob {
+        job_id: "job-wave-v2".to_string(),
+        asset_uuid: "asset-wave-v2".to_string(),
+        lock_token: "lock-wave-v2".to_string(),
+        fencing_token: 1,
+        job_type: DerivedJobType::GenerateAudioWaveform,
+        source_storage_id: "nas-main".to_string(),
+        source_original_relative: "INBOX/mix.wav".to_string(),
+        source_sidecars_relative: Vec::new(),
+    };
+    let dir = tempfile::tempdir().expect("tempdir");
+    let staged = dir.path().join("mix.wav");
+    std::fs::write(&staged, b"audio-source").expect("write");
+
+    let plan = planner
+        .plan_for_claimed_job_with_source(&claimed, Some(staged.as_path()), &[])
+        .expect("plan");
+
+    assert_eq!(plan.uploads.len(), 1);
+    let upload = &plan.uploads[0];
+    assert_eq!(upload.init.kind, DerivedKind::Waveform);
+    assert_eq!(upload.init.content_type, "application/octet-stream");
+    validate_derived_upload_init(&upload.init).expect("content type allowed for waveform");
+    assert!(upload.parts[0].chunk_path.ends_with("mix.waveform.bin"));
+    assert_eq!(
+        plan.submit.manifest[0].reference,
+        "/api/v1/assets/asset-wave-v2/derived/waveform"
+    );
+    let metrics = plan.submit.metrics.expect("waveform metrics");
+    assert_eq!(
+        metrics.get("waveform_format"),
+        Some(&serde_json::json!("waveform_v2"))
+    );
+    assert_eq!(
+        metrics.get("waveform_samples_per_peak"),
+        Some(&serde_json::json!([256, 1024, 4096, 16384]))
+    );
+    assert!(!metrics.contains_key("waveform_bucket_count"));
+    let requests = generator.requests.lock().expect("requests");
+    assert_eq!(requests[0].format, AudioWaveformFormat::PeaksV2);
+}
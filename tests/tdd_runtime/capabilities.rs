@@ -1,8 +1,10 @@
 use std::collections::BTreeSet;
 
 use retaia_agent::{
-    AgentCapability, declared_agent_capabilities, declared_agent_capabilities_with_ffmpeg,
-    ffmpeg_available, has_required_capabilities, photo_source_extension_supported,
+    AgentCapability, HEIF_DECODER_COMMANDS, declared_agent_capabilities,
+    declared_agent_capabilities_with_ffmpeg, ffmpeg_available, has_required_capabilities,
+    heif_decoder_available, heif_decoder_command, photo_proxy_available,
+    photo_source_extension_supported, photo_source_extension_supported_with_heif,
 };
 
 #[test]
@@ -14,13 +16,13 @@ fn tdd_first_agent_capability_is_media_facts_v1() {
 fn tdd_declared_agent_capabilities_contains_v1_processing_capability_set() {
     let declared = declared_agent_capabilities();
     let expected_base = BTreeSet::from([
-        "audio.waveform@1".to_string(),
         "media.facts@1".to_string(),
         "media.thumbnails@1".to_string(),
     ]);
     assert!(expected_base.is_subset(&declared));
 
     let proxy_caps = BTreeSet::from([
+        "audio.waveform@1".to_string(),
         "media.previews.audio@1".to_string(),
         "media.previews.photo@1".to_string(),
         "media.previews.video@1".to_string(),
@@ -28,6 +30,7 @@ fn tdd_declared_agent_capabilities_contains_v1_processing_capability_set() {
     if ffmpeg_available() {
         assert!(proxy_caps.is_subset(&declared));
     } else {
+        assert!(!declared.contains("audio.waveform@1"));
         assert!(!declared.contains("media.previews.video@1"));
         assert!(!declared.contains("media.previews.audio@1"));
         assert!(declared.contains("media.previews.photo@1"));
@@ -39,6 +42,7 @@ fn tdd_declared_agent_capabilities_without_ffmpeg_excludes_only_audio_video_prox
     let declared = declared_agent_capabilities_with_ffmpeg(false);
     assert!(declared.contains("media.facts@1"));
     assert!(declared.contains("media.thumbnails@1"));
+    assert!(!declared.contains("audio.waveform@1"));
     assert!(!declared.contains("media.previews.video@1"));
     assert!(!declared.contains("media.previews.audio@1"));
     assert!(declared.contains("media.previews.photo@1"));
@@ -60,6 +64,30 @@ fn tdd_photo_source_extension_support_covers_standard_and_camera_raw_formats() {
     assert!(!photo_source_extension_supported("wav"));
 }
 
+#[test]
+fn tdd_heif_family_extensions_are_supported_only_when_libheif_is_found() {
+    for extension in ["heic", "HEIF", ".hif", "avif"] {
+        assert!(photo_source_extension_supported_with_heif(extension, true));
+        assert!(!photo_source_extension_supported_with_heif(
+            extension, false
+        ));
+        assert_eq!(
+            photo_source_extension_supported(extension),
+            heif_decoder_available()
+        );
+    }
+    assert!(photo_source_extension_supported_with_heif("jpg", false));
+}
+
+#[test]
+fn tdd_photo_capability_does_not_require_libheif_and_heif_probe_finds_real_binaries() {
+    assert!(photo_proxy_available());
+    assert_eq!(heif_decoder_available(), heif_decoder_command().is_some());
+    if let Some(command) = heif_decoder_command() {
+        assert!(HEIF_DECODER_COMMANDS.contains(&command));
+    }
+}
+
 #[test]
 fn tdd_has_required_capabilities_checks_subset_relation() {
     let declared = BTreeSet::from([
@@ -3,8 +3,8 @@ use std::sync::{Mutex, OnceLock};
 use tempfile::tempdir;
 
 use retaia_agent::{
-    AgentRuntimeConfig, AuthMode, ConfigRepository, FileConfigRepository, LogLevel,
-    TechnicalAuthConfig, load_config_from_path,
+    AgentRuntimeConfig, AuthMode, ConfigRepository, FileConfigRepository, JobSelectionStrategy,
+    LogLevel, TechnicalAuthConfig, load_config_from_path,
 };
 
 fn env_guard() -> &'static Mutex<()> {
@@ -34,6 +34,8 @@ fn valid_config() -> AgentRuntimeConfig {
         storage_mounts,
         max_parallel_jobs: 3,
         log_level: LogLevel::Debug,
+        job_selection: JobSelectionStrategy::default(),
+        upload_part_size_bytes: None,
     }
 }
 
@@ -3,8 +3,8 @@ use std::sync::{Mutex, OnceLock};
 use tempfile::tempdir;
 
 use retaia_agent::{
-    AgentRuntimeConfig, AuthMode, ConfigStoreError, LogLevel, TechnicalAuthConfig,
-    load_config_from_path, save_config_to_path, system_config_file_path,
+    AgentRuntimeConfig, AuthMode, ConfigStoreError, JobSelectionStrategy, LogLevel,
+    TechnicalAuthConfig, load_config_from_path, save_config_to_path, system_config_file_path,
 };
 
 fn env_guard() -> &'static Mutex<()> {
@@ -34,6 +34,8 @@ fn valid_config() -> AgentRuntimeConfig {
         storage_mounts,
         max_parallel_jobs: 3,
         log_level: LogLevel::Info,
+        job_selection: JobSelectionStrategy::AssetFair,
+        upload_part_size_bytes: Some(16 * 1024 * 1024),
     }
 }
 
@@ -53,6 +55,28 @@ fn tdd_config_store_roundtrip_preserves_runtime_config() {
     assert_eq!(loaded, config);
 }
 
+#[test]
+fn tdd_config_store_defaults_job_selection_for_configs_saved_before_the_setting_existed() {
+    let _guard = env_guard().lock().expect("env guard");
+    use_memory_secret_store();
+    let dir = tempdir().expect("temp dir");
+    let path = dir.path().join("legacy.toml");
+    std::fs::write(
+        &path,
+        "core_api_url = \"https://core.retaia.local/api/v1\"\n\
+         ollama_url = \"http://127.0.0.1:11434\"\n\
+         auth_mode = \"interactive\"\n\
+         max_parallel_jobs = 2\n\
+         log_level = \"info\"\n",
+    )
+    .expect("write legacy config");
+
+    let loaded = load_config_from_path(&path).expect("legacy config should load");
+
+    assert_eq!(loaded.job_selection, JobSelectionStrategy::Fifo);
+    assert_eq!(loaded.upload_part_size_bytes, None);
+}
+
 #[test]
 fn tdd_config_store_rejects_invalid_config_before_persist() {
     let _guard = env_guard().lock().expect("env guard");
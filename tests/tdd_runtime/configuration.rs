@@ -1,6 +1,6 @@
 use retaia_agent::{
-    AgentRuntimeConfig, AuthMode, ConfigValidationError, LogLevel, TechnicalAuthConfig,
-    compact_validation_reason, normalize_core_api_url, validate_config,
+    AgentRuntimeConfig, AuthMode, ConfigValidationError, JobSelectionStrategy, LogLevel,
+    TechnicalAuthConfig, compact_validation_reason, normalize_core_api_url, validate_config,
 };
 
 fn valid_config() -> AgentRuntimeConfig {
@@ -12,6 +12,8 @@ fn valid_config() -> AgentRuntimeConfig {
         storage_mounts: std::collections::BTreeMap::new(),
         max_parallel_jobs: 2,
         log_level: LogLevel::Info,
+        job_selection: JobSelectionStrategy::default(),
+        upload_part_size_bytes: None,
     }
 }
 
@@ -140,3 +142,17 @@ fn tdd_configuration_rejects_empty_storage_mount_id() {
     let errors = validate_config(&config).expect_err("empty storage id must fail");
     assert!(errors.contains(&ConfigValidationError::EmptyStorageMountId));
 }
+
+#[test]
+fn tdd_configuration_rejects_zero_upload_part_size() {
+    let mut config = valid_config();
+    config.upload_part_size_bytes = Some(0);
+
+    assert_eq!(
+        validate_config(&config),
+        Err(vec![ConfigValidationError::InvalidUploadPartSize])
+    );
+
+    config.upload_part_size_bytes = Some(1);
+    assert_eq!(validate_config(&config), Ok(()));
+}
@@ -1,6 +1,7 @@
 use retaia_agent::{
-    AgentRuntimeConfig, AuthMode, ConfigField, ConfigInterface, ConfigValidationError, LogLevel,
-    RuntimeConfigUpdate, apply_config_update, supported_config_fields,
+    AgentRuntimeConfig, AuthMode, ConfigField, ConfigInterface, ConfigValidationError,
+    JobSelectionStrategy, LogLevel, RuntimeConfigUpdate, apply_config_update,
+    supported_config_fields,
 };
 
 fn valid_config() -> AgentRuntimeConfig {
@@ -12,6 +13,8 @@ fn valid_config() -> AgentRuntimeConfig {
         storage_mounts: std::collections::BTreeMap::new(),
         max_parallel_jobs: 2,
         log_level: LogLevel::Info,
+        job_selection: JobSelectionStrategy::default(),
+        upload_part_size_bytes: None,
     }
 }
 
@@ -27,6 +30,7 @@ fn tdd_supported_configuration_fields_are_identical_for_gui_and_cli() {
     assert!(gui.contains(&ConfigField::TechnicalSecretKey));
     assert!(gui.contains(&ConfigField::MaxParallelJobs));
     assert!(gui.contains(&ConfigField::LogLevel));
+    assert!(gui.contains(&ConfigField::JobSelection));
 }
 
 #[test]
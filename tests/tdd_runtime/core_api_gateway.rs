@@ -9,12 +9,14 @@ fn tdd_runtime_snapshot_from_polled_jobs_maps_known_running_and_current_job() {
         CoreJobView {
             job_id: "job-1".to_string(),
             asset_uuid: "asset-1".to_string(),
+            job_type: None,
             state: CoreJobState::Pending,
             required_capabilities: vec!["media.facts@1".to_string()],
         },
         CoreJobView {
             job_id: "job-2".to_string(),
             asset_uuid: "asset-2".to_string(),
+            job_type: None,
             state: CoreJobState::Claimed,
             required_capabilities: vec!["media.facts@1".to_string()],
         },
@@ -37,6 +39,7 @@ fn tdd_runtime_snapshot_from_polled_jobs_maps_failed_jobs_to_notification_input(
     let jobs = vec![CoreJobView {
         job_id: "job-fail".to_string(),
         asset_uuid: "asset-fail".to_string(),
+        job_type: None,
         state: CoreJobState::Failed,
         required_capabilities: vec!["media.facts@1".to_string()],
     }];
@@ -53,12 +56,14 @@ fn tdd_filter_jobs_for_declared_capabilities_keeps_only_subset_matches() {
         CoreJobView {
             job_id: "job-supported".to_string(),
             asset_uuid: "asset-1".to_string(),
+            job_type: None,
             state: CoreJobState::Pending,
             required_capabilities: vec!["media.facts@1".to_string()],
         },
         CoreJobView {
             job_id: "job-unsupported".to_string(),
             asset_uuid: "asset-2".to_string(),
+            job_type: None,
             state: CoreJobState::Pending,
             required_capabilities: vec!["media.unknown@1".to_string()],
         },
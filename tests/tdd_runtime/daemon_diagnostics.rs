@@ -1,9 +1,9 @@
 use retaia_agent::{
     AgentRuntimeConfig, AuthMode, CompletedJobEntry, DaemonCurrentJobStats, DaemonCycleEntry,
-    DaemonDiagnosticsSnapshot, DaemonLastJobStats, DaemonRuntimeStats, DaemonStatus, LogLevel,
-    TechnicalAuthConfig, append_redacted_config_markdown, build_bug_report_markdown,
-    daemon_status_as_label, redacted_runtime_config_from, render_daemon_inspect,
-    render_daemon_inspect_json,
+    DaemonDiagnosticsSnapshot, DaemonLastJobStats, DaemonRuntimeStats, DaemonStatus,
+    JobSelectionStrategy, LogLevel, TechnicalAuthConfig, append_redacted_config_markdown,
+    build_bug_report_markdown, daemon_status_as_label, redacted_runtime_config_from,
+    render_daemon_inspect, render_daemon_inspect_json,
 };
 
 #[test]
@@ -44,6 +44,7 @@ fn tdd_daemon_diagnostics_build_bug_report_with_stats_and_history() {
                 duration_ms: 950,
                 completed_at_unix_ms: 1700000000900,
             }),
+            drain: None,
         }),
         completed_jobs: vec![CompletedJobEntry {
             completed_at_unix_ms: 1700000000900,
@@ -126,6 +127,8 @@ fn tdd_daemon_diagnostics_render_inspect_json_contains_redacted_config() {
         )]),
         max_parallel_jobs: 3,
         log_level: LogLevel::Info,
+        job_selection: JobSelectionStrategy::default(),
+        upload_part_size_bytes: None,
     });
     let rendered =
         render_daemon_inspect_json(&snapshot, Some("/tmp/history.sqlite3"), Some(&config));
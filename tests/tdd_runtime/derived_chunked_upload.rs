@@ -0,0 +1,432 @@
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use retaia_agent::{
+    ClaimedDerivedJob, DerivedExecutionPlan, DerivedExecutionPlanner, DerivedJobExecutorError,
+    DerivedJobFailure, DerivedJobType, DerivedKind, DerivedManifestItem, DerivedProcessingError,
+    DerivedProcessingGateway, DerivedUploadComplete, DerivedUploadInit, DerivedUploadPart,
+    DerivedUploadPlan, GatewayRetryPolicies, GatewayRetryPolicy, HeartbeatReceipt,
+    SubmitDerivedPayload, UploadedDerivedPart, attach_upload_integrity, execute_derived_job_once,
+    execute_derived_job_once_with_retry_policies, split_upload_parts,
+};
+
+struct ChunkedGateway {
+    calls: Mutex<Vec<String>>,
+    part_failures: Mutex<Vec<(u32, DerivedProcessingError)>>,
+    received_chunks: Mutex<Vec<Vec<u8>>>,
+    completed_parts: Mutex<Option<Vec<UploadedDerivedPart>>>,
+}
+
+impl ChunkedGateway {
+    fn new(part_failures: Vec<(u32, DerivedProcessingError)>) -> Self {
+        Self {
+            calls: Mutex::new(Vec::new()),
+            part_failures: Mutex::new(part_failures),
+            received_chunks: Mutex::new(Vec::new()),
+            completed_parts: Mutex::new(None),
+        }
+    }
+
+    fn calls(&self) -> Vec<String> {
+        self.calls.lock().expect("calls").clone()
+    }
+
+    fn record(&self, call: String) {
+        self.calls.lock().expect("calls").push(call);
+    }
+}
+
+impl DerivedProcessingGateway for ChunkedGateway {
+    fn claim_job(&self, job_id: &str) -> Result<ClaimedDerivedJob, DerivedProcessingError> {
+        self.record(format!("claim:{job_id}"));
+        Ok(ClaimedDerivedJob {
+            job_id: job_id.to_string(),
+            asset_uuid: "asset-1".to_string(),
+            lock_token: "lock-1".to_string(),
+            fencing_token: 1,
+            job_type: DerivedJobType::GeneratePreview,
+            source_storage_id: "nas-main".to_string(),
+            source_original_relative: "INBOX/source.mov".to_string(),
+            source_sidecars_relative: Vec::new(),
+        })
+    }
+
+    fn fetch_asset_revision_etag(
+        &self,
+        _asset_uuid: &str,
+    ) -> Result<String, DerivedProcessingError> {
+        Ok("\"asset-rev-1\"".to_string())
+    }
+
+    fn heartbeat(
+        &self,
+        _job_id: &str,
+        _lock_token: &str,
+        fencing_token: i32,
+    ) -> Result<HeartbeatReceipt, DerivedProcessingError> {
+        Ok(HeartbeatReceipt {
+            locked_until: None,
+            fencing_token,
+        })
+    }
+
+    fn submit_derived(
+        &self,
+        job_id: &str,
+        _lock_token: &str,
+        _fencing_token: i32,
+        _idempotency_key: &str,
+        _payload: &SubmitDerivedPayload,
+    ) -> Result<(), DerivedProcessingError> {
+        self.record(format!("submit:{job_id}"));
+        Ok(())
+    }
+
+    fn fail_job(
+        &self,
+        job_id: &str,
+        _lock_token: &str,
+        _fencing_token: i32,
+        _idempotency_key: &str,
+        failure: &DerivedJobFailure,
+    ) -> Result<(), DerivedProcessingError> {
+        self.record(format!("fail:{job_id}:{}", failure.error_code));
+        Ok(())
+    }
+
+    fn upload_init(&self, _request: &DerivedUploadInit) -> Result<(), DerivedProcessingError> {
+        self.record("upload_init".to_string());
+        Ok(())
+    }
+
+    fn upload_part(
+        &self,
+        request: &DerivedUploadPart,
+    ) -> Result<UploadedDerivedPart, DerivedProcessingError> {
+        self.record(format!("upload_part:{}", request.part_number));
+        let mut failures = self.part_failures.lock().expect("failures");
+        if let Some(index) = failures
+            .iter()
+            .position(|(part_number, _)| *part_number == request.part_number)
+        {
+            return Err(failures.remove(index).1);
+        }
+        drop(failures);
+        let chunk = request
+            .read_chunk()
+            .map_err(|error| DerivedProcessingError::Transport(error.to_string()))?;
+        self.received_chunks.lock().expect("chunks").push(chunk);
+        Ok(UploadedDerivedPart {
+            part_number: request.part_number,
+            part_etag: format!("etag-{}", request.part_number),
+        })
+    }
+
+    fn upload_complete(
+        &self,
+        request: &DerivedUploadComplete,
+    ) -> Result<(), DerivedProcessingError> {
+        self.record("upload_complete".to_string());
+        *self.completed_parts.lock().expect("parts") = request.parts.clone();
+        Ok(())
+    }
+}
+
+struct ChunkedProxyPlanner {
+    artifact: PathBuf,
+    part_size_bytes: u64,
+    tamper_after_planning: bool,
+}
+
+impl DerivedExecutionPlanner for ChunkedProxyPlanner {
+    fn plan_for_claimed_job(
+        &self,
+        claimed: &ClaimedDerivedJob,
+    ) -> Result<DerivedExecutionPlan, DerivedJobExecutorError> {
+        let size_bytes = std::fs::metadata(&self.artifact)
+            .expect("artifact metadata")
+            .len();
+        let mut upload = DerivedUploadPlan {
+            init: DerivedUploadInit {
+                asset_uuid: claimed.asset_uuid.clone(),
+                revision_etag: String::new(),
+                kind: DerivedKind::PreviewVideo,
+                content_type: "video/mp4".to_string(),
+                size_bytes,
+                sha256: None,
+                idempotency_key: "idem-init".to_string(),
+            },
+            parts: split_upload_parts(
+                &claimed.asset_uuid,
+                "up-1",
+                &self.artifact,
+                size_bytes,
+                self.part_size_bytes,
+            ),
+            complete: DerivedUploadComplete {
+                asset_uuid: claimed.asset_uuid.clone(),
+                revision_etag: String::new(),
+                upload_id: "up-1".to_string(),
+                idempotency_key: "idem-complete".to_string(),
+                parts: None,
+            },
+        };
+        let sha256 = attach_upload_integrity(&mut upload, &self.artifact).expect("integrity");
+        if self.tamper_after_planning {
+            std::fs::write(&self.artifact, b"9876543210").expect("tamper artifact");
+        }
+        Ok(DerivedExecutionPlan {
+            uploads: vec![upload],
+            submit: SubmitDerivedPayload {
+                job_type: DerivedJobType::GeneratePreview,
+                manifest: vec![DerivedManifestItem {
+                    kind: DerivedKind::PreviewVideo,
+                    reference: "s3://derived/proxy.mp4".to_string(),
+                    size_bytes: Some(size_bytes),
+                    sha256: Some(sha256),
+                }],
+                facts_patch: None,
+                transcript_patch: None,
+                warnings: None,
+                metrics: None,
+            },
+            submit_idempotency_key: "idem-submit".to_string(),
+        })
+    }
+}
+
+fn write_artifact(dir: &Path, content: &[u8]) -> PathBuf {
+    let path = dir.join("proxy.mp4");
+    std::fs::write(&path, content).expect("write artifact");
+    path
+}
+
+#[test]
+fn tdd_split_upload_parts_covers_file_with_contiguous_ranges() {
+    let path = Path::new("/tmp/proxy.mp4");
+
+    let parts = split_upload_parts("asset-1", "up-1", path, 10, 4);
+
+    assert_eq!(
+        parts
+            .iter()
+            .map(|part| (part.part_number, part.byte_range.clone()))
+            .collect::<Vec<_>>(),
+        vec![(1, Some(0..4)), (2, Some(4..8)), (3, Some(8..10))]
+    );
+    assert!(parts.iter().all(|part| part.upload_id == "up-1"));
+    assert!(parts.iter().all(|part| part.chunk_path == path));
+}
+
+#[test]
+fn tdd_split_upload_parts_keeps_small_files_in_a_single_whole_file_part() {
+    let path = Path::new("/tmp/proxy.mp4");
+
+    assert_eq!(
+        split_upload_parts("asset-1", "up-1", path, 8, 8)
+            .into_iter()
+            .map(|part| (part.part_number, part.byte_range))
+            .collect::<Vec<_>>(),
+        vec![(1, None)]
+    );
+    assert_eq!(split_upload_parts("asset-1", "up-1", path, 0, 8).len(), 1);
+}
+
+#[test]
+fn tdd_split_upload_parts_keeps_part_sizes_above_u32_range() {
+    let path = Path::new("/tmp/proxy.mp4");
+    let part_size = u64::from(u32::MAX) + 1;
+
+    let parts = split_upload_parts("asset-1", "up-1", path, 2 * part_size + 5, part_size);
+
+    assert_eq!(
+        parts
+            .into_iter()
+            .map(|part| (part.part_number, part.byte_range))
+            .collect::<Vec<_>>(),
+        vec![
+            (1, Some(0..part_size)),
+            (2, Some(part_size..2 * part_size)),
+            (3, Some(2 * part_size..2 * part_size + 5)),
+        ]
+    );
+}
+
+#[test]
+fn tdd_derived_upload_part_reads_only_its_byte_range() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let artifact = write_artifact(dir.path(), b"0123456789");
+
+    let parts = split_upload_parts("asset-1", "up-1", &artifact, 10, 4);
+
+    assert_eq!(parts[1].read_chunk().expect("chunk"), b"4567".to_vec());
+    assert_eq!(parts[2].read_chunk().expect("chunk"), b"89".to_vec());
+}
+
+#[test]
+fn tdd_execute_derived_job_once_retries_failed_part_and_completes_with_all_etags() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let artifact = write_artifact(dir.path(), b"0123456789");
+    let gateway = ChunkedGateway::new(vec![(
+        2,
+        DerivedProcessingError::Transport("connection reset".to_string()),
+    )]);
+    let planner = ChunkedProxyPlanner {
+        artifact,
+        part_size_bytes: 4,
+        tamper_after_planning: false,
+    };
+
+    execute_derived_job_once(&gateway, &planner, "job-1").expect("flow");
+
+    let upload_calls = gateway
+        .calls()
+        .into_iter()
+        .filter(|call| call.starts_with("upload_part:"))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        upload_calls,
+        vec![
+            "upload_part:1".to_string(),
+            "upload_part:2".to_string(),
+            "upload_part:2".to_string(),
+            "upload_part:3".to_string(),
+        ]
+    );
+    assert_eq!(
+        gateway.received_chunks.lock().expect("chunks").concat(),
+        b"0123456789".to_vec()
+    );
+    assert_eq!(
+        gateway.completed_parts.lock().expect("parts").clone(),
+        Some(vec![
+            UploadedDerivedPart {
+                part_number: 1,
+                part_etag: "etag-1".to_string(),
+            },
+            UploadedDerivedPart {
+                part_number: 2,
+                part_etag: "etag-2".to_string(),
+            },
+            UploadedDerivedPart {
+                part_number: 3,
+                part_etag: "etag-3".to_string(),
+            },
+        ])
+    );
+}
+
+#[test]
+fn tdd_execute_derived_job_once_waits_for_retry_after_on_throttled_part() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let artifact = write_artifact(dir.path(), b"0123456789");
+    let gateway = ChunkedGateway::new(vec![(
+        1,
+        DerivedProcessingError::Throttled {
+            retry_after_ms: Some(5),
+        },
+    )]);
+    let planner = ChunkedProxyPlanner {
+        artifact,
+        part_size_bytes: 4,
+        tamper_after_planning: false,
+    };
+
+    execute_derived_job_once(&gateway, &planner, "job-1").expect("flow");
+
+    let calls = gateway.calls();
+    assert_eq!(
+        calls
+            .iter()
+            .filter(|call| call.as_str() == "upload_part:1")
+            .count(),
+        2
+    );
+    assert_eq!(calls.last(), Some(&"submit:job-1".to_string()));
+}
+
+#[test]
+fn tdd_execute_derived_job_once_fails_on_transient_part_error_without_retry_policy() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let artifact = write_artifact(dir.path(), b"0123456789");
+    let gateway = ChunkedGateway::new(vec![(
+        2,
+        DerivedProcessingError::Transport("connection reset".to_string()),
+    )]);
+    let planner = ChunkedProxyPlanner {
+        artifact,
+        part_size_bytes: 4,
+        tamper_after_planning: false,
+    };
+
+    let error = execute_derived_job_once_with_retry_policies(
+        &gateway,
+        &planner,
+        "job-1",
+        GatewayRetryPolicies::uniform(GatewayRetryPolicy::no_retry()),
+    )
+    .expect_err("must fail");
+
+    assert_eq!(
+        error,
+        DerivedJobExecutorError::Gateway(DerivedProcessingError::Transport(
+            "connection reset".to_string()
+        ))
+    );
+    assert_eq!(
+        gateway.calls().last(),
+        Some(&"fail:job-1:CORE_TRANSPORT".to_string())
+    );
+}
+
+#[test]
+fn tdd_execute_derived_job_once_does_not_retry_non_transient_part_errors() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let artifact = write_artifact(dir.path(), b"0123456789");
+    let gateway = ChunkedGateway::new(vec![(1, DerivedProcessingError::StaleLockToken)]);
+    let planner = ChunkedProxyPlanner {
+        artifact,
+        part_size_bytes: 4,
+        tamper_after_planning: false,
+    };
+
+    let error = execute_derived_job_once(&gateway, &planner, "job-1").expect_err("must fail");
+
+    assert_eq!(
+        error,
+        DerivedJobExecutorError::Gateway(DerivedProcessingError::StaleLockToken)
+    );
+    let calls = gateway.calls();
+    assert_eq!(
+        calls
+            .iter()
+            .filter(|call| call.starts_with("upload_part:"))
+            .count(),
+        1
+    );
+    assert!(!calls.contains(&"upload_complete".to_string()));
+    assert!(!calls.iter().any(|call| call.starts_with("fail:")));
+}
+
+#[test]
+fn tdd_execute_derived_job_once_rejects_artifact_modified_after_planning() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let artifact = write_artifact(dir.path(), b"0123456789");
+    let gateway = ChunkedGateway::new(Vec::new());
+    let planner = ChunkedProxyPlanner {
+        artifact,
+        part_size_bytes: 4,
+        tamper_after_planning: true,
+    };
+
+    let error = execute_derived_job_once(&gateway, &planner, "job-1").expect_err("must fail");
+
+    assert_eq!(error.error_code(), "DERIVED_INTEGRITY_MISMATCH");
+    assert!(error.is_retryable());
+    let calls = gateway.calls();
+    assert!(!calls.contains(&"upload_complete".to_string()));
+    assert_eq!(
+        calls.last(),
+        Some(&"fail:job-1:DERIVED_INTEGRITY_MISMATCH".to_string())
+    );
+}
@@ -0,0 +1,89 @@
+use std::path::Path;
+
+use retaia_agent::{
+    DerivedIntegrityError, DerivedKind, DerivedUploadComplete, DerivedUploadInit,
+    DerivedUploadPlan, attach_upload_integrity, digest_artifact, sha256_file, split_upload_parts,
+    verify_upload_integrity,
+};
+
+const ABC_SHA256: &str = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+
+fn upload_plan(artifact: &Path, size_bytes: u64, part_size_bytes: u64) -> DerivedUploadPlan {
+    DerivedUploadPlan {
+        init: DerivedUploadInit {
+            asset_uuid: "asset-1".to_string(),
+            revision_etag: String::new(),
+            kind: DerivedKind::PreviewVideo,
+            content_type: "video/mp4".to_string(),
+            size_bytes,
+            sha256: None,
+            idempotency_key: "idem-init".to_string(),
+        },
+        parts: split_upload_parts("asset-1", "up-1", artifact, size_bytes, part_size_bytes),
+        complete: DerivedUploadComplete {
+            asset_uuid: "asset-1".to_string(),
+            revision_etag: String::new(),
+            upload_id: "up-1".to_string(),
+            idempotency_key: "idem-complete".to_string(),
+            parts: None,
+        },
+    }
+}
+
+#[test]
+fn tdd_sha256_file_streams_artifact_into_lowercase_hex_digest() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let artifact = dir.path().join("proxy.bin");
+    std::fs::write(&artifact, b"abc").expect("write artifact");
+
+    assert_eq!(sha256_file(&artifact).expect("sha256"), ABC_SHA256);
+}
+
+#[test]
+fn tdd_digest_artifact_hashes_each_part_range_in_a_single_pass() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let artifact = dir.path().join("proxy.bin");
+    let content = vec![7_u8; 150_000];
+    std::fs::write(&artifact, &content).expect("write artifact");
+    let parts = split_upload_parts("asset-1", "up-1", &artifact, 150_000, 100_000);
+
+    let digest = digest_artifact(&artifact, &parts).expect("digest");
+
+    assert_eq!(digest.part_sha256.len(), 2);
+    std::fs::write(dir.path().join("first.bin"), &content[..100_000]).expect("write first");
+    std::fs::write(dir.path().join("second.bin"), &content[100_000..]).expect("write second");
+    assert_eq!(
+        digest.part_sha256[0],
+        sha256_file(&dir.path().join("first.bin")).expect("sha256")
+    );
+    assert_eq!(
+        digest.part_sha256[1],
+        sha256_file(&dir.path().join("second.bin")).expect("sha256")
+    );
+    assert_eq!(digest.sha256, sha256_file(&artifact).expect("sha256"));
+}
+
+#[test]
+fn tdd_verify_upload_integrity_detects_artifact_changed_after_hashing() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let artifact = dir.path().join("proxy.bin");
+    std::fs::write(&artifact, b"abcdef").expect("write artifact");
+    let mut upload = upload_plan(&artifact, 6, 4);
+
+    let sha256 = attach_upload_integrity(&mut upload, &artifact).expect("attach");
+    assert_eq!(upload.init.sha256.as_deref(), Some(sha256.as_str()));
+    assert!(upload.parts.iter().all(|part| part.sha256.is_some()));
+    assert_eq!(verify_upload_integrity(&upload), Ok(()));
+
+    std::fs::write(&artifact, b"abcdeX").expect("rewrite artifact");
+    let error = verify_upload_integrity(&upload).expect_err("must detect change");
+    assert!(matches!(error, DerivedIntegrityError::Mismatch { .. }));
+    assert_eq!(error.error_code(), "DERIVED_INTEGRITY_MISMATCH");
+}
+
+#[test]
+fn tdd_verify_upload_integrity_skips_uploads_without_checksums() {
+    let upload = upload_plan(Path::new("/nonexistent/proxy.bin"), 6, 4);
+
+    assert_eq!(verify_upload_integrity(&upload), Ok(()));
+}
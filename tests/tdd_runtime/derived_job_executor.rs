@@ -1,13 +1,15 @@
 use std::sync::Mutex;
 
 use retaia_agent::{
-    AgentRuntimeConfig, AudioProxyRequest, AuthMode, ClaimedDerivedJob, DerivedExecutionPlan,
-    DerivedExecutionPlanner, DerivedJobExecutorError, DerivedJobType, DerivedKind,
-    DerivedManifestItem, DerivedProcessingError, DerivedProcessingGateway, DerivedUploadComplete,
-    DerivedUploadInit, DerivedUploadPart, FactsPatchPayload, HeartbeatReceipt, LogLevel,
-    PhotoProxyRequest, ProxyGenerationError, ProxyGenerator, RuntimeDerivedPlanner,
+    AgentRuntimeConfig, AudioProxyRequest, AuthMode, CancellationToken, ClaimedDerivedJob,
+    DerivedExecutionPlan, DerivedExecutionPlanner, DerivedJobExecutorError, DerivedJobFailure,
+    DerivedJobProgressSink, DerivedJobType, DerivedKind, DerivedManifestItem,
+    DerivedProcessingError, DerivedProcessingGateway, DerivedUploadComplete, DerivedUploadInit,
+    DerivedUploadPart, FactsPatchPayload, HeartbeatReceipt, JobSelectionStrategy, JobStage,
+    LogLevel, NoopDerivedJobJournal, PhotoDecodePath, PhotoProxyRequest, ProxyGenerationError,
+    ProxyGenerator, ProxyProgressSink, RuntimeDerivedPlanner, RuntimeStatusEvent,
     SubmitDerivedPayload, UploadedDerivedPart, VideoProxyRequest, execute_derived_job_once,
-    execute_derived_job_once_with_source_staging,
+    execute_derived_job_once_with_progress, execute_derived_job_once_with_source_staging,
 };
 use std::sync::Arc;
 
@@ -25,6 +27,7 @@ impl ProxyGenerator for WritingPreviewGenerator {
     fn generate_video_proxy(
         &self,
         request: &VideoProxyRequest,
+        _progress: &dyn ProxyProgressSink,
     ) -> Result<(), ProxyGenerationError> {
         std::fs::write(&request.output_path, b"generated-video")
             .map_err(|error| ProxyGenerationError::Process(error.to_string()))
@@ -33,6 +36,7 @@ impl ProxyGenerator for WritingPreviewGenerator {
     fn generate_audio_proxy(
         &self,
         request: &AudioProxyRequest,
+        _progress: &dyn ProxyProgressSink,
     ) -> Result<(), ProxyGenerationError> {
         std::fs::write(&request.output_path, b"generated-audio")
             .map_err(|error| ProxyGenerationError::Process(error.to_string()))
@@ -41,14 +45,17 @@ impl ProxyGenerator for WritingPreviewGenerator {
     fn generate_photo_proxy(
         &self,
         request: &PhotoProxyRequest,
-    ) -> Result<(), ProxyGenerationError> {
+        _progress: &dyn ProxyProgressSink,
+    ) -> Result<Option<PhotoDecodePath>, ProxyGenerationError> {
         std::fs::write(&request.output_path, b"generated-photo")
             .map_err(|error| ProxyGenerationError::Process(error.to_string()))
+            .map(|()| None)
     }
 
     fn extract_media_facts(
         &self,
         _input_path: &str,
+        _progress: &dyn ProxyProgressSink,
     ) -> Result<FactsPatchPayload, ProxyGenerationError> {
         Ok(FactsPatchPayload {
             duration_ms: Some(1_000),
@@ -63,9 +70,54 @@ impl ProxyGenerator for WritingPreviewGenerator {
     }
 }
 
+#[derive(Debug, Default)]
+struct ProgressReportingGenerator;
+
+impl ProxyGenerator for ProgressReportingGenerator {
+    fn generate_video_proxy(
+        &self,
+        request: &VideoProxyRequest,
+        progress: &dyn ProxyProgressSink,
+    ) -> Result<(), ProxyGenerationError> {
+        for percent in [50, 50, 100] {
+            progress.report_progress(percent);
+        }
+        WritingPreviewGenerator.generate_video_proxy(request, progress)
+    }
+
+    fn generate_audio_proxy(
+        &self,
+        request: &AudioProxyRequest,
+        progress: &dyn ProxyProgressSink,
+    ) -> Result<(), ProxyGenerationError> {
+        WritingPreviewGenerator.generate_audio_proxy(request, progress)
+    }
+
+    fn generate_photo_proxy(
+        &self,
+        request: &PhotoProxyRequest,
+        progress: &dyn ProxyProgressSink,
+    ) -> Result<Option<PhotoDecodePath>, ProxyGenerationError> {
+        WritingPreviewGenerator.generate_photo_proxy(request, progress)
+    }
+}
+
+#[derive(Default)]
+struct RecordingProgressSink {
+    events: Mutex<Vec<RuntimeStatusEvent>>,
+}
+
+impl DerivedJobProgressSink for RecordingProgressSink {
+    fn report(&self, event: RuntimeStatusEvent) {
+        self.events.lock().expect("events").push(event);
+    }
+}
+
 #[derive(Default)]
 struct MemoryGateway {
     calls: Mutex<Vec<String>>,
+    fail_idempotency_keys: Mutex<Vec<String>>,
+    heartbeat_error: Option<DerivedProcessingError>,
 }
 
 impl MemoryGateway {
@@ -113,6 +165,9 @@ impl DerivedProcessingGateway for MemoryGateway {
             .lock()
             .expect("calls")
             .push(format!("heartbeat:{job_id}"));
+        if let Some(error) = self.heartbeat_error.clone() {
+            return Err(error);
+        }
         Ok(HeartbeatReceipt {
             locked_until: None,
             fencing_token: 1,
@@ -134,6 +189,25 @@ impl DerivedProcessingGateway for MemoryGateway {
         Ok(())
     }
 
+    fn fail_job(
+        &self,
+        job_id: &str,
+        _lock_token: &str,
+        _fencing_token: i32,
+        idempotency_key: &str,
+        failure: &DerivedJobFailure,
+    ) -> Result<(), DerivedProcessingError> {
+        self.calls
+            .lock()
+            .expect("calls")
+            .push(format!("fail:{job_id}:{}", failure.error_code));
+        self.fail_idempotency_keys
+            .lock()
+            .expect("fail keys")
+            .push(idempotency_key.to_string());
+        Ok(())
+    }
+
     fn upload_init(&self, request: &DerivedUploadInit) -> Result<(), DerivedProcessingError> {
         self.calls
             .lock()
@@ -192,6 +266,8 @@ impl DerivedExecutionPlanner for ProxyPlanner {
                     upload_id: "up-1".to_string(),
                     part_number: 1,
                     chunk_path: std::path::PathBuf::from("/tmp/up-1.bin"),
+                    byte_range: None,
+                    sha256: None,
                 }],
                 complete: DerivedUploadComplete {
                     asset_uuid: claimed.asset_uuid.clone(),
@@ -307,6 +383,21 @@ impl DerivedProcessingGateway for ExtractFactsGateway {
         Ok(())
     }
 
+    fn fail_job(
+        &self,
+        job_id: &str,
+        _lock_token: &str,
+        _fencing_token: i32,
+        _idempotency_key: &str,
+        failure: &DerivedJobFailure,
+    ) -> Result<(), DerivedProcessingError> {
+        self.calls
+            .lock()
+            .expect("calls")
+            .push(format!("fail:{job_id}:{}", failure.error_code));
+        Ok(())
+    }
+
     fn upload_init(&self, _request: &DerivedUploadInit) -> Result<(), DerivedProcessingError> {
         self.calls
             .lock()
@@ -529,6 +620,21 @@ impl DerivedProcessingGateway for WaveformGateway {
         Ok(())
     }
 
+    fn fail_job(
+        &self,
+        job_id: &str,
+        _lock_token: &str,
+        _fencing_token: i32,
+        _idempotency_key: &str,
+        failure: &DerivedJobFailure,
+    ) -> Result<(), DerivedProcessingError> {
+        self.calls
+            .lock()
+            .expect("calls")
+            .push(format!("fail:{job_id}:{}", failure.error_code));
+        Ok(())
+    }
+
     fn upload_init(&self, request: &DerivedUploadInit) -> Result<(), DerivedProcessingError> {
         self.calls
             .lock()
@@ -587,6 +693,8 @@ impl DerivedExecutionPlanner for ValidWaveformPlanner {
                     upload_id: "up-wave-1".to_string(),
                     part_number: 1,
                     chunk_path: std::path::PathBuf::from("/tmp/up-wave-1.bin"),
+                    byte_range: None,
+                    sha256: None,
                 }],
                 complete: DerivedUploadComplete {
                     asset_uuid: claimed.asset_uuid.clone(),
@@ -681,7 +789,6 @@ fn tdd_execute_derived_job_once_runs_claim_heartbeat_upload_submit_flow() {
             "fetch_revision_etag:asset-1".to_string(),
             "heartbeat:job-1".to_string(),
             "upload_init:asset-1".to_string(),
-            "heartbeat:job-1".to_string(),
             "upload_part:1".to_string(),
             "heartbeat:job-1".to_string(),
             "upload_complete:asset-1".to_string(),
@@ -777,6 +884,156 @@ fn tdd_execute_derived_job_once_rejects_waveform_job_without_waveform_output() {
     );
 }
 
+#[test]
+fn tdd_execute_derived_job_once_reports_non_retryable_failure_when_plan_is_invalid() {
+    let gateway = MemoryGateway::default();
+
+    let err = execute_derived_job_once(&gateway, &MissingIdempotencyPlanner, "job-1")
+        .expect_err("missing key should fail");
+    assert!(!err.is_retryable());
+    assert_eq!(
+        gateway.calls().last().cloned(),
+        Some("fail:job-1:MISSING_SUBMIT_IDEMPOTENCY_KEY".to_string())
+    );
+}
+
+#[test]
+fn tdd_execute_derived_job_once_scopes_fail_idempotency_key_to_the_claim() {
+    let gateway = MemoryGateway::default();
+
+    execute_derived_job_once(&gateway, &MissingIdempotencyPlanner, "job-1")
+        .expect_err("missing key should fail");
+
+    assert_eq!(
+        *gateway.fail_idempotency_keys.lock().expect("fail keys"),
+        vec!["agent-fail-job-1-lock-1".to_string()]
+    );
+}
+
+#[test]
+fn tdd_execute_derived_job_once_does_not_send_fail_after_losing_the_lease() {
+    for lost in [
+        DerivedProcessingError::LockInvalid,
+        DerivedProcessingError::StaleLockToken,
+    ] {
+        let gateway = MemoryGateway {
+            heartbeat_error: Some(lost.clone()),
+            ..MemoryGateway::default()
+        };
+
+        let err = execute_derived_job_once(&gateway, &ProxyPlanner, "job-1")
+            .expect_err("lost lease must stop the job");
+
+        assert_eq!(err, DerivedJobExecutorError::Gateway(lost));
+        assert!(
+            !gateway.calls().iter().any(|call| call.starts_with("fail:")),
+            "unexpected fail call: {:?}",
+            gateway.calls()
+        );
+    }
+}
+
+#[test]
+fn tdd_execute_derived_job_once_reports_retryable_failure_when_source_staging_fails() {
+    let settings = AgentRuntimeConfig {
+        core_api_url: "https://core.retaia.local".to_string(),
+        ollama_url: "http://127.0.0.1:11434".to_string(),
+        auth_mode: AuthMode::Interactive,
+        technical_auth: None,
+        storage_mounts: std::collections::BTreeMap::new(),
+        max_parallel_jobs: 1,
+        log_level: LogLevel::Info,
+        job_selection: JobSelectionStrategy::default(),
+        upload_part_size_bytes: None,
+    };
+
+    let gateway = MemoryGateway::default();
+    let err =
+        execute_derived_job_once_with_source_staging(&gateway, &ProxyPlanner, "job-1", &settings)
+            .expect_err("missing mapping must fail");
+    assert!(err.is_retryable());
+    assert!(
+        !gateway
+            .calls()
+            .iter()
+            .any(|call| call.starts_with("submit:"))
+    );
+    assert_eq!(
+        gateway.calls().last().cloned(),
+        Some("fail:job-1:SOURCE_PATH_UNRESOLVED".to_string())
+    );
+}
+
+#[test]
+fn tdd_derived_job_executor_error_maps_to_stable_failure_classification() {
+    let cases = vec![
+        (
+            DerivedJobExecutorError::Gateway(DerivedProcessingError::Transport("reset".into())),
+            "CORE_TRANSPORT",
+            true,
+        ),
+        (
+            DerivedJobExecutorError::Gateway(DerivedProcessingError::UnexpectedStatus(503)),
+            "CORE_UNEXPECTED_STATUS",
+            true,
+        ),
+        (
+            DerivedJobExecutorError::Gateway(DerivedProcessingError::UnexpectedStatus(400)),
+            "CORE_UNEXPECTED_STATUS",
+            false,
+        ),
+        (
+            DerivedJobExecutorError::SourceStaging(
+                retaia_agent::SourceStagingError::InsufficientDiskSpace {
+                    required_bytes: 10,
+                    available_bytes: 1,
+                },
+            ),
+            "INSUFFICIENT_DISK_SPACE",
+            true,
+        ),
+        (
+            DerivedJobExecutorError::SourceStaging(
+                retaia_agent::SourceStagingError::SourceNotFile("dir".into()),
+            ),
+            "SOURCE_NOT_FILE",
+            false,
+        ),
+        (
+            DerivedJobExecutorError::ProxyGeneration(ProxyGenerationError::CommandFailed {
+                status_code: Some(1),
+                stderr: "invalid data".into(),
+            }),
+            "PROXY_COMMAND_FAILED",
+            false,
+        ),
+        (
+            DerivedJobExecutorError::ProxyGeneration(ProxyGenerationError::Process("spawn".into())),
+            "PROXY_PROCESS_FAILED",
+            true,
+        ),
+        (
+            DerivedJobExecutorError::ProxyGeneration(ProxyGenerationError::DecodeFailed(
+                "corrupt source".into(),
+            )),
+            "PROXY_DECODE_FAILED",
+            false,
+        ),
+        (
+            DerivedJobExecutorError::Planner("boom".into()),
+            "PLANNER_FAILED",
+            false,
+        ),
+    ];
+
+    for (error, code, retryable) in cases {
+        let failure = error.job_failure();
+        assert_eq!(failure.error_code, code);
+        assert_eq!(failure.retryable, retryable, "{code}");
+        assert_eq!(failure.message, Some(error.to_string()));
+    }
+}
+
 #[test]
 fn tdd_execute_derived_job_once_with_source_staging_copies_source_before_processing() {
     let source_root = tempfile::tempdir().expect("source root");
@@ -798,6 +1055,8 @@ fn tdd_execute_derived_job_once_with_source_staging_copies_source_before_process
         storage_mounts,
         max_parallel_jobs: 1,
         log_level: LogLevel::Info,
+        job_selection: JobSelectionStrategy::default(),
+        upload_part_size_bytes: None,
     };
 
     let gateway = MemoryGateway::default();
@@ -817,6 +1076,8 @@ fn tdd_execute_derived_job_once_with_source_staging_fails_explicitly_when_mappin
         storage_mounts: std::collections::BTreeMap::new(),
         max_parallel_jobs: 1,
         log_level: LogLevel::Info,
+        job_selection: JobSelectionStrategy::default(),
+        upload_part_size_bytes: None,
     };
 
     let gateway = MemoryGateway::default();
@@ -847,6 +1108,8 @@ fn tdd_execute_derived_job_once_with_runtime_planner_emits_upload_calls_with_sta
         storage_mounts,
         max_parallel_jobs: 1,
         log_level: LogLevel::Info,
+        job_selection: JobSelectionStrategy::default(),
+        upload_part_size_bytes: None,
     };
 
     let gateway = MemoryGateway::default();
@@ -868,6 +1131,74 @@ fn tdd_execute_derived_job_once_with_runtime_planner_emits_upload_calls_with_sta
     );
 }
 
+#[test]
+fn tdd_execute_derived_job_once_with_progress_maps_generator_progress_into_processing_stage() {
+    let source_root = tempfile::tempdir().expect("source root");
+    write_storage_marker(source_root.path(), "nas-main");
+    let source_path = source_root.path().join("INBOX/sample-source.bin");
+    std::fs::create_dir_all(source_path.parent().expect("parent")).expect("mkdir");
+    std::fs::write(&source_path, b"source-bytes").expect("write source");
+
+    let mut storage_mounts = std::collections::BTreeMap::new();
+    storage_mounts.insert(
+        "nas-main".to_string(),
+        source_root.path().display().to_string(),
+    );
+    let settings = AgentRuntimeConfig {
+        core_api_url: "https://core.retaia.local".to_string(),
+        ollama_url: "http://127.0.0.1:11434".to_string(),
+        auth_mode: AuthMode::Interactive,
+        technical_auth: None,
+        storage_mounts,
+        max_parallel_jobs: 1,
+        log_level: LogLevel::Info,
+        job_selection: JobSelectionStrategy::default(),
+        upload_part_size_bytes: None,
+    };
+
+    let gateway = MemoryGateway::default();
+    let planner = RuntimeDerivedPlanner::new(
+        Arc::new(ProgressReportingGenerator),
+        Arc::new(WritingPreviewGenerator),
+    );
+    let sink = RecordingProgressSink::default();
+    execute_derived_job_once_with_progress(
+        &gateway,
+        &planner,
+        "job-1",
+        &settings,
+        &sink,
+        &NoopDerivedJobJournal,
+        &CancellationToken::new(),
+    )
+    .expect("flow with progress");
+
+    let processing = sink
+        .events
+        .lock()
+        .expect("events")
+        .iter()
+        .filter_map(|event| match event {
+            RuntimeStatusEvent::JobProgress {
+                progress_percent,
+                stage: JobStage::Processing,
+                short_status,
+                ..
+            } => Some((*progress_percent, short_status.clone())),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        processing,
+        vec![
+            (5, "staging source".to_string()),
+            (10, "processing".to_string()),
+            (35, "processing 50%".to_string()),
+            (60, "processing 100%".to_string()),
+        ]
+    );
+}
+
 #[test]
 fn tdd_execute_derived_job_once_with_runtime_planner_supports_extract_facts_without_upload_calls() {
     let source_root = tempfile::tempdir().expect("source root");
@@ -894,6 +1225,8 @@ fn tdd_execute_derived_job_once_with_runtime_planner_supports_extract_facts_with
         storage_mounts,
         max_parallel_jobs: 1,
         log_level: LogLevel::Info,
+        job_selection: JobSelectionStrategy::default(),
+        upload_part_size_bytes: None,
     };
 
     let gateway = ExtractFactsGateway::default();
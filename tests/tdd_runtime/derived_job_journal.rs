@@ -0,0 +1,394 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use retaia_agent::{
+    AgentRuntimeConfig, AuthMode, CancellationToken, ClaimedDerivedJob, DerivedExecutionPlan,
+    DerivedExecutionPlanner, DerivedJobExecutorError, DerivedJobFailure, DerivedJobJournal,
+    DerivedJobJournalEntry, DerivedJobJournalError, DerivedJobJournalStage, DerivedJobType,
+    DerivedKind, DerivedManifestItem, DerivedProcessingError, DerivedProcessingGateway,
+    DerivedUploadComplete, DerivedUploadInit, DerivedUploadPart, DerivedUploadPlan,
+    HeartbeatReceipt, JobSelectionStrategy, LogLevel, RuntimeStatusTracker,
+    SqliteDerivedJobJournal, SubmitDerivedPayload, UploadedDerivedPart, attach_upload_integrity,
+    execute_derived_job_once_with_progress, split_upload_parts,
+};
+
+#[derive(Default)]
+struct MemoryJournal {
+    entries: Mutex<BTreeMap<String, DerivedJobJournalEntry>>,
+}
+
+impl DerivedJobJournal for MemoryJournal {
+    fn load(&self, job_id: &str) -> Result<Option<DerivedJobJournalEntry>, DerivedJobJournalError> {
+        Ok(self.entries.lock().expect("entries").get(job_id).cloned())
+    }
+
+    fn save(&self, entry: &DerivedJobJournalEntry) -> Result<(), DerivedJobJournalError> {
+        self.entries
+            .lock()
+            .expect("entries")
+            .insert(entry.claimed.job_id.clone(), entry.clone());
+        Ok(())
+    }
+
+    fn remove(&self, job_id: &str) -> Result<(), DerivedJobJournalError> {
+        self.entries.lock().expect("entries").remove(job_id);
+        Ok(())
+    }
+
+    fn pending_job_ids(&self) -> Result<Vec<String>, DerivedJobJournalError> {
+        Ok(self
+            .entries
+            .lock()
+            .expect("entries")
+            .keys()
+            .cloned()
+            .collect())
+    }
+}
+
+struct ResumableGateway {
+    calls: Mutex<Vec<String>>,
+    stale_lock_token: Option<&'static str>,
+    completed_parts: Mutex<Option<Vec<UploadedDerivedPart>>>,
+}
+
+impl ResumableGateway {
+    fn new(stale_lock_token: Option<&'static str>) -> Self {
+        Self {
+            calls: Mutex::new(Vec::new()),
+            stale_lock_token,
+            completed_parts: Mutex::new(None),
+        }
+    }
+
+    fn calls(&self) -> Vec<String> {
+        self.calls.lock().expect("calls").clone()
+    }
+
+    fn record(&self, call: String) {
+        self.calls.lock().expect("calls").push(call);
+    }
+}
+
+impl DerivedProcessingGateway for ResumableGateway {
+    fn claim_job(&self, job_id: &str) -> Result<ClaimedDerivedJob, DerivedProcessingError> {
+        self.record(format!("claim:{job_id}"));
+        Ok(claimed_job(job_id, "lock-fresh", 7))
+    }
+
+    fn fetch_asset_revision_etag(
+        &self,
+        _asset_uuid: &str,
+    ) -> Result<String, DerivedProcessingError> {
+        Ok("\"asset-rev-1\"".to_string())
+    }
+
+    fn heartbeat(
+        &self,
+        _job_id: &str,
+        lock_token: &str,
+        fencing_token: i32,
+    ) -> Result<HeartbeatReceipt, DerivedProcessingError> {
+        if self.stale_lock_token == Some(lock_token) {
+            return Err(DerivedProcessingError::StaleLockToken);
+        }
+        Ok(HeartbeatReceipt {
+            locked_until: None,
+            fencing_token,
+        })
+    }
+
+    fn submit_derived(
+        &self,
+        job_id: &str,
+        lock_token: &str,
+        _fencing_token: i32,
+        _idempotency_key: &str,
+        _payload: &SubmitDerivedPayload,
+    ) -> Result<(), DerivedProcessingError> {
+        self.record(format!("submit:{job_id}:{lock_token}"));
+        Ok(())
+    }
+
+    fn fail_job(
+        &self,
+        job_id: &str,
+        _lock_token: &str,
+        _fencing_token: i32,
+        _idempotency_key: &str,
+        failure: &DerivedJobFailure,
+    ) -> Result<(), DerivedProcessingError> {
+        self.record(format!("fail:{job_id}:{}", failure.error_code));
+        Ok(())
+    }
+
+    fn upload_init(&self, _request: &DerivedUploadInit) -> Result<(), DerivedProcessingError> {
+        self.record("upload_init".to_string());
+        Ok(())
+    }
+
+    fn upload_part(
+        &self,
+        request: &DerivedUploadPart,
+    ) -> Result<UploadedDerivedPart, DerivedProcessingError> {
+        self.record(format!("upload_part:{}", request.part_number));
+        Ok(UploadedDerivedPart {
+            part_number: request.part_number,
+            part_etag: format!("etag-{}", request.part_number),
+        })
+    }
+
+    fn upload_complete(
+        &self,
+        request: &DerivedUploadComplete,
+    ) -> Result<(), DerivedProcessingError> {
+        self.record("upload_complete".to_string());
+        *self.completed_parts.lock().expect("parts") = request.parts.clone();
+        Ok(())
+    }
+}
+
+struct ProxyPlanner {
+    artifact: PathBuf,
+    planned: Mutex<usize>,
+}
+
+impl DerivedExecutionPlanner for ProxyPlanner {
+    fn plan_for_claimed_job(
+        &self,
+        claimed: &ClaimedDerivedJob,
+    ) -> Result<DerivedExecutionPlan, DerivedJobExecutorError> {
+        *self.planned.lock().expect("planned") += 1;
+        Ok(proxy_plan(claimed, &self.artifact))
+    }
+}
+
+fn claimed_job(job_id: &str, lock_token: &str, fencing_token: i32) -> ClaimedDerivedJob {
+    ClaimedDerivedJob {
+        job_id: job_id.to_string(),
+        asset_uuid: "asset-1".to_string(),
+        lock_token: lock_token.to_string(),
+        fencing_token,
+        job_type: DerivedJobType::GeneratePreview,
+        source_storage_id: "nas-main".to_string(),
+        source_original_relative: "INBOX/source.mov".to_string(),
+        source_sidecars_relative: Vec::new(),
+    }
+}
+
+fn proxy_plan(claimed: &ClaimedDerivedJob, artifact: &Path) -> DerivedExecutionPlan {
+    let size_bytes = std::fs::metadata(artifact).expect("artifact").len();
+    let mut upload = DerivedUploadPlan {
+        init: DerivedUploadInit {
+            asset_uuid: claimed.asset_uuid.clone(),
+            revision_etag: String::new(),
+            kind: DerivedKind::PreviewVideo,
+            content_type: "video/mp4".to_string(),
+            size_bytes,
+            sha256: None,
+            idempotency_key: "idem-init".to_string(),
+        },
+        parts: split_upload_parts(&claimed.asset_uuid, "up-1", artifact, size_bytes, 4),
+        complete: DerivedUploadComplete {
+            asset_uuid: claimed.asset_uuid.clone(),
+            revision_etag: String::new(),
+            upload_id: "up-1".to_string(),
+            idempotency_key: "idem-complete".to_string(),
+            parts: None,
+        },
+    };
+    let sha256 = attach_upload_integrity(&mut upload, artifact).expect("integrity");
+    DerivedExecutionPlan {
+        uploads: vec![upload],
+        submit: SubmitDerivedPayload {
+            job_type: DerivedJobType::GeneratePreview,
+            manifest: vec![DerivedManifestItem {
+                kind: DerivedKind::PreviewVideo,
+                reference: "s3://derived/proxy.mp4".to_string(),
+                size_bytes: Some(size_bytes),
+                sha256: Some(sha256),
+            }],
+            facts_patch: None,
+            transcript_patch: None,
+            warnings: None,
+            metrics: None,
+        },
+        submit_idempotency_key: "idem-submit".to_string(),
+    }
+}
+
+fn settings_with_source(root: &Path) -> AgentRuntimeConfig {
+    let marker = r#"{"version":1,"storage_id":"nas-main","paths":{"inbox":"INBOX","archive":"ARCHIVE","rejects":"REJECTS"}}"#;
+    std::fs::write(root.join(".retaia"), marker).expect("write marker");
+    std::fs::create_dir_all(root.join("INBOX")).expect("inbox");
+    std::fs::write(root.join("INBOX/source.mov"), b"source").expect("source");
+    let mut storage_mounts = BTreeMap::new();
+    storage_mounts.insert("nas-main".to_string(), root.display().to_string());
+    AgentRuntimeConfig {
+        core_api_url: "http://localhost:3000/api/v1".to_string(),
+        ollama_url: "http://localhost:11434".to_string(),
+        auth_mode: AuthMode::Interactive,
+        technical_auth: None,
+        storage_mounts,
+        max_parallel_jobs: 1,
+        log_level: LogLevel::Info,
+        job_selection: JobSelectionStrategy::default(),
+        upload_part_size_bytes: None,
+    }
+}
+
+fn journaled_entry_with_first_part(artifact: &Path, staging_dir: &Path) -> DerivedJobJournalEntry {
+    let claimed = claimed_job("job-1", "lock-previous", 3);
+    let plan = proxy_plan(&claimed, artifact);
+    let mut entry = DerivedJobJournalEntry::claimed(&claimed);
+    entry.record_plan(&plan, Some(staging_dir.to_path_buf()));
+    let first = &plan.uploads[0].parts[0];
+    entry.record_part(
+        "up-1",
+        &UploadedDerivedPart {
+            part_number: 1,
+            part_etag: "etag-journaled-1".to_string(),
+        },
+        first.sha256.as_deref(),
+    );
+    entry
+}
+
+#[test]
+fn tdd_sqlite_derived_job_journal_roundtrips_entries_and_lists_pending_jobs() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let artifact = dir.path().join("proxy.mp4");
+    std::fs::write(&artifact, b"0123456789").expect("artifact");
+    let journal = SqliteDerivedJobJournal::open_at_path(&dir.path().join("daemon-history.sqlite3"))
+        .expect("journal");
+    let entry = journaled_entry_with_first_part(&artifact, dir.path());
+
+    journal.save(&entry).expect("save");
+    journal
+        .save(&DerivedJobJournalEntry::claimed(&claimed_job(
+            "job-2", "lock-2", 1,
+        )))
+        .expect("save second");
+
+    let loaded = journal.load("job-1").expect("load").expect("entry");
+    assert_eq!(loaded, entry);
+    assert_eq!(loaded.stage, DerivedJobJournalStage::Uploading);
+    assert_eq!(
+        journal.pending_job_ids().expect("pending"),
+        vec!["job-1".to_string(), "job-2".to_string()]
+    );
+
+    journal.remove("job-1").expect("remove");
+    assert_eq!(journal.load("job-1").expect("load"), None);
+    assert_eq!(
+        journal.pending_job_ids().expect("pending"),
+        vec!["job-2".to_string()]
+    );
+}
+
+#[test]
+fn tdd_execute_derived_job_resumes_journaled_job_without_reclaiming_or_reuploading_parts() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let staging_dir = dir.path().join("staging");
+    std::fs::create_dir_all(&staging_dir).expect("staging");
+    let artifact = staging_dir.join("proxy.mp4");
+    std::fs::write(&artifact, b"0123456789").expect("artifact");
+    let source_root = tempfile::tempdir().expect("source root");
+    let settings = settings_with_source(source_root.path());
+    let journal = MemoryJournal::default();
+    journal
+        .save(&journaled_entry_with_first_part(&artifact, &staging_dir))
+        .expect("seed journal");
+    let gateway = ResumableGateway::new(None);
+    let planner = ProxyPlanner {
+        artifact: artifact.clone(),
+        planned: Mutex::new(0),
+    };
+    let tracker = Mutex::new(RuntimeStatusTracker::new());
+
+    let report = execute_derived_job_once_with_progress(
+        &gateway,
+        &planner,
+        "job-1",
+        &settings,
+        &tracker,
+        &journal,
+        &CancellationToken::new(),
+    )
+    .expect("resume");
+
+    assert_eq!(report.upload_count, 1);
+    assert_eq!(*planner.planned.lock().expect("planned"), 0);
+    let calls = gateway.calls();
+    assert!(!calls.iter().any(|call| call.starts_with("claim:")));
+    assert_eq!(
+        calls
+            .iter()
+            .filter(|call| call.starts_with("upload_part:"))
+            .cloned()
+            .collect::<Vec<_>>(),
+        vec!["upload_part:2".to_string(), "upload_part:3".to_string()]
+    );
+    assert_eq!(
+        gateway
+            .completed_parts
+            .lock()
+            .expect("parts")
+            .as_ref()
+            .map(|parts| parts[0].part_etag.clone()),
+        Some("etag-journaled-1".to_string())
+    );
+    assert_eq!(
+        calls.last(),
+        Some(&"submit:job-1:lock-previous".to_string())
+    );
+    assert!(journal.pending_job_ids().expect("pending").is_empty());
+    assert!(!staging_dir.exists());
+}
+
+#[test]
+fn tdd_execute_derived_job_claims_fresh_when_journaled_lease_was_lost() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let staging_dir = dir.path().join("staging");
+    std::fs::create_dir_all(&staging_dir).expect("staging");
+    let artifact = dir.path().join("proxy.mp4");
+    std::fs::write(&artifact, b"0123456789").expect("artifact");
+    let source_root = tempfile::tempdir().expect("source root");
+    let settings = settings_with_source(source_root.path());
+    let journal = MemoryJournal::default();
+    journal
+        .save(&journaled_entry_with_first_part(&artifact, &staging_dir))
+        .expect("seed journal");
+    let gateway = ResumableGateway::new(Some("lock-previous"));
+    let planner = ProxyPlanner {
+        artifact,
+        planned: Mutex::new(0),
+    };
+    let tracker = Mutex::new(RuntimeStatusTracker::new());
+
+    execute_derived_job_once_with_progress(
+        &gateway,
+        &planner,
+        "job-1",
+        &settings,
+        &tracker,
+        &journal,
+        &CancellationToken::new(),
+    )
+    .expect("fresh run");
+
+    let calls = gateway.calls();
+    assert_eq!(calls.first(), Some(&"claim:job-1".to_string()));
+    assert_eq!(*planner.planned.lock().expect("planned"), 1);
+    assert_eq!(
+        calls
+            .iter()
+            .filter(|call| call.starts_with("upload_part:"))
+            .count(),
+        3
+    );
+    assert_eq!(calls.last(), Some(&"submit:job-1:lock-fresh".to_string()));
+    assert!(journal.pending_job_ids().expect("pending").is_empty());
+    assert!(!staging_dir.exists());
+}
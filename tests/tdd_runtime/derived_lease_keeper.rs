@@ -0,0 +1,543 @@
+use std::sync::Mutex;
+use std::time::Duration;
+
+use chrono::{TimeZone, Utc};
+use retaia_agent::{
+    CancellationReason, CancellationToken, ClaimedDerivedJob, DerivedExecutionPlan,
+    DerivedExecutionPlanner, DerivedJobExecutorError, DerivedJobFailure, DerivedJobLease,
+    DerivedJobType, DerivedKind, DerivedManifestItem, DerivedProcessingError,
+    DerivedProcessingGateway, DerivedUploadComplete, DerivedUploadInit, DerivedUploadPart,
+    DerivedUploadPlan, FactsPatchPayload, HeartbeatReceipt, LeaseKeeperPolicy,
+    ProxyGenerationError, ProxyProgressSink, SubmitDerivedPayload, UploadedDerivedPart,
+    execute_derived_job_once, is_lease_lost,
+};
+
+struct LeaseGateway {
+    calls: Mutex<Vec<String>>,
+    heartbeats: Mutex<Vec<Result<HeartbeatReceipt, DerivedProcessingError>>>,
+    part_upload_time: Duration,
+}
+
+impl LeaseGateway {
+    fn new(heartbeats: Vec<Result<HeartbeatReceipt, DerivedProcessingError>>) -> Self {
+        Self {
+            calls: Mutex::new(Vec::new()),
+            heartbeats: Mutex::new(heartbeats),
+            part_upload_time: Duration::ZERO,
+        }
+    }
+
+    fn with_part_upload_time(mut self, part_upload_time: Duration) -> Self {
+        self.part_upload_time = part_upload_time;
+        self
+    }
+
+    fn calls(&self) -> Vec<String> {
+        self.calls.lock().expect("calls").clone()
+    }
+
+    fn heartbeat_count(&self) -> usize {
+        self.calls()
+            .iter()
+            .filter(|call| call.starts_with("heartbeat:"))
+            .count()
+    }
+}
+
+impl DerivedProcessingGateway for LeaseGateway {
+    fn claim_job(&self, job_id: &str) -> Result<ClaimedDerivedJob, DerivedProcessingError> {
+        self.calls
+            .lock()
+            .expect("calls")
+            .push(format!("claim:{job_id}"));
+        Ok(claimed_job(job_id))
+    }
+
+    fn fetch_asset_revision_etag(
+        &self,
+        _asset_uuid: &str,
+    ) -> Result<String, DerivedProcessingError> {
+        Ok("\"asset-rev-1\"".to_string())
+    }
+
+    fn heartbeat(
+        &self,
+        job_id: &str,
+        _lock_token: &str,
+        fencing_token: i32,
+    ) -> Result<HeartbeatReceipt, DerivedProcessingError> {
+        self.calls
+            .lock()
+            .expect("calls")
+            .push(format!("heartbeat:{job_id}:{fencing_token}"));
+        let mut heartbeats = self.heartbeats.lock().expect("heartbeats");
+        if heartbeats.is_empty() {
+            return Ok(HeartbeatReceipt {
+                locked_until: None,
+                fencing_token,
+            });
+        }
+        heartbeats.remove(0)
+    }
+
+    fn submit_derived(
+        &self,
+        job_id: &str,
+        _lock_token: &str,
+        fencing_token: i32,
+        _idempotency_key: &str,
+        _payload: &SubmitDerivedPayload,
+    ) -> Result<(), DerivedProcessingError> {
+        self.calls
+            .lock()
+            .expect("calls")
+            .push(format!("submit:{job_id}:{fencing_token}"));
+        Ok(())
+    }
+
+    fn fail_job(
+        &self,
+        job_id: &str,
+        _lock_token: &str,
+        _fencing_token: i32,
+        _idempotency_key: &str,
+        failure: &DerivedJobFailure,
+    ) -> Result<(), DerivedProcessingError> {
+        self.calls
+            .lock()
+            .expect("calls")
+            .push(format!("fail:{job_id}:{}", failure.error_code));
+        Ok(())
+    }
+
+    fn upload_init(&self, _request: &DerivedUploadInit) -> Result<(), DerivedProcessingError> {
+        Ok(())
+    }
+
+    fn upload_part(
+        &self,
+        request: &DerivedUploadPart,
+    ) -> Result<UploadedDerivedPart, DerivedProcessingError> {
+        self.calls
+            .lock()
+            .expect("calls")
+            .push(format!("upload_part:{}", request.part_number));
+        std::thread::sleep(self.part_upload_time);
+        Ok(UploadedDerivedPart {
+            part_number: request.part_number,
+            part_etag: format!("etag-{}", request.part_number),
+        })
+    }
+
+    fn upload_complete(
+        &self,
+        _request: &DerivedUploadComplete,
+    ) -> Result<(), DerivedProcessingError> {
+        Ok(())
+    }
+}
+
+struct SlowFactsPlanner {
+    duration: Duration,
+}
+
+impl DerivedExecutionPlanner for SlowFactsPlanner {
+    fn plan_for_claimed_job(
+        &self,
+        claimed: &ClaimedDerivedJob,
+    ) -> Result<DerivedExecutionPlan, DerivedJobExecutorError> {
+        std::thread::sleep(self.duration);
+        Ok(DerivedExecutionPlan {
+            uploads: Vec::new(),
+            submit: SubmitDerivedPayload {
+                job_type: claimed.job_type,
+                manifest: Vec::new(),
+                facts_patch: Some(FactsPatchPayload::default()),
+                transcript_patch: None,
+                warnings: None,
+                metrics: None,
+            },
+            submit_idempotency_key: format!("agent-submit-{}", claimed.job_id),
+        })
+    }
+}
+
+struct TwoPartUploadPlanner;
+
+impl DerivedExecutionPlanner for TwoPartUploadPlanner {
+    fn plan_for_claimed_job(
+        &self,
+        claimed: &ClaimedDerivedJob,
+    ) -> Result<DerivedExecutionPlan, DerivedJobExecutorError> {
+        let part = |part_number| DerivedUploadPart {
+            asset_uuid: claimed.asset_uuid.clone(),
+            revision_etag: String::new(),
+            upload_id: "up-1".to_string(),
+            part_number,
+            chunk_path: std::path::PathBuf::from("/tmp/up-1.bin"),
+            byte_range: None,
+            sha256: None,
+        };
+        Ok(DerivedExecutionPlan {
+            uploads: vec![DerivedUploadPlan {
+                init: DerivedUploadInit {
+                    asset_uuid: claimed.asset_uuid.clone(),
+                    revision_etag: String::new(),
+                    kind: DerivedKind::PreviewVideo,
+                    content_type: "video/mp4".to_string(),
+                    size_bytes: 2048,
+                    sha256: None,
+                    idempotency_key: "idem-init".to_string(),
+                },
+                parts: vec![part(1), part(2)],
+                complete: DerivedUploadComplete {
+                    asset_uuid: claimed.asset_uuid.clone(),
+                    revision_etag: String::new(),
+                    upload_id: "up-1".to_string(),
+                    idempotency_key: "idem-complete".to_string(),
+                    parts: None,
+                },
+            }],
+            submit: SubmitDerivedPayload {
+                job_type: claimed.job_type,
+                manifest: vec![DerivedManifestItem {
+                    kind: DerivedKind::PreviewVideo,
+                    reference: "s3://derived/proxy.mp4".to_string(),
+                    size_bytes: Some(2048),
+                    sha256: None,
+                }],
+                facts_patch: Some(FactsPatchPayload::default()),
+                transcript_patch: None,
+                warnings: None,
+                metrics: None,
+            },
+            submit_idempotency_key: format!("agent-submit-{}", claimed.job_id),
+        })
+    }
+}
+
+struct CancellableProxyPlanner;
+
+impl DerivedExecutionPlanner for CancellableProxyPlanner {
+    fn plan_for_claimed_job(
+        &self,
+        _claimed: &ClaimedDerivedJob,
+    ) -> Result<DerivedExecutionPlan, DerivedJobExecutorError> {
+        Err(DerivedJobExecutorError::Planner(
+            "progress-aware planning expected".to_string(),
+        ))
+    }
+
+    fn plan_for_claimed_job_with_progress(
+        &self,
+        _claimed: &ClaimedDerivedJob,
+        _staged_source_path: Option<&std::path::Path>,
+        _staged_sidecar_paths: &[std::path::PathBuf],
+        progress: &dyn ProxyProgressSink,
+    ) -> Result<DerivedExecutionPlan, DerivedJobExecutorError> {
+        let cancellation = progress.cancellation().expect("cancellation token");
+        let deadline = std::time::Instant::now() + Duration::from_secs(5);
+        while !cancellation.is_cancelled() {
+            assert!(std::time::Instant::now() < deadline, "never cancelled");
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        Err(DerivedJobExecutorError::ProxyGeneration(
+            ProxyGenerationError::Cancelled,
+        ))
+    }
+}
+
+fn claimed_job(job_id: &str) -> ClaimedDerivedJob {
+    ClaimedDerivedJob {
+        job_id: job_id.to_string(),
+        asset_uuid: "asset-lease".to_string(),
+        lock_token: "lock-lease".to_string(),
+        fencing_token: 1,
+        job_type: DerivedJobType::ExtractFacts,
+        source_storage_id: "nas-main".to_string(),
+        source_original_relative: "INBOX/source.mov".to_string(),
+        source_sidecars_relative: Vec::new(),
+    }
+}
+
+fn fast_policy() -> LeaseKeeperPolicy {
+    LeaseKeeperPolicy {
+        fallback_interval: Duration::from_millis(20),
+        min_interval: Duration::from_millis(10),
+        max_interval: Duration::from_millis(50),
+    }
+}
+
+fn locked_until_in(duration: Duration) -> Option<String> {
+    let deadline = Utc::now() + chrono::Duration::from_std(duration).expect("duration");
+    Some(deadline.to_rfc3339())
+}
+
+#[test]
+fn tdd_lease_keeper_policy_renews_at_half_of_remaining_lease_within_bounds() {
+    let policy = LeaseKeeperPolicy::default();
+    let now = Utc.with_ymd_and_hms(2026, 1, 1, 12, 0, 0).unwrap();
+
+    assert_eq!(
+        policy.next_renewal_delay(Some("2026-01-01T12:01:00Z"), now),
+        Duration::from_secs(30)
+    );
+    assert_eq!(
+        policy.next_renewal_delay(Some("2026-01-01T13:00:00Z"), now),
+        Duration::from_secs(60)
+    );
+    assert_eq!(
+        policy.next_renewal_delay(Some("2026-01-01T11:59:00Z"), now),
+        Duration::from_secs(1)
+    );
+    assert_eq!(
+        policy.next_renewal_delay(Some("not-a-date"), now),
+        Duration::from_secs(30)
+    );
+    assert_eq!(
+        policy.next_renewal_delay(None, now),
+        Duration::from_secs(30)
+    );
+}
+
+#[test]
+fn tdd_lease_renew_updates_fencing_token_and_locked_until() {
+    let gateway = LeaseGateway::new(vec![Ok(HeartbeatReceipt {
+        locked_until: Some("2026-01-01T12:05:00Z".to_string()),
+        fencing_token: 7,
+    })]);
+    let lease = DerivedJobLease::new(&claimed_job("job-renew"), fast_policy());
+
+    lease.renew(&gateway).expect("renew");
+
+    assert_eq!(lease.fencing_token(), 7);
+    assert_eq!(
+        lease.locked_until(),
+        Some("2026-01-01T12:05:00Z".to_string())
+    );
+    assert_eq!(gateway.calls(), vec!["heartbeat:job-renew:1".to_string()]);
+}
+
+#[test]
+fn tdd_lease_keep_alive_heartbeats_on_its_own_schedule_until_stopped() {
+    let gateway = LeaseGateway::new(vec![
+        Ok(HeartbeatReceipt {
+            locked_until: locked_until_in(Duration::from_millis(40)),
+            fencing_token: 2,
+        }),
+        Ok(HeartbeatReceipt {
+            locked_until: locked_until_in(Duration::from_millis(40)),
+            fencing_token: 3,
+        }),
+    ]);
+    let lease = DerivedJobLease::new(&claimed_job("job-keep"), fast_policy());
+
+    std::thread::scope(|scope| {
+        scope.spawn(|| lease.keep_alive(&gateway));
+        std::thread::sleep(Duration::from_millis(200));
+        lease.stop();
+    });
+
+    assert!(gateway.heartbeat_count() >= 3);
+    assert!(gateway.calls().starts_with(&[
+        "heartbeat:job-keep:1".to_string(),
+        "heartbeat:job-keep:2".to_string()
+    ]));
+    assert_eq!(lease.fencing_token(), 3);
+    assert!(lease.ensure_held().is_ok());
+}
+
+#[test]
+fn tdd_lease_keep_alive_retries_transient_errors_and_stops_on_stale_lock_token() {
+    let gateway = LeaseGateway::new(vec![
+        Err(DerivedProcessingError::Transport(
+            "connection reset".to_string(),
+        )),
+        Err(DerivedProcessingError::StaleLockToken),
+    ]);
+    let lease = DerivedJobLease::new(&claimed_job("job-stale"), fast_policy());
+
+    lease.keep_alive(&gateway);
+
+    assert_eq!(gateway.heartbeat_count(), 2);
+    assert_eq!(
+        lease.lost_error(),
+        Some(DerivedProcessingError::StaleLockToken)
+    );
+    assert_eq!(
+        lease.renew(&gateway),
+        Err(DerivedProcessingError::StaleLockToken)
+    );
+    assert_eq!(gateway.heartbeat_count(), 2);
+}
+
+#[test]
+fn tdd_lease_keep_alive_backs_off_exponentially_on_repeated_transient_errors() {
+    let gateway = LeaseGateway::new(
+        (0..20)
+            .map(|_| Err(DerivedProcessingError::Transport("offline".to_string())))
+            .collect(),
+    );
+    let policy = LeaseKeeperPolicy {
+        fallback_interval: Duration::from_millis(10),
+        min_interval: Duration::from_millis(20),
+        max_interval: Duration::from_secs(5),
+    };
+    let lease = DerivedJobLease::new(&claimed_job("job-outage"), policy);
+
+    std::thread::scope(|scope| {
+        scope.spawn(|| lease.keep_alive(&gateway));
+        std::thread::sleep(Duration::from_millis(400));
+        lease.stop();
+    });
+
+    // 10ms, then +20/+40/+80/+160ms: a fixed 20ms retry would reach ~20 calls.
+    let count = gateway.heartbeat_count();
+    assert!((3..=6).contains(&count), "heartbeats: {count}");
+    assert!(lease.ensure_held().is_ok());
+}
+
+#[test]
+fn tdd_lease_keep_alive_retries_once_more_before_the_lease_expires() {
+    let mut heartbeats = vec![Ok(HeartbeatReceipt {
+        locked_until: locked_until_in(Duration::from_millis(250)),
+        fencing_token: 2,
+    })];
+    heartbeats
+        .extend((0..5).map(|_| Err(DerivedProcessingError::Transport("offline".to_string()))));
+    let gateway = LeaseGateway::new(heartbeats);
+    let policy = LeaseKeeperPolicy {
+        fallback_interval: Duration::from_millis(10),
+        min_interval: Duration::from_millis(100),
+        max_interval: Duration::from_secs(5),
+    };
+    let lease = DerivedJobLease::new(&claimed_job("job-expiry"), policy);
+
+    std::thread::scope(|scope| {
+        scope.spawn(|| lease.keep_alive(&gateway));
+        std::thread::sleep(Duration::from_millis(350));
+        lease.stop();
+    });
+
+    // 10ms ok, 130ms and 230ms fail, then the 200ms backoff is pulled in to
+    // the 250ms expiry instead of landing at 430ms.
+    assert_eq!(gateway.heartbeat_count(), 4);
+}
+
+#[test]
+fn tdd_lease_lost_cancels_the_attached_cancellation_token() {
+    let gateway = LeaseGateway::new(vec![Err(DerivedProcessingError::StaleLockToken)]);
+    let cancellation = CancellationToken::new();
+    let lease = DerivedJobLease::new(&claimed_job("job-cancel"), fast_policy())
+        .with_cancellation(cancellation.clone());
+
+    let _ = lease.renew(&gateway);
+
+    assert_eq!(cancellation.reason(), Some(CancellationReason::LeaseLost));
+}
+
+#[test]
+fn tdd_execute_derived_job_once_cancels_in_flight_processing_when_lease_is_lost() {
+    let gateway = LeaseGateway::new(vec![
+        Ok(HeartbeatReceipt {
+            locked_until: locked_until_in(Duration::from_secs(1)),
+            fencing_token: 2,
+        }),
+        Ok(HeartbeatReceipt {
+            locked_until: locked_until_in(Duration::from_secs(1)),
+            fencing_token: 3,
+        }),
+        Err(DerivedProcessingError::StaleLockToken),
+    ]);
+
+    let error = execute_derived_job_once(&gateway, &CancellableProxyPlanner, "job-kill")
+        .expect_err("lost lease must cancel processing");
+
+    assert_eq!(
+        error,
+        DerivedJobExecutorError::Gateway(DerivedProcessingError::StaleLockToken)
+    );
+    assert!(!gateway.calls().iter().any(|call| call.starts_with("fail:")));
+}
+
+#[test]
+fn tdd_is_lease_lost_only_matches_stale_or_invalid_lock() {
+    assert!(is_lease_lost(&DerivedProcessingError::StaleLockToken));
+    assert!(is_lease_lost(&DerivedProcessingError::LockInvalid));
+    assert!(!is_lease_lost(&DerivedProcessingError::LockRequired));
+    assert!(!is_lease_lost(&DerivedProcessingError::Throttled {
+        retry_after_ms: None
+    }));
+}
+
+#[test]
+fn tdd_execute_derived_job_once_heartbeats_during_long_planning_and_aborts_on_lost_lease() {
+    let gateway = LeaseGateway::new(vec![
+        Ok(HeartbeatReceipt {
+            locked_until: locked_until_in(Duration::from_secs(1)),
+            fencing_token: 2,
+        }),
+        Ok(HeartbeatReceipt {
+            locked_until: locked_until_in(Duration::from_secs(1)),
+            fencing_token: 3,
+        }),
+        Err(DerivedProcessingError::LockInvalid),
+    ]);
+    let planner = SlowFactsPlanner {
+        duration: Duration::from_millis(1_500),
+    };
+
+    let error = execute_derived_job_once(&gateway, &planner, "job-slow")
+        .expect_err("lost lease must abort the job");
+
+    assert_eq!(
+        error,
+        DerivedJobExecutorError::Gateway(DerivedProcessingError::LockInvalid)
+    );
+    assert_eq!(
+        gateway.calls(),
+        vec![
+            "claim:job-slow".to_string(),
+            "heartbeat:job-slow:1".to_string(),
+            "heartbeat:job-slow:2".to_string(),
+            "heartbeat:job-slow:3".to_string(),
+        ]
+    );
+}
+
+#[test]
+fn tdd_execute_derived_job_once_stops_uploading_parts_once_the_keeper_loses_the_lease() {
+    let held = || {
+        Ok(HeartbeatReceipt {
+            locked_until: locked_until_in(Duration::from_secs(1)),
+            fencing_token: 1,
+        })
+    };
+    let gateway = LeaseGateway::new(vec![
+        held(),
+        held(),
+        held(),
+        Err(DerivedProcessingError::StaleLockToken),
+    ])
+    .with_part_upload_time(Duration::from_millis(1_500));
+
+    let error = execute_derived_job_once(&gateway, &TwoPartUploadPlanner, "job-parts")
+        .expect_err("lost lease must stop the upload");
+
+    assert_eq!(
+        error,
+        DerivedJobExecutorError::Gateway(DerivedProcessingError::StaleLockToken)
+    );
+    // Parts are not heartbeated one by one: the keeper's renewal lost the lease.
+    assert_eq!(
+        gateway.calls(),
+        vec![
+            "claim:job-parts".to_string(),
+            "heartbeat:job-parts:1".to_string(),
+            "heartbeat:job-parts:1".to_string(),
+            "heartbeat:job-parts:1".to_string(),
+            "upload_part:1".to_string(),
+            "heartbeat:job-parts:1".to_string(),
+        ]
+    );
+}
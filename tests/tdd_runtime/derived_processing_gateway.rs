@@ -4,6 +4,10 @@ use retaia_agent::{DerivedKind, DerivedUploadInit, validate_derived_upload_init}
 fn tdd_derived_kind_content_type_matrix_matches_v1_constraints() {
     assert!(DerivedKind::PreviewVideo.allows_content_type("video/mp4"));
     assert!(!DerivedKind::PreviewVideo.allows_content_type("video/webm"));
+    assert!(DerivedKind::PreviewVideoHls.allows_content_type("application/vnd.apple.mpegurl"));
+    assert!(DerivedKind::PreviewVideoHls.allows_content_type("video/iso.segment"));
+    assert!(DerivedKind::PreviewVideoHls.allows_content_type("video/mp4"));
+    assert!(!DerivedKind::PreviewVideoHls.allows_content_type("video/mp2t"));
 
     assert!(DerivedKind::PreviewAudio.allows_content_type("audio/mp4"));
     assert!(DerivedKind::PreviewAudio.allows_content_type("audio/mpeg"));
@@ -13,6 +17,11 @@ fn tdd_derived_kind_content_type_matrix_matches_v1_constraints() {
     assert!(DerivedKind::PreviewPhoto.allows_content_type("image/webp"));
     assert!(DerivedKind::Thumb.allows_content_type("image/jpeg"));
     assert!(!DerivedKind::Thumb.allows_content_type("image/png"));
+    assert!(DerivedKind::ThumbSprite.allows_content_type("image/webp"));
+    assert!(DerivedKind::ThumbSprite.allows_content_type("image/jpeg"));
+    assert!(!DerivedKind::ThumbSprite.allows_content_type("image/png"));
+    assert!(DerivedKind::ThumbVtt.allows_content_type("text/vtt"));
+    assert!(!DerivedKind::ThumbVtt.allows_content_type("text/plain"));
 
     assert!(DerivedKind::Waveform.allows_content_type("application/json"));
     assert!(DerivedKind::Waveform.allows_content_type("application/octet-stream"));
@@ -1,10 +1,18 @@
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex};
 
 use chrono::{TimeZone, Utc};
+use std::time::Duration;
+
 use retaia_agent::{
-    AudioProxyFormat, AudioProxyRequest, AudioWaveformRequest, CommandOutput, CommandRunner,
-    FfmpegProxyGenerator, FileTimestampProvider, ProxyGenerationError, ProxyGenerator,
-    ThumbnailFormat, VideoProxyRequest, VideoThumbnailRequest,
+    AudioProxyFormat, AudioProxyRequest, AudioWaveformFormat, AudioWaveformRequest,
+    CancellationReason, CancellationToken, ClaimedDerivedJob, CommandLimits, CommandOutput,
+    CommandRunner, DerivedExecutionPlanner, DerivedJobType, FfmpegProxyGenerator,
+    FileTimestampProvider, MediaOperation, MediaOperationTimeout, MediaTimeoutKind,
+    MediaTimeoutOverride, MediaToolTimeouts, NoopProxyProgressSink, ProxyGenerationError,
+    ProxyGenerator, ProxyProgressSink, RuntimeDerivedPlanner, SpriteSheetLayout, StdCommandRunner,
+    ThumbnailFormat, VideoFrameAnalysisRequest, VideoHlsLadderRequest, VideoProxyRequest,
+    VideoRendition, VideoSpriteSheetRequest, VideoThumbnailRequest, build_video_hls_ladder_args,
+    ffmpeg_progress_percent,
 };
 
 #[derive(Debug)]
@@ -49,6 +57,15 @@ impl FakeRunner {
             args: call.args.clone(),
         }
     }
+
+    fn last_call(&self) -> RecordedCall {
+        let calls = self.calls.lock().expect("calls");
+        let call = calls.last().expect("at least one call");
+        RecordedCall {
+            program: call.program.clone(),
+            args: call.args.clone(),
+        }
+    }
 }
 
 impl CommandRunner for FakeRunner {
@@ -61,6 +78,123 @@ impl CommandRunner for FakeRunner {
     }
 }
 
+struct ProgressRunner {
+    probed_duration: &'static str,
+    progress_stdout: &'static str,
+    calls: Mutex<Vec<RecordedCall>>,
+}
+
+impl CommandRunner for ProgressRunner {
+    fn run(&self, program: &str, args: &[String]) -> Result<CommandOutput, ProxyGenerationError> {
+        self.calls.lock().expect("calls").push(RecordedCall {
+            program: program.to_string(),
+            args: args.to_vec(),
+        });
+        let stdout = if program.ends_with("ffprobe") {
+            self.probed_duration
+        } else {
+            self.progress_stdout
+        };
+        Ok(CommandOutput {
+            status_code: Some(0),
+            stdout: stdout.to_string(),
+            stderr: String::new(),
+        })
+    }
+}
+
+#[derive(Default)]
+struct RecordingProgress {
+    percents: Mutex<Vec<u8>>,
+    cancellation: CancellationToken,
+}
+
+impl ProxyProgressSink for RecordingProgress {
+    fn report_progress(&self, percent: u8) {
+        self.percents.lock().expect("percents").push(percent);
+    }
+
+    fn cancellation(&self) -> Option<&CancellationToken> {
+        Some(&self.cancellation)
+    }
+}
+
+struct PartialOutputRunner;
+
+impl CommandRunner for PartialOutputRunner {
+    fn run(&self, _program: &str, _args: &[String]) -> Result<CommandOutput, ProxyGenerationError> {
+        Ok(CommandOutput {
+            status_code: Some(0),
+            stdout: "10.0".to_string(),
+            stderr: String::new(),
+        })
+    }
+
+    fn run_streaming(
+        &self,
+        _program: &str,
+        args: &[String],
+        _on_stdout_line: &mut dyn FnMut(&str),
+        limits: &CommandLimits<'_>,
+    ) -> Result<CommandOutput, ProxyGenerationError> {
+        std::fs::write(args.last().expect("output path"), b"partial").expect("partial output");
+        limits
+            .cancellation
+            .expect("cancellation token")
+            .cancel(CancellationReason::Paused);
+        Err(ProxyGenerationError::Cancelled)
+    }
+}
+
+type RecordedLimits = (MediaOperation, Option<Duration>, Option<Duration>);
+
+struct StalledRunner {
+    limits: Mutex<Vec<RecordedLimits>>,
+}
+
+impl CommandRunner for StalledRunner {
+    fn run(&self, _program: &str, _args: &[String]) -> Result<CommandOutput, ProxyGenerationError> {
+        Ok(CommandOutput {
+            status_code: Some(0),
+            stdout: "10.0".to_string(),
+            stderr: String::new(),
+        })
+    }
+
+    fn run_with_limits(
+        &self,
+        program: &str,
+        args: &[String],
+        limits: &CommandLimits<'_>,
+    ) -> Result<CommandOutput, ProxyGenerationError> {
+        self.limits.lock().expect("limits").push((
+            limits.operation,
+            limits.wall_clock,
+            limits.no_progress,
+        ));
+        self.run(program, args)
+    }
+
+    fn run_streaming(
+        &self,
+        _program: &str,
+        args: &[String],
+        _on_stdout_line: &mut dyn FnMut(&str),
+        limits: &CommandLimits<'_>,
+    ) -> Result<CommandOutput, ProxyGenerationError> {
+        self.limits.lock().expect("limits").push((
+            limits.operation,
+            limits.wall_clock,
+            limits.no_progress,
+        ));
+        std::fs::write(args.last().expect("output path"), b"partial").expect("partial output");
+        Err(ProxyGenerationError::TimedOut {
+            operation: limits.operation,
+            kind: MediaTimeoutKind::NoProgress,
+        })
+    }
+}
+
 struct WaveformRunner {
     calls: Mutex<Vec<RecordedCall>>,
 }
@@ -95,6 +229,13 @@ impl CommandRunner for WaveformRunner {
             program: program.to_string(),
             args: args.to_vec(),
         });
+        if program.ends_with("ffprobe") {
+            return Ok(CommandOutput {
+                status_code: Some(0),
+                stdout: "12.5\n".to_string(),
+                stderr: String::new(),
+            });
+        }
         let wav_path = args.last().expect("wav output path");
         let spec = hound::WavSpec {
             channels: 1,
@@ -145,10 +286,10 @@ fn tdd_ffmpeg_video_proxy_uses_h264_aac_cfr_and_faststart() {
     };
 
     generator
-        .generate_video_proxy(&request)
+        .generate_video_proxy(&request, &NoopProxyProgressSink)
         .expect("video proxy should succeed");
 
-    let call = generator_runner_call(&generator);
+    let call = generator.runner().last_call();
     let joined = call.args.join(" ");
     assert_eq!(call.program, "ffmpeg");
     assert!(joined.contains("-c:v libx264"));
@@ -187,10 +328,10 @@ fn tdd_ffmpeg_audio_proxy_mp4_uses_aac_low_profile_and_faststart() {
     };
 
     generator
-        .generate_audio_proxy(&request)
+        .generate_audio_proxy(&request, &NoopProxyProgressSink)
         .expect("audio proxy should succeed");
 
-    let call = generator_runner_call(&generator);
+    let call = generator.runner().last_call();
     let joined = call.args.join(" ");
     assert!(joined.contains("-c:a aac"));
     assert!(joined.contains("-profile:a aac_low"));
@@ -213,7 +354,7 @@ fn tdd_ffmpeg_proxy_rejects_invalid_request_before_running_process() {
     };
 
     let err = generator
-        .generate_video_proxy(&invalid)
+        .generate_video_proxy(&invalid, &NoopProxyProgressSink)
         .expect_err("invalid request must fail");
     assert!(matches!(err, ProxyGenerationError::InvalidRequest(_)));
     assert_eq!(generator_runner_call_count(&generator), 0);
@@ -232,7 +373,7 @@ fn tdd_ffmpeg_thumbnail_uses_webp_encoder_and_representative_seek() {
     };
 
     generator
-        .generate_video_thumbnail(&request)
+        .generate_video_thumbnail(&request, &NoopProxyProgressSink)
         .expect("thumbnail generation should succeed");
 
     let call = generator_runner_call(&generator);
@@ -252,11 +393,15 @@ fn tdd_ffmpeg_waveform_generates_json_with_requested_bucket_count() {
     let output = dir.path().join("waveform.json");
 
     generator
-        .generate_audio_waveform(&AudioWaveformRequest {
-            input_path: "/tmp/in.wav".to_string(),
-            output_path: output.display().to_string(),
-            bucket_count: 100,
-        })
+        .generate_audio_waveform(
+            &AudioWaveformRequest {
+                input_path: "/tmp/in.wav".to_string(),
+                output_path: output.display().to_string(),
+                bucket_count: 100,
+                format: AudioWaveformFormat::Json,
+            },
+            &NoopProxyProgressSink,
+        )
         .expect("waveform generation should succeed");
 
     let payload: serde_json::Value =
@@ -289,7 +434,7 @@ fn tdd_ffmpeg_extract_media_facts_maps_ffprobe_json_to_patch() {
     let generator = FfmpegProxyGenerator::new("ffmpeg".to_string(), runner);
 
     let facts = generator
-        .extract_media_facts("/tmp/in.mov")
+        .extract_media_facts("/tmp/in.mov", &NoopProxyProgressSink)
         .expect("facts extraction should succeed");
 
     assert_eq!(facts.duration_ms, Some(12_345));
@@ -344,7 +489,7 @@ fn tdd_ffmpeg_extract_media_facts_repairs_rode_bext_timestamp_from_file_year() {
         ),
     );
     let facts = generator
-        .extract_media_facts(&path.display().to_string())
+        .extract_media_facts(&path.display().to_string(), &NoopProxyProgressSink)
         .expect("facts extraction should succeed");
 
     assert_eq!(facts.media_format.as_deref(), Some("wav"));
@@ -390,7 +535,7 @@ fn tdd_ffmpeg_extract_media_facts_falls_back_to_file_timestamp_when_bext_date_ca
         None,
     );
     let facts = generator
-        .extract_media_facts(&path.display().to_string())
+        .extract_media_facts(&path.display().to_string(), &NoopProxyProgressSink)
         .expect("facts extraction should succeed");
 
     assert_eq!(facts.captured_at.as_deref(), Some("2026-03-21T10:40:00Z"));
@@ -430,7 +575,7 @@ fn tdd_ffmpeg_extract_media_facts_falls_back_to_file_creation_time_for_plain_wav
     let path = dir.path().join("plain.wav");
     write_test_wav_with_chunks(&path, None, None);
     let facts = generator
-        .extract_media_facts(&path.display().to_string())
+        .extract_media_facts(&path.display().to_string(), &NoopProxyProgressSink)
         .expect("facts extraction should succeed");
 
     assert_eq!(facts.media_format.as_deref(), Some("wav"));
@@ -512,3 +657,643 @@ fn generator_runner_call_count<T: FileTimestampProvider>(
 ) -> usize {
     generator.runner().call_count()
 }
+
+#[test]
+fn tdd_ffmpeg_progress_percent_parses_out_time_against_probed_duration() {
+    assert_eq!(
+        ffmpeg_progress_percent("out_time_us=2500000", Some(10_000)),
+        Some(25)
+    );
+    assert_eq!(
+        ffmpeg_progress_percent("out_time_ms=5000000", Some(10_000)),
+        Some(50)
+    );
+    assert_eq!(
+        ffmpeg_progress_percent("out_time_us=12000000", Some(10_000)),
+        Some(99)
+    );
+    assert_eq!(ffmpeg_progress_percent("progress=end", None), Some(100));
+    assert_eq!(
+        ffmpeg_progress_percent("out_time_us=N/A", Some(10_000)),
+        None
+    );
+    assert_eq!(ffmpeg_progress_percent("out_time_us=2500000", None), None);
+    assert_eq!(ffmpeg_progress_percent("frame=42", Some(10_000)), None);
+}
+
+#[test]
+fn tdd_ffmpeg_video_proxy_with_progress_streams_progress_from_pipe() {
+    let runner = ProgressRunner {
+        probed_duration: "10.000000\n",
+        progress_stdout: "frame=10\nout_time_us=2500000\nprogress=continue\nout_time_ms=2500000\nout_time_us=5000000\nprogress=continue\nout_time_us=10000000\nprogress=end\n",
+        calls: Mutex::new(Vec::new()),
+    };
+    let generator = FfmpegProxyGenerator::new("/opt/bin/ffmpeg".to_string(), runner);
+    let progress = RecordingProgress::default();
+
+    generator
+        .generate_video_proxy(
+            &VideoProxyRequest {
+                input_path: "/in/source.mov".to_string(),
+                output_path: "/out/proxy.mp4".to_string(),
+                max_width: 1280,
+                max_height: 720,
+                video_bitrate_kbps: 3000,
+                audio_bitrate_kbps: 128,
+            },
+            &progress,
+        )
+        .expect("proxy");
+
+    assert_eq!(
+        progress.percents.lock().expect("percents").clone(),
+        vec![25, 50, 99, 100]
+    );
+    let calls = generator.runner().calls.lock().expect("calls");
+    assert_eq!(calls.len(), 2);
+    assert_eq!(calls[0].program, "/opt/bin/ffprobe");
+    assert_eq!(calls[0].args.last(), Some(&"/in/source.mov".to_string()));
+    assert_eq!(calls[1].program, "/opt/bin/ffmpeg");
+    assert_eq!(
+        calls[1].args[..3],
+        [
+            "-progress".to_string(),
+            "pipe:1".to_string(),
+            "-nostats".to_string()
+        ]
+    );
+}
+
+#[test]
+fn tdd_ffmpeg_video_proxy_removes_partial_output_when_cancelled() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let output_path = dir.path().join("proxy.mp4");
+    let generator = FfmpegProxyGenerator::new("ffmpeg".to_string(), PartialOutputRunner);
+
+    let error = generator
+        .generate_video_proxy(
+            &VideoProxyRequest {
+                input_path: "/in/source.mov".to_string(),
+                output_path: output_path.display().to_string(),
+                max_width: 1280,
+                max_height: 720,
+                video_bitrate_kbps: 3000,
+                audio_bitrate_kbps: 128,
+            },
+            &RecordingProgress::default(),
+        )
+        .expect_err("cancelled");
+
+    assert_eq!(error, ProxyGenerationError::Cancelled);
+    assert!(error.is_retryable());
+    assert!(!output_path.exists());
+}
+
+#[derive(Default)]
+struct TokenRecordingRunner {
+    operations: Mutex<Vec<(MediaOperation, bool)>>,
+}
+
+impl CommandRunner for TokenRecordingRunner {
+    fn run(&self, _program: &str, _args: &[String]) -> Result<CommandOutput, ProxyGenerationError> {
+        Ok(CommandOutput {
+            status_code: Some(0),
+            stdout: "10.0".to_string(),
+            stderr: String::new(),
+        })
+    }
+
+    fn run_with_limits(
+        &self,
+        program: &str,
+        args: &[String],
+        limits: &CommandLimits<'_>,
+    ) -> Result<CommandOutput, ProxyGenerationError> {
+        self.operations
+            .lock()
+            .expect("operations")
+            .push((limits.operation, limits.cancellation.is_some()));
+        if limits
+            .cancellation
+            .is_some_and(CancellationToken::is_cancelled)
+        {
+            return Err(ProxyGenerationError::Cancelled);
+        }
+        self.run(program, args)
+    }
+}
+
+#[test]
+fn tdd_ffmpeg_generator_passes_job_cancellation_to_every_media_process() {
+    let generator =
+        FfmpegProxyGenerator::new("ffmpeg".to_string(), TokenRecordingRunner::default());
+    let progress = RecordingProgress::default();
+    progress.cancellation.cancel(CancellationReason::Paused);
+    let dir = tempfile::tempdir().expect("tempdir");
+    let output = |name: &str| dir.path().join(name).display().to_string();
+
+    let results = [
+        generator.generate_video_proxy(
+            &VideoProxyRequest {
+                input_path: "/in/clip.mov".to_string(),
+                output_path: output("proxy.mp4"),
+                max_width: 1280,
+                max_height: 720,
+                video_bitrate_kbps: 3000,
+                audio_bitrate_kbps: 128,
+            },
+            &progress,
+        ),
+        generator.generate_audio_proxy(
+            &AudioProxyRequest {
+                input_path: "/in/clip.wav".to_string(),
+                output_path: output("proxy.m4a"),
+                format: AudioProxyFormat::Mp4Aac,
+                audio_bitrate_kbps: 160,
+                sample_rate_hz: 48000,
+            },
+            &progress,
+        ),
+        generator.generate_video_thumbnail(
+            &VideoThumbnailRequest {
+                input_path: "/in/clip.mov".to_string(),
+                output_path: output("thumb.webp"),
+                format: ThumbnailFormat::Webp,
+                max_width: 480,
+                seek_ms: 1_000,
+            },
+            &progress,
+        ),
+        generator.generate_video_sprite_sheet(
+            &VideoSpriteSheetRequest {
+                input_path: "/in/clip.mov".to_string(),
+                output_path: output("sprite.webp"),
+                format: ThumbnailFormat::Webp,
+                layout: SpriteSheetLayout {
+                    interval_ms: 1_000,
+                    tile_width: 160,
+                    tile_height: 90,
+                    columns: 2,
+                    rows: 2,
+                    tile_count: 4,
+                },
+            },
+            &progress,
+        ),
+        generator
+            .analyze_video_frames(
+                &VideoFrameAnalysisRequest {
+                    input_path: "/in/clip.mov".to_string(),
+                    duration_ms: 10_000,
+                    sample_interval_ms: 500,
+                },
+                &progress,
+            )
+            .map(|_| ()),
+        generator.generate_audio_waveform(
+            &AudioWaveformRequest {
+                input_path: "/in/clip.mov".to_string(),
+                output_path: output("clip.waveform.json"),
+                bucket_count: 100,
+                format: AudioWaveformFormat::Json,
+            },
+            &progress,
+        ),
+        generator
+            .extract_media_facts("/in/clip.mov", &progress)
+            .map(|_| ()),
+        generator
+            .analyze_loudness("/in/clip.mov", &progress)
+            .map(|_| ()),
+    ];
+
+    assert!(
+        results
+            .iter()
+            .all(|result| *result == Err(ProxyGenerationError::Cancelled))
+    );
+    let operations = generator
+        .runner()
+        .operations
+        .lock()
+        .expect("operations")
+        .clone();
+    assert!(operations.iter().all(|(_, has_token)| *has_token));
+    for operation in [
+        MediaOperation::VideoProxy,
+        MediaOperation::AudioProxy,
+        MediaOperation::Thumbnail,
+        MediaOperation::SpriteSheet,
+        MediaOperation::SceneAnalysis,
+        MediaOperation::WaveformDecode,
+        MediaOperation::Probe,
+        MediaOperation::LoudnessAnalysis,
+    ] {
+        assert!(
+            operations
+                .iter()
+                .any(|(recorded, _)| *recorded == operation)
+        );
+    }
+}
+
+#[test]
+fn tdd_runtime_derived_planner_hands_job_cancellation_to_thumbnail_and_facts_processes() {
+    let generator = Arc::new(FfmpegProxyGenerator::new(
+        "ffmpeg".to_string(),
+        TokenRecordingRunner::default(),
+    ));
+    let planner = RuntimeDerivedPlanner::new(generator.clone(), generator.clone());
+    let progress = RecordingProgress::default();
+    progress.cancellation.cancel(CancellationReason::Stopped);
+    let dir = tempfile::tempdir().expect("tempdir");
+    let staged = dir.path().join("clip.mov");
+    std::fs::write(&staged, b"video").expect("write");
+
+    for job_type in [
+        DerivedJobType::GenerateThumbnails,
+        DerivedJobType::ExtractFacts,
+        DerivedJobType::GenerateAudioWaveform,
+    ] {
+        let claimed = ClaimedDerivedJob {
+            job_id: format!("job-{job_type:?}"),
+            asset_uuid: "asset-cancel".to_string(),
+            lock_token: "lock-cancel".to_string(),
+            fencing_token: 1,
+            job_type,
+            source_storage_id: "nas-main".to_string(),
+            source_original_relative: "INBOX/clip.mov".to_string(),
+            source_sidecars_relative: Vec::new(),
+        };
+        assert!(
+            planner
+                .plan_for_claimed_job_with_progress(&claimed, Some(&staged), &[], &progress)
+                .is_err()
+        );
+    }
+
+    let operations = generator
+        .runner()
+        .operations
+        .lock()
+        .expect("operations")
+        .clone();
+    assert!(!operations.is_empty());
+    assert!(operations.iter().all(|(_, has_token)| *has_token));
+}
+
+#[cfg(unix)]
+#[test]
+fn tdd_std_command_runner_kills_process_group_when_cancelled() {
+    let cancellation = CancellationToken::new();
+    let canceller = cancellation.clone();
+    let mut lines = Vec::new();
+    let started = std::time::Instant::now();
+
+    let result = std::thread::scope(|scope| {
+        scope.spawn(move || {
+            std::thread::sleep(std::time::Duration::from_millis(200));
+            canceller.cancel(CancellationReason::Stopped);
+        });
+        StdCommandRunner.run_streaming(
+            "sh",
+            &[
+                "-c".to_string(),
+                "echo started; sleep 30; echo finished".to_string(),
+            ],
+            &mut |line| lines.push(line.to_string()),
+            &CommandLimits::new(MediaOperation::VideoProxy).with_cancellation(Some(&cancellation)),
+        )
+    });
+
+    assert_eq!(result, Err(ProxyGenerationError::Cancelled));
+    assert_eq!(lines, vec!["started".to_string()]);
+    assert!(started.elapsed() < std::time::Duration::from_secs(10));
+}
+
+#[test]
+fn tdd_media_operation_timeout_scales_wall_clock_with_media_duration_up_to_cap() {
+    let timeout = MediaOperationTimeout {
+        base: Duration::from_secs(60),
+        per_media_second: Duration::from_secs(2),
+        max: Duration::from_secs(600),
+        no_progress: Some(Duration::from_secs(30)),
+    };
+
+    assert_eq!(
+        timeout.wall_clock_for(Some(90_000)),
+        Duration::from_secs(240)
+    );
+    assert_eq!(
+        timeout.wall_clock_for(Some(10 * 60 * 60 * 1000)),
+        Duration::from_secs(600)
+    );
+    assert_eq!(timeout.wall_clock_for(None), Duration::from_secs(600));
+    assert_eq!(
+        MediaToolTimeouts::default()
+            .for_operation(MediaOperation::Probe)
+            .no_progress,
+        None
+    );
+}
+
+#[test]
+fn tdd_media_timeout_override_caps_wall_clock_and_sets_no_progress_watchdog() {
+    let video_proxy = MediaTimeoutOverride::parse("video_proxy=7200,300").expect("override");
+    let probe = MediaTimeoutOverride::parse("probe=30").expect("override");
+    let thumbnail = MediaTimeoutOverride::parse("thumbnail=90,0").expect("override");
+
+    let timeouts = MediaToolTimeouts::default()
+        .with_override(video_proxy)
+        .with_override(probe)
+        .with_override(thumbnail);
+
+    assert_eq!(
+        timeouts.video_proxy.wall_clock_for(None),
+        Duration::from_secs(7200)
+    );
+    assert_eq!(
+        timeouts.video_proxy.no_progress,
+        Some(Duration::from_secs(300))
+    );
+    assert_eq!(
+        timeouts.probe,
+        MediaOperationTimeout::fixed(Duration::from_secs(30), None)
+    );
+    assert_eq!(timeouts.thumbnail.no_progress, None);
+    assert_eq!(
+        timeouts.audio_proxy,
+        MediaToolTimeouts::default().audio_proxy
+    );
+}
+
+#[test]
+fn tdd_media_timeout_override_rejects_unknown_operations_and_zero_wall_clock() {
+    assert!(MediaTimeoutOverride::parse("transcode=60").is_err());
+    assert!(MediaTimeoutOverride::parse("probe=0").is_err());
+    assert!(MediaTimeoutOverride::parse("probe").is_err());
+    assert!(MediaTimeoutOverride::parse("probe=soon").is_err());
+}
+
+#[test]
+fn tdd_ffmpeg_video_proxy_times_out_with_scaled_limits_and_removes_partial_output() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let output_path = dir.path().join("proxy.mp4");
+    let timeouts = MediaToolTimeouts {
+        video_proxy: MediaOperationTimeout {
+            base: Duration::from_secs(30),
+            per_media_second: Duration::from_secs(3),
+            max: Duration::from_secs(3600),
+            no_progress: Some(Duration::from_secs(45)),
+        },
+        ..MediaToolTimeouts::default()
+    };
+    let generator = FfmpegProxyGenerator::new(
+        "ffmpeg".to_string(),
+        StalledRunner {
+            limits: Mutex::new(Vec::new()),
+        },
+    )
+    .with_timeouts(timeouts);
+
+    let error = generator
+        .generate_video_proxy(
+            &VideoProxyRequest {
+                input_path: "/in/source.mov".to_string(),
+                output_path: output_path.display().to_string(),
+                max_width: 1280,
+                max_height: 720,
+                video_bitrate_kbps: 3000,
+                audio_bitrate_kbps: 128,
+            },
+            &RecordingProgress::default(),
+        )
+        .expect_err("timed out");
+
+    assert_eq!(
+        error,
+        ProxyGenerationError::TimedOut {
+            operation: MediaOperation::VideoProxy,
+            kind: MediaTimeoutKind::NoProgress,
+        }
+    );
+    assert_eq!(error.error_code(), "PROXY_TIMEOUT");
+    assert!(error.is_retryable());
+    assert!(!output_path.exists());
+    assert_eq!(
+        generator.runner().limits.lock().expect("limits").clone(),
+        vec![
+            (MediaOperation::Probe, Some(Duration::from_secs(60)), None),
+            (
+                MediaOperation::VideoProxy,
+                Some(Duration::from_secs(60)),
+                Some(Duration::from_secs(45))
+            ),
+        ]
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn tdd_std_command_runner_kills_process_on_wall_clock_timeout() {
+    let started = std::time::Instant::now();
+
+    let result = StdCommandRunner.run_with_limits(
+        "sh",
+        &["-c".to_string(), "sleep 30".to_string()],
+        &CommandLimits {
+            wall_clock: Some(Duration::from_millis(200)),
+            ..CommandLimits::new(MediaOperation::Probe)
+        },
+    );
+
+    assert_eq!(
+        result,
+        Err(ProxyGenerationError::TimedOut {
+            operation: MediaOperation::Probe,
+            kind: MediaTimeoutKind::WallClock,
+        })
+    );
+    assert!(started.elapsed() < Duration::from_secs(10));
+}
+
+#[cfg(unix)]
+#[test]
+fn tdd_std_command_runner_kills_process_when_output_stalls() {
+    let mut lines = Vec::new();
+    let started = std::time::Instant::now();
+
+    let result = StdCommandRunner.run_streaming(
+        "sh",
+        &[
+            "-c".to_string(),
+            "echo started; echo working >&2; sleep 30; echo finished".to_string(),
+        ],
+        &mut |line| lines.push(line.to_string()),
+        &CommandLimits {
+            wall_clock: Some(Duration::from_secs(20)),
+            no_progress: Some(Duration::from_millis(300)),
+            ..CommandLimits::new(MediaOperation::WaveformDecode)
+        },
+    );
+
+    assert_eq!(
+        result,
+        Err(ProxyGenerationError::TimedOut {
+            operation: MediaOperation::WaveformDecode,
+            kind: MediaTimeoutKind::NoProgress,
+        })
+    );
+    assert_eq!(lines, vec!["started".to_string()]);
+    assert!(started.elapsed() < Duration::from_secs(10));
+}
+
+#[cfg(unix)]
+#[test]
+fn tdd_std_command_runner_collects_stdout_under_limits() {
+    let output = StdCommandRunner
+        .run_with_limits(
+            "sh",
+            &["-c".to_string(), "echo 12.5".to_string()],
+            &CommandLimits {
+                wall_clock: Some(Duration::from_secs(10)),
+                ..CommandLimits::new(MediaOperation::Probe)
+            },
+        )
+        .expect("probe output");
+
+    assert_eq!(output.status_code, Some(0));
+    assert_eq!(output.stdout.trim(), "12.5");
+}
+
+#[cfg(unix)]
+#[test]
+fn tdd_std_command_runner_keeps_draining_stdout_after_non_utf8_lines() {
+    let mut lines = Vec::new();
+
+    // 200k lines overflow the pipe buffer if the reader stops at the bad line.
+    let output = StdCommandRunner
+        .run_streaming(
+            "sh",
+            &[
+                "-c".to_string(),
+                "printf 'file=caf\\351.mov\\n'; seq 200000; echo progress=end".to_string(),
+            ],
+            &mut |line| lines.push(line.to_string()),
+            &CommandLimits {
+                wall_clock: Some(Duration::from_secs(20)),
+                no_progress: Some(Duration::from_secs(5)),
+                ..CommandLimits::new(MediaOperation::VideoProxy)
+            },
+        )
+        .expect("streamed output");
+
+    assert_eq!(output.status_code, Some(0));
+    assert_eq!(
+        lines.first().map(String::as_str),
+        Some("file=caf\u{fffd}.mov")
+    );
+    assert_eq!(lines.len(), 200_002);
+    assert_eq!(lines.last().map(String::as_str), Some("progress=end"));
+}
+
+fn hls_ladder_request() -> VideoHlsLadderRequest {
+    VideoHlsLadderRequest {
+        input_path: "/tmp/in.mov".to_string(),
+        output_dir: "/tmp/ladder".to_string(),
+        renditions: vec![
+            VideoRendition::new("360p", 640, 360, 800),
+            VideoRendition::new("1080p", 1920, 1080, 5_000),
+        ],
+        audio_bitrate_kbps: 128,
+        segment_duration_secs: 4,
+    }
+}
+
+#[test]
+fn tdd_ffmpeg_hls_ladder_encodes_every_rendition_in_one_pass() {
+    let args = build_video_hls_ladder_args(&hls_ladder_request(), true);
+    let joined = args.join(" ");
+
+    assert!(joined.contains("[0:v:0]split=2[s0][s1]"));
+    assert!(joined.contains("[s0]scale=w=640:h=360:force_original_aspect_ratio=decrease"));
+    assert!(joined.contains("[s1]scale=w=1920:h=1080:force_original_aspect_ratio=decrease"));
+    assert!(joined.contains("-b:v:0 800k"));
+    assert!(joined.contains("-b:v:1 5000k"));
+    assert!(joined.contains("-force_key_frames expr:gte(t,n_forced*4)"));
+    assert!(joined.contains("-hls_segment_type fmp4"));
+    assert!(joined.contains("-hls_playlist_type vod"));
+    assert!(joined.contains("-master_pl_name master.m3u8"));
+    assert!(joined.contains("-c:a aac"));
+    assert_eq!(
+        args.iter()
+            .position(|arg| arg == "-var_stream_map")
+            .map(|index| args[index + 1].as_str()),
+        Some("v:0,a:0,name:360p v:1,a:1,name:1080p")
+    );
+    assert_eq!(args.last().map(String::as_str), Some("/tmp/ladder/%v.m3u8"));
+}
+
+#[test]
+fn tdd_ffmpeg_hls_ladder_leaves_audio_out_when_source_has_none() {
+    let args = build_video_hls_ladder_args(&hls_ladder_request(), false);
+    let joined = args.join(" ");
+
+    assert!(!joined.contains("0:a:0"));
+    assert!(!joined.contains("-c:a"));
+    assert!(joined.contains("v:0,name:360p v:1,name:1080p"));
+}
+
+#[test]
+fn tdd_ffmpeg_hls_ladder_rejects_invalid_renditions_without_running_ffmpeg() {
+    let generator = FfmpegProxyGenerator::new("ffmpeg".to_string(), FakeRunner::success());
+    let mut request = hls_ladder_request();
+    request.renditions[0].name = "../360p".to_string();
+
+    let error = generator
+        .generate_video_hls_ladder(&request, &NoopProxyProgressSink)
+        .expect_err("invalid rendition name must be rejected");
+
+    assert!(matches!(error, ProxyGenerationError::InvalidRequest(_)));
+    assert_eq!(generator.runner().call_count(), 0);
+}
+
+#[test]
+fn tdd_ffmpeg_hls_ladder_runs_under_its_own_media_timeout() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let generator =
+        FfmpegProxyGenerator::new("ffmpeg".to_string(), TokenRecordingRunner::default());
+    let mut request = hls_ladder_request();
+    request.output_dir = dir.path().join("ladder").display().to_string();
+
+    generator
+        .generate_video_hls_ladder(&request, &NoopProxyProgressSink)
+        .expect("ladder");
+
+    let operations = generator
+        .runner()
+        .operations
+        .lock()
+        .expect("operations")
+        .clone();
+    assert!(
+        operations
+            .iter()
+            .any(|(operation, _)| *operation == MediaOperation::VideoHlsLadder)
+    );
+    assert!(
+        !operations
+            .iter()
+            .any(|(operation, _)| *operation == MediaOperation::VideoProxy)
+    );
+    let timeouts = MediaToolTimeouts::default();
+    let one_hour_ms = Some(60 * 60 * 1000);
+    assert!(
+        timeouts.video_hls_ladder.wall_clock_for(one_hour_ms)
+            > timeouts.video_proxy.wall_clock_for(one_hour_ms)
+    );
+    assert_eq!(
+        MediaOperation::parse("video_hls_ladder"),
+        Some(MediaOperation::VideoHlsLadder)
+    );
+}
@@ -0,0 +1,203 @@
+use std::cell::Cell;
+use std::time::{Duration, Instant};
+
+use retaia_agent::{
+    CancellationReason, CancellationToken, DerivedProcessingError, GatewayCall,
+    GatewayRetryPolicies, GatewayRetryPolicy, retry_gateway_call,
+};
+
+fn fast_policy(max_attempts: u32) -> GatewayRetryPolicy {
+    GatewayRetryPolicy {
+        max_attempts,
+        min_delay: Duration::from_millis(1),
+        max_delay: Duration::from_millis(5),
+    }
+}
+
+#[test]
+fn tdd_gateway_retry_classifies_transient_errors() {
+    assert!(
+        DerivedProcessingError::Throttled {
+            retry_after_ms: None
+        }
+        .is_retryable()
+    );
+    assert!(DerivedProcessingError::Transport("reset".to_string()).is_retryable());
+    assert!(DerivedProcessingError::UnexpectedStatus(503).is_retryable());
+    assert!(!DerivedProcessingError::UnexpectedStatus(404).is_retryable());
+    for error in [
+        DerivedProcessingError::Unauthorized,
+        DerivedProcessingError::LockRequired,
+        DerivedProcessingError::LockInvalid,
+        DerivedProcessingError::StaleLockToken,
+        DerivedProcessingError::MissingLockToken,
+        DerivedProcessingError::MissingFencingToken,
+    ] {
+        assert!(!error.is_retryable(), "{error:?} must not be retried");
+    }
+}
+
+#[test]
+fn tdd_gateway_retry_stops_after_max_attempts() {
+    let calls = Cell::new(0);
+
+    let result: Result<(), _> =
+        retry_gateway_call(&fast_policy(3), 7, None, &CancellationToken::new(), || {
+            calls.set(calls.get() + 1);
+            Err(DerivedProcessingError::Transport("reset".to_string()))
+        });
+
+    assert_eq!(
+        result,
+        Err(DerivedProcessingError::Transport("reset".to_string()))
+    );
+    assert_eq!(calls.get(), 3);
+}
+
+#[test]
+fn tdd_gateway_retry_returns_first_success() {
+    let calls = Cell::new(0);
+
+    let result = retry_gateway_call(&fast_policy(5), 7, None, &CancellationToken::new(), || {
+        calls.set(calls.get() + 1);
+        if calls.get() < 3 {
+            Err(DerivedProcessingError::UnexpectedStatus(502))
+        } else {
+            Ok("etag-1")
+        }
+    });
+
+    assert_eq!(result, Ok("etag-1"));
+    assert_eq!(calls.get(), 3);
+}
+
+#[test]
+fn tdd_gateway_retry_does_not_retry_lock_errors() {
+    let calls = Cell::new(0);
+
+    let result: Result<(), _> =
+        retry_gateway_call(&fast_policy(5), 7, None, &CancellationToken::new(), || {
+            calls.set(calls.get() + 1);
+            Err(DerivedProcessingError::LockInvalid)
+        });
+
+    assert_eq!(result, Err(DerivedProcessingError::LockInvalid));
+    assert_eq!(calls.get(), 1);
+}
+
+#[test]
+fn tdd_gateway_retry_honours_retry_after_over_computed_backoff() {
+    let policy = GatewayRetryPolicy {
+        max_attempts: 2,
+        min_delay: Duration::from_secs(30),
+        max_delay: Duration::from_secs(60),
+    };
+    let calls = Cell::new(0);
+    let started = Instant::now();
+
+    let result = retry_gateway_call(&policy, 7, None, &CancellationToken::new(), || {
+        calls.set(calls.get() + 1);
+        if calls.get() == 1 {
+            Err(DerivedProcessingError::Throttled {
+                retry_after_ms: Some(5),
+            })
+        } else {
+            Ok(())
+        }
+    });
+
+    assert_eq!(result, Ok(()));
+    assert_eq!(calls.get(), 2);
+    assert!(started.elapsed() < Duration::from_secs(5));
+}
+
+#[test]
+fn tdd_gateway_retry_gives_up_when_wait_would_outlive_the_lease() {
+    let calls = Cell::new(0);
+    let started = Instant::now();
+
+    let result: Result<(), _> = retry_gateway_call(
+        &fast_policy(5),
+        7,
+        Some(Instant::now() + Duration::from_millis(200)),
+        &CancellationToken::new(),
+        || {
+            calls.set(calls.get() + 1);
+            Err(DerivedProcessingError::Throttled {
+                retry_after_ms: Some(10_000),
+            })
+        },
+    );
+
+    assert_eq!(
+        result,
+        Err(DerivedProcessingError::Throttled {
+            retry_after_ms: Some(10_000)
+        })
+    );
+    assert_eq!(calls.get(), 1);
+    assert!(started.elapsed() < Duration::from_secs(1));
+}
+
+#[test]
+fn tdd_gateway_retry_waits_the_full_retry_after_beyond_max_delay() {
+    let calls = Cell::new(0);
+    let started = Instant::now();
+
+    let result = retry_gateway_call(
+        &fast_policy(2),
+        7,
+        Some(Instant::now() + Duration::from_secs(30)),
+        &CancellationToken::new(),
+        || {
+            calls.set(calls.get() + 1);
+            if calls.get() == 1 {
+                Err(DerivedProcessingError::Throttled {
+                    retry_after_ms: Some(150),
+                })
+            } else {
+                Ok(())
+            }
+        },
+    );
+
+    assert_eq!(result, Ok(()));
+    assert_eq!(calls.get(), 2);
+    assert!(started.elapsed() >= Duration::from_millis(150));
+}
+
+#[test]
+fn tdd_gateway_retry_stops_waiting_once_cancelled() {
+    let cancellation = CancellationToken::new();
+    let calls = Cell::new(0);
+
+    let result: Result<(), _> = retry_gateway_call(&fast_policy(5), 7, None, &cancellation, || {
+        calls.set(calls.get() + 1);
+        cancellation.cancel(CancellationReason::Stopped);
+        Err(DerivedProcessingError::Transport("reset".to_string()))
+    });
+
+    assert!(result.is_err());
+    assert_eq!(calls.get(), 1);
+}
+
+#[test]
+fn tdd_gateway_retry_policies_are_selected_per_call() {
+    let policies = GatewayRetryPolicies {
+        submit: fast_policy(8),
+        ..GatewayRetryPolicies::default()
+    };
+
+    assert_eq!(policies.for_call(GatewayCall::Submit).max_attempts, 8);
+    assert_eq!(policies.for_call(GatewayCall::Heartbeat).max_attempts, 3);
+    assert_eq!(
+        policies.for_call(GatewayCall::UploadPart),
+        GatewayRetryPolicy::default()
+    );
+    assert_eq!(
+        GatewayRetryPolicies::uniform(GatewayRetryPolicy::no_retry())
+            .for_call(GatewayCall::UploadComplete)
+            .max_attempts,
+        1
+    );
+}
@@ -0,0 +1,115 @@
+use std::collections::BTreeSet;
+
+use retaia_agent::{
+    AssetFairSelection, CoreJobState, CoreJobView, DerivedJobType, FifoSelection,
+    JobSelectionContext, JobSelectionStrategy, JobTypePrioritySelection, job_selection_policy_for,
+    select_pending_jobs,
+};
+
+fn job(job_id: &str, asset_uuid: &str, job_type: DerivedJobType) -> CoreJobView {
+    CoreJobView {
+        job_id: job_id.to_string(),
+        asset_uuid: asset_uuid.to_string(),
+        job_type: Some(job_type),
+        state: CoreJobState::Pending,
+        required_capabilities: vec!["media.facts@1".to_string()],
+    }
+}
+
+fn context() -> JobSelectionContext {
+    JobSelectionContext {
+        declared_capabilities: BTreeSet::from([
+            "media.facts@1".to_string(),
+            "media.previews.video@1".to_string(),
+        ]),
+        ..JobSelectionContext::default()
+    }
+}
+
+fn job_ids(jobs: Vec<CoreJobView>) -> Vec<String> {
+    jobs.into_iter().map(|job| job.job_id).collect()
+}
+
+#[test]
+fn tdd_job_type_priority_runs_facts_before_a_large_preview_at_the_head_of_the_queue() {
+    let jobs = vec![
+        job("preview-a", "asset-a", DerivedJobType::GeneratePreview),
+        job("thumbs-b", "asset-b", DerivedJobType::GenerateThumbnails),
+        job("facts-c", "asset-c", DerivedJobType::ExtractFacts),
+        job(
+            "waveform-d",
+            "asset-d",
+            DerivedJobType::GenerateAudioWaveform,
+        ),
+    ];
+
+    let selected = select_pending_jobs(&JobTypePrioritySelection, jobs.clone(), &context(), 4);
+    let fifo = select_pending_jobs(&FifoSelection, jobs, &context(), 1);
+
+    assert_eq!(
+        job_ids(selected),
+        vec!["facts-c", "thumbs-b", "waveform-d", "preview-a"]
+    );
+    assert_eq!(job_ids(fifo), vec!["preview-a"]);
+}
+
+#[test]
+fn tdd_asset_fair_selection_interleaves_assets_and_defers_busy_ones() {
+    let jobs = vec![
+        job("a-facts", "asset-a", DerivedJobType::ExtractFacts),
+        job("a-thumbs", "asset-a", DerivedJobType::GenerateThumbnails),
+        job("a-preview", "asset-a", DerivedJobType::GeneratePreview),
+        job("b-preview", "asset-b", DerivedJobType::GeneratePreview),
+        job("c-facts", "asset-c", DerivedJobType::ExtractFacts),
+    ];
+    let mut busy = context();
+    busy.busy_asset_uuids.insert("asset-c".to_string());
+
+    let selected = select_pending_jobs(&AssetFairSelection, jobs, &busy, 5);
+
+    assert_eq!(
+        job_ids(selected),
+        vec!["a-facts", "b-preview", "c-facts", "a-thumbs", "a-preview"]
+    );
+}
+
+#[test]
+fn tdd_select_pending_jobs_skips_partially_supported_running_and_non_pending_jobs() {
+    let mut partial = job("partial", "asset-a", DerivedJobType::ExtractFacts);
+    partial.required_capabilities = vec![
+        "media.facts@1".to_string(),
+        "speech.transcription@1".to_string(),
+    ];
+    let mut claimed = job("claimed", "asset-b", DerivedJobType::ExtractFacts);
+    claimed.state = CoreJobState::Claimed;
+    let running = job("running", "asset-c", DerivedJobType::ExtractFacts);
+    let mut context = context();
+    context.running_job_ids.insert("running".to_string());
+    let jobs = vec![
+        partial,
+        claimed,
+        running,
+        job("first", "asset-d", DerivedJobType::GeneratePreview),
+        job("second", "asset-e", DerivedJobType::GeneratePreview),
+    ];
+
+    let selected = select_pending_jobs(
+        job_selection_policy_for(JobSelectionStrategy::JobTypePriority).as_ref(),
+        jobs,
+        &context,
+        1,
+    );
+
+    assert_eq!(job_ids(selected), vec!["first"]);
+}
+
+#[test]
+fn tdd_job_selection_policy_names_match_config_values() {
+    for strategy in [
+        JobSelectionStrategy::Fifo,
+        JobSelectionStrategy::JobTypePriority,
+        JobSelectionStrategy::AssetFair,
+    ] {
+        assert_eq!(job_selection_policy_for(strategy).name(), strategy.as_str());
+    }
+}
@@ -0,0 +1,289 @@
+use std::sync::{Arc, Mutex};
+
+use retaia_agent::{
+    AudioProxyRequest, ClaimedDerivedJob, CommandOutput, CommandRunner, DerivedExecutionPlanner,
+    DerivedJobType, FactsPatchPayload, FfmpegProxyGenerator, LoudnessMeasurement,
+    NoopProxyProgressSink, PhotoDecodePath, PhotoProxyRequest, ProxyGenerationError,
+    ProxyGenerator, ProxyProgressSink, RuntimeDerivedPlanner, SubmitDerivedPayload,
+    VideoProxyRequest, build_loudness_analysis_args, parse_ebur128_summary,
+};
+
+const EBUR128_STDERR: &str = "\
+Input #0, mov,mp4,m4a,3gp,3g2,mj2, from '/in/interview.mov':
+size=N/A time=00:01:30.00 bitrate=N/A speed= 312x\r\
+[Parsed_ebur128_0 @ 0x600000d2c000] Summary:
+
+  Integrated loudness:
+    I:         -23.4 LUFS
+    Threshold: -33.6 LUFS
+
+  Loudness range:
+    LRA:         6.2 LU
+    Threshold: -43.7 LUFS
+    LRA low:   -27.9 LUFS
+    LRA high:  -21.7 LUFS
+
+  True peak:
+    Peak:       -1.3 dBFS
+";
+
+#[test]
+fn tdd_ebur128_summary_parses_integrated_loudness_range_and_true_peak() {
+    assert_eq!(
+        parse_ebur128_summary(EBUR128_STDERR),
+        Some(LoudnessMeasurement {
+            integrated_lufs: -23.4,
+            loudness_range_lu: 6.2,
+            true_peak_dbtp: Some(-1.3),
+        })
+    );
+}
+
+#[test]
+fn tdd_ebur128_summary_keeps_silence_and_rejects_truncated_output() {
+    let silence = "Summary:\n  I: -70.0 LUFS\n  LRA: 0.0 LU\n  Peak: -inf dBFS\n";
+
+    assert_eq!(
+        parse_ebur128_summary(silence),
+        Some(LoudnessMeasurement {
+            integrated_lufs: -70.0,
+            loudness_range_lu: 0.0,
+            true_peak_dbtp: None,
+        })
+    );
+    assert_eq!(parse_ebur128_summary("Summary:\n  I: -20.0 LUFS\n"), None);
+    assert_eq!(parse_ebur128_summary("size=N/A time=00:00:01.00\r"), None);
+}
+
+#[test]
+fn tdd_ffmpeg_loudness_args_measure_first_audio_stream_with_true_peak() {
+    let args = build_loudness_analysis_args("/in/interview.mov");
+
+    assert!(
+        args.windows(2)
+            .any(|pair| pair == ["-i", "/in/interview.mov"])
+    );
+    assert!(args.windows(2).any(|pair| pair == ["-map", "0:a:0"]));
+    assert!(
+        args.windows(2)
+            .any(|pair| pair == ["-af", "ebur128=peak=true:framelog=quiet"])
+    );
+    assert!(!args.iter().any(|arg| arg == "-nostats"));
+    assert!(args.ends_with(&["-f".to_string(), "null".to_string(), "-".to_string()]));
+}
+
+struct LoudnessRunner {
+    status_code: Option<i32>,
+    stderr: &'static str,
+    calls: Mutex<Vec<Vec<String>>>,
+}
+
+impl CommandRunner for LoudnessRunner {
+    fn run(&self, _program: &str, args: &[String]) -> Result<CommandOutput, ProxyGenerationError> {
+        self.calls.lock().expect("calls").push(args.to_vec());
+        Ok(CommandOutput {
+            status_code: self.status_code,
+            stdout: String::new(),
+            stderr: self.stderr.to_string(),
+        })
+    }
+}
+
+fn loudness_generator(
+    status_code: Option<i32>,
+    stderr: &'static str,
+) -> FfmpegProxyGenerator<LoudnessRunner> {
+    FfmpegProxyGenerator::new(
+        "ffmpeg".to_string(),
+        LoudnessRunner {
+            status_code,
+            stderr,
+            calls: Mutex::new(Vec::new()),
+        },
+    )
+}
+
+#[test]
+fn tdd_ffmpeg_analyze_loudness_reads_summary_from_stderr() {
+    let generator = loudness_generator(Some(0), EBUR128_STDERR);
+
+    let loudness = generator
+        .analyze_loudness("/in/interview.mov", &NoopProxyProgressSink)
+        .expect("loudness");
+
+    assert_eq!(loudness.integrated_lufs, -23.4);
+    assert_eq!(loudness.true_peak_dbtp, Some(-1.3));
+    let calls = generator.runner().calls.lock().expect("calls");
+    assert!(calls.iter().any(|args| {
+        args.iter()
+            .any(|arg| arg == "ebur128=peak=true:framelog=quiet")
+    }));
+}
+
+#[test]
+fn tdd_ffmpeg_analyze_loudness_reports_failures() {
+    assert!(matches!(
+        loudness_generator(Some(0), "").analyze_loudness(" ", &NoopProxyProgressSink),
+        Err(ProxyGenerationError::InvalidRequest(_))
+    ));
+    assert!(matches!(
+        loudness_generator(Some(1), "Stream map '0:a:0' matches no streams.")
+            .analyze_loudness("/in/silent.mov", &NoopProxyProgressSink),
+        Err(ProxyGenerationError::CommandFailed { .. })
+    ));
+    assert!(matches!(
+        loudness_generator(Some(0), "size=N/A")
+            .analyze_loudness("/in/clip.mov", &NoopProxyProgressSink),
+        Err(ProxyGenerationError::Process(_))
+    ));
+}
+
+struct FactsGenerator {
+    audio_codec: Option<&'static str>,
+    loudness: Result<LoudnessMeasurement, ProxyGenerationError>,
+    loudness_calls: Mutex<usize>,
+}
+
+impl FactsGenerator {
+    fn new(
+        audio_codec: Option<&'static str>,
+        loudness: Result<LoudnessMeasurement, ProxyGenerationError>,
+    ) -> Self {
+        Self {
+            audio_codec,
+            loudness,
+            loudness_calls: Mutex::new(0),
+        }
+    }
+}
+
+impl ProxyGenerator for FactsGenerator {
+    fn generate_video_proxy(
+        &self,
+        _request: &VideoProxyRequest,
+        _progress: &dyn ProxyProgressSink,
+    ) -> Result<(), ProxyGenerationError> {
+        Ok(())
+    }
+
+    fn generate_audio_proxy(
+        &self,
+        _request: &AudioProxyRequest,
+        _progress: &dyn ProxyProgressSink,
+    ) -> Result<(), ProxyGenerationError> {
+        Ok(())
+    }
+
+    fn generate_photo_proxy(
+        &self,
+        _request: &PhotoProxyRequest,
+        _progress: &dyn ProxyProgressSink,
+    ) -> Result<Option<PhotoDecodePath>, ProxyGenerationError> {
+        Ok(None)
+    }
+
+    fn extract_media_facts(
+        &self,
+        _input_path: &str,
+        _progress: &dyn ProxyProgressSink,
+    ) -> Result<FactsPatchPayload, ProxyGenerationError> {
+        Ok(FactsPatchPayload {
+            duration_ms: Some(90_000),
+            audio_codec: self.audio_codec.map(str::to_string),
+            ..FactsPatchPayload::default()
+        })
+    }
+
+    fn analyze_loudness(
+        &self,
+        _input_path: &str,
+        _progress: &dyn ProxyProgressSink,
+    ) -> Result<LoudnessMeasurement, ProxyGenerationError> {
+        *self.loudness_calls.lock().expect("loudness calls") += 1;
+        self.loudness.clone()
+    }
+}
+
+fn extract_facts(generator: Arc<FactsGenerator>, original: &str) -> SubmitDerivedPayload {
+    let planner = RuntimeDerivedPlanner::new(generator.clone(), generator);
+    let claimed = ClaimedDerivedJob {
+        job_id: "job-loudness".to_string(),
+        asset_uuid: "asset-loudness".to_string(),
+        lock_token: "lock-loudness".to_string(),
+        fencing_token: 1,
+        job_type: DerivedJobType::ExtractFacts,
+        source_storage_id: "nas-main".to_string(),
+        source_original_relative: format!("INBOX/{original}"),
+        source_sidecars_relative: Vec::new(),
+    };
+    let dir = tempfile::tempdir().expect("tempdir");
+    let staged = dir.path().join(original);
+    std::fs::write(&staged, b"source-bytes").expect("write");
+
+    planner
+        .plan_for_claimed_job_with_source(&claimed, Some(staged.as_path()), &[])
+        .expect("plan")
+        .submit
+}
+
+fn metric(submit: &SubmitDerivedPayload, key: &str) -> Option<f64> {
+    submit
+        .metrics
+        .as_ref()
+        .and_then(|metrics| metrics.get(key))
+        .and_then(|value| value.as_f64())
+}
+
+#[test]
+fn tdd_runtime_derived_planner_submits_loudness_in_extract_facts_metrics() {
+    let generator = Arc::new(FactsGenerator::new(
+        Some("aac"),
+        Ok(LoudnessMeasurement {
+            integrated_lufs: -16.1,
+            loudness_range_lu: 4.5,
+            true_peak_dbtp: Some(-0.8),
+        }),
+    ));
+
+    let submit = extract_facts(generator.clone(), "interview.mov");
+
+    assert_eq!(
+        submit
+            .facts_patch
+            .as_ref()
+            .expect("facts patch")
+            .duration_ms,
+        Some(90_000)
+    );
+    assert_eq!(metric(&submit, "loudness_integrated_lufs"), Some(-16.1));
+    assert_eq!(metric(&submit, "loudness_range_lu"), Some(4.5));
+    assert_eq!(metric(&submit, "true_peak_dbtp"), Some(-0.8));
+    assert_eq!(*generator.loudness_calls.lock().expect("calls"), 1);
+}
+
+#[test]
+fn tdd_runtime_derived_planner_keeps_facts_when_loudness_is_unavailable() {
+    let failing = Arc::new(FactsGenerator::new(
+        Some("pcm_s24le"),
+        Err(ProxyGenerationError::Process("ffmpeg missing".to_string())),
+    ));
+    let submit = extract_facts(failing, "take.wav");
+    assert_eq!(
+        submit
+            .facts_patch
+            .as_ref()
+            .expect("facts patch")
+            .duration_ms,
+        Some(90_000)
+    );
+    assert_eq!(metric(&submit, "loudness_integrated_lufs"), None);
+    assert_eq!(metric(&submit, "true_peak_dbtp"), None);
+
+    let silent_video = Arc::new(FactsGenerator::new(
+        None,
+        Err(ProxyGenerationError::Process("unexpected".to_string())),
+    ));
+    let submit = extract_facts(silent_video.clone(), "broll.mov");
+    assert_eq!(metric(&submit, "loudness_range_lu"), None);
+    assert_eq!(*silent_video.loudness_calls.lock().expect("calls"), 0);
+}
@@ -0,0 +1,267 @@
+use std::path::Path;
+use std::sync::Mutex;
+
+use retaia_agent::{
+    AudioWaveformFormat, AudioWaveformRequest, CancellationReason, CancellationToken,
+    CommandLimits, CommandOutput, CommandRunner, FfmpegProxyGenerator, MediaOperation,
+    NoopProxyProgressSink, ProxyGenerationError, ProxyGenerator, ProxyProgressSink, WaveformPeaks,
+    audio_source_extension_supported, declared_agent_capabilities_with_runtime,
+    write_waveform_with_native_decoder,
+};
+
+// Left carries a 16000 amplitude square wave, right is silent.
+fn write_stereo_wav(path: &Path, sample_rate: u32, frames: u32) {
+    let spec = hound::WavSpec {
+        channels: 2,
+        sample_rate,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let mut writer = hound::WavWriter::create(path, spec).expect("create wav");
+    for index in 0..frames {
+        let left = if index % 2 == 0 { 16_000_i16 } else { -16_000 };
+        writer.write_sample(left).expect("left");
+        writer.write_sample(0_i16).expect("right");
+    }
+    writer.finalize().expect("finalize wav");
+}
+
+fn request(input: &Path, output: &Path, format: AudioWaveformFormat) -> AudioWaveformRequest {
+    AudioWaveformRequest {
+        input_path: input.display().to_string(),
+        output_path: output.display().to_string(),
+        bucket_count: 100,
+        format,
+    }
+}
+
+#[derive(Default)]
+struct DecodingRunner {
+    calls: Mutex<Vec<String>>,
+}
+
+impl CommandRunner for DecodingRunner {
+    fn run(&self, program: &str, args: &[String]) -> Result<CommandOutput, ProxyGenerationError> {
+        self.calls.lock().expect("calls").push(program.to_string());
+        if program.ends_with("ffprobe") {
+            return Ok(CommandOutput {
+                status_code: Some(0),
+                stdout: "0.5\n".to_string(),
+                stderr: String::new(),
+            });
+        }
+        write_stereo_wav(Path::new(args.last().expect("wav path")), 16_000, 8_000);
+        Ok(CommandOutput {
+            status_code: Some(0),
+            stdout: String::new(),
+            stderr: String::new(),
+        })
+    }
+}
+
+#[test]
+fn tdd_audio_source_extension_support_covers_common_formats() {
+    for extension in ["wav", "WAV", ".flac", "mp3", "aac", "m4a", "ogg", "oga"] {
+        assert!(
+            audio_source_extension_supported(extension),
+            "{extension} should be decodable"
+        );
+    }
+    for extension in ["", "wma", "opus", "mov", "jpg"] {
+        assert!(!audio_source_extension_supported(extension));
+    }
+}
+
+#[test]
+fn tdd_declared_capabilities_keep_waveform_gated_on_ffmpeg_despite_native_decoding() {
+    // Core cannot restrict waveform jobs to natively decodable sources.
+    let without_ffmpeg = declared_agent_capabilities_with_runtime(false, true);
+    assert!(!without_ffmpeg.contains("audio.waveform@1"));
+    assert!(without_ffmpeg.contains("media.facts@1"));
+
+    let with_ffmpeg = declared_agent_capabilities_with_runtime(true, true);
+    assert!(with_ffmpeg.contains("audio.waveform@1"));
+}
+
+#[test]
+fn tdd_native_waveform_json_downmixes_channels_without_resampling() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let input = dir.path().join("stereo.wav");
+    let output = dir.path().join("stereo.waveform.json");
+    write_stereo_wav(&input, 44_100, 44_100);
+
+    write_waveform_with_native_decoder(
+        &request(&input, &output, AudioWaveformFormat::Json),
+        &CommandLimits::new(MediaOperation::WaveformDecode),
+    )
+    .expect("native waveform");
+
+    let payload: serde_json::Value =
+        serde_json::from_slice(&std::fs::read(&output).expect("read json")).expect("json");
+    assert_eq!(payload["duration_ms"], serde_json::json!(1_000));
+    assert_eq!(payload["bucket_count"], serde_json::json!(100));
+    let samples = payload["samples"].as_array().expect("samples");
+    assert_eq!(samples.len(), 100);
+    let expected = 8_000.0 / f64::from(i16::MAX);
+    assert!(
+        samples
+            .iter()
+            .all(|sample| (sample.as_f64().expect("f64") - expected).abs() < 1e-4)
+    );
+}
+
+#[test]
+fn tdd_native_waveform_reports_undecodable_sources_as_non_retryable() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let input = dir.path().join("broken.wav");
+    std::fs::write(&input, b"not a riff file").expect("write");
+    let output = dir.path().join("broken.waveform.json");
+
+    let error = write_waveform_with_native_decoder(
+        &request(&input, &output, AudioWaveformFormat::Json),
+        &CommandLimits::new(MediaOperation::WaveformDecode),
+    )
+    .expect_err("corrupt source");
+
+    assert!(matches!(error, ProxyGenerationError::DecodeFailed(_)));
+    assert!(!error.is_retryable());
+}
+
+#[test]
+fn tdd_ffmpeg_generator_decodes_supported_audio_natively_without_spawning_ffmpeg() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let input = dir.path().join("take.wav");
+    let output = dir.path().join("take.waveform.bin");
+    write_stereo_wav(&input, 44_100, 22_050);
+    let generator = FfmpegProxyGenerator::new("ffmpeg".to_string(), DecodingRunner::default());
+
+    generator
+        .generate_audio_waveform(
+            &request(&input, &output, AudioWaveformFormat::PeaksV2),
+            &NoopProxyProgressSink,
+        )
+        .expect("waveform");
+
+    assert!(generator.runner().calls.lock().expect("calls").is_empty());
+    let peaks = WaveformPeaks::decode(&std::fs::read(&output).expect("read")).expect("v2");
+    assert_eq!((peaks.channel_count, peaks.sample_rate), (2, 44_100));
+    assert_eq!(peaks.duration_ms, 500);
+    let left = peaks.peak(0, 0, 0).expect("left peak");
+    assert_eq!((left.min, left.max, left.rms), (-16_000, 16_000, 16_000));
+    assert_eq!(peaks.peak(0, 0, 1).expect("right peak").max, 0);
+}
+
+#[test]
+fn tdd_ffmpeg_generator_falls_back_to_ffmpeg_when_native_decoding_fails() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let input = dir.path().join("broken.wav");
+    std::fs::write(&input, b"not a riff file").expect("write");
+    let output = dir.path().join("broken.waveform.json");
+    let generator = FfmpegProxyGenerator::new("ffmpeg".to_string(), DecodingRunner::default());
+
+    generator
+        .generate_audio_waveform(
+            &request(&input, &output, AudioWaveformFormat::Json),
+            &NoopProxyProgressSink,
+        )
+        .expect("ffmpeg fallback");
+
+    let calls = generator.runner().calls.lock().expect("calls").clone();
+    assert!(calls.iter().any(|program| program == "ffmpeg"));
+    let payload: serde_json::Value =
+        serde_json::from_slice(&std::fs::read(&output).expect("read json")).expect("json");
+    assert_eq!(payload["duration_ms"], serde_json::json!(500));
+}
+
+struct MissingFfmpegRunner;
+
+impl CommandRunner for MissingFfmpegRunner {
+    fn run(&self, _program: &str, _args: &[String]) -> Result<CommandOutput, ProxyGenerationError> {
+        Err(ProxyGenerationError::Process(
+            "No such file or directory (os error 2)".to_string(),
+        ))
+    }
+}
+
+#[test]
+fn tdd_ffmpeg_generator_reports_native_decode_error_when_ffmpeg_fallback_is_missing() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let input = dir.path().join("broken.wav");
+    std::fs::write(&input, b"not a riff file").expect("write");
+    let output = dir.path().join("broken.waveform.json");
+    let generator = FfmpegProxyGenerator::new("ffmpeg".to_string(), MissingFfmpegRunner);
+
+    let error = generator
+        .generate_audio_waveform(
+            &request(&input, &output, AudioWaveformFormat::Json),
+            &NoopProxyProgressSink,
+        )
+        .expect_err("both decoders fail");
+
+    let ProxyGenerationError::Process(message) = error else {
+        panic!("expected process error, got {error:?}");
+    };
+    assert!(message.contains("native audio decode failed"), "{message}");
+    assert!(message.contains("ffmpeg fallback failed"), "{message}");
+}
+
+struct CancelledProgress {
+    cancellation: CancellationToken,
+}
+
+impl ProxyProgressSink for CancelledProgress {
+    fn report_progress(&self, _percent: u8) {}
+
+    fn cancellation(&self) -> Option<&CancellationToken> {
+        Some(&self.cancellation)
+    }
+}
+
+#[test]
+fn tdd_native_waveform_decoding_stops_on_cancellation_without_ffmpeg_fallback() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let input = dir.path().join("take.wav");
+    let output = dir.path().join("take.waveform.json");
+    write_stereo_wav(&input, 44_100, 44_100);
+    let generator = FfmpegProxyGenerator::new("ffmpeg".to_string(), DecodingRunner::default());
+    let progress = CancelledProgress {
+        cancellation: CancellationToken::new(),
+    };
+    progress.cancellation.cancel(CancellationReason::LeaseLost);
+
+    let error = generator
+        .generate_audio_waveform(
+            &request(&input, &output, AudioWaveformFormat::Json),
+            &progress,
+        )
+        .expect_err("cancelled");
+
+    assert_eq!(error, ProxyGenerationError::Cancelled);
+    assert!(generator.runner().calls.lock().expect("calls").is_empty());
+    assert!(!output.exists());
+}
+
+#[test]
+fn tdd_native_waveform_decoding_honours_the_wall_clock_limit() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let input = dir.path().join("take.wav");
+    let output = dir.path().join("take.waveform.json");
+    write_stereo_wav(&input, 44_100, 44_100);
+
+    let error = write_waveform_with_native_decoder(
+        &request(&input, &output, AudioWaveformFormat::Json),
+        &CommandLimits {
+            wall_clock: Some(std::time::Duration::ZERO),
+            ..CommandLimits::new(MediaOperation::WaveformDecode)
+        },
+    )
+    .expect_err("timed out");
+
+    assert!(matches!(
+        error,
+        ProxyGenerationError::TimedOut {
+            operation: MediaOperation::WaveformDecode,
+            ..
+        }
+    ));
+}
@@ -0,0 +1,175 @@
+use std::sync::Arc;
+
+use retaia_agent::{
+    AudioProxyRequest, AudioWaveformRequest, DerivedJobType, FactsPatchPayload, OFFLINE_JOB_ID,
+    OFFLINE_SUBMIT_PAYLOAD_FILE_NAME, OfflineProcessError, OfflineProcessRequest, PhotoDecodePath,
+    PhotoProxyRequest, ProxyGenerationError, ProxyGenerator, ProxyProgressSink,
+    RuntimeDerivedPlanner, VideoProxyRequest, VideoThumbnailRequest, run_offline_processing,
+};
+
+#[derive(Debug, Default)]
+struct WritingGenerator;
+
+impl ProxyGenerator for WritingGenerator {
+    fn generate_video_proxy(
+        &self,
+        request: &VideoProxyRequest,
+        _progress: &dyn ProxyProgressSink,
+    ) -> Result<(), ProxyGenerationError> {
+        std::fs::write(&request.output_path, b"generated-video")
+            .map_err(|error| ProxyGenerationError::Process(error.to_string()))
+    }
+
+    fn generate_audio_proxy(
+        &self,
+        request: &AudioProxyRequest,
+        _progress: &dyn ProxyProgressSink,
+    ) -> Result<(), ProxyGenerationError> {
+        std::fs::write(&request.output_path, b"generated-audio")
+            .map_err(|error| ProxyGenerationError::Process(error.to_string()))
+    }
+
+    fn generate_photo_proxy(
+        &self,
+        request: &PhotoProxyRequest,
+        _progress: &dyn ProxyProgressSink,
+    ) -> Result<Option<PhotoDecodePath>, ProxyGenerationError> {
+        std::fs::write(&request.output_path, b"generated-photo")
+            .map_err(|error| ProxyGenerationError::Process(error.to_string()))
+            .map(|()| None)
+    }
+
+    fn generate_video_thumbnail(
+        &self,
+        request: &VideoThumbnailRequest,
+        _progress: &dyn ProxyProgressSink,
+    ) -> Result<(), ProxyGenerationError> {
+        std::fs::write(&request.output_path, b"generated-thumbnail")
+            .map_err(|error| ProxyGenerationError::Process(error.to_string()))
+    }
+
+    fn generate_audio_waveform(
+        &self,
+        request: &AudioWaveformRequest,
+        _progress: &dyn ProxyProgressSink,
+    ) -> Result<(), ProxyGenerationError> {
+        std::fs::write(
+            &request.output_path,
+            br#"{"duration_ms":1000,"bucket_count":1000,"samples":[0.1,0.5]}"#,
+        )
+        .map_err(|error| ProxyGenerationError::Process(error.to_string()))
+    }
+
+    fn extract_media_facts(
+        &self,
+        _input_path: &str,
+        _progress: &dyn ProxyProgressSink,
+    ) -> Result<FactsPatchPayload, ProxyGenerationError> {
+        Ok(FactsPatchPayload {
+            duration_ms: Some(2_000),
+            media_format: Some("mp4".to_string()),
+            width: Some(1920),
+            height: Some(1080),
+            ..FactsPatchPayload::default()
+        })
+    }
+}
+
+fn planner() -> RuntimeDerivedPlanner {
+    RuntimeDerivedPlanner::new(Arc::new(WritingGenerator), Arc::new(WritingGenerator))
+}
+
+#[test]
+fn tdd_offline_processing_writes_artifacts_and_submit_payload_to_output_dir() {
+    let source_dir = tempfile::tempdir().expect("source dir");
+    let output_dir = tempfile::tempdir().expect("output dir");
+    let source = source_dir.path().join("A001_C002.mp4");
+    std::fs::write(&source, b"camera-bytes").expect("source");
+
+    let report = run_offline_processing(
+        &planner(),
+        &OfflineProcessRequest {
+            source_path: source.clone(),
+            sidecar_paths: Vec::new(),
+            job_type: DerivedJobType::GeneratePreview,
+            output_dir: output_dir.path().join("out"),
+        },
+    )
+    .expect("offline processing");
+
+    assert_eq!(report.claimed.job_id, OFFLINE_JOB_ID);
+    assert_eq!(report.claimed.source_original_relative, "A001_C002.mp4");
+    assert_eq!(report.artifact_paths.len(), 1);
+    let artifact = &report.artifact_paths[0];
+    assert!(artifact.starts_with(output_dir.path().join("out")));
+    assert_eq!(
+        std::fs::read(artifact).expect("artifact"),
+        b"generated-video"
+    );
+
+    let payload_path = output_dir
+        .path()
+        .join("out")
+        .join(OFFLINE_SUBMIT_PAYLOAD_FILE_NAME);
+    assert_eq!(report.submit_payload_path, payload_path);
+    let payload: serde_json::Value =
+        serde_json::from_slice(&std::fs::read(&payload_path).expect("payload")).expect("json");
+    assert_eq!(payload["job_type"], "generate_preview");
+    assert!(payload["manifest"].is_array());
+
+    // Only the original stays next to the source, and no scratch dir is left behind.
+    assert_eq!(
+        std::fs::read_dir(source_dir.path()).expect("dir").count(),
+        1
+    );
+    assert_eq!(
+        std::fs::read_dir(output_dir.path().join("out"))
+            .expect("dir")
+            .count(),
+        2
+    );
+}
+
+#[test]
+fn tdd_offline_processing_dumps_facts_for_extract_facts_jobs() {
+    let source_dir = tempfile::tempdir().expect("source dir");
+    let output_dir = tempfile::tempdir().expect("output dir");
+    let source = source_dir.path().join("clip.mov");
+    std::fs::write(&source, b"camera-bytes").expect("source");
+
+    let report = run_offline_processing(
+        &planner(),
+        &OfflineProcessRequest {
+            source_path: source,
+            sidecar_paths: Vec::new(),
+            job_type: DerivedJobType::ExtractFacts,
+            output_dir: output_dir.path().to_path_buf(),
+        },
+    )
+    .expect("offline processing");
+
+    assert!(report.artifact_paths.is_empty());
+    let payload: serde_json::Value =
+        serde_json::from_slice(&std::fs::read(&report.submit_payload_path).expect("payload"))
+            .expect("json");
+    assert_eq!(payload["facts_patch"]["duration_ms"], 2_000);
+    assert_eq!(payload["facts_patch"]["width"], 1920);
+}
+
+#[test]
+fn tdd_offline_processing_rejects_missing_source() {
+    let output_dir = tempfile::tempdir().expect("output dir");
+
+    let error = run_offline_processing(
+        &planner(),
+        &OfflineProcessRequest {
+            source_path: output_dir.path().join("missing.mp4"),
+            sidecar_paths: Vec::new(),
+            job_type: DerivedJobType::GeneratePreview,
+            output_dir: output_dir.path().to_path_buf(),
+        },
+    )
+    .expect_err("missing source must fail");
+
+    assert!(matches!(error, OfflineProcessError::SourceNotFile(_)));
+}
@@ -0,0 +1,132 @@
+use image::codecs::png::PngEncoder;
+use image::{DynamicImage, ExtendedColorType, GenericImageView, ImageEncoder, Rgb, RgbImage};
+use moxcms::ColorProfile;
+use retaia_agent::{
+    ADOBE_RGB_COLOR_SPACE, DISPLAY_P3_COLOR_SPACE, NoopProxyProgressSink, PROPHOTO_RGB_COLOR_SPACE,
+    PhotoProxyFormat, PhotoProxyRequest, ProxyGenerator, RustPhotoProxyGenerator, SRGB_COLOR_SPACE,
+    color_space_name_from_description, convert_to_srgb, icc_color_space_name, read_icc_profile,
+};
+
+fn write_png_with_icc(path: &std::path::Path, pixel: [u8; 3], icc: Option<Vec<u8>>) {
+    let image = RgbImage::from_pixel(16, 16, Rgb(pixel));
+    let file = std::fs::File::create(path).expect("create png");
+    let mut encoder = PngEncoder::new(file);
+    if let Some(icc) = icc {
+        encoder.set_icc_profile(icc).expect("png accepts icc");
+    }
+    encoder
+        .write_image(image.as_raw(), 16, 16, ExtendedColorType::Rgb8)
+        .expect("encode png");
+}
+
+#[test]
+fn tdd_photo_color_space_names_are_normalized_from_icc_descriptions() {
+    assert_eq!(
+        color_space_name_from_description("sRGB IEC61966-2.1"),
+        SRGB_COLOR_SPACE
+    );
+    assert_eq!(
+        color_space_name_from_description("Adobe RGB (1998)"),
+        ADOBE_RGB_COLOR_SPACE
+    );
+    assert_eq!(
+        color_space_name_from_description("Compatible with Adobe RGB (1998)"),
+        ADOBE_RGB_COLOR_SPACE
+    );
+    assert_eq!(
+        color_space_name_from_description("Display P3"),
+        DISPLAY_P3_COLOR_SPACE
+    );
+    assert_eq!(
+        color_space_name_from_description("ProPhoto RGB"),
+        PROPHOTO_RGB_COLOR_SPACE
+    );
+    assert_eq!(
+        color_space_name_from_description("  Studio Monitor v2 "),
+        "Studio Monitor v2"
+    );
+}
+
+#[test]
+fn tdd_photo_color_conversion_saturates_adobe_rgb_into_srgb() {
+    let source = DynamicImage::ImageRgb8(RgbImage::from_pixel(2, 2, Rgb([60, 160, 60])));
+
+    let converted = convert_to_srgb(&source, &ColorProfile::new_adobe_rgb())
+        .expect("conversion")
+        .expect("adobe rgb needs conversion");
+
+    let pixel = converted.get_pixel(0, 0);
+    assert!(pixel[1] > pixel[0] && pixel[1] > pixel[2]);
+    assert!(
+        i32::from(pixel[1]) - i32::from(pixel[0]) > 100,
+        "sRGB green should spread further from red than in Adobe RGB, got {pixel:?}"
+    );
+}
+
+#[test]
+fn tdd_photo_color_conversion_skips_srgb_and_keeps_neutrals() {
+    let gray = DynamicImage::ImageRgb8(RgbImage::from_pixel(2, 2, Rgb([128, 128, 128])));
+
+    assert!(
+        convert_to_srgb(&gray, &ColorProfile::new_srgb())
+            .expect("conversion")
+            .is_none()
+    );
+    let converted = convert_to_srgb(&gray, &ColorProfile::new_display_p3())
+        .expect("conversion")
+        .expect("display p3 needs conversion");
+    let pixel = converted.get_pixel(1, 1);
+    assert!(pixel[0].abs_diff(128) <= 2 && pixel[0].abs_diff(pixel[1]) <= 1);
+    assert!(pixel[1].abs_diff(pixel[2]) <= 1);
+}
+
+#[test]
+fn tdd_photo_proxy_converts_embedded_icc_profile_and_reports_color_space() {
+    let temp = tempfile::tempdir().expect("tempdir");
+    let tagged = temp.path().join("adobe.png");
+    let untagged = temp.path().join("untagged.png");
+    let icc = ColorProfile::new_adobe_rgb().encode().expect("encode icc");
+    write_png_with_icc(&tagged, [60, 160, 60], Some(icc));
+    write_png_with_icc(&untagged, [60, 160, 60], None);
+    let generator = RustPhotoProxyGenerator::default();
+
+    assert!(read_icc_profile(&untagged.display().to_string()).is_none());
+    let icc = read_icc_profile(&tagged.display().to_string()).expect("icc profile");
+    assert_eq!(
+        icc_color_space_name(&icc).as_deref(),
+        Some(ADOBE_RGB_COLOR_SPACE)
+    );
+
+    let proxy = |input: &std::path::Path, name: &str| {
+        let output = temp.path().join(name);
+        generator
+            .generate_photo_proxy(
+                &PhotoProxyRequest {
+                    input_path: input.display().to_string(),
+                    output_path: output.display().to_string(),
+                    format: PhotoProxyFormat::Webp,
+                    max_width: 16,
+                    max_height: 16,
+                },
+                &NoopProxyProgressSink,
+            )
+            .expect("proxy generation");
+        image::open(output).expect("open proxy").get_pixel(8, 8)
+    };
+    let converted = proxy(&tagged, "adobe.webp");
+    let untouched = proxy(&untagged, "untagged.webp");
+    assert!(untouched[1].abs_diff(160) <= 3);
+    assert!(
+        converted[1] > untouched[1] + 5 || converted[0] + 20 < untouched[0],
+        "converted {converted:?} should be more saturated than {untouched:?}"
+    );
+
+    let facts = generator
+        .extract_media_facts(&tagged.display().to_string(), &NoopProxyProgressSink)
+        .expect("facts");
+    assert_eq!(facts.color_space.as_deref(), Some(ADOBE_RGB_COLOR_SPACE));
+    let facts = generator
+        .extract_media_facts(&untagged.display().to_string(), &NoopProxyProgressSink)
+        .expect("facts");
+    assert_eq!(facts.color_space, None);
+}
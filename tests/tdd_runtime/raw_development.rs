@@ -0,0 +1,102 @@
+use image::GenericImageView;
+use rawloader::{CFA, Orientation, RawImage, RawImageData};
+use retaia_agent::develop_raw_image;
+
+fn bayer_frame(width: usize, height: usize, sample: impl Fn(usize) -> u16) -> RawImage {
+    let cfa = CFA::new("RGGB");
+    let data = (0..height)
+        .flat_map(|row| (0..width).map(move |col| (row, col)))
+        .map(|(row, col)| sample(cfa.color_at(row, col)))
+        .collect();
+    RawImage {
+        make: "Test".to_string(),
+        model: "Bayer".to_string(),
+        clean_make: "Test".to_string(),
+        clean_model: "Bayer".to_string(),
+        width,
+        height,
+        cpp: 1,
+        wb_coeffs: [1.0, 1.0, 1.0, f32::NAN],
+        whitelevels: [4095; 4],
+        blacklevels: [0; 4],
+        xyz_to_cam: [[0.0; 3]; 4],
+        cfa,
+        crops: [0; 4],
+        blackareas: Vec::new(),
+        orientation: Orientation::Normal,
+        data: RawImageData::Integer(data),
+    }
+}
+
+fn center_pixel(raw: &RawImage) -> [u8; 3] {
+    let image = develop_raw_image(raw).expect("raw development");
+    let pixel = image.get_pixel(image.width() / 2, image.height() / 2);
+    [pixel[0], pixel[1], pixel[2]]
+}
+
+#[test]
+fn tdd_raw_development_keeps_neutral_frame_gray() {
+    let raw = bayer_frame(8, 8, |_| 2048);
+
+    let [r, g, b] = center_pixel(&raw);
+
+    assert_eq!(r, g);
+    assert_eq!(g, b);
+    assert!(r > 128, "linear mid-level should be gamma encoded, got {r}");
+}
+
+#[test]
+fn tdd_raw_development_applies_white_balance_coefficients() {
+    let mut raw = bayer_frame(8, 8, |color| match color {
+        0 => 1000,
+        2 => 500,
+        _ => 2000,
+    });
+    raw.wb_coeffs = [2.0, 1.0, 4.0, f32::NAN];
+
+    let [r, g, b] = center_pixel(&raw);
+
+    assert!(r.abs_diff(g) <= 1, "r={r} g={g}");
+    assert!(b.abs_diff(g) <= 1, "b={b} g={g}");
+}
+
+#[test]
+fn tdd_raw_development_maps_black_level_to_black() {
+    let mut raw = bayer_frame(8, 8, |_| 512);
+    raw.blacklevels = [512; 4];
+
+    assert_eq!(center_pixel(&raw), [0, 0, 0]);
+}
+
+#[test]
+fn tdd_raw_development_respects_sensor_crops() {
+    let mut raw = bayer_frame(12, 10, |_| 1024);
+    raw.crops = [2, 2, 2, 4];
+
+    let image = develop_raw_image(&raw).expect("raw development");
+
+    assert_eq!(image.dimensions(), (6, 6));
+}
+
+#[test]
+fn tdd_raw_development_maps_saturated_white_through_camera_matrix() {
+    let mut raw = bayer_frame(8, 8, |_| 4095);
+    // Canon EOS 5D Mark II (dcraw adobe_coeff), scaled by 1/10000.
+    raw.xyz_to_cam = [
+        [0.4716, 0.0603, -0.0830],
+        [-0.7798, 1.5474, 0.2480],
+        [-0.1496, 0.1937, 0.6651],
+        [0.0, 0.0, 0.0],
+    ];
+    raw.wb_coeffs = [2.0, 1.0, 1.5, f32::NAN];
+
+    assert_eq!(center_pixel(&raw), [255, 255, 255]);
+}
+
+#[test]
+fn tdd_raw_development_rejects_truncated_sensor_data() {
+    let mut raw = bayer_frame(8, 8, |_| 1024);
+    raw.data = RawImageData::Integer(vec![0; 10]);
+
+    assert!(develop_raw_image(&raw).is_err());
+}
@@ -0,0 +1,237 @@
+use std::io::Cursor;
+use std::sync::{Arc, Mutex};
+
+use image::{DynamicImage, GenericImageView, ImageFormat};
+use retaia_agent::{
+    ClaimedDerivedJob, DerivedExecutionPlanner, DerivedJobType, NoopProxyProgressSink,
+    PhotoDecodePath, PhotoProxyFormat, PhotoProxyRequest, ProxyGenerator, RawPhotoDecoder,
+    RuntimeDerivedPlanner, RustPhotoProxyGenerator, decode_largest_embedded_preview,
+    embedded_jpeg_ranges,
+};
+
+struct CountingRawDecoder {
+    calls: Mutex<usize>,
+}
+
+impl CountingRawDecoder {
+    fn new() -> Self {
+        Self {
+            calls: Mutex::new(0),
+        }
+    }
+
+    fn calls(&self) -> usize {
+        *self.calls.lock().expect("calls")
+    }
+}
+
+impl RawPhotoDecoder for CountingRawDecoder {
+    fn decode_photo(&self, _input_path: &str) -> Result<DynamicImage, String> {
+        *self.calls.lock().expect("calls") += 1;
+        Ok(DynamicImage::new_rgb8(200, 150))
+    }
+}
+
+// Minimal little-endian TIFF writer: every entry is a single LONG value.
+struct TiffBuilder {
+    bytes: Vec<u8>,
+}
+
+impl TiffBuilder {
+    fn new() -> Self {
+        Self {
+            bytes: b"II*\0\0\0\0\0".to_vec(),
+        }
+    }
+
+    fn blob(&mut self, data: &[u8]) -> u32 {
+        let offset = self.bytes.len() as u32;
+        self.bytes.extend_from_slice(data);
+        if self.bytes.len() % 2 == 1 {
+            self.bytes.push(0);
+        }
+        offset
+    }
+
+    fn ifd(&mut self, entries: &[(u16, u32)]) -> u32 {
+        let offset = self.bytes.len() as u32;
+        self.bytes
+            .extend_from_slice(&(entries.len() as u16).to_le_bytes());
+        for (tag, value) in entries {
+            self.bytes.extend_from_slice(&tag.to_le_bytes());
+            self.bytes.extend_from_slice(&4_u16.to_le_bytes());
+            self.bytes.extend_from_slice(&1_u32.to_le_bytes());
+            self.bytes.extend_from_slice(&value.to_le_bytes());
+        }
+        self.bytes.extend_from_slice(&0_u32.to_le_bytes());
+        offset
+    }
+
+    fn finish(mut self, first_ifd: u32) -> Vec<u8> {
+        self.bytes[4..8].copy_from_slice(&first_ifd.to_le_bytes());
+        self.bytes
+    }
+}
+
+fn jpeg(width: u32, height: u32) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    DynamicImage::new_rgb8(width, height)
+        .write_to(&mut Cursor::new(&mut bytes), ImageFormat::Jpeg)
+        .expect("encode jpeg");
+    bytes
+}
+
+// NEF-like layout: small IFD0 thumbnail, large JPEG strip in a SubIFD and a
+// JPEG-compressed CFA SubIFD that must not be mistaken for a preview.
+fn raw_container(
+    preview_width: u32,
+    preview_height: u32,
+    orientation: u32,
+) -> (Vec<u8>, [std::ops::Range<usize>; 2]) {
+    let thumbnail = jpeg(160, 120);
+    let preview = jpeg(preview_width, preview_height);
+    let sensor = jpeg(64, 64);
+
+    let mut tiff = TiffBuilder::new();
+    let thumbnail_offset = tiff.blob(&thumbnail);
+    let preview_offset = tiff.blob(&preview);
+    let sensor_offset = tiff.blob(&sensor);
+    let sensor_ifd = tiff.ifd(&[
+        (0x0103, 7),
+        (0x0106, 32803),
+        (0x0111, sensor_offset),
+        (0x0117, sensor.len() as u32),
+    ]);
+    let preview_ifd = tiff.ifd(&[
+        (0x0103, 6),
+        (0x0111, preview_offset),
+        (0x0117, preview.len() as u32),
+        (0x014a, sensor_ifd),
+    ]);
+    let ifd0 = tiff.ifd(&[
+        (0x0112, orientation),
+        (0x014a, preview_ifd),
+        (0x0201, thumbnail_offset),
+        (0x0202, thumbnail.len() as u32),
+    ]);
+    let thumbnail_start = thumbnail_offset as usize;
+    let preview_start = preview_offset as usize;
+    (
+        tiff.finish(ifd0),
+        [
+            thumbnail_start..thumbnail_start + thumbnail.len(),
+            preview_start..preview_start + preview.len(),
+        ],
+    )
+}
+
+#[test]
+fn tdd_raw_embedded_preview_lists_jpeg_streams_and_skips_sensor_data() {
+    let (bytes, expected) = raw_container(800, 600, 1);
+
+    assert_eq!(embedded_jpeg_ranges(&bytes), expected.to_vec());
+}
+
+#[test]
+fn tdd_raw_embedded_preview_ignores_non_tiff_containers() {
+    assert!(embedded_jpeg_ranges(b"not a raw file").is_empty());
+    assert!(embedded_jpeg_ranges(&jpeg(16, 16)).is_empty());
+}
+
+#[test]
+fn tdd_raw_embedded_preview_decodes_largest_preview_only_when_big_enough() {
+    let (bytes, _) = raw_container(800, 600, 1);
+
+    let preview = decode_largest_embedded_preview(&bytes, 640, 640).expect("preview");
+    assert_eq!(preview.dimensions(), (800, 600));
+    assert!(decode_largest_embedded_preview(&bytes, 1000, 1000).is_none());
+}
+
+#[test]
+fn tdd_rust_photo_proxy_generator_prefers_embedded_preview_for_raw_sources() {
+    let temp = tempfile::tempdir().expect("tempdir");
+    let input = temp.path().join("DSC_0001.NEF");
+    std::fs::write(&input, raw_container(800, 600, 1).0).expect("write raw");
+    let generator = RustPhotoProxyGenerator::new(CountingRawDecoder::new());
+    let request = |name: &str, max: u16| PhotoProxyRequest {
+        input_path: input.display().to_string(),
+        output_path: temp.path().join(name).display().to_string(),
+        format: PhotoProxyFormat::Webp,
+        max_width: max,
+        max_height: max,
+    };
+
+    let decode_path = generator
+        .generate_photo_proxy(&request("small.webp", 400), &NoopProxyProgressSink)
+        .expect("embedded preview proxy");
+    assert_eq!(decode_path, Some(PhotoDecodePath::EmbeddedPreview));
+    assert_eq!(generator.raw_decoder().calls(), 0);
+    let produced = image::open(temp.path().join("small.webp")).expect("open proxy");
+    assert_eq!(produced.dimensions(), (400, 300));
+
+    let decode_path = generator
+        .generate_photo_proxy(&request("large.webp", 1000), &NoopProxyProgressSink)
+        .expect("raw development proxy");
+    assert_eq!(decode_path, Some(PhotoDecodePath::RawDevelopment));
+    assert_eq!(generator.raw_decoder().calls(), 1);
+}
+
+#[test]
+fn tdd_runtime_derived_planner_reports_photo_decode_path_in_metrics() {
+    let photo_generator = Arc::new(RustPhotoProxyGenerator::new(CountingRawDecoder::new()));
+    let planner = RuntimeDerivedPlanner::new(
+        Arc::new(RustPhotoProxyGenerator::default()),
+        photo_generator,
+    );
+    let claimed = ClaimedDerivedJob {
+        job_id: "job-thumb-nef".to_string(),
+        asset_uuid: "asset-thumb-nef".to_string(),
+        lock_token: "lock-thumb-nef".to_string(),
+        fencing_token: 1,
+        job_type: DerivedJobType::GenerateThumbnails,
+        source_storage_id: "nas-main".to_string(),
+        source_original_relative: "INBOX/DSC_0001.NEF".to_string(),
+        source_sidecars_relative: Vec::new(),
+    };
+    let dir = tempfile::tempdir().expect("tempdir");
+    let staged = dir.path().join("DSC_0001.NEF");
+    std::fs::write(&staged, raw_container(800, 600, 1).0).expect("write raw");
+
+    let plan = planner
+        .plan_for_claimed_job_with_source(&claimed, Some(staged.as_path()), &[])
+        .expect("plan");
+
+    let metrics = plan.submit.metrics.expect("metrics");
+    assert_eq!(
+        metrics.get("photo_decode_path"),
+        Some(&serde_json::json!("embedded_preview"))
+    );
+}
+
+#[test]
+fn tdd_rust_photo_proxy_generator_orients_embedded_preview_of_portrait_raw() {
+    let temp = tempfile::tempdir().expect("tempdir");
+    let input = temp.path().join("DSC_0002.NEF");
+    let output = temp.path().join("portrait.webp");
+    std::fs::write(&input, raw_container(800, 600, 6).0).expect("write raw");
+    let generator = RustPhotoProxyGenerator::new(CountingRawDecoder::new());
+
+    // 800x600 stored sideways only covers an 850x700 box once rotated upright.
+    let decode_path = generator
+        .generate_photo_proxy(
+            &PhotoProxyRequest {
+                input_path: input.display().to_string(),
+                output_path: output.display().to_string(),
+                format: PhotoProxyFormat::Webp,
+                max_width: 850,
+                max_height: 700,
+            },
+            &NoopProxyProgressSink,
+        )
+        .expect("embedded preview proxy");
+
+    assert_eq!(decode_path, Some(PhotoDecodePath::EmbeddedPreview));
+    assert_eq!(generator.raw_decoder().calls(), 0);
+    let produced = image::open(&output).expect("open proxy");
+    assert_eq!(produced.dimensions(), (525, 700));
+}
@@ -1,7 +1,9 @@
 use retaia_agent::{
     AudioProxyRequest, AudioWaveformRequest, ClaimedDerivedJob, DerivedExecutionPlanner,
-    DerivedJobType, DerivedKind, FactsPatchPayload, PhotoProxyRequest, ProxyGenerationError,
-    ProxyGenerator, RuntimeDerivedPlanner, VideoProxyRequest, VideoThumbnailRequest,
+    DerivedJobType, DerivedKind, FactsPatchPayload, PhotoDecodePath, PhotoProxyRequest,
+    ProxyGenerationError, ProxyGenerator, ProxyProgressSink, RuntimeDerivedPlanner,
+    VideoHlsLadderRequest, VideoPreviewProfile, VideoProxyRequest, VideoRendition,
+    VideoThumbnailRequest,
 };
 use std::sync::Arc;
 use std::sync::Mutex;
@@ -13,6 +15,7 @@ impl ProxyGenerator for WritingPreviewGenerator {
     fn generate_video_proxy(
         &self,
         request: &VideoProxyRequest,
+        _progress: &dyn ProxyProgressSink,
     ) -> Result<(), ProxyGenerationError> {
         std::fs::write(&request.output_path, b"generated-video")
             .map_err(|error| ProxyGenerationError::Process(error.to_string()))
@@ -21,22 +24,47 @@ impl ProxyGenerator for WritingPreviewGenerator {
     fn generate_audio_proxy(
         &self,
         request: &AudioProxyRequest,
+        _progress: &dyn ProxyProgressSink,
     ) -> Result<(), ProxyGenerationError> {
         std::fs::write(&request.output_path, b"generated-audio")
             .map_err(|error| ProxyGenerationError::Process(error.to_string()))
     }
 
+    fn generate_video_hls_ladder(
+        &self,
+        request: &VideoHlsLadderRequest,
+        _progress: &dyn ProxyProgressSink,
+    ) -> Result<(), ProxyGenerationError> {
+        let dir = std::path::Path::new(&request.output_dir);
+        let write = |name: String, bytes: &[u8]| {
+            std::fs::write(dir.join(name), bytes)
+                .map_err(|error| ProxyGenerationError::Process(error.to_string()))
+        };
+        std::fs::create_dir_all(dir)
+            .map_err(|error| ProxyGenerationError::Process(error.to_string()))?;
+        write("master.m3u8".to_string(), b"#EXTM3U")?;
+        for rendition in &request.renditions {
+            write(format!("{}.m3u8", rendition.name), b"#EXTM3U")?;
+            write(format!("{}_init.mp4", rendition.name), b"init")?;
+            write(format!("{}_00000.m4s", rendition.name), b"segment")?;
+        }
+        Ok(())
+    }
+
     fn generate_photo_proxy(
         &self,
         request: &PhotoProxyRequest,
-    ) -> Result<(), ProxyGenerationError> {
+        _progress: &dyn ProxyProgressSink,
+    ) -> Result<Option<PhotoDecodePath>, ProxyGenerationError> {
         std::fs::write(&request.output_path, b"generated-photo")
             .map_err(|error| ProxyGenerationError::Process(error.to_string()))
+            .map(|()| None)
     }
 
     fn generate_video_thumbnail(
         &self,
         request: &VideoThumbnailRequest,
+        _progress: &dyn ProxyProgressSink,
     ) -> Result<(), ProxyGenerationError> {
         std::fs::write(&request.output_path, b"generated-thumbnail")
             .map_err(|error| ProxyGenerationError::Process(error.to_string()))
@@ -45,6 +73,7 @@ impl ProxyGenerator for WritingPreviewGenerator {
     fn generate_audio_waveform(
         &self,
         request: &AudioWaveformRequest,
+        _progress: &dyn ProxyProgressSink,
     ) -> Result<(), ProxyGenerationError> {
         std::fs::write(
             &request.output_path,
@@ -56,6 +85,7 @@ impl ProxyGenerator for WritingPreviewGenerator {
     fn extract_media_facts(
         &self,
         _input_path: &str,
+        _progress: &dyn ProxyProgressSink,
     ) -> Result<FactsPatchPayload, ProxyGenerationError> {
         Ok(FactsPatchPayload {
             duration_ms: Some(2_000),
@@ -83,6 +113,7 @@ impl ProxyGenerator for UnknownDurationThumbnailGenerator {
     fn generate_video_proxy(
         &self,
         request: &VideoProxyRequest,
+        _progress: &dyn ProxyProgressSink,
     ) -> Result<(), ProxyGenerationError> {
         std::fs::write(&request.output_path, b"generated-video")
             .map_err(|error| ProxyGenerationError::Process(error.to_string()))
@@ -91,6 +122,7 @@ impl ProxyGenerator for UnknownDurationThumbnailGenerator {
     fn generate_audio_proxy(
         &self,
         request: &AudioProxyRequest,
+        _progress: &dyn ProxyProgressSink,
     ) -> Result<(), ProxyGenerationError> {
         std::fs::write(&request.output_path, b"generated-audio")
             .map_err(|error| ProxyGenerationError::Process(error.to_string()))
@@ -99,14 +131,17 @@ impl ProxyGenerator for UnknownDurationThumbnailGenerator {
     fn generate_photo_proxy(
         &self,
         request: &PhotoProxyRequest,
-    ) -> Result<(), ProxyGenerationError> {
+        _progress: &dyn ProxyProgressSink,
+    ) -> Result<Option<PhotoDecodePath>, ProxyGenerationError> {
         std::fs::write(&request.output_path, b"generated-photo")
             .map_err(|error| ProxyGenerationError::Process(error.to_string()))
+            .map(|()| None)
     }
 
     fn generate_video_thumbnail(
         &self,
         request: &VideoThumbnailRequest,
+        _progress: &dyn ProxyProgressSink,
     ) -> Result<(), ProxyGenerationError> {
         std::fs::write(&request.output_path, b"generated-thumbnail")
             .map_err(|error| ProxyGenerationError::Process(error.to_string()))
@@ -115,6 +150,7 @@ impl ProxyGenerator for UnknownDurationThumbnailGenerator {
     fn generate_audio_waveform(
         &self,
         request: &AudioWaveformRequest,
+        _progress: &dyn ProxyProgressSink,
     ) -> Result<(), ProxyGenerationError> {
         std::fs::write(
             &request.output_path,
@@ -126,6 +162,7 @@ impl ProxyGenerator for UnknownDurationThumbnailGenerator {
     fn extract_media_facts(
         &self,
         _input_path: &str,
+        _progress: &dyn ProxyProgressSink,
     ) -> Result<FactsPatchPayload, ProxyGenerationError> {
         Ok(FactsPatchPayload {
             duration_ms: None,
@@ -160,6 +197,7 @@ impl ProxyGenerator for ThumbnailFactsGenerator {
     fn generate_video_proxy(
         &self,
         request: &VideoProxyRequest,
+        _progress: &dyn ProxyProgressSink,
     ) -> Result<(), ProxyGenerationError> {
         std::fs::write(&request.output_path, b"generated-video")
             .map_err(|error| ProxyGenerationError::Process(error.to_string()))
@@ -168,6 +206,7 @@ impl ProxyGenerator for ThumbnailFactsGenerator {
     fn generate_audio_proxy(
         &self,
         request: &AudioProxyRequest,
+        _progress: &dyn ProxyProgressSink,
     ) -> Result<(), ProxyGenerationError> {
         std::fs::write(&request.output_path, b"generated-audio")
             .map_err(|error| ProxyGenerationError::Process(error.to_string()))
@@ -176,14 +215,17 @@ impl ProxyGenerator for ThumbnailFactsGenerator {
     fn generate_photo_proxy(
         &self,
         request: &PhotoProxyRequest,
-    ) -> Result<(), ProxyGenerationError> {
+        _progress: &dyn ProxyProgressSink,
+    ) -> Result<Option<PhotoDecodePath>, ProxyGenerationError> {
         std::fs::write(&request.output_path, b"generated-photo")
             .map_err(|error| ProxyGenerationError::Process(error.to_string()))
+            .map(|()| None)
     }
 
     fn generate_video_thumbnail(
         &self,
         request: &VideoThumbnailRequest,
+        _progress: &dyn ProxyProgressSink,
     ) -> Result<(), ProxyGenerationError> {
         self.thumbnail_requests
             .lock()
@@ -196,6 +238,7 @@ impl ProxyGenerator for ThumbnailFactsGenerator {
     fn generate_audio_waveform(
         &self,
         request: &AudioWaveformRequest,
+        _progress: &dyn ProxyProgressSink,
     ) -> Result<(), ProxyGenerationError> {
         std::fs::write(
             &request.output_path,
@@ -207,6 +250,7 @@ impl ProxyGenerator for ThumbnailFactsGenerator {
     fn extract_media_facts(
         &self,
         _input_path: &str,
+        _progress: &dyn ProxyProgressSink,
     ) -> Result<FactsPatchPayload, ProxyGenerationError> {
         Ok(FactsPatchPayload {
             duration_ms: Some(self.duration_ms),
@@ -306,6 +350,85 @@ fn tdd_runtime_derived_planner_with_staged_source_builds_upload_plan() {
     );
 }
 
+#[test]
+fn tdd_runtime_derived_planner_packages_hls_ladder_when_profile_is_enabled() {
+    let planner = RuntimeDerivedPlanner::new(
+        Arc::new(WritingPreviewGenerator),
+        Arc::new(WritingPreviewGenerator),
+    )
+    .with_video_preview_profile(VideoPreviewProfile::HlsLadder(vec![
+        VideoRendition::new("360p", 640, 360, 800),
+        VideoRendition::new("720p", 1280, 720, 2_500),
+    ]));
+    let claimed = ClaimedDerivedJob {
+        job_id: "job-video-hls".to_string(),
+        asset_uuid: "asset-video-hls".to_string(),
+        lock_token: "lock-video-hls".to_string(),
+        fencing_token: 1,
+        job_type: DerivedJobType::GeneratePreview,
+        source_storage_id: "nas-main".to_string(),
+        source_original_relative: "INBOX/clip.mov".to_string(),
+        source_sidecars_relative: Vec::new(),
+    };
+    let dir = tempfile::tempdir().expect("tempdir");
+    let staged = dir.path().join("clip.mov");
+    std::fs::write(&staged, b"staged-bytes").expect("write");
+
+    let plan = planner
+        .plan_for_claimed_job_with_source(&claimed, Some(staged.as_path()), &[])
+        .expect("plan");
+
+    assert_eq!(plan.uploads.len(), 7);
+    assert!(
+        plan.uploads
+            .iter()
+            .all(|upload| upload.init.kind == DerivedKind::PreviewVideoHls)
+    );
+    assert!(plan.uploads.iter().all(|upload| {
+        DerivedKind::PreviewVideoHls.allows_content_type(&upload.init.content_type)
+    }));
+    assert_eq!(
+        plan.uploads[0].init.content_type,
+        "application/vnd.apple.mpegurl"
+    );
+    let references = plan
+        .submit
+        .manifest
+        .iter()
+        .map(|item| item.reference.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        references[0],
+        "/api/v1/assets/asset-video-hls/derived/preview_video_hls/master.m3u8"
+    );
+    assert!(
+        references
+            .contains(&"/api/v1/assets/asset-video-hls/derived/preview_video_hls/720p_00000.m4s")
+    );
+    assert!(
+        plan.submit
+            .manifest
+            .iter()
+            .all(|item| item.sha256.is_some())
+    );
+    let segment = plan
+        .uploads
+        .iter()
+        .find(|upload| upload.parts[0].chunk_path.ends_with("360p_00000.m4s"))
+        .expect("segment upload");
+    assert_eq!(segment.init.content_type, "video/iso.segment");
+    assert_eq!(segment.init.size_bytes, 7);
+    let metrics = plan.submit.metrics.expect("preview metrics");
+    assert_eq!(
+        metrics.get("preview_kind"),
+        Some(&serde_json::json!("preview_video_hls"))
+    );
+    assert_eq!(
+        metrics.get("preview_renditions"),
+        Some(&serde_json::json!(["360p", "720p"]))
+    );
+}
+
 #[test]
 fn tdd_runtime_derived_planner_builds_photo_preview_upload_as_webp() {
     let planner = RuntimeDerivedPlanner::new(
@@ -391,6 +514,50 @@ fn tdd_runtime_derived_planner_builds_thumbnail_upload_as_webp() {
     assert_eq!(metrics.get("thumbnail_count"), Some(&serde_json::json!(9)));
 }
 
+#[test]
+fn tdd_runtime_derived_planner_builds_single_photo_thumbnail_without_video_seeking() {
+    let video_generator = Arc::new(ThumbnailFactsGenerator::new(180_000));
+    let planner =
+        RuntimeDerivedPlanner::new(video_generator.clone(), Arc::new(WritingPreviewGenerator));
+    let claimed = ClaimedDerivedJob {
+        job_id: "job-thumb-photo".to_string(),
+        asset_uuid: "asset-thumb-photo".to_string(),
+        lock_token: "lock-thumb-photo".to_string(),
+        fencing_token: 1,
+        job_type: DerivedJobType::GenerateThumbnails,
+        source_storage_id: "nas-main".to_string(),
+        source_original_relative: "INBOX/DSC_0001.NEF".to_string(),
+        source_sidecars_relative: Vec::new(),
+    };
+    let dir = tempfile::tempdir().expect("tempdir");
+    let staged = dir.path().join("DSC_0001.NEF");
+    std::fs::write(&staged, b"raw-bytes").expect("write");
+
+    let plan = planner
+        .plan_for_claimed_job_with_source(&claimed, Some(staged.as_path()), &[])
+        .expect("plan");
+
+    assert!(video_generator.thumbnail_requests().is_empty());
+    assert_eq!(plan.uploads.len(), 1);
+    assert_eq!(plan.uploads[0].init.kind, DerivedKind::Thumb);
+    assert_eq!(plan.uploads[0].init.content_type, "image/webp");
+    assert!(
+        plan.uploads[0].parts[0]
+            .chunk_path
+            .ends_with("DSC_0001.thumb.1.webp")
+    );
+    assert_eq!(
+        plan.submit.manifest[0].reference,
+        "/api/v1/assets/asset-thumb-photo/derived/thumb"
+    );
+    let metrics = plan.submit.metrics.expect("thumbnail metrics");
+    assert_eq!(
+        metrics.get("thumbnail_profile"),
+        Some(&serde_json::json!("photo_representative_v1"))
+    );
+    assert_eq!(metrics.get("thumbnail_count"), Some(&serde_json::json!(1)));
+}
+
 #[test]
 fn tdd_runtime_derived_planner_uses_short_video_representative_seek() {
     let generator = Arc::new(ThumbnailFactsGenerator::new(90_000));
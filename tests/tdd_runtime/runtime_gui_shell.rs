@@ -1,7 +1,7 @@
 use retaia_agent::{
     AgentRuntimeConfig, AuthMode, ClientRuntimeTarget, DaemonLabelRequest, DaemonLevel,
-    DaemonManager, DaemonManagerError, DaemonStatus, LogLevel, RuntimeSession,
-    apply_gui_menu_action, menu_view,
+    DaemonManager, DaemonManagerError, DaemonStatus, JobSelectionStrategy, LogLevel,
+    RuntimeSession, apply_gui_menu_action, menu_view,
 };
 use retaia_agent::{GuiDaemonContext, GuiMenuAction};
 
@@ -14,6 +14,8 @@ fn config() -> AgentRuntimeConfig {
         storage_mounts: std::collections::BTreeMap::new(),
         max_parallel_jobs: 2,
         log_level: LogLevel::Info,
+        job_selection: JobSelectionStrategy::default(),
+        upload_part_size_bytes: None,
     }
 }
 
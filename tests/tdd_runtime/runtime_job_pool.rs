@@ -0,0 +1,531 @@
+use std::collections::BTreeSet;
+use std::sync::atomic::{AtomicBool, AtomicI32, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use retaia_agent::{
+    AbandonedJob, AgentRunState, AgentRuntimeConfig, AuthMode, CancellationReason,
+    ClaimedDerivedJob, ClientRuntimeTarget, CoreApiGateway, CoreApiGatewayError, CoreJobState,
+    CoreJobView, CoreServerPolicy, DerivedExecutionPlan, DerivedExecutionPlanner,
+    DerivedJobExecutorError, DerivedJobFailure, DerivedJobType, DerivedProcessingError,
+    DerivedProcessingGateway, DerivedUploadComplete, DerivedUploadInit, DerivedUploadPart,
+    FactsPatchPayload, HeartbeatReceipt, JobSelectionStrategy, JobStage, LogLevel, MenuAction,
+    PollEndpoint, ProxyGenerationError, ProxyProgressSink, RuntimeJobOutcome, RuntimeJobPool,
+    RuntimeSession, SubmitDerivedPayload, UploadedDerivedPart,
+};
+
+fn write_storage_marker(root: &std::path::Path, storage_id: &str) {
+    let marker = format!(
+        r#"{{"version":1,"storage_id":"{storage_id}","paths":{{"inbox":"INBOX","archive":"ARCHIVE","rejects":"REJECTS"}}}}"#
+    );
+    std::fs::write(root.join(".retaia"), marker).expect("write marker");
+}
+
+#[derive(Debug)]
+struct PendingJobsGateway {
+    job_ids: Vec<&'static str>,
+}
+
+impl CoreApiGateway for PendingJobsGateway {
+    fn poll_jobs(&self) -> Result<Vec<CoreJobView>, CoreApiGatewayError> {
+        Ok(self
+            .job_ids
+            .iter()
+            .map(|job_id| CoreJobView {
+                job_id: job_id.to_string(),
+                asset_uuid: format!("asset-{job_id}"),
+                job_type: None,
+                state: CoreJobState::Pending,
+                required_capabilities: vec!["media.facts@1".to_string()],
+            })
+            .collect())
+    }
+}
+
+#[derive(Debug, Default)]
+struct RecordingDerivedGateway {
+    calls: Mutex<Vec<String>>,
+    renewed_fencing_token: AtomicI32,
+    fail_fencing_tokens: Mutex<Vec<i32>>,
+}
+
+impl RecordingDerivedGateway {
+    fn calls(&self) -> Vec<String> {
+        self.calls.lock().expect("calls mutex").clone()
+    }
+
+    fn fail_fencing_tokens(&self) -> Vec<i32> {
+        self.fail_fencing_tokens
+            .lock()
+            .expect("fencing mutex")
+            .clone()
+    }
+
+    fn record(&self, call: String) {
+        self.calls.lock().expect("calls mutex").push(call);
+    }
+}
+
+impl DerivedProcessingGateway for RecordingDerivedGateway {
+    fn claim_job(&self, job_id: &str) -> Result<ClaimedDerivedJob, DerivedProcessingError> {
+        self.record(format!("claim:{job_id}"));
+        Ok(ClaimedDerivedJob {
+            job_id: job_id.to_string(),
+            asset_uuid: format!("asset-{job_id}"),
+            lock_token: format!("lock-{job_id}"),
+            fencing_token: 1,
+            job_type: DerivedJobType::ExtractFacts,
+            source_storage_id: "nas-main".to_string(),
+            source_original_relative: "INBOX/source.mov".to_string(),
+            source_sidecars_relative: Vec::new(),
+        })
+    }
+
+    fn fetch_asset_revision_etag(
+        &self,
+        _asset_uuid: &str,
+    ) -> Result<String, DerivedProcessingError> {
+        Ok("\"asset-rev-1\"".to_string())
+    }
+
+    fn heartbeat(
+        &self,
+        _job_id: &str,
+        _lock_token: &str,
+        fencing_token: i32,
+    ) -> Result<HeartbeatReceipt, DerivedProcessingError> {
+        // Core bumps the fencing token on every renewal.
+        self.renewed_fencing_token
+            .store(fencing_token + 1, Ordering::SeqCst);
+        Ok(HeartbeatReceipt {
+            locked_until: None,
+            fencing_token: fencing_token + 1,
+        })
+    }
+
+    fn submit_derived(
+        &self,
+        job_id: &str,
+        _lock_token: &str,
+        _fencing_token: i32,
+        _idempotency_key: &str,
+        _payload: &SubmitDerivedPayload,
+    ) -> Result<(), DerivedProcessingError> {
+        self.record(format!("submit:{job_id}"));
+        Ok(())
+    }
+
+    fn fail_job(
+        &self,
+        job_id: &str,
+        _lock_token: &str,
+        fencing_token: i32,
+        _idempotency_key: &str,
+        failure: &DerivedJobFailure,
+    ) -> Result<(), DerivedProcessingError> {
+        self.record(format!("fail:{job_id}:{}", failure.error_code));
+        self.fail_fencing_tokens
+            .lock()
+            .expect("fencing mutex")
+            .push(fencing_token);
+        Ok(())
+    }
+
+    fn upload_init(&self, _request: &DerivedUploadInit) -> Result<(), DerivedProcessingError> {
+        Ok(())
+    }
+
+    fn upload_part(
+        &self,
+        request: &DerivedUploadPart,
+    ) -> Result<UploadedDerivedPart, DerivedProcessingError> {
+        Ok(UploadedDerivedPart {
+            part_number: request.part_number,
+            part_etag: format!("etag-{}", request.part_number),
+        })
+    }
+
+    fn upload_complete(
+        &self,
+        _request: &DerivedUploadComplete,
+    ) -> Result<(), DerivedProcessingError> {
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default)]
+struct GatedFactsPlanner {
+    released: AtomicBool,
+    active: AtomicUsize,
+    max_active: AtomicUsize,
+}
+
+impl GatedFactsPlanner {
+    fn release(&self) {
+        self.released.store(true, Ordering::SeqCst);
+    }
+}
+
+impl DerivedExecutionPlanner for GatedFactsPlanner {
+    fn plan_for_claimed_job(
+        &self,
+        claimed: &ClaimedDerivedJob,
+    ) -> Result<DerivedExecutionPlan, DerivedJobExecutorError> {
+        let active = self.active.fetch_add(1, Ordering::SeqCst) + 1;
+        self.max_active.fetch_max(active, Ordering::SeqCst);
+        while !self.released.load(Ordering::SeqCst) {
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        self.active.fetch_sub(1, Ordering::SeqCst);
+        Ok(DerivedExecutionPlan {
+            uploads: Vec::new(),
+            submit: SubmitDerivedPayload {
+                job_type: claimed.job_type,
+                manifest: Vec::new(),
+                facts_patch: Some(FactsPatchPayload::default()),
+                transcript_patch: None,
+                warnings: None,
+                metrics: None,
+            },
+            submit_idempotency_key: format!("agent-submit-{}", claimed.job_id),
+        })
+    }
+}
+
+#[derive(Debug, Default)]
+struct CancellableProxyPlanner;
+
+impl DerivedExecutionPlanner for CancellableProxyPlanner {
+    fn plan_for_claimed_job(
+        &self,
+        _claimed: &ClaimedDerivedJob,
+    ) -> Result<DerivedExecutionPlan, DerivedJobExecutorError> {
+        Err(DerivedJobExecutorError::Planner(
+            "progress-aware planning expected".to_string(),
+        ))
+    }
+
+    fn plan_for_claimed_job_with_progress(
+        &self,
+        _claimed: &ClaimedDerivedJob,
+        _staged_source_path: Option<&std::path::Path>,
+        _staged_sidecar_paths: &[std::path::PathBuf],
+        progress: &dyn ProxyProgressSink,
+    ) -> Result<DerivedExecutionPlan, DerivedJobExecutorError> {
+        let cancellation = progress.cancellation().expect("cancellation token");
+        wait_until(|| cancellation.is_cancelled());
+        Err(DerivedJobExecutorError::ProxyGeneration(
+            ProxyGenerationError::Cancelled,
+        ))
+    }
+}
+
+fn session_with_source(max_parallel_jobs: u16) -> (tempfile::TempDir, RuntimeSession) {
+    let source_root = tempfile::tempdir().expect("source root");
+    write_storage_marker(source_root.path(), "nas-main");
+    let source_path = source_root.path().join("INBOX/source.mov");
+    std::fs::create_dir_all(source_path.parent().expect("parent")).expect("create dirs");
+    std::fs::write(&source_path, b"source").expect("write source");
+
+    let mut mounts = std::collections::BTreeMap::new();
+    mounts.insert(
+        "nas-main".to_string(),
+        source_root.path().display().to_string(),
+    );
+    let settings = AgentRuntimeConfig {
+        core_api_url: "http://localhost:3000/api/v1".to_string(),
+        ollama_url: "http://localhost:11434".to_string(),
+        auth_mode: AuthMode::Interactive,
+        technical_auth: None,
+        storage_mounts: mounts,
+        max_parallel_jobs,
+        log_level: LogLevel::Info,
+        job_selection: JobSelectionStrategy::default(),
+        upload_part_size_bytes: None,
+    };
+    let mut session = RuntimeSession::new(ClientRuntimeTarget::Agent, settings).expect("session");
+    session.apply_server_policy(CoreServerPolicy {
+        min_poll_interval_seconds: Some(5),
+        feature_flags: std::collections::BTreeMap::new(),
+    });
+    let _ = session.on_poll_success(PollEndpoint::Jobs, 5_000, true);
+    (source_root, session)
+}
+
+fn wait_until(mut condition: impl FnMut() -> bool) {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while !condition() {
+        assert!(Instant::now() < deadline, "condition not met in time");
+        std::thread::sleep(Duration::from_millis(5));
+    }
+}
+
+fn drain(pool: &mut RuntimeJobPool) -> Vec<RuntimeJobOutcome> {
+    let mut outcomes = Vec::new();
+    wait_until(|| {
+        outcomes.extend(pool.collect_finished());
+        pool.is_idle()
+    });
+    outcomes
+}
+
+#[test]
+fn tdd_runtime_job_pool_runs_claimed_jobs_concurrently_up_to_max_parallel_jobs() {
+    let (_source_root, session) = session_with_source(2);
+    let core = PendingJobsGateway {
+        job_ids: vec!["job-1", "job-2", "job-3"],
+    };
+    let derived = Arc::new(RecordingDerivedGateway::default());
+    let planner = Arc::new(GatedFactsPlanner::default());
+    let mut pool = RuntimeJobPool::new(derived.clone(), planner.clone());
+
+    let started = pool
+        .dispatch_pending_jobs(&session, &core)
+        .expect("dispatch");
+    assert_eq!(started, vec!["job-1".to_string(), "job-2".to_string()]);
+    assert_eq!(pool.available_slots(2), 0);
+    wait_until(|| planner.active.load(Ordering::SeqCst) == 2);
+
+    assert!(
+        pool.dispatch_pending_jobs(&session, &core)
+            .expect("dispatch")
+            .is_empty()
+    );
+    let snapshot = pool.snapshot();
+    assert_eq!(snapshot.running_job_ids.len(), 2);
+    let current = snapshot.current_job.expect("current job");
+    assert_eq!(current.stage, JobStage::Processing);
+    assert_eq!(current.progress_percent, 10);
+
+    planner.release();
+    let outcomes = drain(&mut pool);
+    assert_eq!(outcomes.len(), 2);
+    assert!(outcomes.iter().all(|outcome| outcome.result.is_ok()));
+    assert_eq!(planner.max_active.load(Ordering::SeqCst), 2);
+    assert!(pool.snapshot().running_job_ids.is_empty());
+    assert!(pool.snapshot().current_job.is_none());
+    let calls = derived.calls();
+    assert!(calls.contains(&"submit:job-1".to_string()));
+    assert!(calls.contains(&"submit:job-2".to_string()));
+    assert!(!calls.contains(&"claim:job-3".to_string()));
+}
+
+#[test]
+fn tdd_runtime_job_pool_take_snapshot_reports_finished_jobs_once() {
+    let (_source_root, session) = session_with_source(2);
+    let core = PendingJobsGateway {
+        job_ids: vec!["job-1", "job-2"],
+    };
+    let derived = Arc::new(RecordingDerivedGateway::default());
+    let planner = Arc::new(GatedFactsPlanner::default());
+    let mut pool = RuntimeJobPool::new(derived, planner.clone());
+
+    pool.dispatch_pending_jobs(&session, &core)
+        .expect("dispatch");
+    planner.release();
+    drain(&mut pool);
+
+    let reported = pool.take_snapshot();
+    assert!(reported.known_job_ids.contains("job-1"));
+    assert!(reported.known_job_ids.contains("job-2"));
+    let next = pool.take_snapshot();
+    assert!(next.known_job_ids.is_empty());
+    assert!(next.failed_jobs.is_empty());
+}
+
+#[test]
+fn tdd_runtime_job_pool_selects_jobs_against_the_capabilities_it_was_built_with() {
+    let (_source_root, session) = session_with_source(2);
+    let core = PendingJobsGateway {
+        job_ids: vec!["job-1"],
+    };
+    let derived = Arc::new(RecordingDerivedGateway::default());
+    let planner = Arc::new(GatedFactsPlanner::default());
+    planner.release();
+    let mut pool = RuntimeJobPool::new(derived.clone(), planner)
+        .with_declared_capabilities(BTreeSet::from(["audio.waveform@1".to_string()]));
+
+    for _ in 0..2 {
+        assert!(
+            pool.dispatch_pending_jobs(&session, &core)
+                .expect("dispatch")
+                .is_empty()
+        );
+    }
+    assert!(derived.calls().is_empty());
+}
+
+#[test]
+fn tdd_runtime_job_pool_does_not_dispatch_while_paused() {
+    let (_source_root, mut session) = session_with_source(4);
+    let core = PendingJobsGateway {
+        job_ids: vec!["job-1"],
+    };
+    let derived = Arc::new(RecordingDerivedGateway::default());
+    let planner = Arc::new(GatedFactsPlanner::default());
+    planner.release();
+    let mut pool = RuntimeJobPool::new(derived.clone(), planner);
+
+    session.on_menu_action(MenuAction::Pause);
+    assert!(
+        pool.dispatch_pending_jobs(&session, &core)
+            .expect("dispatch")
+            .is_empty()
+    );
+    assert!(pool.is_idle());
+    assert!(derived.calls().is_empty());
+
+    session.on_menu_action(MenuAction::PlayResume);
+    assert_eq!(
+        pool.dispatch_pending_jobs(&session, &core)
+            .expect("dispatch"),
+        vec!["job-1".to_string()]
+    );
+    let outcomes = drain(&mut pool);
+    assert_eq!(outcomes.len(), 1);
+    assert_eq!(outcomes[0].job_id, "job-1");
+}
+
+#[test]
+fn tdd_runtime_job_pool_reports_failed_jobs_in_snapshot() {
+    let (source_root, session) = session_with_source(1);
+    std::fs::remove_file(source_root.path().join("INBOX/source.mov")).expect("remove source");
+    let core = PendingJobsGateway {
+        job_ids: vec!["job-1"],
+    };
+    let derived = Arc::new(RecordingDerivedGateway::default());
+    let planner = Arc::new(GatedFactsPlanner::default());
+    planner.release();
+    let mut pool = RuntimeJobPool::new(derived.clone(), planner);
+
+    pool.dispatch_pending_jobs(&session, &core)
+        .expect("dispatch");
+    let outcomes = drain(&mut pool);
+
+    assert_eq!(outcomes.len(), 1);
+    let error = outcomes[0].result.as_ref().expect_err("job should fail");
+    let snapshot = pool.snapshot();
+    assert!(snapshot.running_job_ids.is_empty());
+    assert_eq!(snapshot.failed_jobs.len(), 1);
+    assert_eq!(snapshot.failed_jobs[0].job_id, "job-1");
+    assert_eq!(snapshot.failed_jobs[0].error_code, error.error_code());
+    assert!(
+        derived
+            .calls()
+            .contains(&format!("fail:job-1:{}", error.error_code()))
+    );
+}
+
+#[test]
+fn tdd_runtime_job_pool_cancels_in_flight_jobs_when_paused_and_releases_them_as_retryable() {
+    let (_source_root, session) = session_with_source(1);
+    let core = PendingJobsGateway {
+        job_ids: vec!["job-1"],
+    };
+    let derived = Arc::new(RecordingDerivedGateway::default());
+    let mut pool = RuntimeJobPool::new(derived.clone(), Arc::new(CancellableProxyPlanner));
+
+    pool.dispatch_pending_jobs(&session, &core)
+        .expect("dispatch");
+    wait_until(|| derived.calls().contains(&"claim:job-1".to_string()));
+    assert!(pool.cancel_for_run_state(AgentRunState::Running).is_empty());
+    assert_eq!(
+        pool.cancel_for_run_state(AgentRunState::Paused),
+        vec!["job-1".to_string()]
+    );
+    assert!(pool.cancel_all(CancellationReason::Stopped).is_empty());
+    let outcomes = drain(&mut pool);
+
+    assert_eq!(outcomes.len(), 1);
+    let error = outcomes[0].result.as_ref().expect_err("job cancelled");
+    assert_eq!(
+        *error,
+        DerivedJobExecutorError::Cancelled(CancellationReason::Paused)
+    );
+    assert!(error.is_retryable());
+    assert!(
+        derived
+            .calls()
+            .contains(&"fail:job-1:JOB_CANCELLED".to_string())
+    );
+}
+
+#[test]
+fn tdd_runtime_job_pool_releases_leases_of_workers_that_ignore_cancellation() {
+    let (_source_root, session) = session_with_source(1);
+    let core = PendingJobsGateway {
+        job_ids: vec!["job-1"],
+    };
+    let derived = Arc::new(RecordingDerivedGateway::default());
+    let planner = Arc::new(GatedFactsPlanner::default());
+    let mut pool = RuntimeJobPool::new(derived.clone(), planner.clone());
+
+    pool.dispatch_pending_jobs(&session, &core)
+        .expect("dispatch");
+    wait_until(|| planner.active.load(Ordering::SeqCst) == 1);
+
+    let abandoned = pool.release_abandoned_jobs(Duration::from_millis(50));
+
+    assert!(matches!(
+        abandoned.as_slice(),
+        [AbandonedJob { job_id, release: Some(Ok(())) }] if job_id == "job-1"
+    ));
+    assert!(
+        derived
+            .calls()
+            .contains(&"fail:job-1:JOB_CANCELLED".to_string())
+    );
+    planner.release();
+    drain(&mut pool);
+}
+
+#[test]
+fn tdd_runtime_job_pool_releases_abandoned_leases_with_the_renewed_fencing_token() {
+    let (_source_root, session) = session_with_source(1);
+    let core = PendingJobsGateway {
+        job_ids: vec!["job-1"],
+    };
+    let derived = Arc::new(RecordingDerivedGateway::default());
+    let planner = Arc::new(GatedFactsPlanner::default());
+    let mut pool = RuntimeJobPool::new(derived.clone(), planner.clone());
+
+    pool.dispatch_pending_jobs(&session, &core)
+        .expect("dispatch");
+    wait_until(|| planner.active.load(Ordering::SeqCst) == 1);
+    pool.release_abandoned_jobs(Duration::from_millis(50));
+
+    // Claimed with token 1; the journal never saw the renewals.
+    let renewed = derived.renewed_fencing_token.load(Ordering::SeqCst);
+    assert!(renewed > 1);
+    assert_eq!(derived.fail_fencing_tokens(), vec![renewed]);
+    planner.release();
+    drain(&mut pool);
+}
+
+#[test]
+fn tdd_runtime_job_pool_leaves_release_to_workers_that_stop_within_the_wait() {
+    let (_source_root, session) = session_with_source(1);
+    let core = PendingJobsGateway {
+        job_ids: vec!["job-1"],
+    };
+    let derived = Arc::new(RecordingDerivedGateway::default());
+    let mut pool = RuntimeJobPool::new(derived.clone(), Arc::new(CancellableProxyPlanner));
+
+    pool.dispatch_pending_jobs(&session, &core)
+        .expect("dispatch");
+    wait_until(|| derived.calls().contains(&"claim:job-1".to_string()));
+
+    assert!(
+        pool.release_abandoned_jobs(Duration::from_secs(5))
+            .is_empty()
+    );
+    assert!(pool.is_idle());
+    assert_eq!(
+        derived
+            .calls()
+            .iter()
+            .filter(|call| call.starts_with("fail:job-1"))
+            .count(),
+        1
+    );
+}
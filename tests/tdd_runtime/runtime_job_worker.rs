@@ -3,10 +3,10 @@ use std::sync::{Arc, Mutex};
 use image::{Rgb, RgbImage};
 use retaia_agent::{
     AgentRuntimeConfig, AuthMode, ClaimedDerivedJob, CoreApiGateway, CoreApiGatewayError,
-    CoreJobState, CoreJobView, CoreServerPolicy, DerivedJobType, DerivedProcessingError,
-    DerivedProcessingGateway, DerivedUploadComplete, DerivedUploadInit, DerivedUploadPart,
-    HeartbeatReceipt, LogLevel, RuntimeDerivedPlanner, RuntimeSession, SubmitDerivedPayload,
-    UploadedDerivedPart, process_next_pending_job,
+    CoreJobState, CoreJobView, CoreServerPolicy, DerivedJobFailure, DerivedJobType,
+    DerivedProcessingError, DerivedProcessingGateway, DerivedUploadComplete, DerivedUploadInit,
+    DerivedUploadPart, HeartbeatReceipt, JobSelectionStrategy, LogLevel, RuntimeDerivedPlanner,
+    RuntimeSession, SubmitDerivedPayload, UploadedDerivedPart, process_next_pending_job,
 };
 
 fn write_storage_marker(root: &std::path::Path, storage_id: &str) {
@@ -24,6 +24,7 @@ impl CoreApiGateway for SinglePendingGateway {
         Ok(vec![CoreJobView {
             job_id: "job-1".to_string(),
             asset_uuid: "asset-1".to_string(),
+            job_type: None,
             state: CoreJobState::Pending,
             required_capabilities: vec!["media.previews.photo@1".to_string()],
         }])
@@ -101,6 +102,21 @@ impl DerivedProcessingGateway for RecordingDerivedGateway {
         Ok(())
     }
 
+    fn fail_job(
+        &self,
+        job_id: &str,
+        _lock_token: &str,
+        _fencing_token: i32,
+        _idempotency_key: &str,
+        failure: &DerivedJobFailure,
+    ) -> Result<(), DerivedProcessingError> {
+        self.calls
+            .lock()
+            .expect("calls mutex")
+            .push(format!("fail:{job_id}:{}", failure.error_code));
+        Ok(())
+    }
+
     fn upload_init(&self, _request: &DerivedUploadInit) -> Result<(), DerivedProcessingError> {
         Ok(())
     }
@@ -145,6 +161,8 @@ fn tdd_runtime_job_worker_processes_first_pending_job_with_source_staging() {
         storage_mounts: mounts,
         max_parallel_jobs: 1,
         log_level: LogLevel::Info,
+        job_selection: JobSelectionStrategy::default(),
+        upload_part_size_bytes: None,
     };
     let mut session =
         RuntimeSession::new(retaia_agent::ClientRuntimeTarget::Agent, settings).expect("session");
@@ -1,8 +1,8 @@
 use retaia_agent::{
     AgentRuntimeConfig, AuthMode, ClientRuntimeTarget, CoreApiGateway, CoreApiGatewayError,
-    CoreJobState, CoreJobView, LogLevel, NotificationBridgeError, NotificationMessage,
-    NotificationSink, PollEndpoint, RuntimePollCycleStatus, RuntimeSession, RuntimeSyncPlan,
-    SystemNotification, run_runtime_poll_cycle,
+    CoreJobState, CoreJobView, JobSelectionStrategy, LogLevel, NotificationBridgeError,
+    NotificationMessage, NotificationSink, PollEndpoint, RuntimePollCycleStatus, RuntimeSession,
+    RuntimeSyncPlan, SystemNotification, run_runtime_poll_cycle,
 };
 use std::cell::RefCell;
 
@@ -15,6 +15,8 @@ fn settings() -> AgentRuntimeConfig {
         storage_mounts: std::collections::BTreeMap::new(),
         max_parallel_jobs: 2,
         log_level: LogLevel::Info,
+        job_selection: JobSelectionStrategy::default(),
+        upload_part_size_bytes: None,
     }
 }
 
@@ -226,6 +228,7 @@ fn tdd_runtime_poll_cycle_success_projects_claimed_job_and_dispatches_new_job_no
         result: Ok(vec![CoreJobView {
             job_id: "job-42".to_string(),
             asset_uuid: "asset-42".to_string(),
+            job_type: None,
             state: CoreJobState::Claimed,
             required_capabilities: vec!["media.facts@1".to_string()],
         }]),
@@ -1,8 +1,9 @@
 use retaia_agent::{
-    AgentRunState, AgentRuntimeConfig, AuthMode, ClientRuntimeTarget, CoreServerPolicy, LogLevel,
-    MenuAction, NotificationBridgeError, NotificationMessage, NotificationSink, PollDecisionReason,
-    PollEndpoint, PollSignal, PushChannel, PushHint, RuntimeSession, RuntimeSnapshot,
-    RuntimeSyncPlan, SystemNotification, TechnicalAuthConfig,
+    AgentRunState, AgentRuntimeConfig, AuthMode, ClientRuntimeTarget, CoreServerPolicy,
+    JobSelectionStrategy, JobStage, JobStatus, LogLevel, MenuAction, NotificationBridgeError,
+    NotificationMessage, NotificationSink, PollDecisionReason, PollEndpoint, PollSignal,
+    PushChannel, PushHint, RuntimeSession, RuntimeSnapshot, RuntimeSyncPlan, SystemNotification,
+    TechnicalAuthConfig,
 };
 use std::cell::RefCell;
 
@@ -15,6 +16,8 @@ fn settings() -> AgentRuntimeConfig {
         storage_mounts: std::collections::BTreeMap::new(),
         max_parallel_jobs: 2,
         log_level: LogLevel::Info,
+        job_selection: JobSelectionStrategy::default(),
+        upload_part_size_bytes: None,
     }
 }
 
@@ -157,3 +160,43 @@ fn tdd_runtime_session_update_snapshot_and_dispatch_returns_report() {
     assert!(report.dispatch.failed.is_empty());
     assert_eq!(sink.delivered.borrow().as_slice(), &["New job received"]);
 }
+
+#[test]
+fn tdd_runtime_session_overlays_local_job_progress_on_polled_snapshot() {
+    let mut session = RuntimeSession::new(ClientRuntimeTarget::Agent, settings()).expect("session");
+    let sink = CaptureSink::default();
+
+    let mut remote = RuntimeSnapshot::default();
+    remote.known_job_ids.insert("job-1".to_string());
+    remote.known_job_ids.insert("job-2".to_string());
+    let _ = session.update_snapshot_and_dispatch(remote.clone(), &sink);
+
+    let mut local = RuntimeSnapshot::default();
+    local.known_job_ids.insert("job-1".to_string());
+    local.running_job_ids.insert("job-1".to_string());
+    local.current_job = Some(JobStatus {
+        job_id: "job-1".to_string(),
+        asset_uuid: "asset-1".to_string(),
+        progress_percent: 60,
+        stage: JobStage::Upload,
+        short_status: "uploading 1/2".to_string(),
+    });
+    let _ = session.update_local_jobs_and_dispatch(local, &sink);
+
+    let status = session.status_view();
+    let current = status.current_job.expect("local job should be current");
+    assert_eq!(current.job_id, "job-1");
+    assert_eq!(current.progress_percent, 60);
+
+    let _ = session.update_snapshot_and_dispatch(remote, &sink);
+    assert_eq!(
+        session
+            .status_view()
+            .current_job
+            .map(|job| job.progress_percent),
+        Some(60)
+    );
+
+    let _ = session.update_local_jobs_and_dispatch(RuntimeSnapshot::default(), &sink);
+    assert!(session.status_view().current_job.is_none());
+}
@@ -1,5 +1,6 @@
 use retaia_agent::{
     ConnectivityState, JobStage, RuntimeStatusEvent, RuntimeStatusTracker, SystemNotification,
+    merge_local_job_snapshot,
 };
 
 #[test]
@@ -77,6 +78,37 @@ fn tdd_status_tracker_failure_is_deduplicated_by_job_id() {
     assert_eq!(failed[0].error_code, "E_NETWORK");
 }
 
+#[test]
+fn tdd_status_tracker_forgets_finished_jobs_but_keeps_running_ones() {
+    let mut tracker = RuntimeStatusTracker::new();
+    for job_id in ["job-1", "job-2", "job-3"] {
+        tracker.apply(RuntimeStatusEvent::JobClaimed {
+            job_id: job_id.to_string(),
+            asset_uuid: "asset-x".to_string(),
+        });
+    }
+    tracker.apply(RuntimeStatusEvent::JobCompleted {
+        job_id: "job-1".to_string(),
+    });
+    tracker.apply(RuntimeStatusEvent::JobFailed {
+        job_id: "job-2".to_string(),
+        error_code: "E_TIMEOUT".to_string(),
+    });
+
+    tracker.forget_finished_jobs();
+
+    let snapshot = tracker.snapshot();
+    assert_eq!(
+        snapshot.known_job_ids.iter().collect::<Vec<_>>(),
+        vec!["job-3"]
+    );
+    assert!(snapshot.failed_jobs.is_empty());
+    assert_eq!(
+        snapshot.current_job.as_ref().map(|job| job.job_id.as_str()),
+        Some("job-3")
+    );
+}
+
 #[test]
 fn tdd_status_tracker_updates_connectivity_auth_and_version_fields() {
     let mut tracker = RuntimeStatusTracker::new();
@@ -117,3 +149,39 @@ fn tdd_status_tracker_snapshot_can_feed_notifications() {
     let second = ui.update_snapshot(tracker.snapshot().clone());
     assert_eq!(second, vec![SystemNotification::AllJobsDone]);
 }
+
+#[test]
+fn tdd_merge_local_job_snapshot_keeps_remote_state_and_local_progress() {
+    let mut remote = RuntimeStatusTracker::new();
+    remote.apply(RuntimeStatusEvent::JobDiscovered {
+        job_id: "job-remote".to_string(),
+    });
+    remote.apply(RuntimeStatusEvent::JobFailed {
+        job_id: "job-local".to_string(),
+        error_code: "E_REMOTE".to_string(),
+    });
+    let mut local = RuntimeStatusTracker::new();
+    local.apply(RuntimeStatusEvent::JobClaimed {
+        job_id: "job-local".to_string(),
+        asset_uuid: "asset-a".to_string(),
+    });
+    local.apply(RuntimeStatusEvent::JobFailed {
+        job_id: "job-local".to_string(),
+        error_code: "E_LOCAL".to_string(),
+    });
+    local.apply(RuntimeStatusEvent::JobClaimed {
+        job_id: "job-next".to_string(),
+        asset_uuid: "asset-b".to_string(),
+    });
+
+    let merged = merge_local_job_snapshot(remote.snapshot(), local.snapshot());
+
+    assert!(merged.known_job_ids.contains("job-remote"));
+    assert!(merged.known_job_ids.contains("job-next"));
+    assert!(merged.running_job_ids.contains("job-next"));
+    assert_eq!(merged.failed_jobs.len(), 1);
+    assert_eq!(
+        merged.current_job.as_ref().map(|job| job.job_id.as_str()),
+        Some("job-next")
+    );
+}
@@ -1,17 +1,23 @@
 use std::sync::Mutex;
+use std::time::Duration;
 
 use exif::{DateTime as ExifDateTime, Rational, Value};
-use image::{DynamicImage, GenericImageView, ImageFormat};
+use image::{DynamicImage, GenericImageView, ImageFormat, Rgb, RgbImage};
 use retaia_agent::infrastructure::rust_photo_proxy_generator::{
-    exif_datetime_to_utc_rfc3339, gps_coordinate_to_decimal, gps_timestamp_to_utc_rfc3339,
+    apply_exif_orientation, exif_datetime_to_utc_rfc3339, gps_coordinate_to_decimal,
+    gps_timestamp_to_utc_rfc3339,
 };
 use retaia_agent::{
-    PhotoProxyFormat, PhotoProxyRequest, ProxyGenerationError, ProxyGenerator, RawPhotoDecoder,
+    CancellationReason, CancellationToken, CommandLimits, MediaOperation, MediaOperationTimeout,
+    MediaToolTimeouts, NoopProxyProgressSink, PhotoDecodePath, PhotoProxyFormat, PhotoProxyRequest,
+    ProxyGenerationError, ProxyGenerator, ProxyProgressSink, RawPhotoDecoder,
     RustPhotoProxyGenerator,
 };
 
 struct StubRawDecoder {
     calls: Mutex<usize>,
+    heif_calls: Mutex<usize>,
+    heif_limits: Mutex<Option<(MediaOperation, Option<Duration>)>>,
     image: DynamicImage,
 }
 
@@ -19,6 +25,8 @@ impl StubRawDecoder {
     fn new(image: DynamicImage) -> Self {
         Self {
             calls: Mutex::new(0),
+            heif_calls: Mutex::new(0),
+            heif_limits: Mutex::new(None),
             image,
         }
     }
@@ -26,6 +34,10 @@ impl StubRawDecoder {
     fn calls(&self) -> usize {
         *self.calls.lock().expect("calls")
     }
+
+    fn heif_calls(&self) -> usize {
+        *self.heif_calls.lock().expect("heif calls")
+    }
 }
 
 impl RawPhotoDecoder for StubRawDecoder {
@@ -33,6 +45,33 @@ impl RawPhotoDecoder for StubRawDecoder {
         *self.calls.lock().expect("calls") += 1;
         Ok(self.image.clone())
     }
+
+    fn decode_heif(
+        &self,
+        _input_path: &str,
+        limits: &CommandLimits<'_>,
+    ) -> Result<DynamicImage, ProxyGenerationError> {
+        *self.heif_calls.lock().expect("heif calls") += 1;
+        if limits
+            .cancellation
+            .is_some_and(CancellationToken::is_cancelled)
+        {
+            return Err(ProxyGenerationError::Cancelled);
+        }
+        *self.heif_limits.lock().expect("heif limits") =
+            Some((limits.operation, limits.wall_clock));
+        Ok(self.image.clone())
+    }
+}
+
+struct TokenProgress(CancellationToken);
+
+impl ProxyProgressSink for TokenProgress {
+    fn report_progress(&self, _percent: u8) {}
+
+    fn cancellation(&self) -> Option<&CancellationToken> {
+        Some(&self.0)
+    }
 }
 
 #[test]
@@ -46,13 +85,16 @@ fn tdd_rust_photo_proxy_generator_emits_jpeg_with_max_dimensions_applied() {
 
     let generator = RustPhotoProxyGenerator::default();
     generator
-        .generate_photo_proxy(&PhotoProxyRequest {
-            input_path: input.display().to_string(),
-            output_path: output.display().to_string(),
-            format: PhotoProxyFormat::Jpeg,
-            max_width: 640,
-            max_height: 360,
-        })
+        .generate_photo_proxy(
+            &PhotoProxyRequest {
+                input_path: input.display().to_string(),
+                output_path: output.display().to_string(),
+                format: PhotoProxyFormat::Jpeg,
+                max_width: 640,
+                max_height: 360,
+            },
+            &NoopProxyProgressSink,
+        )
         .expect("jpeg proxy generation should succeed");
 
     let produced = image::open(&output).expect("output image should open");
@@ -72,13 +114,16 @@ fn tdd_rust_photo_proxy_generator_uses_raw_fallback_when_image_open_fails() {
     let generator = RustPhotoProxyGenerator::new(decoder);
 
     generator
-        .generate_photo_proxy(&PhotoProxyRequest {
-            input_path: missing_input.display().to_string(),
-            output_path: output.display().to_string(),
-            format: PhotoProxyFormat::Webp,
-            max_width: 80,
-            max_height: 40,
-        })
+        .generate_photo_proxy(
+            &PhotoProxyRequest {
+                input_path: missing_input.display().to_string(),
+                output_path: output.display().to_string(),
+                format: PhotoProxyFormat::Webp,
+                max_width: 80,
+                max_height: 40,
+            },
+            &NoopProxyProgressSink,
+        )
         .expect("raw fallback should generate output");
 
     assert_eq!(generator.raw_decoder().calls(), 1);
@@ -91,13 +136,16 @@ fn tdd_rust_photo_proxy_generator_rejects_zero_dimensions() {
     let generator = RustPhotoProxyGenerator::default();
 
     let error = generator
-        .generate_photo_proxy(&PhotoProxyRequest {
-            input_path: "/tmp/source.png".to_string(),
-            output_path: "/tmp/proxy.jpg".to_string(),
-            format: PhotoProxyFormat::Jpeg,
-            max_width: 0,
-            max_height: 360,
-        })
+        .generate_photo_proxy(
+            &PhotoProxyRequest {
+                input_path: "/tmp/source.png".to_string(),
+                output_path: "/tmp/proxy.jpg".to_string(),
+                format: PhotoProxyFormat::Jpeg,
+                max_width: 0,
+                max_height: 360,
+            },
+            &NoopProxyProgressSink,
+        )
         .expect_err("invalid dimensions should fail");
 
     assert!(matches!(error, ProxyGenerationError::InvalidRequest(_)));
@@ -108,13 +156,16 @@ fn tdd_rust_photo_proxy_generator_rejects_empty_output_path() {
     let generator = RustPhotoProxyGenerator::default();
 
     let error = generator
-        .generate_photo_proxy(&PhotoProxyRequest {
-            input_path: "/tmp/source.png".to_string(),
-            output_path: String::new(),
-            format: PhotoProxyFormat::Jpeg,
-            max_width: 640,
-            max_height: 360,
-        })
+        .generate_photo_proxy(
+            &PhotoProxyRequest {
+                input_path: "/tmp/source.png".to_string(),
+                output_path: String::new(),
+                format: PhotoProxyFormat::Jpeg,
+                max_width: 640,
+                max_height: 360,
+            },
+            &NoopProxyProgressSink,
+        )
         .expect_err("empty output path should fail");
 
     assert!(matches!(error, ProxyGenerationError::InvalidRequest(_)));
@@ -125,13 +176,16 @@ fn tdd_rust_photo_proxy_generator_rejects_zero_max_height() {
     let generator = RustPhotoProxyGenerator::default();
 
     let error = generator
-        .generate_photo_proxy(&PhotoProxyRequest {
-            input_path: "/tmp/source.png".to_string(),
-            output_path: "/tmp/proxy.jpg".to_string(),
-            format: PhotoProxyFormat::Jpeg,
-            max_width: 640,
-            max_height: 0,
-        })
+        .generate_photo_proxy(
+            &PhotoProxyRequest {
+                input_path: "/tmp/source.png".to_string(),
+                output_path: "/tmp/proxy.jpg".to_string(),
+                format: PhotoProxyFormat::Jpeg,
+                max_width: 640,
+                max_height: 0,
+            },
+            &NoopProxyProgressSink,
+        )
         .expect_err("zero max_height should fail");
 
     assert!(matches!(error, ProxyGenerationError::InvalidRequest(_)));
@@ -145,13 +199,16 @@ fn tdd_rust_photo_proxy_generator_missing_input_path_returns_process_error() {
 
     let generator = RustPhotoProxyGenerator::default();
     let error = generator
-        .generate_photo_proxy(&PhotoProxyRequest {
-            input_path: input.display().to_string(),
-            output_path: output.display().to_string(),
-            format: PhotoProxyFormat::Jpeg,
-            max_width: 320,
-            max_height: 200,
-        })
+        .generate_photo_proxy(
+            &PhotoProxyRequest {
+                input_path: input.display().to_string(),
+                output_path: output.display().to_string(),
+                format: PhotoProxyFormat::Jpeg,
+                max_width: 320,
+                max_height: 200,
+            },
+            &NoopProxyProgressSink,
+        )
         .expect_err("missing input should fail");
 
     assert!(matches!(error, ProxyGenerationError::Process(_)));
@@ -167,17 +224,20 @@ fn tdd_rust_photo_proxy_generator_mismatched_raw_extension_with_text_content_fai
 
     let generator = RustPhotoProxyGenerator::default();
     let error = generator
-        .generate_photo_proxy(&PhotoProxyRequest {
-            input_path: input.display().to_string(),
-            output_path: output.display().to_string(),
-            format: PhotoProxyFormat::Webp,
-            max_width: 320,
-            max_height: 200,
-        })
+        .generate_photo_proxy(
+            &PhotoProxyRequest {
+                input_path: input.display().to_string(),
+                output_path: output.display().to_string(),
+                format: PhotoProxyFormat::Webp,
+                max_width: 320,
+                max_height: 200,
+            },
+            &NoopProxyProgressSink,
+        )
         .expect_err("fake raw should fail");
 
     match error {
-        ProxyGenerationError::Process(message) => {
+        ProxyGenerationError::DecodeFailed(message) => {
             assert!(message.contains("unable to decode photo source"))
         }
         other => panic!("unexpected error variant: {other:?}"),
@@ -216,16 +276,19 @@ fn tdd_rust_photo_proxy_generator_mixed_batch_reports_success_and_failure_counts
 
     for (index, input) in entries.iter().enumerate() {
         let output = temp.path().join(format!("proxy-{index}.webp"));
-        let result = generator.generate_photo_proxy(&PhotoProxyRequest {
-            input_path: input.display().to_string(),
-            output_path: output.display().to_string(),
-            format: PhotoProxyFormat::Webp,
-            max_width: 320,
-            max_height: 180,
-        });
+        let result = generator.generate_photo_proxy(
+            &PhotoProxyRequest {
+                input_path: input.display().to_string(),
+                output_path: output.display().to_string(),
+                format: PhotoProxyFormat::Webp,
+                max_width: 320,
+                max_height: 180,
+            },
+            &NoopProxyProgressSink,
+        );
         match result {
-            Ok(()) => success += 1,
-            Err(ProxyGenerationError::Process(_)) => failed += 1,
+            Ok(_) => success += 1,
+            Err(ProxyGenerationError::DecodeFailed(_)) => failed += 1,
             Err(other) => panic!("unexpected error variant in mixed batch: {other:?}"),
         }
     }
@@ -250,13 +313,16 @@ fn tdd_rust_photo_proxy_generator_high_volume_local_batch_all_succeed() {
             .expect("save source");
 
         generator
-            .generate_photo_proxy(&PhotoProxyRequest {
-                input_path: input.display().to_string(),
-                output_path: output.display().to_string(),
-                format: PhotoProxyFormat::Webp,
-                max_width: 160,
-                max_height: 120,
-            })
+            .generate_photo_proxy(
+                &PhotoProxyRequest {
+                    input_path: input.display().to_string(),
+                    output_path: output.display().to_string(),
+                    format: PhotoProxyFormat::Webp,
+                    max_width: 160,
+                    max_height: 120,
+                },
+                &NoopProxyProgressSink,
+            )
             .expect("volume proxy generation should succeed");
 
         let produced = image::open(&output).expect("open output");
@@ -332,3 +398,224 @@ fn tdd_rust_photo_gps_timestamp_is_normalized_to_utc() {
 
     assert_eq!(captured_at, "2026-03-22T10:03:39Z");
 }
+
+fn jpeg_with_exif_orientation(width: u32, height: u32, orientation: u16) -> Vec<u8> {
+    let mut jpeg = Vec::new();
+    DynamicImage::new_rgb8(width, height)
+        .write_to(&mut std::io::Cursor::new(&mut jpeg), ImageFormat::Jpeg)
+        .expect("encode jpeg");
+    // APP1 "Exif" segment holding the TIFF header.
+    let tiff = exif_tiff_with_orientation(orientation);
+    let mut app1 = vec![0xff, 0xe1];
+    app1.extend_from_slice(&((tiff.len() + 8) as u16).to_be_bytes());
+    app1.extend_from_slice(b"Exif\0\0");
+    app1.extend_from_slice(&tiff);
+    jpeg.splice(2..2, app1);
+    jpeg
+}
+
+// One-entry little-endian TIFF IFD0 (Orientation, SHORT).
+fn exif_tiff_with_orientation(orientation: u16) -> Vec<u8> {
+    let mut tiff = b"II*\0\x08\0\0\0\x01\0\x12\x01\x03\0\x01\0\0\0".to_vec();
+    tiff.extend_from_slice(&orientation.to_le_bytes());
+    tiff.extend_from_slice(&[0, 0, 0, 0, 0, 0]);
+    tiff
+}
+
+type SourcePixel = fn(u32, u32) -> (u32, u32);
+
+#[test]
+fn tdd_rust_photo_orientation_maps_every_exif_value() {
+    let (width, height) = (3_u32, 2_u32);
+    let source = DynamicImage::ImageRgb8(RgbImage::from_fn(width, height, |x, y| {
+        Rgb([x as u8 * 50, y as u8 * 50, 0])
+    }));
+    // Output pixel (x, y) of each orientation, expressed as a source pixel.
+    let cases: [(u32, SourcePixel); 8] = [
+        (1, |x, y| (x, y)),
+        (2, |x, y| (2 - x, y)),
+        (3, |x, y| (2 - x, 1 - y)),
+        (4, |x, y| (x, 1 - y)),
+        (5, |x, y| (y, x)),
+        (6, |x, y| (y, 1 - x)),
+        (7, |x, y| (2 - y, 1 - x)),
+        (8, |x, y| (2 - y, x)),
+    ];
+
+    for (orientation, source_of) in cases {
+        let oriented = apply_exif_orientation(source.clone(), orientation);
+        let expected_dimensions = if orientation >= 5 {
+            (height, width)
+        } else {
+            (width, height)
+        };
+        assert_eq!(
+            oriented.dimensions(),
+            expected_dimensions,
+            "orientation {orientation}"
+        );
+        for (x, y, pixel) in oriented.pixels() {
+            let (source_x, source_y) = source_of(x, y);
+            assert_eq!(
+                pixel,
+                source.get_pixel(source_x, source_y),
+                "orientation {orientation} at ({x}, {y})"
+            );
+        }
+    }
+}
+
+#[test]
+fn tdd_rust_photo_orientation_ignores_out_of_range_values() {
+    let source = DynamicImage::new_rgb8(3, 2);
+
+    assert_eq!(
+        apply_exif_orientation(source.clone(), 0).dimensions(),
+        (3, 2)
+    );
+    assert_eq!(apply_exif_orientation(source, 9).dimensions(), (3, 2));
+}
+
+#[test]
+fn tdd_rust_photo_proxy_generator_rotates_portrait_jpeg_from_exif_orientation() {
+    let temp = tempfile::tempdir().expect("tempdir");
+    let input = temp.path().join("portrait.jpg");
+    let output = temp.path().join("proxy.webp");
+    std::fs::write(&input, jpeg_with_exif_orientation(40, 20, 6)).expect("write jpeg");
+
+    RustPhotoProxyGenerator::default()
+        .generate_photo_proxy(
+            &PhotoProxyRequest {
+                input_path: input.display().to_string(),
+                output_path: output.display().to_string(),
+                format: PhotoProxyFormat::Webp,
+                max_width: 100,
+                max_height: 100,
+            },
+            &NoopProxyProgressSink,
+        )
+        .expect("proxy generation should succeed");
+
+    let produced = image::open(&output).expect("output image should open");
+    assert_eq!(produced.dimensions(), (50, 100));
+}
+
+#[test]
+fn tdd_rust_photo_facts_report_stored_dimensions_for_jpeg_and_raw_sources() {
+    let temp = tempfile::tempdir().expect("tempdir");
+    let jpeg = temp.path().join("portrait.jpg");
+    let raw = temp.path().join("portrait.cr2");
+    std::fs::write(&jpeg, jpeg_with_exif_orientation(40, 20, 6)).expect("write jpeg");
+    std::fs::write(&raw, exif_tiff_with_orientation(6)).expect("write raw");
+    // Raw decoders hand back upright pixels: the 40x20 sensor frame turned a quarter.
+    let generator =
+        RustPhotoProxyGenerator::new(StubRawDecoder::new(DynamicImage::new_rgb8(20, 40)));
+
+    for input in [&jpeg, &raw] {
+        let facts = generator
+            .extract_media_facts(&input.display().to_string(), &NoopProxyProgressSink)
+            .expect("facts");
+        assert_eq!(
+            (facts.width, facts.height, facts.orientation),
+            (Some(40), Some(20), Some(6)),
+            "{}",
+            input.display()
+        );
+    }
+    assert_eq!(generator.raw_decoder().calls(), 1);
+}
+
+#[test]
+fn tdd_rust_photo_proxy_generator_routes_heif_and_avif_through_heif_decoder() {
+    let temp = tempfile::tempdir().expect("tempdir");
+    let generator =
+        RustPhotoProxyGenerator::new(StubRawDecoder::new(DynamicImage::new_rgb8(300, 400)));
+
+    for name in ["IMG_0001.HEIC", "IMG_0002.heif", "still.avif"] {
+        let input = temp.path().join(name);
+        std::fs::write(&input, b"\0\0\0\x18ftypheic\0\0\0\0heicmif1").expect("write heif");
+        let decode_path = generator
+            .generate_photo_proxy(
+                &PhotoProxyRequest {
+                    input_path: input.display().to_string(),
+                    output_path: temp
+                        .path()
+                        .join(format!("{name}.webp"))
+                        .display()
+                        .to_string(),
+                    format: PhotoProxyFormat::Webp,
+                    max_width: 150,
+                    max_height: 150,
+                },
+                &NoopProxyProgressSink,
+            )
+            .expect("heif proxy generation should succeed");
+        assert_eq!(decode_path, Some(PhotoDecodePath::Heif));
+    }
+
+    assert_eq!(generator.raw_decoder().heif_calls(), 3);
+    assert_eq!(generator.raw_decoder().calls(), 0);
+    let produced = image::open(temp.path().join("still.avif.webp")).expect("open proxy");
+    assert_eq!(produced.dimensions(), (113, 150));
+}
+
+#[test]
+fn tdd_rust_photo_proxy_generator_reports_heif_decoder_failures_as_process_errors() {
+    let temp = tempfile::tempdir().expect("tempdir");
+    let input = temp.path().join("broken.heic");
+    std::fs::write(&input, b"not-a-heif").expect("write heif");
+
+    let error = RustPhotoProxyGenerator::default()
+        .generate_photo_proxy(
+            &PhotoProxyRequest {
+                input_path: input.display().to_string(),
+                output_path: temp.path().join("proxy.webp").display().to_string(),
+                format: PhotoProxyFormat::Webp,
+                max_width: 100,
+                max_height: 100,
+            },
+            &NoopProxyProgressSink,
+        )
+        .expect_err("broken heif should fail");
+
+    assert!(matches!(error, ProxyGenerationError::Process(message) if message.contains("HEIF")));
+}
+
+#[test]
+fn tdd_rust_photo_proxy_generator_supervises_heif_decoding_with_photo_timeout_and_job_token() {
+    let temp = tempfile::tempdir().expect("tempdir");
+    let input = temp.path().join("IMG_0003.HEIC");
+    std::fs::write(&input, b"\0\0\0\x18ftypheic").expect("write heif");
+    let generator =
+        RustPhotoProxyGenerator::new(StubRawDecoder::new(DynamicImage::new_rgb8(64, 48)))
+            .with_timeouts(MediaToolTimeouts {
+                photo_decode: MediaOperationTimeout::fixed(Duration::from_secs(7), None),
+                ..MediaToolTimeouts::default()
+            });
+    let request = PhotoProxyRequest {
+        input_path: input.display().to_string(),
+        output_path: temp.path().join("proxy.webp").display().to_string(),
+        format: PhotoProxyFormat::Webp,
+        max_width: 32,
+        max_height: 32,
+    };
+
+    generator
+        .generate_photo_proxy(&request, &TokenProgress(CancellationToken::new()))
+        .expect("heif proxy generation should succeed");
+    assert_eq!(
+        *generator
+            .raw_decoder()
+            .heif_limits
+            .lock()
+            .expect("heif limits"),
+        Some((MediaOperation::PhotoDecode, Some(Duration::from_secs(7))))
+    );
+
+    let token = CancellationToken::new();
+    token.cancel(CancellationReason::Stopped);
+    let error = generator
+        .generate_photo_proxy(&request, &TokenProgress(token))
+        .expect_err("cancelled job must not decode");
+    assert_eq!(error, ProxyGenerationError::Cancelled);
+}
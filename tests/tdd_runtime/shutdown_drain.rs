@@ -0,0 +1,77 @@
+use std::time::{Duration, Instant};
+
+use retaia_agent::{
+    DaemonDrainStats, DaemonRuntimeStats, DrainPhase, ShutdownDrain, render_drain_stats,
+};
+
+#[test]
+fn tdd_shutdown_drain_moves_from_draining_to_releasing_to_abandoned() {
+    let started = Instant::now();
+    let drain = ShutdownDrain::start(Duration::from_secs(30), started, 1_000)
+        .with_release_grace(Duration::from_secs(5));
+
+    assert_eq!(drain.phase(started), DrainPhase::Draining);
+    assert_eq!(
+        drain.phase(started + Duration::from_secs(29)),
+        DrainPhase::Draining
+    );
+    assert_eq!(
+        drain.phase(started + Duration::from_secs(30)),
+        DrainPhase::Releasing
+    );
+    assert_eq!(
+        drain.phase(started + Duration::from_secs(35)),
+        DrainPhase::Abandoned
+    );
+}
+
+#[test]
+fn tdd_shutdown_drain_with_zero_deadline_releases_immediately() {
+    let started = Instant::now();
+    let drain = ShutdownDrain::start(Duration::ZERO, started, 1_000);
+
+    assert_eq!(drain.phase(started), DrainPhase::Releasing);
+}
+
+#[test]
+fn tdd_shutdown_drain_reports_wall_clock_deadline() {
+    let drain = ShutdownDrain::start(Duration::from_secs(90), Instant::now(), 1_700_000_000_000);
+
+    assert_eq!(drain.started_at_unix_ms(), 1_700_000_000_000);
+    assert_eq!(drain.deadline_at_unix_ms(), 1_700_000_090_000);
+}
+
+#[test]
+fn tdd_shutdown_drain_renders_stats_lines() {
+    let mut out = String::new();
+    render_drain_stats(
+        &mut out,
+        Some(&DaemonDrainStats {
+            phase: DrainPhase::Releasing.as_str().to_string(),
+            started_at_unix_ms: 10,
+            deadline_at_unix_ms: 60_010,
+            active_job_ids: vec!["job-1".to_string(), "job-2".to_string()],
+        }),
+    );
+
+    assert!(out.contains("drain_phase=releasing"));
+    assert!(out.contains("drain_deadline_at_unix_ms=60010"));
+    assert!(out.contains("drain_active_job_ids=job-1,job-2"));
+
+    let mut idle = String::new();
+    render_drain_stats(&mut idle, None);
+    assert_eq!(idle, "drain_phase=-\n");
+}
+
+#[test]
+fn tdd_shutdown_drain_stats_default_to_none_for_legacy_files() {
+    let legacy = serde_json::to_value(DaemonRuntimeStats::new_idle(3)).expect("serialize");
+    let mut legacy = legacy.as_object().expect("object").clone();
+    legacy.remove("drain");
+
+    let stats: DaemonRuntimeStats =
+        serde_json::from_value(serde_json::Value::Object(legacy)).expect("deserialize");
+
+    assert_eq!(stats.tick, 3);
+    assert!(stats.drain.is_none());
+}
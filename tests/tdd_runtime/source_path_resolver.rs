@@ -4,8 +4,9 @@ use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::{Duration, UNIX_EPOCH};
 
 use retaia_agent::{
-    AgentRuntimeConfig, AuthMode, LogLevel, SourcePathResolveError, StorageMarkerProvider,
-    StorageMarkerRead, resolve_processing_input_path, resolve_source_path_with_marker_provider,
+    AgentRuntimeConfig, AuthMode, JobSelectionStrategy, LogLevel, SourcePathResolveError,
+    StorageMarkerProvider, StorageMarkerRead, resolve_processing_input_path,
+    resolve_source_path_with_marker_provider,
 };
 
 #[derive(Debug, Default)]
@@ -58,6 +59,8 @@ fn config_with_mount(mount: &str) -> AgentRuntimeConfig {
         storage_mounts,
         max_parallel_jobs: 1,
         log_level: LogLevel::Info,
+        job_selection: JobSelectionStrategy::default(),
+        upload_part_size_bytes: None,
     }
 }
 
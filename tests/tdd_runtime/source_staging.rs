@@ -2,8 +2,8 @@ use std::path::Path;
 
 use retaia_agent::{
     AgentRuntimeConfig, AuthMode, ClaimedDerivedJob, DerivedJobType, DiskSpaceProbe,
-    Fs2DiskSpaceProbe, LogLevel, SourceStagingError, stage_claimed_job_source,
-    stage_claimed_job_source_with_probe,
+    Fs2DiskSpaceProbe, JobSelectionStrategy, LogLevel, SourceStagingError,
+    stage_claimed_job_source, stage_claimed_job_source_with_probe,
 };
 
 fn write_storage_marker(root: &Path, storage_id: &str) {
@@ -25,6 +25,8 @@ fn config_with_mount(mount_path: &Path) -> AgentRuntimeConfig {
         storage_mounts,
         max_parallel_jobs: 1,
         log_level: LogLevel::Info,
+        job_selection: JobSelectionStrategy::default(),
+        upload_part_size_bytes: None,
     }
 }
 
@@ -0,0 +1,268 @@
+use std::sync::{Arc, Mutex};
+
+use retaia_agent::{
+    AudioProxyRequest, ClaimedDerivedJob, DerivedExecutionPlanner, DerivedJobType, DerivedKind,
+    FactsPatchPayload, PhotoDecodePath, PhotoProxyRequest, ProxyGenerationError, ProxyGenerator,
+    ProxyProgressSink, RuntimeDerivedPlanner, SpriteSheetLayout, ThumbnailFormat,
+    ThumbnailSpriteProfile, VideoProxyRequest, VideoSpriteSheetRequest, VideoThumbnailRequest,
+    build_video_sprite_sheet_args, sprite_sheet_webvtt, validate_derived_upload_init,
+};
+
+struct SpriteGenerator {
+    sprite_requests: Mutex<Vec<VideoSpriteSheetRequest>>,
+}
+
+impl SpriteGenerator {
+    fn new() -> Self {
+        Self {
+            sprite_requests: Mutex::new(Vec::new()),
+        }
+    }
+}
+
+impl ProxyGenerator for SpriteGenerator {
+    fn generate_video_proxy(
+        &self,
+        _request: &VideoProxyRequest,
+        _progress: &dyn ProxyProgressSink,
+    ) -> Result<(), ProxyGenerationError> {
+        Ok(())
+    }
+
+    fn generate_audio_proxy(
+        &self,
+        _request: &AudioProxyRequest,
+        _progress: &dyn ProxyProgressSink,
+    ) -> Result<(), ProxyGenerationError> {
+        Ok(())
+    }
+
+    fn generate_photo_proxy(
+        &self,
+        _request: &PhotoProxyRequest,
+        _progress: &dyn ProxyProgressSink,
+    ) -> Result<Option<PhotoDecodePath>, ProxyGenerationError> {
+        Ok(None)
+    }
+
+    fn generate_video_thumbnail(
+        &self,
+        request: &VideoThumbnailRequest,
+        _progress: &dyn ProxyProgressSink,
+    ) -> Result<(), ProxyGenerationError> {
+        std::fs::write(&request.output_path, b"generated-thumbnail")
+            .map_err(|error| ProxyGenerationError::Process(error.to_string()))
+    }
+
+    fn generate_video_sprite_sheet(
+        &self,
+        request: &VideoSpriteSheetRequest,
+        _progress: &dyn ProxyProgressSink,
+    ) -> Result<(), ProxyGenerationError> {
+        self.sprite_requests
+            .lock()
+            .expect("sprite requests")
+            .push(request.clone());
+        std::fs::write(&request.output_path, b"generated-sprite")
+            .map_err(|error| ProxyGenerationError::Process(error.to_string()))
+    }
+
+    fn extract_media_facts(
+        &self,
+        _input_path: &str,
+        _progress: &dyn ProxyProgressSink,
+    ) -> Result<FactsPatchPayload, ProxyGenerationError> {
+        Ok(FactsPatchPayload {
+            duration_ms: Some(42_000),
+            width: Some(1920),
+            height: Some(1080),
+            ..FactsPatchPayload::default()
+        })
+    }
+}
+
+fn claimed_thumbnail_job() -> ClaimedDerivedJob {
+    ClaimedDerivedJob {
+        job_id: "job-thumb-sprite".to_string(),
+        asset_uuid: "asset-thumb-sprite".to_string(),
+        lock_token: "lock-thumb-sprite".to_string(),
+        fencing_token: 1,
+        job_type: DerivedJobType::GenerateThumbnails,
+        source_storage_id: "nas-main".to_string(),
+        source_original_relative: "INBOX/clip.mov".to_string(),
+        source_sidecars_relative: Vec::new(),
+    }
+}
+
+#[test]
+fn tdd_thumbnail_sprite_layout_follows_source_aspect_and_caps_tile_count() {
+    let profile = ThumbnailSpriteProfile::default();
+
+    assert_eq!(
+        profile.layout_for(42_000, Some(1920), Some(1080)),
+        Some(SpriteSheetLayout {
+            interval_ms: 5_000,
+            tile_width: 160,
+            tile_height: 90,
+            columns: 9,
+            rows: 1,
+            tile_count: 9,
+        })
+    );
+    let long = profile
+        .layout_for(2 * 60 * 60 * 1_000, Some(1080), Some(1920))
+        .expect("long layout");
+    assert_eq!(long.interval_ms, 72_000);
+    assert_eq!((long.tile_count, long.columns, long.rows), (100, 10, 10));
+    assert_eq!(long.tile_height, 284);
+    let unknown = profile.layout_for(12_000, None, None).expect("layout");
+    assert_eq!((unknown.tile_width, unknown.tile_height), (160, 90));
+    assert_eq!(profile.layout_for(0, Some(1920), Some(1080)), None);
+}
+
+#[test]
+fn tdd_thumbnail_sprite_webvtt_maps_time_ranges_to_tile_coordinates() {
+    let layout = SpriteSheetLayout {
+        interval_ms: 5_000,
+        tile_width: 160,
+        tile_height: 90,
+        columns: 2,
+        rows: 2,
+        tile_count: 3,
+    };
+
+    let vtt = sprite_sheet_webvtt(&layout, 12_500, "/api/v1/assets/a/derived/thumb_sprite");
+
+    assert_eq!(
+        vtt,
+        "WEBVTT\n\
+         \n00:00:00.000 --> 00:00:05.000\n/api/v1/assets/a/derived/thumb_sprite#xywh=0,0,160,90\n\
+         \n00:00:05.000 --> 00:00:10.000\n/api/v1/assets/a/derived/thumb_sprite#xywh=160,0,160,90\n\
+         \n00:00:10.000 --> 00:00:12.500\n/api/v1/assets/a/derived/thumb_sprite#xywh=0,90,160,90\n"
+    );
+}
+
+#[test]
+fn tdd_ffmpeg_sprite_sheet_args_tile_fixed_size_frames_into_one_image() {
+    let args = build_video_sprite_sheet_args(&VideoSpriteSheetRequest {
+        input_path: "/in/clip.mov".to_string(),
+        output_path: "/out/clip.thumb_sprite.webp".to_string(),
+        format: ThumbnailFormat::Webp,
+        layout: SpriteSheetLayout {
+            interval_ms: 5_000,
+            tile_width: 160,
+            tile_height: 90,
+            columns: 10,
+            rows: 3,
+            tile_count: 25,
+        },
+    });
+
+    let filter = args
+        .iter()
+        .skip_while(|arg| *arg != "-vf")
+        .nth(1)
+        .expect("video filter");
+    assert!(filter.starts_with("fps=1000/5000,scale=w=160:h=90:"));
+    assert!(filter.contains("pad=w=160:h=90:"));
+    assert!(filter.ends_with("tile=10x3"));
+    assert!(args.windows(2).any(|pair| pair == ["-frames:v", "1"]));
+    assert!(args.windows(2).any(|pair| pair == ["-c:v", "libwebp"]));
+    assert_eq!(
+        args.last().map(String::as_str),
+        Some("/out/clip.thumb_sprite.webp")
+    );
+}
+
+#[test]
+fn tdd_runtime_derived_planner_uploads_sprite_sheet_and_webvtt_when_enabled() {
+    let generator = Arc::new(SpriteGenerator::new());
+    let planner = RuntimeDerivedPlanner::new(generator.clone(), generator.clone())
+        .with_thumbnail_sprite_profile(ThumbnailSpriteProfile::default());
+    let dir = tempfile::tempdir().expect("tempdir");
+    let staged = dir.path().join("clip.mov");
+    std::fs::write(&staged, b"generated-video").expect("write");
+
+    let plan = planner
+        .plan_for_claimed_job_with_source(&claimed_thumbnail_job(), Some(staged.as_path()), &[])
+        .expect("plan");
+
+    assert_eq!(plan.uploads.len(), 11);
+    for upload in &plan.uploads {
+        validate_derived_upload_init(&upload.init).expect("content type allowed for kind");
+    }
+    let sprite = &plan.uploads[9];
+    assert_eq!(sprite.init.kind, DerivedKind::ThumbSprite);
+    assert_eq!(sprite.init.content_type, "image/webp");
+    assert!(
+        sprite.parts[0]
+            .chunk_path
+            .ends_with("clip.thumb_sprite.webp")
+    );
+    let vtt = &plan.uploads[10];
+    assert_eq!(vtt.init.kind, DerivedKind::ThumbVtt);
+    assert_eq!(vtt.init.content_type, "text/vtt");
+    let vtt_body = std::fs::read_to_string(&vtt.parts[0].chunk_path).expect("read vtt");
+    assert!(vtt_body.starts_with("WEBVTT\n"));
+    assert!(vtt_body.contains(
+        "00:00:40.000 --> 00:00:42.000\n/api/v1/assets/asset-thumb-sprite/derived/thumb_sprite#xywh=1280,0,160,90"
+    ));
+
+    let manifest = &plan.submit.manifest;
+    assert_eq!(manifest.len(), 11);
+    assert_eq!(
+        manifest[9].reference,
+        "/api/v1/assets/asset-thumb-sprite/derived/thumb_sprite"
+    );
+    assert_eq!(
+        manifest[10].reference,
+        "/api/v1/assets/asset-thumb-sprite/derived/thumb_vtt"
+    );
+    assert_eq!(manifest[10].sha256, vtt.init.sha256);
+    let metrics = plan.submit.metrics.expect("metrics");
+    assert_eq!(
+        metrics.get("thumbnail_sprite_grid"),
+        Some(&serde_json::json!("9x1"))
+    );
+    assert_eq!(
+        metrics.get("thumbnail_sprite_tiles"),
+        Some(&serde_json::json!(9))
+    );
+    assert_eq!(metrics.get("thumbnail_count"), Some(&serde_json::json!(9)));
+    let requests = generator.sprite_requests.lock().expect("sprite requests");
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].format, ThumbnailFormat::Webp);
+}
+
+#[test]
+fn tdd_runtime_derived_planner_skips_sprite_sheet_by_default() {
+    let generator = Arc::new(SpriteGenerator::new());
+    let planner = RuntimeDerivedPlanner::new(generator.clone(), generator.clone());
+    let dir = tempfile::tempdir().expect("tempdir");
+    let staged = dir.path().join("clip.mov");
+    std::fs::write(&staged, b"generated-video").expect("write");
+
+    let plan = planner
+        .plan_for_claimed_job_with_source(&claimed_thumbnail_job(), Some(staged.as_path()), &[])
+        .expect("plan");
+
+    assert!(
+        plan.uploads
+            .iter()
+            .all(|upload| upload.init.kind == DerivedKind::Thumb)
+    );
+    assert!(
+        generator
+            .sprite_requests
+            .lock()
+            .expect("sprite requests")
+            .is_empty()
+    );
+    assert!(
+        !plan
+            .submit
+            .metrics
+            .expect("metrics")
+            .contains_key("thumbnail_sprite_grid")
+    );
+}
@@ -0,0 +1,395 @@
+use std::sync::{Arc, Mutex};
+
+use retaia_agent::{
+    AudioProxyRequest, ClaimedDerivedJob, CommandOutput, CommandRunner, DerivedExecutionPlanner,
+    DerivedJobExecutorError, DerivedJobType, FactsPatchPayload, FfmpegProxyGenerator,
+    MediaOperation, MediaTimeoutKind, NoopProxyProgressSink, PhotoDecodePath, PhotoProxyRequest,
+    ProxyGenerationError, ProxyGenerator, ProxyProgressSink, RuntimeDerivedPlanner,
+    STORYBOARD_FRAME_COUNT, VideoFrameAnalysisRequest, VideoFrameSample, VideoProxyRequest,
+    VideoThumbnailRequest, build_video_frame_analysis_args, parse_video_frame_analysis,
+    select_storyboard_frames, storyboard_sample_interval_ms,
+};
+
+fn sample(
+    timestamp_ms: u64,
+    scene_score: f32,
+    luma_mean: f32,
+    luma_spread: f32,
+) -> VideoFrameSample {
+    VideoFrameSample {
+        timestamp_ms,
+        scene_score,
+        luma_mean,
+        luma_spread,
+    }
+}
+
+// `scenes` lists (start_ms, end_ms) with one well exposed sample per second.
+fn scene_samples(scenes: &[(u64, u64)]) -> Vec<VideoFrameSample> {
+    scenes
+        .iter()
+        .flat_map(|(start_ms, end_ms)| {
+            (*start_ms..*end_ms)
+                .step_by(1_000)
+                .map(move |timestamp_ms| {
+                    let score = if timestamp_ms == *start_ms { 0.8 } else { 0.02 };
+                    sample(timestamp_ms, score, 110.0, 90.0)
+                })
+        })
+        .collect()
+}
+
+struct SceneAnalysisGenerator {
+    samples: Result<Vec<VideoFrameSample>, ProxyGenerationError>,
+    analysis_requests: Mutex<Vec<VideoFrameAnalysisRequest>>,
+    thumbnail_requests: Mutex<Vec<VideoThumbnailRequest>>,
+}
+
+impl SceneAnalysisGenerator {
+    fn new(samples: Result<Vec<VideoFrameSample>, ProxyGenerationError>) -> Self {
+        Self {
+            samples,
+            analysis_requests: Mutex::new(Vec::new()),
+            thumbnail_requests: Mutex::new(Vec::new()),
+        }
+    }
+
+    fn seek_points(&self) -> Vec<u64> {
+        self.thumbnail_requests
+            .lock()
+            .expect("thumbnail requests")
+            .iter()
+            .map(|request| request.seek_ms)
+            .collect()
+    }
+}
+
+impl ProxyGenerator for SceneAnalysisGenerator {
+    fn generate_video_proxy(
+        &self,
+        _request: &VideoProxyRequest,
+        _progress: &dyn ProxyProgressSink,
+    ) -> Result<(), ProxyGenerationError> {
+        Ok(())
+    }
+
+    fn generate_audio_proxy(
+        &self,
+        _request: &AudioProxyRequest,
+        _progress: &dyn ProxyProgressSink,
+    ) -> Result<(), ProxyGenerationError> {
+        Ok(())
+    }
+
+    fn generate_photo_proxy(
+        &self,
+        _request: &PhotoProxyRequest,
+        _progress: &dyn ProxyProgressSink,
+    ) -> Result<Option<PhotoDecodePath>, ProxyGenerationError> {
+        Ok(None)
+    }
+
+    fn analyze_video_frames(
+        &self,
+        request: &VideoFrameAnalysisRequest,
+        _progress: &dyn ProxyProgressSink,
+    ) -> Result<Vec<VideoFrameSample>, ProxyGenerationError> {
+        self.analysis_requests
+            .lock()
+            .expect("analysis requests")
+            .push(request.clone());
+        self.samples.clone()
+    }
+
+    fn generate_video_thumbnail(
+        &self,
+        request: &VideoThumbnailRequest,
+        _progress: &dyn ProxyProgressSink,
+    ) -> Result<(), ProxyGenerationError> {
+        self.thumbnail_requests
+            .lock()
+            .expect("thumbnail requests")
+            .push(request.clone());
+        std::fs::write(&request.output_path, b"generated-thumbnail")
+            .map_err(|error| ProxyGenerationError::Process(error.to_string()))
+    }
+
+    fn extract_media_facts(
+        &self,
+        _input_path: &str,
+        _progress: &dyn ProxyProgressSink,
+    ) -> Result<FactsPatchPayload, ProxyGenerationError> {
+        Ok(FactsPatchPayload {
+            duration_ms: Some(90_000),
+            ..FactsPatchPayload::default()
+        })
+    }
+}
+
+fn plan_thumbnails(generator: Arc<SceneAnalysisGenerator>) -> serde_json::Value {
+    try_plan_thumbnails(generator, true).expect("plan")
+}
+
+fn try_plan_thumbnails(
+    generator: Arc<SceneAnalysisGenerator>,
+    scene_storyboard: bool,
+) -> Result<serde_json::Value, DerivedJobExecutorError> {
+    let planner = RuntimeDerivedPlanner::new(generator.clone(), generator)
+        .with_scene_storyboard(scene_storyboard);
+    let claimed = ClaimedDerivedJob {
+        job_id: "job-thumb-scenes".to_string(),
+        asset_uuid: "asset-thumb-scenes".to_string(),
+        lock_token: "lock-thumb-scenes".to_string(),
+        fencing_token: 1,
+        job_type: DerivedJobType::GenerateThumbnails,
+        source_storage_id: "nas-main".to_string(),
+        source_original_relative: "INBOX/interview.mov".to_string(),
+        source_sidecars_relative: Vec::new(),
+    };
+    let dir = tempfile::tempdir().expect("tempdir");
+    let staged = dir.path().join("interview.mov");
+    std::fs::write(&staged, b"generated-video").expect("write");
+
+    let plan = planner.plan_for_claimed_job_with_source(&claimed, Some(staged.as_path()), &[])?;
+    let metrics = plan.submit.metrics.expect("thumbnail metrics");
+    Ok(serde_json::json!({
+        "profile": metrics.get("thumbnail_profile"),
+        "count": metrics.get("thumbnail_count"),
+        "uploads": plan.uploads.len(),
+    }))
+}
+
+#[test]
+fn tdd_video_storyboard_keeps_one_frame_for_a_single_scene_interview() {
+    let samples = scene_samples(&[(0, 61_000)]);
+
+    assert_eq!(select_storyboard_frames(&samples, 9), vec![30_000]);
+}
+
+#[test]
+fn tdd_video_storyboard_skips_black_and_flat_frames_of_fades() {
+    // Fade in from black, one scene, fade out to a flat slate.
+    let mut samples = vec![sample(0, 0.0, 16.0, 0.0), sample(1_000, 0.01, 20.0, 4.0)];
+    samples.extend(scene_samples(&[(2_000, 6_000)]));
+    samples.push(sample(6_000, 0.5, 40.0, 3.0));
+    samples.push(sample(7_000, 0.02, 18.0, 1.0));
+
+    // The fade out starts a scene of its own but has no usable frame.
+    assert_eq!(select_storyboard_frames(&samples, 9), vec![3_000]);
+    assert!(select_storyboard_frames(&samples[..2], 9).is_empty());
+    assert!(select_storyboard_frames(&[], 9).is_empty());
+}
+
+#[test]
+fn tdd_video_storyboard_keeps_longest_scenes_in_timeline_order() {
+    let samples = scene_samples(&[
+        (0, 3_000),
+        (3_000, 14_000),
+        (14_000, 15_000),
+        (15_000, 24_000),
+    ]);
+
+    assert_eq!(select_storyboard_frames(&samples, 2), vec![8_000, 19_000]);
+    assert_eq!(
+        select_storyboard_frames(&samples, 9),
+        vec![1_000, 8_000, 14_000, 19_000]
+    );
+}
+
+#[test]
+fn tdd_video_storyboard_sample_interval_bounds_analysis_cost() {
+    assert_eq!(storyboard_sample_interval_ms(10_000), 500);
+    assert_eq!(storyboard_sample_interval_ms(600_000), 1_000);
+    assert_eq!(storyboard_sample_interval_ms(7_200_000), 12_000);
+}
+
+#[test]
+fn tdd_ffmpeg_frame_analysis_args_sample_scene_scores_and_luma_stats() {
+    let args = build_video_frame_analysis_args(&VideoFrameAnalysisRequest {
+        input_path: "/in/interview.mov".to_string(),
+        duration_ms: 90_000,
+        sample_interval_ms: 500,
+    });
+
+    let filter = args
+        .iter()
+        .skip_while(|arg| *arg != "-vf")
+        .nth(1)
+        .expect("video filter");
+    assert!(filter.starts_with("fps=1000/500,"));
+    assert!(filter.contains("select='gte(scene,0)'"));
+    assert!(filter.contains("signalstats"));
+    assert!(filter.ends_with("metadata=mode=print:file=-:direct=1"));
+    assert!(args.windows(2).any(|pair| pair == ["-map", "0:v:0"]));
+    assert!(args.ends_with(&["-f".to_string(), "null".to_string(), "-".to_string()]));
+}
+
+#[test]
+fn tdd_ffmpeg_frame_analysis_parses_metadata_print_output() {
+    let stdout = "\
+frame:0    pts:0       pts_time:0
+lavfi.scene_score=0.000000
+lavfi.signalstats.YMIN=16
+lavfi.signalstats.YLOW=16
+lavfi.signalstats.YAVG=16.2
+lavfi.signalstats.YHIGH=17
+frame:1    pts:1       pts_time:0.5
+lavfi.scene_score=0.612000
+lavfi.signalstats.YLOW=30
+lavfi.signalstats.YAVG=101.5
+lavfi.signalstats.YHIGH=190
+frame:2    pts:2       pts_time:1
+lavfi.scene_score=0.010000
+";
+
+    let samples = parse_video_frame_analysis(stdout);
+
+    assert_eq!(
+        samples,
+        vec![sample(0, 0.0, 16.2, 1.0), sample(500, 0.612, 101.5, 160.0),]
+    );
+}
+
+struct AnalysisRunner {
+    stdout: &'static str,
+    calls: Mutex<Vec<Vec<String>>>,
+}
+
+impl CommandRunner for AnalysisRunner {
+    fn run(&self, _program: &str, args: &[String]) -> Result<CommandOutput, ProxyGenerationError> {
+        self.calls.lock().expect("calls").push(args.to_vec());
+        Ok(CommandOutput {
+            status_code: Some(0),
+            stdout: self.stdout.to_string(),
+            stderr: String::new(),
+        })
+    }
+}
+
+#[test]
+fn tdd_ffmpeg_analyze_video_frames_runs_one_pass_and_rejects_invalid_requests() {
+    let generator = FfmpegProxyGenerator::new(
+        "ffmpeg".to_string(),
+        AnalysisRunner {
+            stdout: "frame:0 pts:0 pts_time:2\nlavfi.signalstats.YLOW=20\nlavfi.signalstats.YAVG=90\nlavfi.signalstats.YHIGH=150\n",
+            calls: Mutex::new(Vec::new()),
+        },
+    );
+    let request = VideoFrameAnalysisRequest {
+        input_path: "/in/clip.mov".to_string(),
+        duration_ms: 10_000,
+        sample_interval_ms: 500,
+    };
+
+    let samples = generator
+        .analyze_video_frames(&request, &NoopProxyProgressSink)
+        .expect("analysis");
+    assert_eq!(samples, vec![sample(2_000, 0.0, 90.0, 130.0)]);
+    assert_eq!(generator.runner().calls.lock().expect("calls").len(), 1);
+
+    let error = generator
+        .analyze_video_frames(
+            &VideoFrameAnalysisRequest {
+                sample_interval_ms: 0,
+                ..request
+            },
+            &NoopProxyProgressSink,
+        )
+        .expect_err("invalid interval");
+    assert!(matches!(error, ProxyGenerationError::InvalidRequest(_)));
+    assert_eq!(generator.runner().calls.lock().expect("calls").len(), 1);
+}
+
+#[test]
+fn tdd_runtime_derived_planner_uses_scene_storyboard_v2_when_analysis_succeeds() {
+    let generator = Arc::new(SceneAnalysisGenerator::new(Ok(scene_samples(&[
+        (0, 29_000),
+        (29_000, 60_000),
+        (60_000, 89_000),
+    ]))));
+
+    let summary = plan_thumbnails(generator.clone());
+
+    assert_eq!(
+        summary,
+        serde_json::json!({"profile": "video_storyboard_v2", "count": 3, "uploads": 3})
+    );
+    assert_eq!(generator.seek_points(), vec![14_000, 44_000, 74_000]);
+    let analysis = generator.analysis_requests.lock().expect("analysis");
+    assert_eq!(analysis.len(), 1);
+    assert_eq!(analysis[0].duration_ms, 90_000);
+    assert_eq!(analysis[0].sample_interval_ms, 500);
+}
+
+#[test]
+fn tdd_runtime_derived_planner_falls_back_to_storyboard_v1_without_usable_scenes() {
+    let black = (0..90_000)
+        .step_by(1_000)
+        .map(|timestamp_ms| sample(timestamp_ms, 0.0, 16.0, 0.0))
+        .collect::<Vec<_>>();
+
+    let generator = Arc::new(SceneAnalysisGenerator::new(Ok(black)));
+
+    let summary = plan_thumbnails(generator.clone());
+
+    assert_eq!(
+        summary,
+        serde_json::json!({
+            "profile": "video_storyboard_v1",
+            "count": STORYBOARD_FRAME_COUNT,
+            "uploads": STORYBOARD_FRAME_COUNT,
+        })
+    );
+    assert_eq!(generator.seek_points().len(), STORYBOARD_FRAME_COUNT);
+}
+
+#[test]
+fn tdd_runtime_derived_planner_skips_scene_analysis_unless_storyboard_v2_is_enabled() {
+    let generator = Arc::new(SceneAnalysisGenerator::new(Ok(scene_samples(&[
+        (0, 29_000),
+        (29_000, 60_000),
+    ]))));
+
+    let summary = try_plan_thumbnails(generator.clone(), false).expect("plan");
+
+    assert_eq!(
+        summary,
+        serde_json::json!({
+            "profile": "video_storyboard_v1",
+            "count": STORYBOARD_FRAME_COUNT,
+            "uploads": STORYBOARD_FRAME_COUNT,
+        })
+    );
+    assert!(
+        generator
+            .analysis_requests
+            .lock()
+            .expect("analysis")
+            .is_empty()
+    );
+}
+
+#[test]
+fn tdd_runtime_derived_planner_fails_thumbnail_job_when_scene_analysis_fails() {
+    for error in [
+        ProxyGenerationError::Cancelled,
+        ProxyGenerationError::TimedOut {
+            operation: MediaOperation::SceneAnalysis,
+            kind: MediaTimeoutKind::WallClock,
+        },
+        ProxyGenerationError::Process("ffmpeg missing".to_string()),
+    ] {
+        let generator = Arc::new(SceneAnalysisGenerator::new(Err(error.clone())));
+
+        let result = try_plan_thumbnails(generator.clone(), true);
+
+        assert!(
+            matches!(
+                &result,
+                Err(DerivedJobExecutorError::ProxyGeneration(actual)) if *actual == error
+            ),
+            "unexpected result for {error:?}: {result:?}"
+        );
+        assert!(generator.seek_points().is_empty());
+    }
+}
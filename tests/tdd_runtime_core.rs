@@ -2,20 +2,38 @@
 mod agent_identity;
 #[path = "tdd_runtime/application.rs"]
 mod application;
+#[path = "tdd_runtime/audio_waveform.rs"]
+mod audio_waveform;
 #[path = "tdd_runtime/core_api_gateway.rs"]
 mod core_api_gateway;
 #[path = "tdd_runtime/daemon_diagnostics.rs"]
 mod daemon_diagnostics;
+#[path = "tdd_runtime/derived_chunked_upload.rs"]
+mod derived_chunked_upload;
+#[path = "tdd_runtime/derived_integrity.rs"]
+mod derived_integrity;
 #[path = "tdd_runtime/derived_job_executor.rs"]
 mod derived_job_executor;
+#[path = "tdd_runtime/derived_job_journal.rs"]
+mod derived_job_journal;
+#[path = "tdd_runtime/derived_lease_keeper.rs"]
+mod derived_lease_keeper;
 #[path = "tdd_runtime/derived_processing_gateway.rs"]
 mod derived_processing_gateway;
 #[path = "tdd_runtime/ffmpeg_proxy_generator.rs"]
 mod ffmpeg_proxy_generator;
+#[path = "tdd_runtime/gateway_retry.rs"]
+mod gateway_retry;
 #[path = "tdd_runtime/i18n.rs"]
 mod i18n;
+#[path = "tdd_runtime/job_selection.rs"]
+mod job_selection;
+#[path = "tdd_runtime/loudness_analysis.rs"]
+mod loudness_analysis;
 #[path = "tdd_runtime/menu.rs"]
 mod menu;
+#[path = "tdd_runtime/native_audio_decoding.rs"]
+mod native_audio_decoding;
 #[path = "tdd_runtime/notification_bridge.rs"]
 mod notification_bridge;
 #[path = "tdd_runtime/notification_sink.rs"]
@@ -24,6 +42,14 @@ mod notification_sink;
 mod notification_sink_selection;
 #[path = "tdd_runtime/notifications.rs"]
 mod notifications;
+#[path = "tdd_runtime/offline_processing.rs"]
+mod offline_processing;
+#[path = "tdd_runtime/photo_color_management.rs"]
+mod photo_color_management;
+#[path = "tdd_runtime/raw_development.rs"]
+mod raw_development;
+#[path = "tdd_runtime/raw_embedded_preview.rs"]
+mod raw_embedded_preview;
 #[path = "tdd_runtime/runtime_cli_shell.rs"]
 mod runtime_cli_shell;
 #[path = "tdd_runtime/runtime_control.rs"]
@@ -36,6 +62,8 @@ mod runtime_desktop_shell_controller;
 mod runtime_gui_shell;
 #[path = "tdd_runtime/runtime_history_store.rs"]
 mod runtime_history_store;
+#[path = "tdd_runtime/runtime_job_pool.rs"]
+mod runtime_job_pool;
 #[path = "tdd_runtime/runtime_job_worker.rs"]
 mod runtime_job_worker;
 #[path = "tdd_runtime/runtime_loop_engine.rs"]
@@ -54,6 +82,8 @@ mod runtime_sync;
 mod runtime_sync_coordinator;
 #[path = "tdd_runtime/rust_photo_proxy_generator.rs"]
 mod rust_photo_proxy_generator;
+#[path = "tdd_runtime/shutdown_drain.rs"]
+mod shutdown_drain;
 #[path = "tdd_runtime/signed_core_http.rs"]
 mod signed_core_http;
 #[path = "tdd_runtime/source_path_resolver.rs"]
@@ -62,3 +92,7 @@ mod source_path_resolver;
 mod source_staging;
 #[path = "support/system_dispatcher_mock.rs"]
 mod system_dispatcher_mock;
+#[path = "tdd_runtime/thumbnail_sprite.rs"]
+mod thumbnail_sprite;
+#[path = "tdd_runtime/video_storyboard.rs"]
+mod video_storyboard;